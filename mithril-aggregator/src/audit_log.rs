@@ -0,0 +1,222 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tokio::fs::{self, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+/// [AuditLog] related errors.
+#[derive(Error, Debug)]
+pub enum AuditLogError {
+    /// Error raised when the audit log file can not be read or written
+    #[error("audit log IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Error raised when an entry of the audit log can not be (de)serialized
+    #[error("audit log serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+/// An action requested to the admin API, as written in the audit log
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditLogEntry {
+    /// Date and time of the action
+    pub timestamp: String,
+
+    /// Name of the action
+    pub action: String,
+
+    /// Address of the requester if known
+    pub remote_address: Option<String>,
+
+    /// Was the requester authorized to perform the action
+    pub authorized: bool,
+
+    /// Outcome of the action
+    pub outcome: String,
+}
+
+impl AuditLogEntry {
+    /// AuditLogEntry factory, timestamped now
+    pub fn new(
+        action: &str,
+        remote_address: Option<String>,
+        authorized: bool,
+        outcome: &str,
+    ) -> Self {
+        Self {
+            timestamp: Utc::now().to_rfc3339(),
+            action: action.to_string(),
+            remote_address,
+            authorized,
+            outcome: outcome.to_string(),
+        }
+    }
+}
+
+/// Default maximum number of unauthorized attempts recorded per window
+const DEFAULT_UNAUTHORIZED_ENTRIES_LIMIT: u64 = 60;
+
+/// Default duration of the window bounding the unauthorized attempts recorded
+const DEFAULT_UNAUTHORIZED_ENTRIES_WINDOW: Duration = Duration::from_secs(60);
+
+/// Unauthorized attempts seen since the start of the current window
+struct UnauthorizedWindow {
+    started_at: Instant,
+    recorded: u64,
+    dropped: u64,
+}
+
+impl UnauthorizedWindow {
+    fn new() -> Self {
+        Self {
+            started_at: Instant::now(),
+            recorded: 0,
+            dropped: 0,
+        }
+    }
+}
+
+/// Append-only log of the actions requested to the admin API, one JSON entry per line
+pub struct AuditLog {
+    path: PathBuf,
+    lock: Mutex<()>,
+    unauthorized_entries_limit: u64,
+    unauthorized_entries_window: Duration,
+    unauthorized_window: Mutex<UnauthorizedWindow>,
+}
+
+impl AuditLog {
+    /// AuditLog factory
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            lock: Mutex::new(()),
+            unauthorized_entries_limit: DEFAULT_UNAUTHORIZED_ENTRIES_LIMIT,
+            unauthorized_entries_window: DEFAULT_UNAUTHORIZED_ENTRIES_WINDOW,
+            unauthorized_window: Mutex::new(UnauthorizedWindow::new()),
+        }
+    }
+
+    /// Set the maximum number of unauthorized attempts recorded per window of the given
+    /// duration
+    pub fn with_unauthorized_entries_limit(mut self, limit: u64, window: Duration) -> Self {
+        self.unauthorized_entries_limit = limit;
+        self.unauthorized_entries_window = window;
+        self
+    }
+
+    /// Append the entry of an unauthorized attempt to the audit log.
+    ///
+    /// At most `unauthorized_entries_limit` attempts are recorded per window, the other ones
+    /// are only counted and summed up in a single entry once the window is over, so that
+    /// unauthenticated clients can not grow the audit log without limit.
+    /// Returns whether the entry was recorded.
+    pub async fn record_unauthorized(&self, entry: &AuditLogEntry) -> Result<bool, AuditLogError> {
+        let mut window = self.unauthorized_window.lock().await;
+
+        if window.started_at.elapsed() >= self.unauthorized_entries_window {
+            let dropped = window.dropped;
+            *window = UnauthorizedWindow::new();
+            if dropped > 0 {
+                self.record(&AuditLogEntry::new(
+                    "unauthorized_attempts",
+                    None,
+                    false,
+                    &format!("{} unauthorized attempts not recorded", dropped),
+                ))
+                .await?;
+            }
+        }
+        if window.recorded >= self.unauthorized_entries_limit {
+            window.dropped += 1;
+            return Ok(false);
+        }
+        window.recorded += 1;
+        self.record(entry).await?;
+
+        Ok(true)
+    }
+
+    /// Append an entry to the audit log
+    pub async fn record(&self, entry: &AuditLogEntry) -> Result<(), AuditLogError> {
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+
+        let _guard = self.lock.lock().await;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+        file.write_all(line.as_bytes()).await?;
+        file.flush().await?;
+
+        Ok(())
+    }
+
+    /// Read all the entries of the audit log, the oldest first
+    pub async fn read_entries(&self) -> Result<Vec<AuditLogEntry>, AuditLogError> {
+        if !self.path.exists() {
+            return Ok(vec![]);
+        }
+        let content = fs::read_to_string(&self.path).await?;
+
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| serde_json::from_str(line).map_err(AuditLogError::from))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_record_and_read_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let audit_log = AuditLog::new(dir.path().join("audit.log"));
+        let entries = vec![
+            AuditLogEntry::new(
+                "pause_signing",
+                Some("127.0.0.1:1234".to_string()),
+                true,
+                "success",
+            ),
+            AuditLogEntry::new("prune_stores", None, false, "unauthorized"),
+        ];
+
+        for entry in &entries {
+            audit_log.record(entry).await.unwrap();
+        }
+
+        assert_eq!(entries, audit_log.read_entries().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_unauthorized_entries_are_bounded() {
+        let dir = tempfile::tempdir().unwrap();
+        let audit_log = AuditLog::new(dir.path().join("audit.log"))
+            .with_unauthorized_entries_limit(2, Duration::from_millis(100));
+        let entry = AuditLogEntry::new("pause_signing", None, false, "unauthorized");
+
+        let mut recorded = vec![];
+        for _ in 0..5 {
+            recorded.push(audit_log.record_unauthorized(&entry).await.unwrap());
+        }
+        assert_eq!(vec![true, true, false, false, false], recorded);
+        assert_eq!(2, audit_log.read_entries().await.unwrap().len());
+
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert!(audit_log.record_unauthorized(&entry).await.unwrap());
+        let entries = audit_log.read_entries().await.unwrap();
+        assert_eq!(4, entries.len());
+        assert_eq!("unauthorized_attempts", entries[2].action);
+        assert_eq!("3 unauthorized attempts not recorded", entries[2].outcome);
+        assert_eq!(entry.action, entries[3].action);
+    }
+}
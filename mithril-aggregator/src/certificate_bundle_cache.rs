@@ -0,0 +1,123 @@
+use mithril_common::certificate_chain::CertificateChainBundle;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Number of certificate chain bundles kept by default
+const DEFAULT_CERTIFICATE_BUNDLE_CACHE_CAPACITY: usize = 10;
+
+/// Certificate hash and trusted checkpoint a bundle was built for
+type CertificateBundleKey = (String, Option<String>);
+
+/// Cache of the certificate chain bundles served by the aggregator
+///
+/// The chain of a sealed certificate never changes, so a bundle is built once by walking the
+/// chain and then served from the cache. Only the most recently built bundles are kept since
+/// the clients mostly request the bundle of the latest certificates.
+pub struct CertificateBundleCache {
+    capacity: usize,
+    bundles: RwLock<VecDeque<(CertificateBundleKey, Arc<CertificateChainBundle>)>>,
+}
+
+impl CertificateBundleCache {
+    /// CertificateBundleCache factory
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            bundles: RwLock::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    /// Get the bundle built for a certificate and a trusted checkpoint if it is cached
+    pub async fn get(
+        &self,
+        certificate_hash: &str,
+        trusted_checkpoint: Option<&str>,
+    ) -> Option<Arc<CertificateChainBundle>> {
+        self.bundles
+            .read()
+            .await
+            .iter()
+            .find(|((hash, checkpoint), _)| {
+                hash == certificate_hash && checkpoint.as_deref() == trusted_checkpoint
+            })
+            .map(|(_, bundle)| bundle.clone())
+    }
+
+    /// Cache the bundle built for a certificate and a trusted checkpoint, the oldest cached
+    /// bundle is dropped if the cache is full
+    pub async fn insert(
+        &self,
+        certificate_hash: &str,
+        trusted_checkpoint: Option<&str>,
+        bundle: Arc<CertificateChainBundle>,
+    ) {
+        if self.capacity == 0
+            || self
+                .get(certificate_hash, trusted_checkpoint)
+                .await
+                .is_some()
+        {
+            return;
+        }
+        let mut bundles = self.bundles.write().await;
+        if bundles.len() == self.capacity {
+            bundles.pop_front();
+        }
+        bundles.push_back((
+            (
+                certificate_hash.to_string(),
+                trusted_checkpoint.map(|checkpoint| checkpoint.to_string()),
+            ),
+            bundle,
+        ));
+    }
+}
+
+impl Default for CertificateBundleCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CERTIFICATE_BUNDLE_CACHE_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mithril_common::fake_data;
+
+    fn bundle(certificate_hash: &str) -> Arc<CertificateChainBundle> {
+        Arc::new(CertificateChainBundle::new(
+            "fingerprint".to_string(),
+            vec![fake_data::certificate(certificate_hash.to_string())],
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_bundles_are_cached_by_certificate_and_checkpoint() {
+        let cache = CertificateBundleCache::new(2);
+        cache
+            .insert("hash-1", Some("checkpoint"), bundle("hash-1"))
+            .await;
+
+        assert_eq!(
+            Some(bundle("hash-1")),
+            cache.get("hash-1", Some("checkpoint")).await
+        );
+        assert_eq!(None, cache.get("hash-1", None).await);
+        assert_eq!(None, cache.get("hash-2", Some("checkpoint")).await);
+    }
+
+    #[tokio::test]
+    async fn test_oldest_bundle_is_dropped_when_full() {
+        let cache = CertificateBundleCache::new(2);
+        for certificate_hash in ["hash-1", "hash-2", "hash-3"] {
+            cache
+                .insert(certificate_hash, None, bundle(certificate_hash))
+                .await;
+        }
+
+        assert_eq!(None, cache.get("hash-1", None).await);
+        assert_eq!(Some(bundle("hash-2")), cache.get("hash-2", None).await);
+        assert_eq!(Some(bundle("hash-3")), cache.get("hash-3", None).await);
+    }
+}
@@ -1,21 +1,31 @@
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use config::{builder::DefaultState, ConfigBuilder, Map, Source, Value, ValueKind};
+use serde::{de::DeserializeOwned, Serialize};
 use slog::Level;
 use slog_scope::debug;
+use std::collections::HashMap;
 use std::error::Error;
 use std::fs;
+use std::hash::Hash;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tokio::time::Duration;
 
-use mithril_common::certificate_chain::MithrilCertificateVerifier;
+use mithril_common::certificate_chain::{CertificateVerifier, MithrilCertificateVerifier};
 use mithril_common::chain_observer::{CardanoCliRunner, ChainObserver};
-use mithril_common::crypto_helper::ProtocolGenesisVerifier;
+use mithril_common::crypto_helper::{ProtocolGenesisPolicy, ProtocolGenesisVerifier};
 use mithril_common::digesters::{CardanoImmutableDigester, ImmutableFileSystemObserver};
-use mithril_common::entities::{Epoch, HexEncodedGenesisSecretKey};
-use mithril_common::store::adapter::SQLiteAdapter;
-use mithril_common::store::StakeStore;
+use mithril_common::entities::{
+    Certificate, CertificatePending, Epoch, HexEncodedGenesisSecretKey,
+    HexEncodedGenesisVerificationKey, PartyId, ProtocolParameters, Signer, Snapshot,
+    StakeDistribution,
+};
+use mithril_common::store::adapter::{open_sqlite_connection, SQLiteAdapter, SQLiteConnection};
+use mithril_common::store::{
+    PruneReport, RetentionPolicyConfiguration, SQLiteTransactionManager, StakeStore,
+    StoreMigrationReport, StoreMigrator, StorePruner,
+};
 use mithril_common::{
     crypto_helper::{key_decode_hex, ProtocolGenesisSigner},
     BeaconProviderImpl,
@@ -23,13 +33,14 @@ use mithril_common::{
 
 use crate::tools::GenesisToolsDependency;
 use crate::{
-    tools::GenesisTools, AggregatorConfig, AggregatorRunner, AggregatorRuntime,
-    CertificatePendingStore, Configuration, DependencyManager, GenesisConfiguration,
-    ProtocolParametersStore, Server,
+    tools::GenesisTools, AggregatorConfig, AggregatorRunner, AggregatorRuntime, AuditLog,
+    CertificateBundleCache, CertificatePendingStore, Configuration, DependencyManager, EventBus,
+    FailedRoundStore, GenesisConfiguration, MetricsService, ProtocolParametersStore,
+    RuntimeControl, RuntimeStateStore, RuntimeStatus, Server, SignerStatisticsStore,
 };
 use crate::{
-    CertificateStore, DefaultConfiguration, GzipSnapshotter, MultiSignerImpl,
-    ProtocolParametersStorer, SingleSignatureStore, VerificationKeyStore,
+    CertificateStore, DefaultConfiguration, MultiSignerImpl, ProtocolParametersStorer,
+    SingleSignatureStore, VerificationKeyStore,
 };
 
 fn setup_genesis_dependencies(
@@ -56,31 +67,31 @@ fn setup_genesis_dependencies(
         sqlite_db_path.clone(),
     )?)));
     let certificate_verifier = Arc::new(MithrilCertificateVerifier::new(slog_scope::logger()));
-    let genesis_verification_key = key_decode_hex(&config.genesis_verification_key)?;
-    let genesis_verifier = Arc::new(ProtocolGenesisVerifier::from_verification_key(
-        genesis_verification_key,
-    ));
+    let genesis_verifier = Arc::new(config.build_genesis_verifier()?);
     let protocol_parameters_store = Arc::new(ProtocolParametersStore::new(
         Box::new(SQLiteAdapter::new(
             "protocol_parameters",
             sqlite_db_path.clone(),
         )?),
-        config.store_retention_limit,
+        config.get_store_retention_policy(&config.protocol_parameters_store_retention),
     ));
     let verification_key_store = Arc::new(VerificationKeyStore::new(
         Box::new(SQLiteAdapter::new(
             "verification_key",
             sqlite_db_path.clone(),
         )?),
-        config.store_retention_limit,
+        config.get_store_retention_policy(&config.verification_key_store_retention),
     ));
     let stake_store = Arc::new(StakeStore::new(
         Box::new(SQLiteAdapter::new("stake", sqlite_db_path.clone())?),
-        config.store_retention_limit,
+        config.get_store_retention_policy(&config.stake_store_retention),
     ));
     let single_signature_store = Arc::new(SingleSignatureStore::new(
-        Box::new(SQLiteAdapter::new("single_signature", sqlite_db_path)?),
-        config.store_retention_limit,
+        Box::new(SQLiteAdapter::new(
+            "signed_entity_single_signature",
+            sqlite_db_path,
+        )?),
+        config.get_store_retention_policy(&config.single_signature_store_retention),
     ));
     let multi_signer = Arc::new(RwLock::new(MultiSignerImpl::new(
         verification_key_store,
@@ -217,6 +228,9 @@ impl MainOpts {
 pub enum MainCommand {
     Genesis(GenesisCommand),
     Serve(ServeCommand),
+    Prune(PruneCommand),
+    MigrateStore(MigrateStoreCommand),
+    VerifyCertificateChain(VerifyCertificateChainCommand),
 }
 
 impl MainCommand {
@@ -227,6 +241,9 @@ impl MainCommand {
         match self {
             Self::Genesis(cmd) => cmd.execute(config_builder).await,
             Self::Serve(cmd) => cmd.execute(config_builder).await,
+            Self::Prune(cmd) => cmd.execute(config_builder).await,
+            Self::MigrateStore(cmd) => cmd.execute(config_builder).await,
+            Self::VerifyCertificateChain(cmd) => cmd.execute(config_builder).await,
         }
     }
 }
@@ -296,39 +313,69 @@ impl ServeCommand {
             .map_err(|e| format!("configuration deserialize error: {}", e))?;
         debug!("SERVE command"; "config" => format!("{:?}", config));
         // Init dependencies
-        let snapshot_store = config.build_snapshot_store()?;
-        let snapshot_uploader = config.build_snapshot_uploader();
-
-        let sqlite_db_path = Some(config.get_sqlite_file());
+        // All the SQLite stores share the same connection so they can be enrolled in the
+        // same transaction.
+        let sqlite_connection = open_sqlite_connection(Some(config.get_sqlite_file()))?;
+        let transaction_manager =
+            Arc::new(SQLiteTransactionManager::new(sqlite_connection.clone()));
+        let snapshot_store = config.build_snapshot_store(sqlite_connection.clone())?;
+        let snapshot_uploader = config.build_snapshot_uploader()?;
 
         let certificate_pending_store = Arc::new(CertificatePendingStore::new(Box::new(
-            SQLiteAdapter::new("pending_certificate", sqlite_db_path.clone())?,
+            SQLiteAdapter::with_connection("pending_certificate", sqlite_connection.clone())?,
         )));
-        let certificate_store = Arc::new(CertificateStore::new(Box::new(SQLiteAdapter::new(
-            "certificate",
-            sqlite_db_path.clone(),
-        )?)));
+        let runtime_state_store = Arc::new(RuntimeStateStore::new(Box::new(
+            SQLiteAdapter::with_connection("runtime_state", sqlite_connection.clone())?,
+        )));
+        let failed_round_store = Arc::new(FailedRoundStore::new(
+            Box::new(SQLiteAdapter::with_connection(
+                "failed_round",
+                sqlite_connection.clone(),
+            )?),
+            config.get_store_retention_policy(&config.failed_round_store_retention),
+        ));
+        let signer_statistics_store = Arc::new(SignerStatisticsStore::new(
+            Box::new(SQLiteAdapter::with_connection(
+                "signer_statistics",
+                sqlite_connection.clone(),
+            )?),
+            config.get_store_retention_policy(&config.signer_statistics_store_retention),
+        ));
+        let certificate_store = Arc::new(CertificateStore::with_retention(
+            Box::new(SQLiteAdapter::with_connection(
+                "certificate",
+                sqlite_connection.clone(),
+            )?),
+            RetentionPolicyConfiguration::build_policy(&config.certificate_store_retention, None),
+            Some(snapshot_store.clone()),
+        ));
         let verification_key_store = Arc::new(VerificationKeyStore::new(
-            Box::new(SQLiteAdapter::new(
+            Box::new(SQLiteAdapter::with_connection(
                 "verification_key",
-                sqlite_db_path.clone(),
+                sqlite_connection.clone(),
             )?),
-            config.store_retention_limit,
+            config.get_store_retention_policy(&config.verification_key_store_retention),
         ));
         let stake_store = Arc::new(StakeStore::new(
-            Box::new(SQLiteAdapter::new("stake", sqlite_db_path.clone())?),
-            config.store_retention_limit,
+            Box::new(SQLiteAdapter::with_connection(
+                "stake",
+                sqlite_connection.clone(),
+            )?),
+            config.get_store_retention_policy(&config.stake_store_retention),
         ));
         let single_signature_store = Arc::new(SingleSignatureStore::new(
-            Box::new(SQLiteAdapter::new(
-                "single_signature",
-                sqlite_db_path.clone(),
+            Box::new(SQLiteAdapter::with_connection(
+                "signed_entity_single_signature",
+                sqlite_connection.clone(),
             )?),
-            config.store_retention_limit,
+            config.get_store_retention_policy(&config.single_signature_store_retention),
         ));
         let protocol_parameters_store = Arc::new(ProtocolParametersStore::new(
-            Box::new(SQLiteAdapter::new("protocol_parameters", sqlite_db_path)?),
-            config.store_retention_limit,
+            Box::new(SQLiteAdapter::with_connection(
+                "protocol_parameters",
+                sqlite_connection,
+            )?),
+            config.get_store_retention_policy(&config.protocol_parameters_store_retention),
         ));
         let chain_observer = Arc::new(
             mithril_common::chain_observer::CardanoCliChainObserver::new(Box::new(
@@ -350,18 +397,23 @@ impl ServeCommand {
             config.db_directory.clone(),
             slog_scope::logger(),
         ));
-        let multi_signer = Arc::new(RwLock::new(MultiSignerImpl::new(
-            verification_key_store.clone(),
-            stake_store.clone(),
-            single_signature_store.clone(),
-            protocol_parameters_store.clone(),
-            chain_observer.clone(),
-        )));
-        let certificate_verifier = Arc::new(MithrilCertificateVerifier::new(slog_scope::logger()));
-        let genesis_verification_key = key_decode_hex(&config.genesis_verification_key)?;
-        let genesis_verifier = Arc::new(ProtocolGenesisVerifier::from_verification_key(
-            genesis_verification_key,
+        let multi_signer = Arc::new(RwLock::new(
+            MultiSignerImpl::new(
+                verification_key_store.clone(),
+                stake_store.clone(),
+                single_signature_store.clone(),
+                protocol_parameters_store.clone(),
+                chain_observer.clone(),
+            )
+            .with_signer_statistics_store(signer_statistics_store.clone()),
         ));
+        let certificate_verifier = Arc::new(MithrilCertificateVerifier::new(slog_scope::logger()));
+        let genesis_verifier = Arc::new(config.build_genesis_verifier()?);
+        let metrics_service = Arc::new(MetricsService::new()?);
+        let runtime_status = Arc::new(RwLock::new(RuntimeStatus::default()));
+        let event_bus = Arc::new(EventBus::default());
+        let runtime_control = Arc::new(RuntimeControl::new());
+        let audit_log = Arc::new(AuditLog::new(config.get_admin_audit_log_file()));
 
         // Snapshotter - Ensure its ongoing snapshot directory exist
         let ongoing_snapshot_directory = config.snapshot_directory.join("pending_snapshot");
@@ -369,10 +421,7 @@ impl ServeCommand {
             fs::create_dir(&ongoing_snapshot_directory)
                 .expect("Pending snapshot directory creation failure");
         }
-        let snapshotter = Arc::new(GzipSnapshotter::new(
-            config.db_directory.clone(),
-            ongoing_snapshot_directory,
-        ));
+        let snapshotter = config.build_snapshotter(ongoing_snapshot_directory)?;
 
         // Init dependency manager
         let dependency_manager = DependencyManager {
@@ -381,11 +430,15 @@ impl ServeCommand {
             snapshot_uploader: snapshot_uploader.clone(),
             multi_signer: multi_signer.clone(),
             certificate_pending_store: certificate_pending_store.clone(),
+            runtime_state_store,
+            failed_round_store,
+            signer_statistics_store,
             certificate_store: certificate_store.clone(),
             verification_key_store: verification_key_store.clone(),
             stake_store: stake_store.clone(),
             single_signature_store: single_signature_store.clone(),
             protocol_parameters_store: protocol_parameters_store.clone(),
+            transaction_manager,
             chain_observer: chain_observer.clone(),
             beacon_provider: beacon_provider.clone(),
             immutable_file_observer,
@@ -393,6 +446,12 @@ impl ServeCommand {
             snapshotter,
             certificate_verifier,
             genesis_verifier,
+            metrics_service,
+            runtime_status,
+            event_bus,
+            runtime_control,
+            audit_log,
+            certificate_bundle_cache: Arc::new(CertificateBundleCache::default()),
         };
         let dependency_manager = Arc::new(dependency_manager);
 
@@ -405,14 +464,27 @@ impl ServeCommand {
 
         // Start snapshot uploader
         let network = config.get_network()?;
+        let signed_entity_kinds = config.list_signed_entity_kinds()?;
+        let snapshot_chunk_size = config.get_snapshot_chunk_size()?;
+        let snapshot_max_increments = config.get_snapshot_max_increments()?;
         let runtime_dependencies = dependency_manager.clone();
         let handle = tokio::spawn(async move {
             let config =
-                AggregatorConfig::new(config.run_interval, network, &config.db_directory.clone());
+                AggregatorConfig::new(config.run_interval, network, &config.db_directory.clone())
+                    .with_signed_entity_kinds(signed_entity_kinds)
+                    .with_pending_certificate_timeout(
+                        config.pending_certificate_timeout.map(Duration::from_secs),
+                    )
+                    .with_snapshot_max_increments(snapshot_max_increments)
+                    .with_snapshot_chunk_size(snapshot_chunk_size);
             let mut runtime = AggregatorRuntime::new(
                 Duration::from_millis(config.interval),
                 None,
                 Arc::new(AggregatorRunner::new(config, runtime_dependencies.clone())),
+                runtime_dependencies.metrics_service.clone(),
+                runtime_dependencies.runtime_status.clone(),
+                runtime_dependencies.event_bus.clone(),
+                runtime_dependencies.runtime_control.clone(),
             )
             .await
             .unwrap();
@@ -441,6 +513,279 @@ impl ServeCommand {
     }
 }
 
+/// Stores pruning command, applies the retention policies of the stores
+#[derive(Parser, Debug, Clone)]
+pub struct PruneCommand {
+    /// Only report the records that would be pruned, without deleting them
+    #[clap(long)]
+    dry_run: bool,
+}
+
+impl PruneCommand {
+    pub async fn execute(
+        &self,
+        config_builder: ConfigBuilder<DefaultState>,
+    ) -> Result<(), Box<dyn Error>> {
+        let config: Configuration = config_builder
+            .build()
+            .map_err(|e| format!("configuration build error: {}", e))?
+            .try_deserialize()
+            .map_err(|e| format!("configuration deserialize error: {}", e))?;
+        debug!("PRUNE command"; "config" => format!("{:?}", config), "dry_run" => self.dry_run);
+        let sqlite_connection = open_sqlite_connection(Some(config.get_sqlite_file()))?;
+        let snapshot_store = config.build_snapshot_store(sqlite_connection.clone())?;
+
+        let verification_key_store = VerificationKeyStore::new(
+            Box::new(SQLiteAdapter::with_connection(
+                "verification_key",
+                sqlite_connection.clone(),
+            )?),
+            config.get_store_retention_policy(&config.verification_key_store_retention),
+        );
+        self.print_report(
+            "verification_key",
+            self.prune(&verification_key_store).await?,
+        );
+
+        let stake_store = StakeStore::new(
+            Box::new(SQLiteAdapter::with_connection(
+                "stake",
+                sqlite_connection.clone(),
+            )?),
+            config.get_store_retention_policy(&config.stake_store_retention),
+        );
+        self.print_report("stake", self.prune(&stake_store).await?);
+
+        let single_signature_store = SingleSignatureStore::new(
+            Box::new(SQLiteAdapter::with_connection(
+                "signed_entity_single_signature",
+                sqlite_connection.clone(),
+            )?),
+            config.get_store_retention_policy(&config.single_signature_store_retention),
+        );
+        self.print_report(
+            "single_signature",
+            self.prune(&single_signature_store).await?,
+        );
+
+        let protocol_parameters_store = ProtocolParametersStore::new(
+            Box::new(SQLiteAdapter::with_connection(
+                "protocol_parameters",
+                sqlite_connection.clone(),
+            )?),
+            config.get_store_retention_policy(&config.protocol_parameters_store_retention),
+        );
+        self.print_report(
+            "protocol_parameters",
+            self.prune(&protocol_parameters_store).await?,
+        );
+
+        let failed_round_store = FailedRoundStore::new(
+            Box::new(SQLiteAdapter::with_connection(
+                "failed_round",
+                sqlite_connection.clone(),
+            )?),
+            config.get_store_retention_policy(&config.failed_round_store_retention),
+        );
+        self.print_report("failed_round", self.prune(&failed_round_store).await?);
+
+        let certificate_store = CertificateStore::with_retention(
+            Box::new(SQLiteAdapter::with_connection(
+                "certificate",
+                sqlite_connection,
+            )?),
+            RetentionPolicyConfiguration::build_policy(&config.certificate_store_retention, None),
+            Some(snapshot_store),
+        );
+        self.print_report("certificate", self.prune(&certificate_store).await?);
+
+        Ok(())
+    }
+
+    async fn prune<S>(&self, store: &S) -> Result<PruneReport<S::Key>, Box<dyn Error>>
+    where
+        S: StorePruner + Sync,
+    {
+        let report = match self.dry_run {
+            true => store.get_prune_report().await?,
+            false => store.prune().await?,
+        };
+
+        Ok(report)
+    }
+
+    fn print_report<K: std::fmt::Debug>(&self, store_name: &str, report: PruneReport<K>) {
+        let action = match self.dry_run {
+            true => "would be pruned",
+            false => "pruned",
+        };
+        println!(
+            "{} store: {} record(s) {} {:?}, {} record(s) kept because referenced {:?}",
+            store_name,
+            report.pruned_keys.len(),
+            action,
+            report.pruned_keys,
+            report.protected_keys.len(),
+            report.protected_keys
+        );
+    }
+}
+
+/// Stores that can be migrated from a json file store to the SQLite database
+///
+/// The single signatures are only kept while their certificate is pending and are now stored
+/// per signed entity, they are not migrated.
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum MigratedStore {
+    Certificate,
+    PendingCertificate,
+    VerificationKey,
+    Stake,
+    ProtocolParameters,
+    Snapshot,
+}
+
+/// Json file store to SQLite migration command, records already migrated are skipped
+#[derive(Parser, Debug, Clone)]
+pub struct MigrateStoreCommand {
+    /// Store to migrate
+    #[clap(long, value_enum)]
+    store: MigratedStore,
+
+    /// Directory of the json file store to migrate
+    #[clap(long)]
+    json_store_directory: PathBuf,
+
+    /// Only verify that the records of the json file store are in the SQLite database
+    #[clap(long)]
+    verify_only: bool,
+}
+
+impl MigrateStoreCommand {
+    pub async fn execute(
+        &self,
+        config_builder: ConfigBuilder<DefaultState>,
+    ) -> Result<(), Box<dyn Error>> {
+        let config: Configuration = config_builder
+            .build()
+            .map_err(|e| format!("configuration build error: {}", e))?
+            .try_deserialize()
+            .map_err(|e| format!("configuration deserialize error: {}", e))?;
+        debug!("MIGRATE STORE command"; "config" => format!("{:?}", config), "store" => ?self.store);
+        let connection = open_sqlite_connection(Some(config.get_sqlite_file()))?;
+
+        let report = match self.store {
+            MigratedStore::Certificate => {
+                self.migrate::<String, Certificate>("certificate", connection)
+                    .await?
+            }
+            MigratedStore::PendingCertificate => {
+                self.migrate::<String, CertificatePending>("pending_certificate", connection)
+                    .await?
+            }
+            MigratedStore::VerificationKey => {
+                self.migrate::<Epoch, HashMap<PartyId, Signer>>("verification_key", connection)
+                    .await?
+            }
+            MigratedStore::Stake => {
+                self.migrate::<Epoch, StakeDistribution>("stake", connection)
+                    .await?
+            }
+            MigratedStore::ProtocolParameters => {
+                self.migrate::<Epoch, ProtocolParameters>("protocol_parameters", connection)
+                    .await?
+            }
+            MigratedStore::Snapshot => {
+                self.migrate::<String, Snapshot>("snapshot", connection)
+                    .await?
+            }
+        };
+        println!("{:?} store: {}", self.store, report);
+
+        Ok(())
+    }
+
+    async fn migrate<K, R>(
+        &self,
+        table_name: &str,
+        connection: SQLiteConnection,
+    ) -> Result<StoreMigrationReport, Box<dyn Error>>
+    where
+        K: Hash + PartialEq + Serialize + DeserializeOwned + Sync + Send + 'static,
+        R: Serialize + DeserializeOwned + Sync + Send + 'static,
+    {
+        Ok(StoreMigrator::<K, R>::run_json_to_sqlite(
+            self.json_store_directory.clone(),
+            table_name,
+            connection,
+            self.verify_only,
+        )
+        .await?)
+    }
+}
+
+/// Certificate chain verification command, reports the outcome of every check of the chain
+#[derive(Parser, Debug, Clone)]
+pub struct VerifyCertificateChainCommand {
+    /// Hash of the certificate to verify, the latest certificate if not set
+    #[clap(long)]
+    certificate_hash: Option<String>,
+
+    /// Enable JSON output
+    #[clap(long)]
+    json: bool,
+}
+
+impl VerifyCertificateChainCommand {
+    pub async fn execute(
+        &self,
+        config_builder: ConfigBuilder<DefaultState>,
+    ) -> Result<(), Box<dyn Error>> {
+        let config: Configuration = config_builder
+            .build()
+            .map_err(|e| format!("configuration build error: {}", e))?
+            .try_deserialize()
+            .map_err(|e| format!("configuration deserialize error: {}", e))?;
+        debug!("VERIFY CERTIFICATE CHAIN command"; "config" => format!("{:?}", config), "certificate_hash" => ?self.certificate_hash);
+        let certificate_store = Arc::new(CertificateStore::new(Box::new(
+            SQLiteAdapter::with_connection(
+                "certificate",
+                open_sqlite_connection(Some(config.get_sqlite_file()))?,
+            )?,
+        )));
+        let certificate = match &self.certificate_hash {
+            Some(certificate_hash) => certificate_store
+                .get_from_hash(certificate_hash)
+                .await?
+                .ok_or_else(|| format!("certificate '{}' not found", certificate_hash))?,
+            None => certificate_store
+                .get_list(1)
+                .await?
+                .pop()
+                .ok_or("the certificate store is empty")?,
+        };
+        let genesis_verifier = config.build_genesis_verifier()?;
+        let report = MithrilCertificateVerifier::new(slog_scope::logger())
+            .verify_certificate_chain_with_report(
+                certificate,
+                certificate_store,
+                &genesis_verifier,
+                None,
+            )
+            .await;
+        if self.json {
+            println!("{}", serde_json::to_string(&report)?);
+        } else {
+            print!("{}", report);
+        }
+        if !report.valid {
+            return Err("the certificate chain is invalid".into());
+        }
+
+        Ok(())
+    }
+}
+
 /// Genesis tools
 #[derive(Parser, Debug, Clone)]
 pub struct GenesisCommand {
@@ -469,6 +814,12 @@ pub enum GenesisSubCommand {
 
     /// Genesis certificate bootstrap command.
     Bootstrap(BootstrapGenesisSubCommand),
+
+    /// Genesis key rotation export command.
+    ExportRotation(ExportRotationGenesisSubCommand),
+
+    /// Genesis key rotation import command.
+    ImportRotation(ImportRotationGenesisSubCommand),
 }
 
 impl GenesisSubCommand {
@@ -480,6 +831,8 @@ impl GenesisSubCommand {
             Self::Bootstrap(cmd) => cmd.execute(config_builder).await,
             Self::Export(cmd) => cmd.execute(config_builder).await,
             Self::Import(cmd) => cmd.execute(config_builder).await,
+            Self::ExportRotation(cmd) => cmd.execute(config_builder).await,
+            Self::ImportRotation(cmd) => cmd.execute(config_builder).await,
         }
     }
 }
@@ -516,9 +869,9 @@ impl ExportGenesisSubCommand {
 
 #[derive(Parser, Debug, Clone)]
 pub struct ImportGenesisSubCommand {
-    /// Signed Payload Path
-    #[clap(long)]
-    signed_payload_path: PathBuf,
+    /// Signed Payload Path, one per custodian of the genesis policy
+    #[clap(long, required = true, num_args = 1..)]
+    signed_payload_path: Vec<PathBuf>,
 }
 
 impl ImportGenesisSubCommand {
@@ -534,7 +887,7 @@ impl ImportGenesisSubCommand {
         debug!("IMPORT GENESIS command"; "config" => format!("{:?}", config));
         println!(
             "Genesis import signed payload from {}",
-            self.signed_payload_path.to_string_lossy()
+            display_paths(&self.signed_payload_path)
         );
         let dependencies = setup_genesis_dependencies(&config)?;
 
@@ -574,3 +927,154 @@ impl BootstrapGenesisSubCommand {
             .await
     }
 }
+
+fn display_paths(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn build_next_genesis_verifier(
+    next_genesis_verification_key: &Option<HexEncodedGenesisVerificationKey>,
+    next_genesis_policy_path: &Option<PathBuf>,
+) -> Result<ProtocolGenesisVerifier, Box<dyn Error>> {
+    match (next_genesis_verification_key, next_genesis_policy_path) {
+        (_, Some(next_genesis_policy_path)) => Ok(ProtocolGenesisVerifier::from_policy(
+            &ProtocolGenesisPolicy::from_file(next_genesis_policy_path)?,
+        )?),
+        (Some(next_genesis_verification_key), None) => {
+            Ok(ProtocolGenesisVerifier::from_verification_key(
+                key_decode_hex(next_genesis_verification_key)?,
+            ))
+        }
+        (None, None) => Err("a next genesis verification key or policy is required".into()),
+    }
+}
+
+/// Genesis key rotation export command
+#[derive(Parser, Debug, Clone)]
+pub struct ExportRotationGenesisSubCommand {
+    /// Genesis verification key that signs the certificate chain after the rotation
+    #[clap(
+        long,
+        required_unless_present = "next_genesis_policy_path",
+        conflicts_with = "next_genesis_policy_path"
+    )]
+    next_genesis_verification_key: Option<HexEncodedGenesisVerificationKey>,
+
+    /// Genesis policy file of the custodians that sign the certificate chain after the rotation
+    #[clap(long)]
+    next_genesis_policy_path: Option<PathBuf>,
+
+    /// Rotation payload signed with the current genesis secret key(s), one per custodian: if set,
+    /// the genesis key rotation certificate payload to sign with the next genesis secret key(s)
+    /// is exported instead of the rotation payload
+    #[clap(long, num_args = 1..)]
+    signed_rotation_payload_path: Vec<PathBuf>,
+
+    /// Target Path
+    #[clap(long)]
+    target_path: PathBuf,
+}
+
+impl ExportRotationGenesisSubCommand {
+    pub async fn execute(
+        &self,
+        config_builder: ConfigBuilder<DefaultState>,
+    ) -> Result<(), Box<dyn Error>> {
+        let config: GenesisConfiguration = config_builder
+            .build()
+            .map_err(|e| format!("configuration build error: {}", e))?
+            .try_deserialize()
+            .map_err(|e| format!("configuration deserialize error: {}", e))?;
+        debug!("EXPORT GENESIS ROTATION command"; "config" => format!("{:?}", config));
+        let next_genesis_verifier = build_next_genesis_verifier(
+            &self.next_genesis_verification_key,
+            &self.next_genesis_policy_path,
+        )?;
+        let dependencies = setup_genesis_dependencies(&config)?;
+
+        let genesis_tools = GenesisTools::from_dependencies(dependencies).await?;
+        match self.signed_rotation_payload_path.as_slice() {
+            [] => {
+                println!(
+                    "Genesis key rotation export payload to sign with the current genesis secret key to {}",
+                    self.target_path.display()
+                );
+                genesis_tools
+                    .export_rotation_payload_to_sign(&next_genesis_verifier, &self.target_path)
+            }
+            signed_rotation_payload_paths => {
+                println!(
+                    "Genesis key rotation export certificate payload to sign with the next genesis secret key to {}",
+                    self.target_path.display()
+                );
+                genesis_tools.export_rotation_certificate_payload_to_sign(
+                    &next_genesis_verifier,
+                    signed_rotation_payload_paths,
+                    &self.target_path,
+                )
+            }
+        }
+    }
+}
+
+/// Genesis key rotation import command
+#[derive(Parser, Debug, Clone)]
+pub struct ImportRotationGenesisSubCommand {
+    /// Genesis verification key that signs the certificate chain after the rotation
+    #[clap(
+        long,
+        required_unless_present = "next_genesis_policy_path",
+        conflicts_with = "next_genesis_policy_path"
+    )]
+    next_genesis_verification_key: Option<HexEncodedGenesisVerificationKey>,
+
+    /// Genesis policy file of the custodians that sign the certificate chain after the rotation
+    #[clap(long)]
+    next_genesis_policy_path: Option<PathBuf>,
+
+    /// Rotation payload signed with the current genesis secret key(s), one per custodian
+    #[clap(long, required = true, num_args = 1..)]
+    signed_rotation_payload_path: Vec<PathBuf>,
+
+    /// Genesis key rotation certificate payload signed with the next genesis secret key(s), one
+    /// per custodian
+    #[clap(long, required = true, num_args = 1..)]
+    signed_payload_path: Vec<PathBuf>,
+}
+
+impl ImportRotationGenesisSubCommand {
+    pub async fn execute(
+        &self,
+        config_builder: ConfigBuilder<DefaultState>,
+    ) -> Result<(), Box<dyn Error>> {
+        let config: GenesisConfiguration = config_builder
+            .build()
+            .map_err(|e| format!("configuration build error: {}", e))?
+            .try_deserialize()
+            .map_err(|e| format!("configuration deserialize error: {}", e))?;
+        debug!("IMPORT GENESIS ROTATION command"; "config" => format!("{:?}", config));
+        println!(
+            "Genesis key rotation import signed payloads from {} and {}",
+            display_paths(&self.signed_rotation_payload_path),
+            display_paths(&self.signed_payload_path)
+        );
+        let next_genesis_verifier = build_next_genesis_verifier(
+            &self.next_genesis_verification_key,
+            &self.next_genesis_policy_path,
+        )?;
+        let dependencies = setup_genesis_dependencies(&config)?;
+
+        let genesis_tools = GenesisTools::from_dependencies(dependencies).await?;
+        genesis_tools
+            .import_rotation_payload_signatures(
+                &next_genesis_verifier,
+                &self.signed_rotation_payload_path,
+                &self.signed_payload_path,
+            )
+            .await
+    }
+}
@@ -1,23 +1,36 @@
 use config::{ConfigError, Map, Source, Value, ValueKind};
+use reqwest::Url;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::env;
 use std::error::Error;
 use std::path::PathBuf;
 use std::sync::Arc;
 
-use mithril_common::entities::{HexEncodedGenesisVerificationKey, ProtocolParameters};
-use mithril_common::store::adapter::SQLiteAdapter;
+use mithril_common::crypto_helper::{ProtocolGenesisPolicy, ProtocolGenesisVerifier};
+use mithril_common::entities::{
+    CompressionAlgorithm, HexEncodedGenesisVerificationKey, ProtocolParameters, SignedEntityKind,
+    SignedEntityKindError,
+};
+use mithril_common::store::adapter::{SQLiteAdapter, SQLiteConnection};
+use mithril_common::store::{RetentionPolicy, RetentionPolicyConfiguration};
 use mithril_common::CardanoNetwork;
 
 use crate::snapshot_stores::LocalSnapshotStore;
-use crate::tools::GcpFileUploader;
+use crate::snapshot_stores::SNAPSHOTS_MANIFEST_FILENAME;
+use crate::tools::{
+    format_public_url, GcpFileUploader, RemoteFileUploader, S3Credentials, S3FileUploader,
+    GCP_DEFAULT_BUCKET, S3_MIN_MULTIPART_PART_SIZE,
+};
 use crate::{
-    LocalSnapshotUploader, RemoteSnapshotStore, RemoteSnapshotUploader, SnapshotStore,
-    SnapshotUploader,
+    GzipSnapshotter, LocalSnapshotUploader, RemoteSnapshotStore, RemoteSnapshotUploader,
+    SnapshotStore, SnapshotUploader, Snapshotter, ZstandardSnapshotter,
 };
 
 // TODO: 'LIST_SNAPSHOTS_MAX_ITEMS' keep as const or in config, or add a parameter to `list_snapshots`?
 const LIST_SNAPSHOTS_MAX_ITEMS: usize = 20;
 const SQLITE_FILE: &str = "aggregator.sqlite3";
+const ADMIN_AUDIT_LOG_FILE: &str = "admin_audit.log";
 
 /// Aggregator configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -40,8 +53,9 @@ pub struct Configuration {
     /// Protocol parameters
     pub protocol_parameters: ProtocolParameters,
 
-    /// Snapshots manifest location
-    pub url_snapshot_manifest: String,
+    /// Snapshots manifest location, it must be the location where the snapshots manifest is
+    /// uploaded in the bucket of the remote storage. Derived from this bucket if not set
+    pub url_snapshot_manifest: Option<String>,
 
     /// Type of snapshot store to use
     pub snapshot_store_type: SnapshotStoreType,
@@ -49,6 +63,61 @@ pub struct Configuration {
     /// Type of snapshot uploader to use
     pub snapshot_uploader_type: SnapshotUploaderType,
 
+    /// Bucket of the Google Cloud Storage, `cardano-testnet` if not set
+    pub gcp_bucket: Option<String>,
+
+    /// Prefix of the names of the objects uploaded to the Google Cloud Storage bucket
+    pub gcp_prefix: Option<String>,
+
+    /// Template of the public URL of the objects uploaded to the Google Cloud Storage bucket,
+    /// where `{bucket}` and `{key}` are replaced by the bucket and the name of the object.
+    /// The objects are addressed on `https://storage.googleapis.com` if not set
+    pub gcp_public_url_template: Option<String>,
+
+    /// Access control policy of the objects uploaded to the Google Cloud Storage bucket,
+    /// `public_read` if not set
+    pub gcp_acl_policy: Option<GcpAclPolicy>,
+
+    /// Endpoint of the S3 compatible object storage, ie: `https://s3.eu-west-1.amazonaws.com`
+    pub s3_endpoint: Option<String>,
+
+    /// Bucket of the S3 compatible object storage
+    pub s3_bucket: Option<String>,
+
+    /// Prefix of the keys of the objects uploaded to the S3 bucket
+    pub s3_prefix: Option<String>,
+
+    /// Region of the S3 compatible object storage, `us-east-1` if not set
+    pub s3_region: Option<String>,
+
+    /// Template of the public URL of the objects uploaded to the S3 bucket, where `{bucket}`
+    /// and `{key}` are replaced by the bucket and the key of the object. The objects are
+    /// addressed on the S3 endpoint if not set
+    pub s3_public_url_template: Option<String>,
+
+    /// Size in Bytes of the parts of the multipart uploads to the S3 bucket, 8 MiB if not set
+    pub s3_multipart_part_size: Option<u64>,
+
+    /// Compression algorithm of the snapshot archives
+    pub snapshot_compression_algorithm: CompressionAlgorithm,
+
+    /// Compression level of the snapshot archives, the default level of the compression
+    /// algorithm if not set
+    pub snapshot_compression_level: Option<i32>,
+
+    /// Number of threads used to compress the snapshot archives, only the zstandard
+    /// compression algorithm supports more than one thread
+    pub snapshot_compression_threads: Option<u32>,
+
+    /// Maximum number of incremental snapshots, that only contain the immutable files added
+    /// since the previous snapshot, created after a full snapshot. Only full snapshots are
+    /// created if not set
+    pub snapshot_max_increments: Option<u64>,
+
+    /// Size in Bytes of the content addressed chunks the snapshot archives are split into,
+    /// the snapshot archives are uploaded as a single file if not set
+    pub snapshot_chunk_size: Option<u64>,
+
     /// Server listening IP
     pub server_ip: String,
 
@@ -67,14 +136,50 @@ pub struct Configuration {
     /// Directory to store aggregator data (Certificates, Snapshots, Protocol Parameters, ...)
     pub data_stores_directory: PathBuf,
 
-    /// Genesis verification key
+    /// Genesis verification key, or hex encoded genesis policy
     pub genesis_verification_key: HexEncodedGenesisVerificationKey,
 
+    /// Genesis policy file, a t-of-n policy that overrides the genesis verification key
+    pub genesis_policy_path: Option<PathBuf>,
+
     /// Max number of records in stores.
     /// When new records are added, oldest records are automatically deleted so
     /// there can always be at max the number of records specified by this
     /// setting.
     pub store_retention_limit: Option<usize>,
+
+    /// Retention policy of the verification key store, overrides `store_retention_limit`.
+    pub verification_key_store_retention: Option<RetentionPolicyConfiguration>,
+
+    /// Retention policy of the stake store, overrides `store_retention_limit`.
+    pub stake_store_retention: Option<RetentionPolicyConfiguration>,
+
+    /// Retention policy of the single signature store, overrides `store_retention_limit`.
+    pub single_signature_store_retention: Option<RetentionPolicyConfiguration>,
+
+    /// Retention policy of the protocol parameters store, overrides `store_retention_limit`.
+    pub protocol_parameters_store_retention: Option<RetentionPolicyConfiguration>,
+
+    /// Retention policy of the certificate store, certificates are all kept if not set.
+    /// Certificates referenced by another certificate or by a snapshot are never pruned.
+    pub certificate_store_retention: Option<RetentionPolicyConfiguration>,
+
+    /// Retention policy of the signer statistics store, overrides `store_retention_limit`.
+    pub signer_statistics_store_retention: Option<RetentionPolicyConfiguration>,
+
+    /// Retention policy of the failed round store, overrides `store_retention_limit`.
+    pub failed_round_store_retention: Option<RetentionPolicyConfiguration>,
+
+    /// Comma separated list of the kinds of entities to certify in addition to the full
+    /// Cardano immutable files, ie: `MithrilStakeDistribution`
+    pub signed_entity_types: Option<String>,
+
+    /// Bearer token required to call the admin API, the admin API is disabled if not set
+    pub admin_token: Option<AdminToken>,
+
+    /// Time after which a pending certificate that did not reach its quorum expires, in
+    /// seconds, pending certificates never expire if not set
+    pub pending_certificate_timeout: Option<u64>,
 }
 
 /// Snapshot store type enumerates the different kinds of snapshot stores.
@@ -85,6 +190,8 @@ pub struct Configuration {
 pub enum SnapshotStoreType {
     /// Google storage.
     Gcp,
+    /// S3 compatible object storage.
+    S3,
     /// Local hard drive storage.
     Local,
 }
@@ -95,10 +202,56 @@ pub enum SnapshotStoreType {
 pub enum SnapshotUploaderType {
     /// Uploader to GCP storage.
     Gcp,
+    /// Uploader to an S3 compatible object storage.
+    S3,
     /// Uploader to local storage.
     Local,
 }
 
+/// Access control policy of the objects uploaded to Google Cloud Storage.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GcpAclPolicy {
+    /// The uploaded objects are given public read access.
+    PublicRead,
+    /// The access of the uploaded objects is the one granted by the bucket, required by the
+    /// buckets with uniform bucket-level access.
+    BucketPolicy,
+}
+
+/// Bearer token required to call the admin API.
+///
+/// It is (de)serialized as a plain string, its [Debug] output is redacted so the
+/// token is not written to the logs along with the configuration.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AdminToken(String);
+
+impl AdminToken {
+    /// AdminToken factory
+    pub fn new(token: &str) -> Self {
+        Self(token.to_string())
+    }
+
+    /// Check if the given token is this one, in a time that doesn't depend on the
+    /// position of their first difference
+    pub fn matches(&self, token: &str) -> bool {
+        self.0.len() == token.len()
+            && self
+                .0
+                .bytes()
+                .zip(token.bytes())
+                .fold(0, |difference, (a, b)| difference | (a ^ b))
+                == 0
+    }
+}
+
+impl std::fmt::Debug for AdminToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "AdminToken(<redacted>)")
+    }
+}
+
 impl Configuration {
     /// Build the server URL from configuration.
     pub fn get_server_url(&self) -> String {
@@ -106,16 +259,23 @@ impl Configuration {
     }
 
     /// Create a snapshot store from the configuration settings.
-    pub fn build_snapshot_store(&self) -> Result<Arc<dyn SnapshotStore>, Box<dyn Error>> {
+    ///
+    /// A local snapshot store uses the given SQLite connection.
+    pub fn build_snapshot_store(
+        &self,
+        sqlite_connection: SQLiteConnection,
+    ) -> Result<Arc<dyn SnapshotStore>, Box<dyn Error>> {
         match self.snapshot_store_type {
-            SnapshotStoreType::Gcp => Ok(Arc::new(RemoteSnapshotStore::new(
-                Box::new(GcpFileUploader::default()),
-                self.url_snapshot_manifest.clone(),
-            ))),
+            SnapshotStoreType::Gcp => Ok(Arc::new(
+                self.build_remote_snapshot_store(Box::new(self.build_gcp_file_uploader()?))?,
+            )),
+            SnapshotStoreType::S3 => Ok(Arc::new(self.build_remote_snapshot_store(Box::new(
+                self.build_s3_file_uploader(read_s3_credentials()?)?,
+            ))?)),
             SnapshotStoreType::Local => Ok(Arc::new(LocalSnapshotStore::new(
-                Box::new(SQLiteAdapter::new(
+                Box::new(SQLiteAdapter::with_connection(
                     "snapshot",
-                    Some(self.get_sqlite_file()),
+                    sqlite_connection,
                 )?),
                 LIST_SNAPSHOTS_MAX_ITEMS,
             ))),
@@ -123,15 +283,217 @@ impl Configuration {
     }
 
     /// Create a snapshot uploader from configuration settings.
-    pub fn build_snapshot_uploader(&self) -> Arc<dyn SnapshotUploader> {
+    pub fn build_snapshot_uploader(&self) -> Result<Arc<dyn SnapshotUploader>, ConfigError> {
         match self.snapshot_uploader_type {
-            SnapshotUploaderType::Gcp => Arc::new(RemoteSnapshotUploader::new(Box::new(
-                GcpFileUploader::default(),
-            ))),
-            SnapshotUploaderType::Local => Arc::new(LocalSnapshotUploader::new(
+            SnapshotUploaderType::Gcp => {
+                let file_uploader = self.build_gcp_file_uploader()?;
+                self.get_url_snapshot_manifest(&file_uploader)?;
+
+                Ok(Arc::new(RemoteSnapshotUploader::new(Box::new(
+                    file_uploader,
+                ))))
+            }
+            SnapshotUploaderType::S3 => {
+                let file_uploader = self.build_s3_file_uploader(read_s3_credentials()?)?;
+                self.get_url_snapshot_manifest(&file_uploader)?;
+
+                Ok(Arc::new(RemoteSnapshotUploader::new(Box::new(
+                    file_uploader,
+                ))))
+            }
+            SnapshotUploaderType::Local => Ok(Arc::new(LocalSnapshotUploader::new(
                 self.get_server_url(),
                 &self.snapshot_directory,
+            ))),
+        }
+    }
+
+    /// Create a remote snapshot store that uploads its manifest with the given file uploader.
+    ///
+    /// The manifest is read from the location where the file uploader uploads it.
+    fn build_remote_snapshot_store(
+        &self,
+        file_uploader: Box<dyn RemoteFileUploader>,
+    ) -> Result<RemoteSnapshotStore, ConfigError> {
+        let url_manifest = self.get_url_snapshot_manifest(file_uploader.as_ref())?;
+
+        Ok(RemoteSnapshotStore::new(file_uploader, url_manifest))
+    }
+
+    /// Return the location where the given file uploader uploads the snapshots manifest, an
+    /// explicit `url_snapshot_manifest` must be this location.
+    fn get_url_snapshot_manifest(
+        &self,
+        file_uploader: &dyn RemoteFileUploader,
+    ) -> Result<String, ConfigError> {
+        let location = file_uploader.get_location(SNAPSHOTS_MANIFEST_FILENAME);
+        match &self.url_snapshot_manifest {
+            Some(url_snapshot_manifest) if url_snapshot_manifest != &location => {
+                Err(ConfigError::Message(format!(
+                    "url_snapshot_manifest must be the location where the snapshots manifest is uploaded, ie: {}, got {}",
+                    location, url_snapshot_manifest
+                )))
+            }
+            _ => Ok(location),
+        }
+    }
+
+    /// Create a Google Cloud Platform file uploader from the GCP settings.
+    pub fn build_gcp_file_uploader(&self) -> Result<GcpFileUploader, ConfigError> {
+        let bucket = self.gcp_bucket.as_deref().unwrap_or(GCP_DEFAULT_BUCKET);
+        let is_valid_bucket_char = |c: char| {
+            c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_' || c == '.'
+        };
+        if !(3..=222).contains(&bucket.len())
+            || !bucket.chars().all(is_valid_bucket_char)
+            || !bucket.starts_with(|c: char| c.is_ascii_alphanumeric())
+            || !bucket.ends_with(|c: char| c.is_ascii_alphanumeric())
+        {
+            return Err(ConfigError::Message(format!(
+                "gcp_bucket must be a valid Google Cloud Storage bucket name, got {}",
+                bucket
+            )));
+        }
+        let mut file_uploader = GcpFileUploader::new(bucket);
+        if let Some(prefix) = &self.gcp_prefix {
+            file_uploader = file_uploader.with_prefix(prefix);
+        }
+        if let Some(public_url_template) = &self.gcp_public_url_template {
+            check_public_url_template("gcp_public_url_template", public_url_template)?;
+            file_uploader = file_uploader.with_public_url_template(public_url_template);
+        }
+        if let Some(acl_policy) = self.gcp_acl_policy {
+            file_uploader = file_uploader.with_public_read(acl_policy == GcpAclPolicy::PublicRead);
+        }
+
+        Ok(file_uploader)
+    }
+
+    /// Create an S3 file uploader from the S3 settings that signs its requests with the given
+    /// credentials.
+    pub fn build_s3_file_uploader(
+        &self,
+        credentials: S3Credentials,
+    ) -> Result<S3FileUploader, ConfigError> {
+        let endpoint = self.s3_endpoint.as_ref().ok_or_else(|| {
+            ConfigError::Message("s3_endpoint is required by the S3 storage".to_string())
+        })?;
+        let bucket = self.s3_bucket.as_ref().ok_or_else(|| {
+            ConfigError::Message("s3_bucket is required by the S3 storage".to_string())
+        })?;
+        let mut file_uploader = S3FileUploader::new(
+            endpoint,
+            bucket,
+            self.s3_region.as_deref().unwrap_or("us-east-1"),
+            credentials,
+        );
+        if let Some(prefix) = &self.s3_prefix {
+            file_uploader = file_uploader.with_prefix(prefix);
+        }
+        if let Some(public_url_template) = &self.s3_public_url_template {
+            check_public_url_template("s3_public_url_template", public_url_template)?;
+            file_uploader = file_uploader.with_public_url_template(public_url_template);
+        }
+        if let Some(part_size) = self.s3_multipart_part_size {
+            if part_size < S3_MIN_MULTIPART_PART_SIZE {
+                return Err(ConfigError::Message(format!(
+                    "s3_multipart_part_size must be at least {} Bytes, got {}",
+                    S3_MIN_MULTIPART_PART_SIZE, part_size
+                )));
+            }
+            file_uploader = file_uploader.with_multipart_part_size(part_size);
+        }
+
+        Ok(file_uploader)
+    }
+
+    /// Create a snapshotter from the compression settings, its archives are written in the
+    /// given ongoing snapshot directory.
+    pub fn build_snapshotter(
+        &self,
+        ongoing_snapshot_directory: PathBuf,
+    ) -> Result<Arc<dyn Snapshotter>, ConfigError> {
+        let threads = self.snapshot_compression_threads.unwrap_or(1);
+        if threads == 0 {
+            return Err(ConfigError::Message(
+                "snapshot_compression_threads must be at least 1".to_string(),
+            ));
+        }
+
+        match self.snapshot_compression_algorithm {
+            CompressionAlgorithm::Gzip => {
+                if threads > 1 {
+                    return Err(ConfigError::Message(format!(
+                        "snapshot_compression_threads must be 1 with the gzip compression algorithm, got {}",
+                        threads
+                    )));
+                }
+                let mut snapshotter =
+                    GzipSnapshotter::new(self.db_directory.clone(), ongoing_snapshot_directory);
+                if let Some(level) = self.snapshot_compression_level {
+                    if !(0..=9).contains(&level) {
+                        return Err(ConfigError::Message(format!(
+                            "snapshot_compression_level must be between 0 and 9 with the gzip compression algorithm, got {}",
+                            level
+                        )));
+                    }
+                    snapshotter = snapshotter.with_compression_level(level as u32);
+                }
+
+                Ok(Arc::new(snapshotter))
+            }
+            CompressionAlgorithm::Zstandard => {
+                let mut snapshotter = ZstandardSnapshotter::new(
+                    self.db_directory.clone(),
+                    ongoing_snapshot_directory,
+                )
+                .with_threads(threads);
+                if let Some(level) = self.snapshot_compression_level {
+                    let levels = zstd::compression_level_range();
+                    if !levels.contains(&level) {
+                        return Err(ConfigError::Message(format!(
+                            "snapshot_compression_level must be between {} and {} with the zstandard compression algorithm, got {}",
+                            levels.start(),
+                            levels.end(),
+                            level
+                        )));
+                    }
+                    snapshotter = snapshotter.with_compression_level(level);
+                }
+
+                Ok(Arc::new(snapshotter))
+            }
+        }
+    }
+
+    /// Check configuration and return the maximum number of incremental snapshots created
+    /// after a full snapshot.
+    ///
+    /// The remote snapshot stores only keep the latest snapshot, the base snapshots of an
+    /// incremental snapshot would not be found: incremental snapshots require a local
+    /// snapshot store.
+    pub fn get_snapshot_max_increments(&self) -> Result<u64, ConfigError> {
+        match (self.snapshot_max_increments, &self.snapshot_store_type) {
+            (Some(max_increments), SnapshotStoreType::Gcp | SnapshotStoreType::S3)
+                if max_increments > 0 =>
+            {
+                Err(ConfigError::Message(format!(
+                    "snapshot_max_increments requires a local snapshot store, the {:?} snapshot store only keeps the latest snapshot",
+                    self.snapshot_store_type
+                )))
+            }
+            (max_increments, _) => Ok(max_increments.unwrap_or(0)),
+        }
+    }
+
+    /// Check configuration and return the size of the chunks the snapshot archives are split
+    /// into, if any.
+    pub fn get_snapshot_chunk_size(&self) -> Result<Option<u64>, ConfigError> {
+        match self.snapshot_chunk_size {
+            Some(0) => Err(ConfigError::Message(
+                "snapshot_chunk_size must be greater than 0".to_string(),
             )),
+            chunk_size => Ok(chunk_size),
         }
     }
 
@@ -151,6 +513,46 @@ impl Configuration {
 
         self.data_stores_directory.join(SQLITE_FILE)
     }
+
+    /// Return the audit log file of the admin API, stored next to the SQLite stores.
+    pub fn get_admin_audit_log_file(&self) -> PathBuf {
+        self.data_stores_directory.join(ADMIN_AUDIT_LOG_FILE)
+    }
+
+    /// Return the retention policy of a store from its own configuration if
+    /// set, else from the `store_retention_limit`.
+    pub fn get_store_retention_policy<K, R>(
+        &self,
+        store_retention: &Option<RetentionPolicyConfiguration>,
+    ) -> RetentionPolicy<K, R> {
+        RetentionPolicyConfiguration::build_policy(store_retention, self.store_retention_limit)
+    }
+
+    /// Create the genesis verifier from the genesis policy file if set, else from the
+    /// genesis verification key.
+    pub fn build_genesis_verifier(&self) -> Result<ProtocolGenesisVerifier, Box<dyn Error>> {
+        build_genesis_verifier(&self.genesis_verification_key, &self.genesis_policy_path)
+    }
+
+    /// Return the kinds of entities to certify, the full Cardano immutable files are always
+    /// certified.
+    pub fn list_signed_entity_kinds(&self) -> Result<BTreeSet<SignedEntityKind>, ConfigError> {
+        let mut signed_entity_kinds = BTreeSet::from([SignedEntityKind::CardanoImmutableFilesFull]);
+
+        if let Some(signed_entity_types) = &self.signed_entity_types {
+            for kind in signed_entity_types
+                .split(',')
+                .filter(|kind| !kind.trim().is_empty())
+            {
+                signed_entity_kinds.insert(
+                    kind.parse()
+                        .map_err(|e: SignedEntityKindError| ConfigError::Message(e.to_string()))?,
+                );
+            }
+        }
+
+        Ok(signed_entity_kinds)
+    }
 }
 
 /// Configuration expected for Genesis commands.
@@ -183,11 +585,26 @@ pub struct GenesisConfiguration {
     /// Genesis verification key
     pub genesis_verification_key: String,
 
+    /// Genesis policy file, a t-of-n policy that overrides the genesis verification key
+    pub genesis_policy_path: Option<PathBuf>,
+
     /// Max number of records in stores.
     /// When new records are added, oldest records are automatically deleted so
     /// there can always be at max the number of records specified by this
     /// setting.
     pub store_retention_limit: Option<usize>,
+
+    /// Retention policy of the verification key store, overrides `store_retention_limit`.
+    pub verification_key_store_retention: Option<RetentionPolicyConfiguration>,
+
+    /// Retention policy of the stake store, overrides `store_retention_limit`.
+    pub stake_store_retention: Option<RetentionPolicyConfiguration>,
+
+    /// Retention policy of the single signature store, overrides `store_retention_limit`.
+    pub single_signature_store_retention: Option<RetentionPolicyConfiguration>,
+
+    /// Retention policy of the protocol parameters store, overrides `store_retention_limit`.
+    pub protocol_parameters_store_retention: Option<RetentionPolicyConfiguration>,
 }
 
 impl GenesisConfiguration {
@@ -207,6 +624,66 @@ impl GenesisConfiguration {
 
         self.data_stores_directory.join(SQLITE_FILE)
     }
+
+    /// Return the retention policy of a store from its own configuration if
+    /// set, else from the `store_retention_limit`.
+    pub fn get_store_retention_policy<K, R>(
+        &self,
+        store_retention: &Option<RetentionPolicyConfiguration>,
+    ) -> RetentionPolicy<K, R> {
+        RetentionPolicyConfiguration::build_policy(store_retention, self.store_retention_limit)
+    }
+
+    /// Create the genesis verifier from the genesis policy file if set, else from the
+    /// genesis verification key.
+    pub fn build_genesis_verifier(&self) -> Result<ProtocolGenesisVerifier, Box<dyn Error>> {
+        build_genesis_verifier(&self.genesis_verification_key, &self.genesis_policy_path)
+    }
+}
+
+fn build_genesis_verifier(
+    genesis_verification_key: &HexEncodedGenesisVerificationKey,
+    genesis_policy_path: &Option<PathBuf>,
+) -> Result<ProtocolGenesisVerifier, Box<dyn Error>> {
+    match genesis_policy_path {
+        Some(genesis_policy_path) => Ok(ProtocolGenesisVerifier::from_policy(
+            &ProtocolGenesisPolicy::from_file(genesis_policy_path)?,
+        )?),
+        None => Ok(ProtocolGenesisVerifier::from_hex(genesis_verification_key)?),
+    }
+}
+
+/// Read the credentials of the S3 storage from the `AWS_ACCESS_KEY_ID` and
+/// `AWS_SECRET_ACCESS_KEY` environment variables.
+fn read_s3_credentials() -> Result<S3Credentials, ConfigError> {
+    let read_credential = |name: &str| {
+        env::var(name).map_err(|_| {
+            ConfigError::Message(format!(
+                "Missing {} environment variable required by the S3 storage",
+                name
+            ))
+        })
+    };
+
+    Ok(S3Credentials::new(
+        read_credential("AWS_ACCESS_KEY_ID")?,
+        read_credential("AWS_SECRET_ACCESS_KEY")?,
+    ))
+}
+
+/// Check that a template of the public URL of the uploaded objects is an URL that contains
+/// the `{key}` placeholder, see [crate::tools::format_public_url].
+fn check_public_url_template(name: &str, public_url_template: &str) -> Result<(), ConfigError> {
+    if !public_url_template.contains("{key}")
+        || Url::parse(&format_public_url(public_url_template, "bucket", "key")).is_err()
+    {
+        return Err(ConfigError::Message(format!(
+            "{} must be an URL that contains the {{key}} placeholder, got {}",
+            name, public_url_template
+        )));
+    }
+
+    Ok(())
 }
 
 /// Default configuration with all the default values for configurations.
@@ -229,6 +706,9 @@ pub struct DefaultConfiguration {
 
     /// Type of snapshot uploader to use
     pub snapshot_uploader_type: String,
+
+    /// Compression algorithm of the snapshot archives
+    pub snapshot_compression_algorithm: String,
 }
 
 impl Default for DefaultConfiguration {
@@ -240,6 +720,7 @@ impl Default for DefaultConfiguration {
             snapshot_directory: ".".to_string(),
             snapshot_store_type: "local".to_string(),
             snapshot_uploader_type: "gcp".to_string(),
+            snapshot_compression_algorithm: "gzip".to_string(),
         }
     }
 }
@@ -283,7 +764,275 @@ impl Source for DefaultConfiguration {
                 ValueKind::from(myself.snapshot_uploader_type),
             ),
         );
+        result.insert(
+            "snapshot_compression_algorithm".to_string(),
+            Value::new(
+                Some(&namespace),
+                ValueKind::from(myself.snapshot_compression_algorithm),
+            ),
+        );
 
         Ok(result)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::initialize_dependencies;
+
+    async fn sample_configuration() -> Configuration {
+        let (dependency_manager, _) = initialize_dependencies().await;
+
+        dependency_manager.config
+    }
+
+    #[tokio::test]
+    async fn gcp_bucket_must_be_a_valid_bucket_name() {
+        let config = sample_configuration().await;
+        for bucket in ["cardano-preview", "bucket_1.example", "abc"] {
+            Configuration {
+                gcp_bucket: Some(bucket.to_string()),
+                ..config.clone()
+            }
+            .build_gcp_file_uploader()
+            .unwrap_or_else(|e| panic!("{} should be a valid bucket name: {}", bucket, e));
+        }
+        for bucket in [
+            "ab",
+            "Cardano-Preview",
+            "-bucket",
+            "bucket-",
+            "bucket/name",
+            "",
+        ] {
+            assert!(
+                Configuration {
+                    gcp_bucket: Some(bucket.to_string()),
+                    ..config.clone()
+                }
+                .build_gcp_file_uploader()
+                .is_err(),
+                "{} should not be a valid bucket name",
+                bucket
+            );
+        }
+    }
+
+    #[test]
+    fn public_url_template_must_be_an_url_with_a_key_placeholder() {
+        for template in [
+            "https://cdn.example.com/{key}",
+            "https://storage.googleapis.com/{bucket}/{key}",
+        ] {
+            check_public_url_template("public_url_template", template)
+                .unwrap_or_else(|_| panic!("{} should be a valid template", template));
+        }
+        for template in [
+            "https://cdn.example.com/{bucket}",
+            "cdn.example.com/{key}",
+            "",
+        ] {
+            assert!(
+                check_public_url_template("public_url_template", template).is_err(),
+                "{} should not be a valid template",
+                template
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn incremental_snapshots_require_a_local_snapshot_store() {
+        let config = sample_configuration().await;
+        for (store_type, max_increments) in [
+            (SnapshotStoreType::Local, Some(10)),
+            (SnapshotStoreType::Gcp, None),
+            (SnapshotStoreType::Gcp, Some(0)),
+            (SnapshotStoreType::S3, None),
+        ] {
+            Configuration {
+                snapshot_store_type: store_type.clone(),
+                snapshot_max_increments: max_increments,
+                ..config.clone()
+            }
+            .get_snapshot_max_increments()
+            .unwrap_or_else(|e| {
+                panic!(
+                    "{:?} snapshot store with {:?} increments should be valid: {}",
+                    store_type, max_increments, e
+                )
+            });
+        }
+        for store_type in [SnapshotStoreType::Gcp, SnapshotStoreType::S3] {
+            assert!(
+                Configuration {
+                    snapshot_store_type: store_type.clone(),
+                    snapshot_max_increments: Some(10),
+                    ..config.clone()
+                }
+                .get_snapshot_max_increments()
+                .is_err(),
+                "{:?} snapshot store with increments should not be valid",
+                store_type
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn compression_settings_are_checked() {
+        let config = sample_configuration().await;
+        for (algorithm, level, threads) in [
+            (CompressionAlgorithm::Gzip, None, None),
+            (CompressionAlgorithm::Gzip, Some(9), Some(1)),
+            (CompressionAlgorithm::Zstandard, Some(19), Some(4)),
+        ] {
+            Configuration {
+                snapshot_compression_algorithm: algorithm,
+                snapshot_compression_level: level,
+                snapshot_compression_threads: threads,
+                ..config.clone()
+            }
+            .build_snapshotter(PathBuf::new())
+            .unwrap_or_else(|e| {
+                panic!(
+                    "{:?} with level {:?} and {:?} threads should be valid: {}",
+                    algorithm, level, threads, e
+                )
+            });
+        }
+        for (algorithm, level, threads) in [
+            (CompressionAlgorithm::Gzip, Some(10), None),
+            (CompressionAlgorithm::Gzip, None, Some(2)),
+            (CompressionAlgorithm::Gzip, None, Some(0)),
+            (CompressionAlgorithm::Zstandard, Some(100), None),
+            (CompressionAlgorithm::Zstandard, None, Some(0)),
+        ] {
+            assert!(
+                Configuration {
+                    snapshot_compression_algorithm: algorithm,
+                    snapshot_compression_level: level,
+                    snapshot_compression_threads: threads,
+                    ..config.clone()
+                }
+                .build_snapshotter(PathBuf::new())
+                .is_err(),
+                "{:?} with level {:?} and {:?} threads should be invalid",
+                algorithm,
+                level,
+                threads
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn s3_settings_are_checked() {
+        let config = Configuration {
+            s3_endpoint: Some("http://localhost:9000".to_string()),
+            s3_bucket: Some("bucket".to_string()),
+            ..sample_configuration().await
+        };
+        let credentials =
+            S3Credentials::new("access-key-id".to_string(), "secret-access-key".to_string());
+        assert!(
+            config.build_s3_file_uploader(credentials.clone()).is_ok(),
+            "the S3 settings should be valid"
+        );
+
+        for (invalid_config, reason) in [
+            (
+                Configuration {
+                    s3_endpoint: None,
+                    ..config.clone()
+                },
+                "no endpoint",
+            ),
+            (
+                Configuration {
+                    s3_bucket: None,
+                    ..config.clone()
+                },
+                "no bucket",
+            ),
+            (
+                Configuration {
+                    s3_multipart_part_size: Some(S3_MIN_MULTIPART_PART_SIZE - 1),
+                    ..config.clone()
+                },
+                "a multipart part size too small",
+            ),
+            (
+                Configuration {
+                    s3_public_url_template: Some("https://cdn.example.com/".to_string()),
+                    ..config.clone()
+                },
+                "a public URL template without key",
+            ),
+        ] {
+            assert!(
+                invalid_config
+                    .build_s3_file_uploader(credentials.clone())
+                    .is_err(),
+                "the S3 settings with {} should be invalid",
+                reason
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn url_snapshot_manifest_must_be_the_upload_location() {
+        let config = Configuration {
+            gcp_bucket: Some("cardano-preview".to_string()),
+            ..sample_configuration().await
+        };
+        let file_uploader = config.build_gcp_file_uploader().unwrap();
+        let location = "https://storage.googleapis.com/cardano-preview/snapshots.json";
+
+        for url_snapshot_manifest in [None, Some(location.to_string())] {
+            assert_eq!(
+                location,
+                Configuration {
+                    url_snapshot_manifest,
+                    ..config.clone()
+                }
+                .get_url_snapshot_manifest(&file_uploader)
+                .unwrap()
+            );
+        }
+        assert!(
+            Configuration {
+                snapshot_uploader_type: SnapshotUploaderType::Gcp,
+                gcp_bucket: None,
+                url_snapshot_manifest: Some(location.to_string()),
+                ..config.clone()
+            }
+            .build_snapshot_uploader()
+            .is_err(),
+            "the snapshots manifest of the default bucket is not the one of cardano-preview"
+        );
+    }
+
+    #[test]
+    fn admin_token_matches_only_the_same_token() {
+        let admin_token = AdminToken::new("admin-token");
+
+        assert!(admin_token.matches("admin-token"));
+        assert!(!admin_token.matches("admin-tokem"));
+        assert!(!admin_token.matches("admin-token-longer"));
+        assert!(!admin_token.matches(""));
+    }
+
+    #[test]
+    fn admin_token_is_redacted_from_debug_output() {
+        let admin_token = AdminToken::new("admin-token");
+
+        assert!(!format!("{:?}", admin_token).contains("admin-token"));
+        assert!(!format!("{:?}", Some(admin_token)).contains("admin-token"));
+    }
+
+    #[test]
+    fn admin_token_is_deserialized_from_a_string() {
+        let admin_token: AdminToken = serde_json::from_str(r#""admin-token""#).unwrap();
+
+        assert_eq!(AdminToken::new("admin-token"), admin_token);
+    }
+}
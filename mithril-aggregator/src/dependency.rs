@@ -8,7 +8,7 @@ use mithril_common::digesters::{ImmutableDigester, ImmutableFileObserver};
 use mithril_common::entities::{
     Epoch, ProtocolParameters, Signer, SignerWithStake, StakeDistribution,
 };
-use mithril_common::store::{StakeStore, StakeStorer};
+use mithril_common::store::{StakeStore, StakeStorer, TransactionManager};
 use mithril_common::BeaconProvider;
 
 use crate::configuration::*;
@@ -16,13 +16,18 @@ use crate::multi_signer::MultiSigner;
 use crate::snapshot_stores::SnapshotStore;
 use crate::snapshot_uploaders::SnapshotUploader;
 use crate::{
-    CertificatePendingStore, CertificateStore, ProtocolParametersStore, ProtocolParametersStorer,
-    SingleSignatureStore, Snapshotter, VerificationKeyStore, VerificationKeyStorer,
+    AuditLog, CertificateBundleCache, CertificatePendingStore, CertificateStore, EventBus,
+    FailedRoundStore, MetricsService, ProtocolParametersStore, ProtocolParametersStorer,
+    RuntimeControl, RuntimeStateStore, RuntimeStatus, SignerStatisticsStore, SingleSignatureStore,
+    Snapshotter, VerificationKeyStore, VerificationKeyStorer,
 };
 
 /// MultiSignerWrapper wraps a MultiSigner
 pub type MultiSignerWrapper = Arc<RwLock<dyn MultiSigner>>;
 
+/// RuntimeStatusWrapper wraps the RuntimeStatus shared by the runtime
+pub type RuntimeStatusWrapper = Arc<RwLock<RuntimeStatus>>;
+
 /// DependencyManager handles the dependencies
 pub struct DependencyManager {
     /// Configuration structure.
@@ -40,6 +45,15 @@ pub struct DependencyManager {
     /// Certificate pending store.
     pub certificate_pending_store: Arc<CertificatePendingStore>,
 
+    /// Runtime state store.
+    pub runtime_state_store: Arc<RuntimeStateStore>,
+
+    /// Failed round store.
+    pub failed_round_store: Arc<FailedRoundStore>,
+
+    /// Signer statistics store.
+    pub signer_statistics_store: Arc<SignerStatisticsStore>,
+
     /// Certificate store.
     pub certificate_store: Arc<CertificateStore>,
 
@@ -55,6 +69,9 @@ pub struct DependencyManager {
     /// Protocol parameter store.
     pub protocol_parameters_store: Arc<ProtocolParametersStore>,
 
+    /// Transaction manager of the stores.
+    pub transaction_manager: Arc<dyn TransactionManager>,
+
     /// Chain observer service.
     pub chain_observer: Arc<dyn ChainObserver>,
 
@@ -75,6 +92,24 @@ pub struct DependencyManager {
 
     /// Genesis signature verifier service.
     pub genesis_verifier: Arc<ProtocolGenesisVerifier>,
+
+    /// Metrics service.
+    pub metrics_service: Arc<MetricsService>,
+
+    /// Status of the runtime.
+    pub runtime_status: RuntimeStatusWrapper,
+
+    /// Bus of the lifecycle events of the aggregator.
+    pub event_bus: Arc<EventBus>,
+
+    /// Operations requested to the runtime.
+    pub runtime_control: Arc<RuntimeControl>,
+
+    /// Audit log of the admin API.
+    pub audit_log: Arc<AuditLog>,
+
+    /// Cache of the certificate chain bundles.
+    pub certificate_bundle_cache: Arc<CertificateBundleCache>,
 }
 
 #[doc(hidden)]
@@ -162,18 +197,21 @@ impl DependencyManager {
 #[cfg(test)]
 pub mod tests {
     use crate::{
-        AggregatorConfig, CertificatePendingStore, CertificateStore, Configuration,
-        DependencyManager, DumbSnapshotUploader, DumbSnapshotter, LocalSnapshotStore,
-        MultiSignerImpl, ProtocolParametersStore, SingleSignatureStore, SnapshotStoreType,
-        SnapshotUploaderType, VerificationKeyStore,
+        AggregatorConfig, AuditLog, CertificateBundleCache, CertificatePendingStore,
+        CertificateStore, Configuration, DependencyManager, DumbSnapshotUploader, DumbSnapshotter,
+        EventBus, FailedRoundStore, LocalSnapshotStore, MetricsService, MultiSignerImpl,
+        ProtocolParametersStore, RuntimeControl, RuntimeStateStore, RuntimeStatus,
+        SignerStatisticsStore, SingleSignatureStore, SnapshotStoreType, SnapshotUploaderType,
+        VerificationKeyStore,
     };
     use mithril_common::certificate_chain::MithrilCertificateVerifier;
-    use mithril_common::crypto_helper::{key_encode_hex, ProtocolGenesisSigner};
+    use mithril_common::crypto_helper::ProtocolGenesisSigner;
     use mithril_common::digesters::{DumbImmutableDigester, DumbImmutableFileObserver};
     use mithril_common::{
         chain_observer::FakeObserver,
+        entities::CompressionAlgorithm,
         fake_data,
-        store::{adapter::MemoryAdapter, StakeStore},
+        store::{adapter::MemoryAdapter, DumbTransactionManager, RetentionPolicy, StakeStore},
         BeaconProviderImpl, CardanoNetwork,
     };
     use std::{path::PathBuf, sync::Arc};
@@ -182,25 +220,49 @@ pub mod tests {
     pub async fn initialize_dependencies() -> (DependencyManager, AggregatorConfig) {
         let genesis_signer = ProtocolGenesisSigner::create_deterministic_genesis_signer();
         let genesis_verifier = Arc::new(genesis_signer.create_genesis_verifier());
-        let genesis_verification_key = genesis_verifier.to_verification_key();
         let config = Configuration {
             cardano_cli_path: PathBuf::new(),
             cardano_node_socket_path: PathBuf::new(),
             network_magic: Some(42),
             network: "whatever".to_string(),
             protocol_parameters: fake_data::protocol_parameters(),
-            url_snapshot_manifest: "https://storage.googleapis.com/cardano-testnet/snapshots.json"
-                .to_string(),
+            url_snapshot_manifest: None,
             snapshot_store_type: SnapshotStoreType::Local,
             snapshot_uploader_type: SnapshotUploaderType::Local,
+            gcp_bucket: None,
+            gcp_prefix: None,
+            gcp_public_url_template: None,
+            gcp_acl_policy: None,
+            s3_endpoint: None,
+            s3_bucket: None,
+            s3_prefix: None,
+            s3_region: None,
+            s3_public_url_template: None,
+            s3_multipart_part_size: None,
+            snapshot_compression_algorithm: CompressionAlgorithm::Gzip,
+            snapshot_compression_level: None,
+            snapshot_compression_threads: None,
+            snapshot_max_increments: None,
+            snapshot_chunk_size: None,
             server_ip: "0.0.0.0".to_string(),
             server_port: 8000,
             run_interval: 5000,
             db_directory: PathBuf::new(),
             snapshot_directory: PathBuf::new(),
             data_stores_directory: PathBuf::new(),
-            genesis_verification_key: key_encode_hex(&genesis_verification_key).unwrap(),
+            genesis_verification_key: genesis_verifier.to_hex().unwrap(),
+            genesis_policy_path: None,
             store_retention_limit: None,
+            verification_key_store_retention: None,
+            stake_store_retention: None,
+            single_signature_store_retention: None,
+            protocol_parameters_store_retention: None,
+            certificate_store_retention: None,
+            signer_statistics_store_retention: None,
+            failed_round_store_retention: None,
+            signed_entity_types: None,
+            admin_token: None,
+            pending_certificate_timeout: None,
         };
         let snapshot_store = Arc::new(LocalSnapshotStore::new(
             Box::new(MemoryAdapter::new(None).unwrap()),
@@ -210,24 +272,35 @@ pub mod tests {
         let certificate_pending_store = Arc::new(CertificatePendingStore::new(Box::new(
             MemoryAdapter::new(None).unwrap(),
         )));
+        let runtime_state_store = Arc::new(RuntimeStateStore::new(Box::new(
+            MemoryAdapter::new(None).unwrap(),
+        )));
+        let failed_round_store = Arc::new(FailedRoundStore::new(
+            Box::new(MemoryAdapter::new(None).unwrap()),
+            config.store_retention_limit.into(),
+        ));
+        let signer_statistics_store = Arc::new(SignerStatisticsStore::new(
+            Box::new(MemoryAdapter::new(None).unwrap()),
+            config.store_retention_limit.into(),
+        ));
         let certificate_store = Arc::new(CertificateStore::new(Box::new(
             MemoryAdapter::new(None).unwrap(),
         )));
         let verification_key_store = Arc::new(VerificationKeyStore::new(
             Box::new(MemoryAdapter::new(None).unwrap()),
-            config.store_retention_limit,
+            config.store_retention_limit.into(),
         ));
         let stake_store = Arc::new(StakeStore::new(
             Box::new(MemoryAdapter::new(None).unwrap()),
-            config.store_retention_limit,
+            config.store_retention_limit.into(),
         ));
         let single_signature_store = Arc::new(SingleSignatureStore::new(
             Box::new(MemoryAdapter::new(None).unwrap()),
-            config.store_retention_limit,
+            config.store_retention_limit.into(),
         ));
         let protocol_parameters_store = Arc::new(ProtocolParametersStore::new(
             Box::new(MemoryAdapter::new(None).unwrap()),
-            None,
+            RetentionPolicy::KeepAll,
         ));
         let chain_observer = Arc::new(FakeObserver::default());
         let multi_signer = MultiSignerImpl::new(
@@ -236,7 +309,8 @@ pub mod tests {
             single_signature_store.clone(),
             protocol_parameters_store.clone(),
             chain_observer,
-        );
+        )
+        .with_signer_statistics_store(signer_statistics_store.clone());
         let multi_signer = Arc::new(RwLock::new(multi_signer));
         let immutable_file_observer = Arc::new(DumbImmutableFileObserver::default());
         let chain_observer = Arc::new(FakeObserver::default());
@@ -252,11 +326,15 @@ pub mod tests {
             snapshot_uploader,
             multi_signer,
             certificate_pending_store,
+            runtime_state_store,
+            failed_round_store,
+            signer_statistics_store,
             certificate_store,
             verification_key_store,
             stake_store,
             single_signature_store,
             protocol_parameters_store,
+            transaction_manager: Arc::new(DumbTransactionManager::new()),
             chain_observer,
             beacon_provider,
             immutable_file_observer,
@@ -264,6 +342,14 @@ pub mod tests {
             snapshotter: Arc::new(DumbSnapshotter::new()),
             certificate_verifier,
             genesis_verifier,
+            metrics_service: Arc::new(MetricsService::new().unwrap()),
+            runtime_status: Arc::new(RwLock::new(RuntimeStatus::default())),
+            event_bus: Arc::new(EventBus::default()),
+            runtime_control: Arc::new(RuntimeControl::new()),
+            audit_log: Arc::new(AuditLog::new(
+                std::env::temp_dir().join("mithril_aggregator_test_admin_audit.log"),
+            )),
+            certificate_bundle_cache: Arc::new(CertificateBundleCache::default()),
         };
 
         let config = AggregatorConfig::new(
@@ -0,0 +1,57 @@
+use mithril_common::entities::AggregatorEvent;
+use slog_scope::trace;
+use tokio::sync::broadcast::{self, Receiver, Sender};
+
+/// Number of events kept for the subscribers that are lagging behind
+const DEFAULT_EVENT_BUS_CAPACITY: usize = 1024;
+
+/// Bus that broadcasts the lifecycle events of the aggregator to its subscribers
+pub struct EventBus {
+    sender: Sender<AggregatorEvent>,
+}
+
+impl EventBus {
+    /// EventBus factory
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+
+        Self { sender }
+    }
+
+    /// Publish an event to the current subscribers, the event is dropped if there is none
+    pub fn publish(&self, event: AggregatorEvent) {
+        trace!("EVENT BUS: publish"; "event" => event.name());
+        let _ = self.sender.send(event);
+    }
+
+    /// Subscribe to the events published from now on
+    pub fn subscribe(&self) -> Receiver<AggregatorEvent> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new(DEFAULT_EVENT_BUS_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mithril_common::entities::Epoch;
+
+    #[tokio::test]
+    async fn test_subscribers_receive_events_published_after_subscription() {
+        let event_bus = EventBus::default();
+        event_bus.publish(AggregatorEvent::EpochChanged { epoch: Epoch(1) });
+        let mut receiver = event_bus.subscribe();
+        event_bus.publish(AggregatorEvent::EpochChanged { epoch: Epoch(2) });
+
+        assert_eq!(
+            AggregatorEvent::EpochChanged { epoch: Epoch(2) },
+            receiver.recv().await.unwrap()
+        );
+        assert!(receiver.try_recv().is_err());
+    }
+}
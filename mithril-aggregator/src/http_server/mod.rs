@@ -1,4 +1,4 @@
 mod routes;
 mod server;
 
-pub use server::{Server, SERVER_BASE_PATH};
+pub use server::{Server, ADMIN_BASE_PATH, SERVER_BASE_PATH};
@@ -0,0 +1,701 @@
+use crate::http_server::routes::middlewares;
+use crate::{AuditLog, Configuration, DependencyManager};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use warp::Filter;
+
+pub fn routes(
+    dependency_manager: Arc<DependencyManager>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    pause_signing(dependency_manager.clone())
+        .or(resume_signing(dependency_manager.clone()))
+        .or(new_pending_certificate(dependency_manager.clone()))
+        .or(run_cycle(dependency_manager.clone()))
+        .or(retry_snapshot_upload(dependency_manager.clone()))
+        .or(prune_stores(dependency_manager))
+        .recover(handlers::recover_unauthorized)
+}
+
+/// Filter that only lets through the requests bearing the admin token, it extracts the
+/// address of the requester for the audit log
+fn with_admin_authorization(
+    dependency_manager: Arc<DependencyManager>,
+    action: &'static str,
+) -> impl Filter<Extract = (Option<SocketAddr>,), Error = warp::Rejection> + Clone {
+    warp::header::optional::<String>("authorization")
+        .and(warp::addr::remote())
+        .and(middlewares::with_config(dependency_manager.clone()))
+        .and(middlewares::with_audit_log(dependency_manager))
+        .and_then(
+            move |authorization: Option<String>,
+                  remote_address: Option<SocketAddr>,
+                  config: Configuration,
+                  audit_log: Arc<AuditLog>| async move {
+                handlers::authorize(action, authorization, remote_address, config, audit_log).await
+            },
+        )
+}
+
+/// POST /runtime/pause-signing
+fn pause_signing(
+    dependency_manager: Arc<DependencyManager>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("runtime" / "pause-signing")
+        .and(warp::post())
+        .and(with_admin_authorization(
+            dependency_manager.clone(),
+            "pause_signing",
+        ))
+        .and(middlewares::with_runtime_control(
+            dependency_manager.clone(),
+        ))
+        .and(middlewares::with_audit_log(dependency_manager))
+        .and_then(handlers::pause_signing)
+}
+
+/// POST /runtime/resume-signing
+fn resume_signing(
+    dependency_manager: Arc<DependencyManager>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("runtime" / "resume-signing")
+        .and(warp::post())
+        .and(with_admin_authorization(
+            dependency_manager.clone(),
+            "resume_signing",
+        ))
+        .and(middlewares::with_runtime_control(
+            dependency_manager.clone(),
+        ))
+        .and(middlewares::with_audit_log(dependency_manager))
+        .and_then(handlers::resume_signing)
+}
+
+/// POST /runtime/new-pending-certificate
+fn new_pending_certificate(
+    dependency_manager: Arc<DependencyManager>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("runtime" / "new-pending-certificate")
+        .and(warp::post())
+        .and(with_admin_authorization(
+            dependency_manager.clone(),
+            "new_pending_certificate",
+        ))
+        .and(middlewares::with_runtime_control(
+            dependency_manager.clone(),
+        ))
+        .and(middlewares::with_certificate_pending_store(
+            dependency_manager.clone(),
+        ))
+        .and(middlewares::with_audit_log(dependency_manager))
+        .and_then(handlers::new_pending_certificate)
+}
+
+/// POST /runtime/cycle
+fn run_cycle(
+    dependency_manager: Arc<DependencyManager>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("runtime" / "cycle")
+        .and(warp::post())
+        .and(with_admin_authorization(
+            dependency_manager.clone(),
+            "run_cycle",
+        ))
+        .and(middlewares::with_runtime_control(
+            dependency_manager.clone(),
+        ))
+        .and(middlewares::with_audit_log(dependency_manager))
+        .and_then(handlers::run_cycle)
+}
+
+/// POST /snapshots/retry-upload
+fn retry_snapshot_upload(
+    dependency_manager: Arc<DependencyManager>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("snapshots" / "retry-upload")
+        .and(warp::post())
+        .and(with_admin_authorization(
+            dependency_manager.clone(),
+            "retry_snapshot_upload",
+        ))
+        .and(middlewares::with_runtime_control(
+            dependency_manager.clone(),
+        ))
+        .and(middlewares::with_certificate_pending_store(
+            dependency_manager.clone(),
+        ))
+        .and(middlewares::with_audit_log(dependency_manager))
+        .and_then(handlers::retry_snapshot_upload)
+}
+
+/// POST /stores/prune
+fn prune_stores(
+    dependency_manager: Arc<DependencyManager>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("stores" / "prune")
+        .and(warp::post())
+        .and(with_admin_authorization(
+            dependency_manager.clone(),
+            "prune_stores",
+        ))
+        .and(warp::any().map(move || dependency_manager.clone()))
+        .and_then(handlers::prune_stores)
+}
+
+mod handlers {
+    use crate::http_server::routes::reply;
+    use crate::{
+        AuditLog, AuditLogEntry, AuditLogError, CertificatePendingStore, Configuration,
+        DependencyManager, RuntimeControl,
+    };
+    use mithril_common::store::{PruneReport, StoreError, StorePruner};
+    use serde::Serialize;
+    use slog_scope::{debug, error, warn};
+    use std::convert::Infallible;
+    use std::net::SocketAddr;
+    use std::sync::Arc;
+    use warp::http::StatusCode;
+
+    /// Rejection of the requests that don't bear the admin token
+    #[derive(Debug)]
+    pub struct AdminUnauthorized;
+
+    impl warp::reject::Reject for AdminUnauthorized {}
+
+    /// Number of records pruned from a store
+    #[derive(Debug, Serialize)]
+    pub struct StorePruneSummary {
+        /// Name of the store
+        pub store: String,
+
+        /// Number of records deleted
+        pub pruned_records: usize,
+
+        /// Number of records selected by the retention policy but kept because other
+        /// records reference them
+        pub protected_records: usize,
+    }
+
+    impl StorePruneSummary {
+        fn new<K>(store: &str, report: PruneReport<K>) -> Self {
+            Self {
+                store: store.to_string(),
+                pruned_records: report.pruned_keys.len(),
+                protected_records: report.protected_keys.len(),
+            }
+        }
+    }
+
+    /// Record the entry of an authorized request in the audit log, the requested action must
+    /// not be performed if it could not be recorded
+    async fn audit(
+        audit_log: &AuditLog,
+        action: &str,
+        remote_address: Option<SocketAddr>,
+        outcome: &str,
+    ) -> Result<(), AuditLogError> {
+        let entry = AuditLogEntry::new(
+            action,
+            remote_address.map(|address| address.to_string()),
+            true,
+            outcome,
+        );
+
+        audit_log.record(&entry).await.map_err(|err| {
+            error!("admin::audit_log::error"; "error" => ?err, "entry" => ?entry);
+            err
+        })
+    }
+
+    /// Reply to the requests that could not be recorded in the audit log
+    fn audit_failure(err: AuditLogError) -> Box<dyn warp::Reply> {
+        reply::internal_server_error(format!("could not record the audit entry: {}", err))
+    }
+
+    /// Authorize the requests bearing the admin token, the admin API is not found if no
+    /// token is configured
+    pub async fn authorize(
+        action: &str,
+        authorization: Option<String>,
+        remote_address: Option<SocketAddr>,
+        config: Configuration,
+        audit_log: Arc<AuditLog>,
+    ) -> Result<Option<SocketAddr>, warp::Rejection> {
+        let admin_token = match config.admin_token {
+            Some(admin_token) => admin_token,
+            None => return Err(warp::reject::not_found()),
+        };
+        let is_authorized = authorization
+            .as_deref()
+            .and_then(|authorization| authorization.strip_prefix("Bearer "))
+            .map(|token| admin_token.matches(token))
+            .unwrap_or(false);
+
+        if is_authorized {
+            return Ok(remote_address);
+        }
+
+        let entry = AuditLogEntry::new(
+            action,
+            remote_address.map(|address| address.to_string()),
+            false,
+            "unauthorized",
+        );
+        match audit_log.record_unauthorized(&entry).await {
+            Ok(true) => {
+                warn!("admin::unauthorized"; "action" => action, "remote_address" => ?remote_address);
+            }
+            Ok(false) => {}
+            Err(err) => {
+                error!("admin::audit_log::error"; "error" => ?err, "entry" => ?entry);
+            }
+        }
+
+        Err(warp::reject::custom(AdminUnauthorized))
+    }
+
+    /// Reply to the unauthorized requests, other rejections are left to the other routes
+    pub async fn recover_unauthorized(
+        rejection: warp::Rejection,
+    ) -> Result<Box<dyn warp::Reply>, warp::Rejection> {
+        if rejection.find::<AdminUnauthorized>().is_some() {
+            Ok(reply::unauthorized(
+                "unauthorized".to_string(),
+                "a valid admin bearer token is required".to_string(),
+            ))
+        } else {
+            Err(rejection)
+        }
+    }
+
+    /// Pause signing
+    pub async fn pause_signing(
+        remote_address: Option<SocketAddr>,
+        runtime_control: Arc<RuntimeControl>,
+        audit_log: Arc<AuditLog>,
+    ) -> Result<impl warp::Reply, Infallible> {
+        debug!("⇄ HTTP SERVER: admin/pause_signing");
+
+        if let Err(err) = audit(&audit_log, "pause_signing", remote_address, "success").await {
+            return Ok(audit_failure(err));
+        }
+        runtime_control.pause_signing();
+
+        Ok(reply::empty(StatusCode::NO_CONTENT))
+    }
+
+    /// Resume signing
+    pub async fn resume_signing(
+        remote_address: Option<SocketAddr>,
+        runtime_control: Arc<RuntimeControl>,
+        audit_log: Arc<AuditLog>,
+    ) -> Result<impl warp::Reply, Infallible> {
+        debug!("⇄ HTTP SERVER: admin/resume_signing");
+
+        if let Err(err) = audit(&audit_log, "resume_signing", remote_address, "success").await {
+            return Ok(audit_failure(err));
+        }
+        runtime_control.resume_signing();
+
+        Ok(reply::empty(StatusCode::NO_CONTENT))
+    }
+
+    /// New pending certificate
+    pub async fn new_pending_certificate(
+        remote_address: Option<SocketAddr>,
+        runtime_control: Arc<RuntimeControl>,
+        certificate_pending_store: Arc<CertificatePendingStore>,
+        audit_log: Arc<AuditLog>,
+    ) -> Result<impl warp::Reply, Infallible> {
+        debug!("⇄ HTTP SERVER: admin/new_pending_certificate");
+        let action = "new_pending_certificate";
+
+        match certificate_pending_store.get().await {
+            Ok(Some(_)) => {
+                if let Err(err) = audit(&audit_log, action, remote_address, "success").await {
+                    return Ok(audit_failure(err));
+                }
+                runtime_control.request_new_pending_certificate();
+                Ok(reply::empty(StatusCode::ACCEPTED))
+            }
+            Ok(None) => {
+                if let Err(err) =
+                    audit(&audit_log, action, remote_address, "no pending certificate").await
+                {
+                    return Ok(audit_failure(err));
+                }
+                Ok(reply::conflict(
+                    "no_pending_certificate".to_string(),
+                    "there is no pending certificate to replace".to_string(),
+                ))
+            }
+            Err(err) => {
+                warn!("admin::new_pending_certificate::error"; "error" => ?err);
+                if let Err(err) = audit(&audit_log, action, remote_address, &err.to_string()).await
+                {
+                    return Ok(audit_failure(err));
+                }
+                Ok(reply::internal_server_error(err.to_string()))
+            }
+        }
+    }
+
+    /// Run a cycle of the runtime now
+    pub async fn run_cycle(
+        remote_address: Option<SocketAddr>,
+        runtime_control: Arc<RuntimeControl>,
+        audit_log: Arc<AuditLog>,
+    ) -> Result<impl warp::Reply, Infallible> {
+        debug!("⇄ HTTP SERVER: admin/run_cycle");
+
+        if let Err(err) = audit(&audit_log, "run_cycle", remote_address, "success").await {
+            return Ok(audit_failure(err));
+        }
+        runtime_control.wake_up();
+
+        Ok(reply::empty(StatusCode::ACCEPTED))
+    }
+
+    /// Retry the upload of the snapshot of the pending certificate now, if it failed
+    pub async fn retry_snapshot_upload(
+        remote_address: Option<SocketAddr>,
+        runtime_control: Arc<RuntimeControl>,
+        certificate_pending_store: Arc<CertificatePendingStore>,
+        audit_log: Arc<AuditLog>,
+    ) -> Result<impl warp::Reply, Infallible> {
+        debug!("⇄ HTTP SERVER: admin/retry_snapshot_upload");
+        let action = "retry_snapshot_upload";
+
+        match certificate_pending_store.get().await {
+            Ok(Some(certificate_pending))
+                if runtime_control.get_failed_snapshot_upload().as_ref()
+                    == Some(&certificate_pending.beacon) =>
+            {
+                if let Err(err) = audit(&audit_log, action, remote_address, "success").await {
+                    return Ok(audit_failure(err));
+                }
+                runtime_control.wake_up();
+                Ok(reply::empty(StatusCode::ACCEPTED))
+            }
+            Ok(_) => {
+                if let Err(err) = audit(
+                    &audit_log,
+                    action,
+                    remote_address,
+                    "no failed snapshot upload",
+                )
+                .await
+                {
+                    return Ok(audit_failure(err));
+                }
+                Ok(reply::conflict(
+                    "no_failed_snapshot_upload".to_string(),
+                    "the snapshot of the pending certificate has no failed upload to retry"
+                        .to_string(),
+                ))
+            }
+            Err(err) => {
+                warn!("admin::retry_snapshot_upload::error"; "error" => ?err);
+                if let Err(err) = audit(&audit_log, action, remote_address, &err.to_string()).await
+                {
+                    return Ok(audit_failure(err));
+                }
+                Ok(reply::internal_server_error(err.to_string()))
+            }
+        }
+    }
+
+    async fn prune_all_stores(
+        dependency_manager: &DependencyManager,
+    ) -> Result<Vec<StorePruneSummary>, StoreError> {
+        Ok(vec![
+            StorePruneSummary::new(
+                "certificate",
+                dependency_manager.certificate_store.prune().await?,
+            ),
+            StorePruneSummary::new(
+                "verification_key",
+                dependency_manager.verification_key_store.prune().await?,
+            ),
+            StorePruneSummary::new("stake", dependency_manager.stake_store.prune().await?),
+            StorePruneSummary::new(
+                "single_signature",
+                dependency_manager.single_signature_store.prune().await?,
+            ),
+            StorePruneSummary::new(
+                "protocol_parameters",
+                dependency_manager.protocol_parameters_store.prune().await?,
+            ),
+            StorePruneSummary::new(
+                "signer_statistics",
+                dependency_manager.signer_statistics_store.prune().await?,
+            ),
+            StorePruneSummary::new(
+                "failed_round",
+                dependency_manager.failed_round_store.prune().await?,
+            ),
+        ])
+    }
+
+    /// Prune stores
+    pub async fn prune_stores(
+        remote_address: Option<SocketAddr>,
+        dependency_manager: Arc<DependencyManager>,
+    ) -> Result<impl warp::Reply, Infallible> {
+        debug!("⇄ HTTP SERVER: admin/prune_stores");
+        let audit_log = dependency_manager.audit_log.clone();
+
+        // The outcome of the pruning is only known once it is done: the request is recorded
+        // first, the outcome is then recorded on a best effort basis.
+        if let Err(err) = audit(&audit_log, "prune_stores", remote_address, "requested").await {
+            return Ok(audit_failure(err));
+        }
+
+        match prune_all_stores(&dependency_manager).await {
+            Ok(summaries) => {
+                let outcome = summaries
+                    .iter()
+                    .map(|summary| format!("{}: {} pruned", summary.store, summary.pruned_records))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let _ = audit(&audit_log, "prune_stores", remote_address, &outcome).await;
+                Ok(reply::json(&summaries, StatusCode::OK))
+            }
+            Err(err) => {
+                warn!("admin::prune_stores::error"; "error" => ?err);
+                let _ = audit(&audit_log, "prune_stores", remote_address, &err.to_string()).await;
+                Ok(reply::internal_server_error(err.to_string()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::http_server::ADMIN_BASE_PATH;
+    use crate::{initialize_dependencies, AdminToken};
+    use mithril_common::fake_data;
+    use serde_json::Value;
+    use warp::http::{Method, StatusCode};
+    use warp::test::request;
+
+    use super::*;
+
+    const ADMIN_TOKEN: &str = "admin-token";
+
+    fn setup_router(
+        dependency_manager: Arc<DependencyManager>,
+    ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+        warp::any()
+            .and(warp::path(ADMIN_BASE_PATH))
+            .and(routes(dependency_manager))
+    }
+
+    async fn setup_dependencies(
+        admin_token: Option<&str>,
+    ) -> (DependencyManager, tempfile::TempDir) {
+        let (mut dependency_manager, _) = initialize_dependencies().await;
+        let audit_log_dir = tempfile::tempdir().unwrap();
+        dependency_manager.config.admin_token = admin_token.map(AdminToken::new);
+        dependency_manager.audit_log =
+            Arc::new(AuditLog::new(audit_log_dir.path().join("audit.log")));
+
+        (dependency_manager, audit_log_dir)
+    }
+
+    #[tokio::test]
+    async fn test_admin_routes_not_found_without_admin_token() {
+        let (dependency_manager, _audit_log_dir) = setup_dependencies(None).await;
+
+        let response = request()
+            .method(Method::POST.as_str())
+            .path(&format!("/{}/runtime/pause-signing", ADMIN_BASE_PATH))
+            .header("authorization", format!("Bearer {}", ADMIN_TOKEN))
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        assert_eq!(StatusCode::NOT_FOUND, response.status());
+    }
+
+    #[tokio::test]
+    async fn test_admin_routes_unauthorized_with_wrong_token() {
+        let (dependency_manager, _audit_log_dir) = setup_dependencies(Some(ADMIN_TOKEN)).await;
+        let dependency_manager = Arc::new(dependency_manager);
+
+        let response = request()
+            .method(Method::POST.as_str())
+            .path(&format!("/{}/runtime/pause-signing", ADMIN_BASE_PATH))
+            .header("authorization", "Bearer wrong-token")
+            .reply(&setup_router(dependency_manager.clone()))
+            .await;
+
+        assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+        assert!(!dependency_manager.runtime_control.is_signing_paused());
+        let entries = dependency_manager.audit_log.read_entries().await.unwrap();
+        assert_eq!(1, entries.len());
+        assert_eq!("pause_signing", entries[0].action);
+        assert!(!entries[0].authorized);
+    }
+
+    #[tokio::test]
+    async fn test_pause_and_resume_signing() {
+        let (dependency_manager, _audit_log_dir) = setup_dependencies(Some(ADMIN_TOKEN)).await;
+        let dependency_manager = Arc::new(dependency_manager);
+
+        let response = request()
+            .method(Method::POST.as_str())
+            .path(&format!("/{}/runtime/pause-signing", ADMIN_BASE_PATH))
+            .header("authorization", format!("Bearer {}", ADMIN_TOKEN))
+            .reply(&setup_router(dependency_manager.clone()))
+            .await;
+
+        assert_eq!(StatusCode::NO_CONTENT, response.status());
+        assert!(dependency_manager.runtime_control.is_signing_paused());
+
+        let response = request()
+            .method(Method::POST.as_str())
+            .path(&format!("/{}/runtime/resume-signing", ADMIN_BASE_PATH))
+            .header("authorization", format!("Bearer {}", ADMIN_TOKEN))
+            .reply(&setup_router(dependency_manager.clone()))
+            .await;
+
+        assert_eq!(StatusCode::NO_CONTENT, response.status());
+        assert!(!dependency_manager.runtime_control.is_signing_paused());
+        let actions = dependency_manager
+            .audit_log
+            .read_entries()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|entry| (entry.action, entry.authorized))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("pause_signing".to_string(), true),
+                ("resume_signing".to_string(), true)
+            ],
+            actions
+        );
+    }
+
+    #[tokio::test]
+    async fn test_new_pending_certificate() {
+        let (dependency_manager, _audit_log_dir) = setup_dependencies(Some(ADMIN_TOKEN)).await;
+        let dependency_manager = Arc::new(dependency_manager);
+        let path = format!("/{}/runtime/new-pending-certificate", ADMIN_BASE_PATH);
+
+        let response = request()
+            .method(Method::POST.as_str())
+            .path(&path)
+            .header("authorization", format!("Bearer {}", ADMIN_TOKEN))
+            .reply(&setup_router(dependency_manager.clone()))
+            .await;
+
+        assert_eq!(StatusCode::CONFLICT, response.status());
+
+        dependency_manager
+            .certificate_pending_store
+            .save(fake_data::certificate_pending())
+            .await
+            .unwrap();
+        let response = request()
+            .method(Method::POST.as_str())
+            .path(&path)
+            .header("authorization", format!("Bearer {}", ADMIN_TOKEN))
+            .reply(&setup_router(dependency_manager.clone()))
+            .await;
+
+        assert_eq!(StatusCode::ACCEPTED, response.status());
+        assert!(dependency_manager
+            .runtime_control
+            .take_new_pending_certificate_request());
+    }
+
+    #[tokio::test]
+    async fn test_retry_snapshot_upload() {
+        let (dependency_manager, _audit_log_dir) = setup_dependencies(Some(ADMIN_TOKEN)).await;
+        let dependency_manager = Arc::new(dependency_manager);
+        let path = format!("/{}/snapshots/retry-upload", ADMIN_BASE_PATH);
+        let certificate_pending = fake_data::certificate_pending();
+        dependency_manager
+            .certificate_pending_store
+            .save(certificate_pending.clone())
+            .await
+            .unwrap();
+
+        let response = request()
+            .method(Method::POST.as_str())
+            .path(&path)
+            .header("authorization", format!("Bearer {}", ADMIN_TOKEN))
+            .reply(&setup_router(dependency_manager.clone()))
+            .await;
+
+        assert_eq!(StatusCode::CONFLICT, response.status());
+
+        dependency_manager
+            .runtime_control
+            .record_failed_snapshot_upload(certificate_pending.beacon);
+        let response = request()
+            .method(Method::POST.as_str())
+            .path(&path)
+            .header("authorization", format!("Bearer {}", ADMIN_TOKEN))
+            .reply(&setup_router(dependency_manager.clone()))
+            .await;
+
+        assert_eq!(StatusCode::ACCEPTED, response.status());
+        let outcomes = dependency_manager
+            .audit_log
+            .read_entries()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.outcome)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                "no failed snapshot upload".to_string(),
+                "success".to_string()
+            ],
+            outcomes
+        );
+    }
+
+    #[tokio::test]
+    async fn test_prune_stores() {
+        let (dependency_manager, _audit_log_dir) = setup_dependencies(Some(ADMIN_TOKEN)).await;
+        let dependency_manager = Arc::new(dependency_manager);
+
+        let response = request()
+            .method(Method::POST.as_str())
+            .path(&format!("/{}/stores/prune", ADMIN_BASE_PATH))
+            .header("authorization", format!("Bearer {}", ADMIN_TOKEN))
+            .reply(&setup_router(dependency_manager.clone()))
+            .await;
+
+        assert_eq!(StatusCode::OK, response.status());
+        let summaries: Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(7, summaries.as_array().unwrap().len());
+        assert_eq!("certificate", summaries[0]["store"]);
+        let entries = dependency_manager.audit_log.read_entries().await.unwrap();
+        assert_eq!(2, entries.len());
+        assert_eq!("prune_stores", entries[0].action);
+        assert_eq!("requested", entries[0].outcome);
+    }
+
+    #[tokio::test]
+    async fn test_action_not_performed_when_it_can_not_be_audited() {
+        let (mut dependency_manager, audit_log_dir) = setup_dependencies(Some(ADMIN_TOKEN)).await;
+        // The audit log can not be written in place of a directory
+        dependency_manager.audit_log = Arc::new(AuditLog::new(audit_log_dir.path().to_path_buf()));
+        let dependency_manager = Arc::new(dependency_manager);
+
+        let response = request()
+            .method(Method::POST.as_str())
+            .path(&format!("/{}/runtime/pause-signing", ADMIN_BASE_PATH))
+            .header("authorization", format!("Bearer {}", ADMIN_TOKEN))
+            .reply(&setup_router(dependency_manager.clone()))
+            .await;
+
+        assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, response.status());
+        assert!(!dependency_manager.runtime_control.is_signing_paused());
+    }
+}
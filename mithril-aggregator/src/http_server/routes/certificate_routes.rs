@@ -1,13 +1,74 @@
 use crate::http_server::routes::middlewares;
-use crate::DependencyManager;
+use crate::{CertificateFilter, DependencyManager};
+use mithril_common::entities::{Epoch, ImmutableFileNumber, PartyId};
+use serde::Deserialize;
 use std::sync::Arc;
 use warp::Filter;
 
+/// Query parameters of the certificate chain bundle route
+#[derive(Debug, Deserialize)]
+pub struct CertificateBundleQuery {
+    /// Hash of a certificate trusted by the client, the chain stops there
+    checkpoint: Option<String>,
+}
+
+/// Default number of certificates of a page of the certificates route
+const DEFAULT_CERTIFICATES_LIMIT: usize = 20;
+
+/// Maximum number of certificates of a page of the certificates route
+const MAX_CERTIFICATES_LIMIT: usize = 100;
+
+/// Query parameters of the certificates route
+#[derive(Debug, Deserialize)]
+pub struct CertificateListQuery {
+    /// Lowest epoch of the certificates, included
+    epoch_from: Option<u64>,
+
+    /// Highest epoch of the certificates, included
+    epoch_to: Option<u64>,
+
+    /// Immutable file number of the certificates
+    immutable_file_number: Option<ImmutableFileNumber>,
+
+    /// Party id of a signer of the certificates
+    signer_party_id: Option<PartyId>,
+
+    /// Number of certificates to skip
+    offset: Option<usize>,
+
+    /// Maximum number of certificates to return
+    limit: Option<usize>,
+}
+
+impl CertificateListQuery {
+    fn to_filter(&self) -> CertificateFilter {
+        CertificateFilter {
+            epoch_from: self.epoch_from.map(Epoch),
+            epoch_to: self.epoch_to.map(Epoch),
+            immutable_file_number: self.immutable_file_number,
+            signer_party_id: self.signer_party_id.clone(),
+        }
+    }
+}
+
 pub fn routes(
     dependency_manager: Arc<DependencyManager>,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
-    certificate_pending(dependency_manager.clone())
-        .or(certificate_certificate_hash(dependency_manager))
+    certificates(dependency_manager.clone())
+        .or(certificate_pending(dependency_manager.clone()))
+        .or(certificate_certificate_hash(dependency_manager.clone()))
+        .or(certificate_certificate_hash_bundle(dependency_manager))
+}
+
+/// GET /certificates
+fn certificates(
+    dependency_manager: Arc<DependencyManager>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("certificates")
+        .and(warp::get())
+        .and(warp::query::<CertificateListQuery>())
+        .and(middlewares::with_certificate_store(dependency_manager))
+        .and_then(handlers::certificates)
 }
 
 /// GET /certificate-pending
@@ -32,14 +93,80 @@ fn certificate_certificate_hash(
         .and_then(handlers::certificate_certificate_hash)
 }
 
+/// GET /certificate/{certificate_hash}/bundle
+fn certificate_certificate_hash_bundle(
+    dependency_manager: Arc<DependencyManager>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("certificate" / String / "bundle")
+        .and(warp::get())
+        .and(warp::query::<CertificateBundleQuery>())
+        .and(middlewares::with_certificate_store(
+            dependency_manager.clone(),
+        ))
+        .and(middlewares::with_genesis_verifier(
+            dependency_manager.clone(),
+        ))
+        .and(middlewares::with_certificate_bundle_cache(
+            dependency_manager,
+        ))
+        .and_then(handlers::certificate_certificate_hash_bundle)
+}
+
 mod handlers {
+    use super::{
+        CertificateBundleQuery, CertificateListQuery, DEFAULT_CERTIFICATES_LIMIT,
+        MAX_CERTIFICATES_LIMIT,
+    };
     use crate::http_server::routes::reply;
-    use crate::{CertificatePendingStore, CertificateStore};
+    use crate::{CertificateBundleCache, CertificatePendingStore, CertificateStore};
+    use mithril_common::certificate_chain::CertificateChainBundle;
+    use mithril_common::crypto_helper::ProtocolGenesisVerifier;
+    use mithril_common::entities::CertificateList;
     use slog_scope::{debug, warn};
     use std::convert::Infallible;
     use std::sync::Arc;
     use warp::http::StatusCode;
 
+    /// Certificates
+    pub async fn certificates(
+        query: CertificateListQuery,
+        certificate_store: Arc<CertificateStore>,
+    ) -> Result<impl warp::Reply, Infallible> {
+        debug!("⇄ HTTP SERVER: certificates/{:?}", query);
+
+        let offset = query.offset.unwrap_or_default();
+        let limit = query.limit.unwrap_or(DEFAULT_CERTIFICATES_LIMIT);
+        if limit == 0 || limit > MAX_CERTIFICATES_LIMIT {
+            warn!("certificates::invalid_limit"; "limit" => limit);
+            return Ok(reply::bad_request(
+                "invalid_limit".to_string(),
+                format!(
+                    "the limit must be between 1 and {}, got {}",
+                    MAX_CERTIFICATES_LIMIT, limit
+                ),
+            ));
+        }
+
+        match certificate_store
+            .search(&query.to_filter(), offset, limit)
+            .await
+        {
+            Ok((certificates, total)) => Ok(reply::json(
+                &CertificateList {
+                    certificates,
+                    total: total as u64,
+                    offset: offset as u64,
+                    limit: limit as u64,
+                },
+                StatusCode::OK,
+            )),
+            Err(err) => {
+                warn!("certificates::error"; "error" => ?err);
+                Ok(reply::internal_server_error(err.to_string()))
+            }
+        }
+    }
+
     /// Certificate Pending
     pub async fn certificate_pending(
         certificate_pending_store: Arc<CertificatePendingStore>,
@@ -75,6 +202,59 @@ mod handlers {
             }
         }
     }
+
+    /// Certificate chain bundle by certificate hash
+    pub async fn certificate_certificate_hash_bundle(
+        certificate_hash: String,
+        query: CertificateBundleQuery,
+        certificate_store: Arc<CertificateStore>,
+        genesis_verifier: Arc<ProtocolGenesisVerifier>,
+        certificate_bundle_cache: Arc<CertificateBundleCache>,
+    ) -> Result<impl warp::Reply, Infallible> {
+        debug!(
+            "⇄ HTTP SERVER: certificate_certificate_hash_bundle/{}",
+            certificate_hash
+        );
+        let checkpoint = query.checkpoint.as_deref();
+
+        if let Some(bundle) = certificate_bundle_cache
+            .get(&certificate_hash, checkpoint)
+            .await
+        {
+            return Ok(reply::json(bundle.as_ref(), StatusCode::OK));
+        }
+
+        match certificate_store.get_from_hash(&certificate_hash).await {
+            Ok(Some(_)) => {}
+            Ok(None) => return Ok(reply::empty(StatusCode::NOT_FOUND)),
+            Err(err) => {
+                warn!("certificate_certificate_hash_bundle::error"; "error" => ?err);
+                return Ok(reply::internal_server_error(err.to_string()));
+            }
+        }
+
+        match CertificateChainBundle::build(
+            &certificate_hash,
+            certificate_store,
+            &genesis_verifier,
+            checkpoint,
+        )
+        .await
+        {
+            Ok(bundle) => {
+                let bundle = Arc::new(bundle);
+                certificate_bundle_cache
+                    .insert(&certificate_hash, checkpoint, bundle.clone())
+                    .await;
+
+                Ok(reply::json(bundle.as_ref(), StatusCode::OK))
+            }
+            Err(err) => {
+                warn!("certificate_certificate_hash_bundle::error"; "error" => ?err);
+                Ok(reply::internal_server_error(err.to_string()))
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -83,10 +263,11 @@ mod tests {
 
     use crate::http_server::SERVER_BASE_PATH;
     use mithril_common::apispec::APISpec;
+    use mithril_common::certificate_chain::CertificateChainBundle;
     use mithril_common::store::adapter::FailStoreAdapter;
     use mithril_common::{entities, fake_data};
     use serde_json::Value::Null;
-    use warp::http::Method;
+    use warp::http::{Method, StatusCode};
     use warp::test::request;
 
     use super::*;
@@ -106,6 +287,96 @@ mod tests {
             .and(routes(dependency_manager).with(cors))
     }
 
+    #[tokio::test]
+    async fn test_certificates_get_ok() {
+        let (dependency_manager, _) = initialize_dependencies().await;
+        for ix in 1..=3 {
+            let mut certificate = fake_data::certificate(format!("certificate-{}", ix));
+            certificate.beacon.immutable_file_number = ix;
+            dependency_manager
+                .certificate_store
+                .save(certificate)
+                .await
+                .expect("certificate store save should have succeeded");
+        }
+
+        let method = Method::GET.as_str();
+        let path = "/certificates";
+
+        let response = request()
+            .method(method)
+            .path(&format!(
+                "/{}{}?immutable_file_number=2&limit=10",
+                SERVER_BASE_PATH, path
+            ))
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        let certificate_list: entities::CertificateList =
+            serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(1, certificate_list.total);
+        assert_eq!(10, certificate_list.limit);
+        assert_eq!("certificate-2", certificate_list.certificates[0].hash);
+
+        APISpec::from_file(API_SPEC_FILE)
+            .method(method)
+            .path(path)
+            .validate_request(&Null)
+            .unwrap()
+            .validate_response(&response)
+            .expect("OpenAPI error");
+    }
+
+    #[tokio::test]
+    async fn test_certificates_get_ko_400() {
+        let (dependency_manager, _) = initialize_dependencies().await;
+
+        let method = Method::GET.as_str();
+        let path = "/certificates";
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{}{}?limit=0", SERVER_BASE_PATH, path))
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+        APISpec::from_file(API_SPEC_FILE)
+            .method(method)
+            .path(path)
+            .validate_request(&Null)
+            .unwrap()
+            .validate_response(&response)
+            .expect("OpenAPI error");
+    }
+
+    #[tokio::test]
+    async fn test_certificates_get_ko_500() {
+        let (mut dependency_manager, _) = initialize_dependencies().await;
+        let certificate_store = CertificateStore::new(Box::new(FailStoreAdapter::<
+            String,
+            entities::Certificate,
+        >::new()));
+        dependency_manager.certificate_store = Arc::new(certificate_store);
+
+        let method = Method::GET.as_str();
+        let path = "/certificates";
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{}{}", SERVER_BASE_PATH, path))
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        APISpec::from_file(API_SPEC_FILE)
+            .method(method)
+            .path(path)
+            .validate_request(&Null)
+            .unwrap()
+            .validate_response(&response)
+            .expect("OpenAPI error");
+    }
+
     #[tokio::test]
     async fn test_certificate_pending_get_ok() {
         let method = Method::GET.as_str();
@@ -245,4 +516,113 @@ mod tests {
             .validate_response(&response)
             .expect("OpenAPI error");
     }
+
+    #[tokio::test]
+    async fn test_certificate_certificate_hash_bundle_get_ok() {
+        let (dependency_manager, _) = initialize_dependencies().await;
+        let mut genesis_certificate = fake_data::certificate("{certificate_hash}".to_string());
+        genesis_certificate.previous_hash = "".to_string();
+        dependency_manager
+            .certificate_store
+            .save(genesis_certificate)
+            .await
+            .expect("certificate store save should have succeeded");
+
+        let method = Method::GET.as_str();
+        let path = "/certificate/{certificate_hash}/bundle";
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{}{}", SERVER_BASE_PATH, path))
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        APISpec::from_file(API_SPEC_FILE)
+            .method(method)
+            .path(path)
+            .validate_request(&Null)
+            .unwrap()
+            .validate_response(&response)
+            .expect("OpenAPI error");
+    }
+
+    #[tokio::test]
+    async fn test_certificate_certificate_hash_bundle_get_ok_from_cache() {
+        let (dependency_manager, _) = initialize_dependencies().await;
+        let mut genesis_certificate = fake_data::certificate("{certificate_hash}".to_string());
+        genesis_certificate.previous_hash = "".to_string();
+        let bundle = Arc::new(CertificateChainBundle::new(
+            "fingerprint".to_string(),
+            vec![genesis_certificate],
+        ));
+        dependency_manager
+            .certificate_bundle_cache
+            .insert("{certificate_hash}", None, bundle.clone())
+            .await;
+
+        let method = Method::GET.as_str();
+        let path = "/certificate/{certificate_hash}/bundle";
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{}{}", SERVER_BASE_PATH, path))
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        assert_eq!(StatusCode::OK, response.status());
+        assert_eq!(
+            bundle.as_ref(),
+            &serde_json::from_slice::<CertificateChainBundle>(response.body()).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_certificate_certificate_hash_bundle_get_ok_404() {
+        let (dependency_manager, _) = initialize_dependencies().await;
+
+        let method = Method::GET.as_str();
+        let path = "/certificate/{certificate_hash}/bundle";
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{}{}", SERVER_BASE_PATH, path))
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        APISpec::from_file(API_SPEC_FILE)
+            .method(method)
+            .path(path)
+            .validate_request(&Null)
+            .unwrap()
+            .validate_response(&response)
+            .expect("OpenAPI error");
+    }
+
+    #[tokio::test]
+    async fn test_certificate_certificate_hash_bundle_get_ko_missing_previous_certificate() {
+        let (dependency_manager, _) = initialize_dependencies().await;
+        dependency_manager
+            .certificate_store
+            .save(fake_data::certificate("{certificate_hash}".to_string()))
+            .await
+            .expect("certificate store save should have succeeded");
+
+        let method = Method::GET.as_str();
+        let path = "/certificate/{certificate_hash}/bundle";
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{}{}", SERVER_BASE_PATH, path))
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, response.status());
+        APISpec::from_file(API_SPEC_FILE)
+            .method(method)
+            .path(path)
+            .validate_request(&Null)
+            .unwrap()
+            .validate_response(&response)
+            .expect("OpenAPI error");
+    }
 }
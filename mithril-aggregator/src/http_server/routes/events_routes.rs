@@ -0,0 +1,106 @@
+use crate::http_server::routes::middlewares;
+use crate::DependencyManager;
+use std::sync::Arc;
+use warp::Filter;
+
+pub fn routes(
+    dependency_manager: Arc<DependencyManager>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    events(dependency_manager)
+}
+
+/// GET /events
+fn events(
+    dependency_manager: Arc<DependencyManager>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("events")
+        .and(warp::get())
+        .and(middlewares::with_event_bus(dependency_manager))
+        .and_then(handlers::events)
+}
+
+mod handlers {
+    use crate::EventBus;
+    use mithril_common::entities::AggregatorEvent;
+    use slog_scope::{debug, warn};
+    use std::convert::Infallible;
+    use std::sync::Arc;
+    use tokio_stream::wrappers::{errors::BroadcastStreamRecvError, BroadcastStream};
+    use tokio_stream::StreamExt;
+    use warp::sse::Event;
+
+    fn to_sse_event(event: &AggregatorEvent) -> Result<Event, warp::Error> {
+        Event::default().event(event.name()).json_data(event)
+    }
+
+    /// Server-Sent Events stream of the lifecycle events of the aggregator
+    pub async fn events(event_bus: Arc<EventBus>) -> Result<impl warp::Reply, Infallible> {
+        debug!("⇄ HTTP SERVER: events");
+
+        let events = BroadcastStream::new(event_bus.subscribe()).filter_map(|event| match event {
+            Ok(event) => Some(to_sse_event(&event)),
+            Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                warn!("events::lagged"; "skipped" => skipped);
+                None
+            }
+        });
+
+        Ok(warp::sse::reply(warp::sse::keep_alive().stream(events)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::http_server::SERVER_BASE_PATH;
+    use crate::initialize_dependencies;
+    use mithril_common::entities::{AggregatorEvent, Epoch};
+    use warp::http::{Method, StatusCode};
+    use warp::hyper::body::HttpBody;
+    use warp::test::request;
+    use warp::Reply;
+
+    use super::*;
+
+    fn setup_router(
+        dependency_manager: Arc<DependencyManager>,
+    ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+        let cors = warp::cors()
+            .allow_any_origin()
+            .allow_headers(vec!["content-type"])
+            .allow_methods(vec![Method::GET, Method::POST, Method::OPTIONS]);
+
+        warp::any()
+            .and(warp::path(SERVER_BASE_PATH))
+            .and(routes(dependency_manager).with(cors))
+    }
+
+    #[tokio::test]
+    async fn test_events_get_ok() {
+        let (dependency_manager, _) = initialize_dependencies().await;
+        let dependency_manager = Arc::new(dependency_manager);
+
+        let response = request()
+            .method(Method::GET.as_str())
+            .path(&format!("/{}/events", SERVER_BASE_PATH))
+            .filter(&setup_router(dependency_manager.clone()))
+            .await
+            .unwrap()
+            .into_response();
+        let event = AggregatorEvent::EpochChanged { epoch: Epoch(12) };
+        dependency_manager.event_bus.publish(event.clone());
+
+        assert_eq!(StatusCode::OK, response.status());
+        assert_eq!(
+            "text/event-stream",
+            response.headers().get("content-type").unwrap()
+        );
+        let chunk = response.into_body().data().await.unwrap().unwrap();
+        assert_eq!(
+            format!(
+                "event:epoch_changed\ndata:{}\n\n",
+                serde_json::to_string(&event).unwrap()
+            ),
+            String::from_utf8(chunk.to_vec()).unwrap()
+        );
+    }
+}
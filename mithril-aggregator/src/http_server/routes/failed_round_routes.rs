@@ -0,0 +1,149 @@
+use crate::http_server::routes::middlewares;
+use crate::DependencyManager;
+use std::sync::Arc;
+use warp::Filter;
+
+/// Maximum number of failed rounds returned by the aggregator
+const FAILED_ROUNDS_MAX_ITEMS: usize = 20;
+
+pub fn routes(
+    dependency_manager: Arc<DependencyManager>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    failed_rounds(dependency_manager)
+}
+
+/// GET /failed-rounds
+fn failed_rounds(
+    dependency_manager: Arc<DependencyManager>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("failed-rounds")
+        .and(warp::get())
+        .and(middlewares::with_failed_round_store(dependency_manager))
+        .and_then(handlers::failed_rounds)
+}
+
+mod handlers {
+    use super::FAILED_ROUNDS_MAX_ITEMS;
+    use crate::http_server::routes::reply;
+    use crate::FailedRoundStore;
+    use slog_scope::{debug, warn};
+    use std::convert::Infallible;
+    use std::sync::Arc;
+    use warp::http::StatusCode;
+
+    /// Failed rounds, the most recent first
+    pub async fn failed_rounds(
+        failed_round_store: Arc<FailedRoundStore>,
+    ) -> Result<impl warp::Reply, Infallible> {
+        debug!("⇄ HTTP SERVER: failed_rounds");
+
+        match failed_round_store.get_list(FAILED_ROUNDS_MAX_ITEMS).await {
+            Ok(failed_rounds) => Ok(reply::json(&failed_rounds, StatusCode::OK)),
+            Err(err) => {
+                warn!("failed_rounds::error"; "error" => ?err);
+                Ok(reply::internal_server_error(err.to_string()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    const API_SPEC_FILE: &str = "../openapi.yaml";
+
+    use crate::http_server::SERVER_BASE_PATH;
+    use crate::initialize_dependencies;
+    use crate::FailedRoundStore;
+    use mithril_common::apispec::APISpec;
+    use mithril_common::entities::{FailedRound, SingleSignatures};
+    use mithril_common::fake_data;
+    use mithril_common::store::{adapter::FailStoreAdapter, RetentionPolicy};
+    use serde_json::Value::Null;
+    use warp::http::{Method, StatusCode};
+    use warp::test::request;
+
+    use super::*;
+
+    fn setup_router(
+        dependency_manager: Arc<DependencyManager>,
+    ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+        let cors = warp::cors()
+            .allow_any_origin()
+            .allow_headers(vec!["content-type"])
+            .allow_methods(vec![Method::GET, Method::POST, Method::OPTIONS]);
+
+        warp::any()
+            .and(warp::path(SERVER_BASE_PATH))
+            .and(routes(dependency_manager).with(cors))
+    }
+
+    #[tokio::test]
+    async fn test_failed_rounds_get_ok() {
+        let (dependency_manager, _) = initialize_dependencies().await;
+        let certificate_pending = fake_data::certificate_pending();
+        let single_signatures = SingleSignatures::new(
+            certificate_pending.signers[0].party_id.clone(),
+            "signature".to_string(),
+            vec![1, 3],
+        );
+        let failed_round = FailedRound::new(
+            &certificate_pending,
+            &[single_signatures],
+            "2022-10-18T10:00:00+00:00".to_string(),
+            "2022-10-18T11:00:00+00:00".to_string(),
+        );
+        dependency_manager
+            .failed_round_store
+            .save(failed_round.clone())
+            .await
+            .unwrap();
+
+        let method = Method::GET.as_str();
+        let path = "/failed-rounds";
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{}{}", SERVER_BASE_PATH, path))
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        assert_eq!(StatusCode::OK, response.status());
+        let failed_rounds: Vec<FailedRound> = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(vec![failed_round], failed_rounds);
+        APISpec::from_file(API_SPEC_FILE)
+            .method(method)
+            .path(path)
+            .validate_request(&Null)
+            .unwrap()
+            .validate_response(&response)
+            .expect("OpenAPI error");
+    }
+
+    #[tokio::test]
+    async fn test_failed_rounds_get_ko_500() {
+        let (mut dependency_manager, _) = initialize_dependencies().await;
+        let failed_round_store = FailedRoundStore::new(
+            Box::new(FailStoreAdapter::<String, FailedRound>::new()),
+            RetentionPolicy::KeepAll,
+        );
+        dependency_manager.failed_round_store = Arc::new(failed_round_store);
+
+        let method = Method::GET.as_str();
+        let path = "/failed-rounds";
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{}{}", SERVER_BASE_PATH, path))
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, response.status());
+        APISpec::from_file(API_SPEC_FILE)
+            .method(method)
+            .path(path)
+            .validate_request(&Null)
+            .unwrap()
+            .validate_response(&response)
+            .expect("OpenAPI error");
+    }
+}
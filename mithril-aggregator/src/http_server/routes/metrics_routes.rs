@@ -0,0 +1,132 @@
+use crate::http_server::routes::middlewares;
+use crate::DependencyManager;
+use std::sync::Arc;
+use warp::Filter;
+
+pub fn routes(
+    dependency_manager: Arc<DependencyManager>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    metrics(dependency_manager)
+}
+
+/// GET /metrics
+fn metrics(
+    dependency_manager: Arc<DependencyManager>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("metrics")
+        .and(warp::get())
+        .and(middlewares::with_metrics_service(dependency_manager))
+        .and_then(handlers::metrics)
+}
+
+mod handlers {
+    use crate::http_server::routes::reply;
+    use crate::MetricsService;
+    use slog_scope::{debug, warn};
+    use std::convert::Infallible;
+    use std::sync::Arc;
+    use warp::http::StatusCode;
+
+    /// Metrics in the Prometheus text format
+    pub async fn metrics(
+        metrics_service: Arc<MetricsService>,
+    ) -> Result<impl warp::Reply, Infallible> {
+        debug!("⇄ HTTP SERVER: metrics");
+
+        match metrics_service.export() {
+            Ok(metrics) => Ok(reply::text(metrics, StatusCode::OK)),
+            Err(err) => {
+                warn!("metrics::error"; "error" => ?err);
+                Ok(reply::internal_server_error(err.to_string()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::http_server::routes::router;
+    use crate::http_server::SERVER_BASE_PATH;
+    use crate::multi_signer::MockMultiSigner;
+    use crate::{initialize_dependencies, ProtocolError};
+    use mithril_common::fake_data;
+    use tokio::sync::RwLock;
+    use warp::http::{Method, StatusCode};
+    use warp::test::request;
+
+    use super::*;
+
+    fn setup_router(
+        dependency_manager: Arc<DependencyManager>,
+    ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+        let cors = warp::cors()
+            .allow_any_origin()
+            .allow_headers(vec!["content-type"])
+            .allow_methods(vec![Method::GET, Method::POST, Method::OPTIONS]);
+
+        warp::any()
+            .and(warp::path(SERVER_BASE_PATH))
+            .and(routes(dependency_manager).with(cors))
+    }
+
+    #[tokio::test]
+    async fn test_metrics_get_ok() {
+        let (dependency_manager, _) = initialize_dependencies().await;
+        dependency_manager
+            .metrics_service
+            .increment_signer_registrations();
+        dependency_manager
+            .metrics_service
+            .increment_certificates_produced("MithrilStakeDistribution");
+
+        let response = request()
+            .method(Method::GET.as_str())
+            .path(&format!("/{}/metrics", SERVER_BASE_PATH))
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        assert_eq!(StatusCode::OK, response.status());
+        assert!(response.headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .starts_with("text/plain"));
+        let body = String::from_utf8(response.body().to_vec()).unwrap();
+        assert!(body.contains("# TYPE mithril_aggregator_runtime_cycle_duration_seconds histogram"));
+        assert!(body.contains("mithril_aggregator_signer_registrations_total 1"));
+        assert!(body.contains(
+            "mithril_aggregator_certificates_produced_total{signed_entity_type=\"MithrilStakeDistribution\"} 1"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_metrics_get_after_single_signatures_registration() {
+        let signatures = fake_data::single_signatures(vec![1]);
+        let party_id = signatures.party_id.clone();
+        let mut mock_multi_signer = MockMultiSigner::new();
+        mock_multi_signer
+            .expect_register_single_signature()
+            .return_once(move |_| Err(ProtocolError::ExistingSingleSignature(party_id)));
+        let (mut dependency_manager, _) = initialize_dependencies().await;
+        dependency_manager.multi_signer = Arc::new(RwLock::new(mock_multi_signer));
+        let router = router::routes(Arc::new(dependency_manager));
+
+        let response = request()
+            .method(Method::POST.as_str())
+            .path(&format!("/{}/register-signatures", SERVER_BASE_PATH))
+            .json(&signatures)
+            .reply(&router)
+            .await;
+        assert_eq!(StatusCode::CONFLICT, response.status());
+
+        let response = request()
+            .method(Method::GET.as_str())
+            .path(&format!("/{}/metrics", SERVER_BASE_PATH))
+            .reply(&router)
+            .await;
+
+        assert_eq!(StatusCode::OK, response.status());
+        let body = String::from_utf8(response.body().to_vec()).unwrap();
+        assert!(body.contains("mithril_aggregator_single_signatures_received_total 1"));
+        assert!(body.contains("mithril_aggregator_single_signatures_rejected_total 1"));
+    }
+}
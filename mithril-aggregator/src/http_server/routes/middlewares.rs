@@ -1,8 +1,10 @@
-use crate::dependency::MultiSignerWrapper;
+use crate::dependency::{MultiSignerWrapper, RuntimeStatusWrapper};
 use crate::{
-    CertificatePendingStore, CertificateStore, Configuration, DependencyManager,
-    ProtocolParametersStore, SnapshotStore,
+    AuditLog, CertificateBundleCache, CertificatePendingStore, CertificateStore, Configuration,
+    DependencyManager, EventBus, FailedRoundStore, MetricsService, ProtocolParametersStore,
+    RuntimeControl, SignerStatisticsStore, SingleSignatureStore, SnapshotStore,
 };
+use mithril_common::crypto_helper::ProtocolGenesisVerifier;
 use std::convert::Infallible;
 use std::sync::Arc;
 use warp::Filter;
@@ -28,6 +30,20 @@ pub(crate) fn with_certificate_pending_store(
     warp::any().map(move || dependency_manager.certificate_pending_store.clone())
 }
 
+/// With failed round store
+pub(crate) fn with_failed_round_store(
+    dependency_manager: Arc<DependencyManager>,
+) -> impl Filter<Extract = (Arc<FailedRoundStore>,), Error = Infallible> + Clone {
+    warp::any().map(move || dependency_manager.failed_round_store.clone())
+}
+
+/// With signer statistics store
+pub(crate) fn with_signer_statistics_store(
+    dependency_manager: Arc<DependencyManager>,
+) -> impl Filter<Extract = (Arc<SignerStatisticsStore>,), Error = Infallible> + Clone {
+    warp::any().map(move || dependency_manager.signer_statistics_store.clone())
+}
+
 /// With protocol parameters store
 pub(crate) fn with_protocol_parameters_store(
     dependency_manager: Arc<DependencyManager>,
@@ -48,3 +64,59 @@ pub fn with_config(
 ) -> impl Filter<Extract = (Configuration,), Error = Infallible> + Clone {
     warp::any().map(move || dependency_manager.config.clone())
 }
+
+/// With certificate bundle cache middleware
+pub fn with_certificate_bundle_cache(
+    dependency_manager: Arc<DependencyManager>,
+) -> impl Filter<Extract = (Arc<CertificateBundleCache>,), Error = Infallible> + Clone {
+    warp::any().map(move || dependency_manager.certificate_bundle_cache.clone())
+}
+
+/// With genesis verifier middleware
+pub fn with_genesis_verifier(
+    dependency_manager: Arc<DependencyManager>,
+) -> impl Filter<Extract = (Arc<ProtocolGenesisVerifier>,), Error = Infallible> + Clone {
+    warp::any().map(move || dependency_manager.genesis_verifier.clone())
+}
+
+/// With metrics service middleware
+pub fn with_metrics_service(
+    dependency_manager: Arc<DependencyManager>,
+) -> impl Filter<Extract = (Arc<MetricsService>,), Error = Infallible> + Clone {
+    warp::any().map(move || dependency_manager.metrics_service.clone())
+}
+
+/// With runtime control middleware
+pub fn with_runtime_control(
+    dependency_manager: Arc<DependencyManager>,
+) -> impl Filter<Extract = (Arc<RuntimeControl>,), Error = Infallible> + Clone {
+    warp::any().map(move || dependency_manager.runtime_control.clone())
+}
+
+/// With audit log middleware
+pub fn with_audit_log(
+    dependency_manager: Arc<DependencyManager>,
+) -> impl Filter<Extract = (Arc<AuditLog>,), Error = Infallible> + Clone {
+    warp::any().map(move || dependency_manager.audit_log.clone())
+}
+
+/// With event bus middleware
+pub fn with_event_bus(
+    dependency_manager: Arc<DependencyManager>,
+) -> impl Filter<Extract = (Arc<EventBus>,), Error = Infallible> + Clone {
+    warp::any().map(move || dependency_manager.event_bus.clone())
+}
+
+/// With runtime status middleware
+pub fn with_runtime_status(
+    dependency_manager: Arc<DependencyManager>,
+) -> impl Filter<Extract = (RuntimeStatusWrapper,), Error = Infallible> + Clone {
+    warp::any().map(move || dependency_manager.runtime_status.clone())
+}
+
+/// With single signature store middleware
+pub(crate) fn with_single_signature_store(
+    dependency_manager: Arc<DependencyManager>,
+) -> impl Filter<Extract = (Arc<SingleSignatureStore>,), Error = Infallible> + Clone {
+    warp::any().map(move || dependency_manager.single_signature_store.clone())
+}
@@ -1,8 +1,16 @@
+mod admin_routes;
 mod certificate_routes;
 mod epoch_routes;
+mod events_routes;
+mod failed_round_routes;
+mod metrics_routes;
 mod middlewares;
+mod protocol_version_routes;
 mod reply;
 pub mod router;
 mod signatures_routes;
 mod signer_routes;
+mod signer_statistics_routes;
 mod snapshot_routes;
+mod stake_distribution_routes;
+mod status_routes;
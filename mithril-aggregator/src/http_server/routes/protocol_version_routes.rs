@@ -0,0 +1,139 @@
+use mithril_common::protocol_version::PROTOCOL_VERSION_HEADER;
+use warp::Filter;
+
+pub fn routes() -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    incompatible_protocol_version().or(protocol_versions())
+}
+
+/// Any route requested with an incompatible protocol version, the other requests are left to
+/// the other routes
+fn incompatible_protocol_version(
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::header::optional::<String>(PROTOCOL_VERSION_HEADER)
+        .and_then(handlers::incompatible_protocol_version)
+}
+
+/// GET /protocol-versions
+fn protocol_versions() -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("protocol-versions")
+        .and(warp::get())
+        .and_then(handlers::protocol_versions)
+}
+
+mod handlers {
+    use crate::http_server::routes::reply;
+    use mithril_common::protocol_version::{check_protocol_version, ProtocolVersions};
+    use slog_scope::{debug, warn};
+    use std::convert::Infallible;
+    use warp::http::StatusCode;
+
+    /// Reject a request whose protocol version is not compatible with the aggregator
+    pub async fn incompatible_protocol_version(
+        protocol_version: Option<String>,
+    ) -> Result<impl warp::Reply, warp::Rejection> {
+        match protocol_version.map(|version| check_protocol_version(&version)) {
+            Some(Err(err)) => {
+                warn!("incompatible_protocol_version"; "error" => ?err);
+                Ok(reply::precondition_failed(
+                    "incompatible_protocol_version".to_string(),
+                    err.to_string(),
+                ))
+            }
+            _ => Err(warp::reject()),
+        }
+    }
+
+    /// Protocol versions
+    pub async fn protocol_versions() -> Result<impl warp::Reply, Infallible> {
+        debug!("⇄ HTTP SERVER: protocol_versions");
+
+        Ok(reply::json(&ProtocolVersions::default(), StatusCode::OK))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    const API_SPEC_FILE: &str = "../openapi.yaml";
+
+    use crate::http_server::SERVER_BASE_PATH;
+    use mithril_common::apispec::APISpec;
+    use mithril_common::crypto_helper::PROTOCOL_VERSION;
+    use mithril_common::entities::ClientError;
+    use mithril_common::protocol_version::ProtocolVersions;
+    use serde_json::Value::Null;
+    use warp::http::{Method, StatusCode};
+    use warp::test::request;
+
+    use super::*;
+
+    fn setup_router() -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+        let cors = warp::cors()
+            .allow_any_origin()
+            .allow_headers(vec!["content-type"])
+            .allow_methods(vec![Method::GET, Method::POST, Method::OPTIONS]);
+
+        warp::any()
+            .and(warp::path(SERVER_BASE_PATH))
+            .and(routes().with(cors))
+    }
+
+    #[tokio::test]
+    async fn test_protocol_versions_get_ok() {
+        let method = Method::GET.as_str();
+        let path = "/protocol-versions";
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{}{}", SERVER_BASE_PATH, path))
+            .reply(&setup_router())
+            .await;
+
+        assert_eq!(StatusCode::OK, response.status());
+        assert_eq!(
+            ProtocolVersions::default(),
+            serde_json::from_slice::<ProtocolVersions>(response.body()).unwrap()
+        );
+        APISpec::from_file(API_SPEC_FILE)
+            .method(method)
+            .path(path)
+            .validate_request(&Null)
+            .unwrap()
+            .validate_response(&response)
+            .expect("OpenAPI error");
+    }
+
+    #[tokio::test]
+    async fn test_protocol_versions_get_ok_with_compatible_protocol_version() {
+        let method = Method::GET.as_str();
+        let path = "/protocol-versions";
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{}{}", SERVER_BASE_PATH, path))
+            .header(PROTOCOL_VERSION_HEADER, PROTOCOL_VERSION)
+            .reply(&setup_router())
+            .await;
+
+        assert_eq!(StatusCode::OK, response.status());
+    }
+
+    #[tokio::test]
+    async fn test_any_route_ko_412_with_incompatible_protocol_version() {
+        for path in ["/protocol-versions", "/certificate-pending"] {
+            let response = request()
+                .method(Method::GET.as_str())
+                .path(&format!("/{}{}", SERVER_BASE_PATH, path))
+                .header(PROTOCOL_VERSION_HEADER, "99.0.0")
+                .reply(&setup_router())
+                .await;
+
+            assert_eq!(StatusCode::PRECONDITION_FAILED, response.status());
+            assert_eq!(
+                "incompatible_protocol_version",
+                serde_json::from_slice::<ClientError>(response.body())
+                    .unwrap()
+                    .label
+            );
+        }
+    }
+}
@@ -12,6 +12,13 @@ where
     ))
 }
 
+pub fn text(value: String, status_code: StatusCode) -> Box<dyn warp::Reply> {
+    Box::new(warp::reply::with_status(
+        warp::reply::with_header(value, "content-type", "text/plain; version=0.0.4"),
+        status_code,
+    ))
+}
+
 pub fn empty(status_code: StatusCode) -> Box<dyn warp::Reply> {
     Box::new(warp::reply::with_status(warp::reply::reply(), status_code))
 }
@@ -20,6 +27,21 @@ pub fn bad_request(label: String, message: String) -> Box<dyn warp::Reply> {
     json(&ClientError::new(label, message), StatusCode::BAD_REQUEST)
 }
 
+pub fn unauthorized(label: String, message: String) -> Box<dyn warp::Reply> {
+    json(&ClientError::new(label, message), StatusCode::UNAUTHORIZED)
+}
+
+pub fn precondition_failed(label: String, message: String) -> Box<dyn warp::Reply> {
+    json(
+        &ClientError::new(label, message),
+        StatusCode::PRECONDITION_FAILED,
+    )
+}
+
+pub fn conflict(label: String, message: String) -> Box<dyn warp::Reply> {
+    json(&ClientError::new(label, message), StatusCode::CONFLICT)
+}
+
 pub fn internal_server_error(message: String) -> Box<dyn warp::Reply> {
     json(
         &InternalServerError::new(message),
@@ -1,8 +1,12 @@
 use crate::http_server::routes::{
-    certificate_routes, epoch_routes, signatures_routes, signer_routes, snapshot_routes,
+    admin_routes, certificate_routes, epoch_routes, events_routes, failed_round_routes,
+    metrics_routes, protocol_version_routes, signatures_routes, signer_routes,
+    signer_statistics_routes, snapshot_routes, stake_distribution_routes, status_routes,
 };
-use crate::http_server::SERVER_BASE_PATH;
+use crate::http_server::{ADMIN_BASE_PATH, SERVER_BASE_PATH};
 use crate::DependencyManager;
+use mithril_common::crypto_helper::PROTOCOL_VERSION;
+use mithril_common::protocol_version::PROTOCOL_VERSION_HEADER;
 use std::sync::Arc;
 use warp::http::Method;
 use warp::Filter;
@@ -13,15 +17,36 @@ pub fn routes(
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
     let cors = warp::cors()
         .allow_any_origin()
-        .allow_headers(vec!["content-type"])
-        .allow_methods(vec![Method::GET, Method::POST, Method::OPTIONS]);
+        .allow_headers(vec!["content-type", PROTOCOL_VERSION_HEADER])
+        .allow_methods(vec![Method::GET, Method::POST, Method::OPTIONS])
+        .expose_headers(vec![PROTOCOL_VERSION_HEADER]);
 
-    warp::any().and(warp::path(SERVER_BASE_PATH)).and(
-        certificate_routes::routes(dependency_manager.clone())
+    // The protocol version routes come first as they reject the requests sent with an
+    // incompatible protocol version.
+    let public_routes = warp::any().and(warp::path(SERVER_BASE_PATH)).and(
+        protocol_version_routes::routes()
+            .or(certificate_routes::routes(dependency_manager.clone()))
             .or(snapshot_routes::routes(dependency_manager.clone()))
             .or(signer_routes::routes(dependency_manager.clone()))
+            .or(signer_statistics_routes::routes(dependency_manager.clone()))
             .or(signatures_routes::routes(dependency_manager.clone()))
-            .or(epoch_routes::routes(dependency_manager))
-            .with(cors),
-    )
+            .or(epoch_routes::routes(dependency_manager.clone()))
+            .or(events_routes::routes(dependency_manager.clone()))
+            .or(failed_round_routes::routes(dependency_manager.clone()))
+            .or(metrics_routes::routes(dependency_manager.clone()))
+            .or(stake_distribution_routes::routes(
+                dependency_manager.clone(),
+            ))
+            .or(status_routes::routes(dependency_manager.clone()))
+            .with(cors)
+            .with(warp::reply::with::header(
+                PROTOCOL_VERSION_HEADER,
+                PROTOCOL_VERSION,
+            )),
+    );
+    let admin_routes = warp::any()
+        .and(warp::path(ADMIN_BASE_PATH))
+        .and(admin_routes::routes(dependency_manager));
+
+    public_routes.or(admin_routes)
 }
@@ -16,37 +16,52 @@ fn register_signatures(
     warp::path!("register-signatures")
         .and(warp::post())
         .and(warp::body::json())
-        .and(middlewares::with_multi_signer(dependency_manager))
+        .and(middlewares::with_multi_signer(dependency_manager.clone()))
+        .and(middlewares::with_metrics_service(
+            dependency_manager.clone(),
+        ))
+        .and(middlewares::with_event_bus(dependency_manager))
         .and_then(handlers::register_signatures)
 }
 
 mod handlers {
     use crate::dependency::MultiSignerWrapper;
     use crate::http_server::routes::reply;
-    use crate::ProtocolError;
+    use crate::{EventBus, MetricsService, ProtocolError};
     use mithril_common::entities;
     use slog_scope::{debug, warn};
     use std::convert::Infallible;
+    use std::sync::Arc;
     use warp::http::StatusCode;
 
     /// Register Signatures
     pub async fn register_signatures(
         signature: entities::SingleSignatures,
         multi_signer: MultiSignerWrapper,
+        metrics_service: Arc<MetricsService>,
+        event_bus: Arc<EventBus>,
     ) -> Result<impl warp::Reply, Infallible> {
         debug!("⇄ HTTP SERVER: register_signatures/{:?}", signature);
+        metrics_service.increment_single_signatures_received();
 
         let mut multi_signer = multi_signer.write().await;
         match multi_signer.register_single_signature(&signature).await {
             Err(ProtocolError::ExistingSingleSignature(party_id)) => {
                 debug!("register_signatures::already_exist"; "party_id" => ?party_id);
+                metrics_service.increment_single_signatures_rejected();
                 Ok(reply::empty(StatusCode::CONFLICT))
             }
             Err(err) => {
                 warn!("register_signatures::error"; "error" => ?err);
+                metrics_service.increment_single_signatures_rejected();
                 Ok(reply::internal_server_error(err.to_string()))
             }
-            Ok(()) => Ok(reply::empty(StatusCode::CREATED)),
+            Ok(()) => {
+                event_bus.publish(entities::AggregatorEvent::SignatureAccepted {
+                    party_id: signature.party_id,
+                });
+                Ok(reply::empty(StatusCode::CREATED))
+            }
         }
     }
 }
@@ -6,7 +6,7 @@ use warp::Filter;
 pub fn routes(
     dependency_manager: Arc<DependencyManager>,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
-    register_signer(dependency_manager)
+    register_signer(dependency_manager.clone()).or(registered_signers(dependency_manager))
 }
 
 /// POST /register-signer
@@ -16,29 +16,45 @@ fn register_signer(
     warp::path!("register-signer")
         .and(warp::post())
         .and(warp::body::json())
-        .and(middlewares::with_multi_signer(dependency_manager))
+        .and(middlewares::with_multi_signer(dependency_manager.clone()))
+        .and(middlewares::with_metrics_service(dependency_manager))
         .and_then(handlers::register_signer)
 }
 
+/// GET /signers
+fn registered_signers(
+    dependency_manager: Arc<DependencyManager>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("signers")
+        .and(warp::get())
+        .and(middlewares::with_multi_signer(dependency_manager))
+        .and_then(handlers::registered_signers)
+}
+
 mod handlers {
     use crate::dependency::MultiSignerWrapper;
     use crate::http_server::routes::reply;
-    use crate::ProtocolError;
-    use mithril_common::entities;
+    use crate::{MetricsService, ProtocolError};
+    use mithril_common::entities::{self, RegisteredSigners};
     use slog_scope::{debug, warn};
     use std::convert::Infallible;
+    use std::sync::Arc;
     use warp::http::StatusCode;
 
     /// Register Signer
     pub async fn register_signer(
         signer: entities::Signer,
         multi_signer: MultiSignerWrapper,
+        metrics_service: Arc<MetricsService>,
     ) -> Result<impl warp::Reply, Infallible> {
         debug!("⇄ HTTP SERVER: register_signer/{:?}", signer);
 
         let mut multi_signer = multi_signer.write().await;
         match multi_signer.register_signer(&signer).await {
-            Ok(()) => Ok(reply::empty(StatusCode::CREATED)),
+            Ok(()) => {
+                metrics_service.increment_signer_registrations();
+                Ok(reply::empty(StatusCode::CREATED))
+            }
             Err(ProtocolError::ExistingSigner()) => {
                 debug!("register_signer::already_registered");
                 Ok(reply::empty(StatusCode::CREATED))
@@ -63,6 +79,36 @@ mod handlers {
             }
         }
     }
+
+    /// Registered signers
+    pub async fn registered_signers(
+        multi_signer: MultiSignerWrapper,
+    ) -> Result<impl warp::Reply, Infallible> {
+        debug!("⇄ HTTP SERVER: registered_signers");
+
+        let multi_signer = multi_signer.read().await;
+        let registered_signers = async {
+            let beacon = multi_signer
+                .get_current_beacon()
+                .await
+                .ok_or_else(ProtocolError::UnavailableBeacon)?;
+
+            Ok::<_, ProtocolError>(RegisteredSigners {
+                epoch: beacon.epoch,
+                signers: multi_signer.get_signers_with_stake().await?,
+                next_epoch: beacon.epoch + 1,
+                next_signers: multi_signer.get_next_signers_with_stake().await?,
+            })
+        };
+
+        match registered_signers.await {
+            Ok(registered_signers) => Ok(reply::json(&registered_signers, StatusCode::OK)),
+            Err(err) => {
+                warn!("registered_signers::error"; "error" => ?err);
+                Ok(reply::internal_server_error(err.to_string()))
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -71,9 +117,10 @@ mod tests {
 
     use mithril_common::apispec::APISpec;
     use mithril_common::crypto_helper::ProtocolRegistrationError;
-    use mithril_common::fake_data;
+    use mithril_common::{entities, fake_data};
+    use serde_json::Value::Null;
     use tokio::sync::RwLock;
-    use warp::http::Method;
+    use warp::http::{Method, StatusCode};
     use warp::test::request;
 
     use super::*;
@@ -215,4 +262,73 @@ mod tests {
             .validate_response(&response)
             .expect("OpenAPI error");
     }
+
+    #[tokio::test]
+    async fn test_registered_signers_get_ok() {
+        let signers = fake_data::signers_with_stakes(3);
+        let (current_signers, next_signers) = (signers[0..2].to_vec(), signers[1..3].to_vec());
+        let mut mock_multi_signer = MockMultiSigner::new();
+        mock_multi_signer
+            .expect_get_current_beacon()
+            .return_once(|| Some(fake_data::beacon()));
+        mock_multi_signer
+            .expect_get_signers_with_stake()
+            .return_once(move || Ok(current_signers));
+        mock_multi_signer
+            .expect_get_next_signers_with_stake()
+            .return_once(move || Ok(next_signers));
+        let (mut dependency_manager, _) = initialize_dependencies().await;
+        dependency_manager.multi_signer = Arc::new(RwLock::new(mock_multi_signer));
+
+        let method = Method::GET.as_str();
+        let path = "/signers";
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{}{}", SERVER_BASE_PATH, path))
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        let registered_signers: entities::RegisteredSigners =
+            serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(fake_data::beacon().epoch, registered_signers.epoch);
+        assert_eq!(signers[0..2].to_vec(), registered_signers.signers);
+        assert_eq!(signers[1..3].to_vec(), registered_signers.next_signers);
+
+        APISpec::from_file(API_SPEC_FILE)
+            .method(method)
+            .path(path)
+            .validate_request(&Null)
+            .unwrap()
+            .validate_response(&response)
+            .expect("OpenAPI error");
+    }
+
+    #[tokio::test]
+    async fn test_registered_signers_get_ko_500() {
+        let mut mock_multi_signer = MockMultiSigner::new();
+        mock_multi_signer
+            .expect_get_current_beacon()
+            .return_once(|| None);
+        let (mut dependency_manager, _) = initialize_dependencies().await;
+        dependency_manager.multi_signer = Arc::new(RwLock::new(mock_multi_signer));
+
+        let method = Method::GET.as_str();
+        let path = "/signers";
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{}{}", SERVER_BASE_PATH, path))
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, response.status());
+        APISpec::from_file(API_SPEC_FILE)
+            .method(method)
+            .path(path)
+            .validate_request(&Null)
+            .unwrap()
+            .validate_response(&response)
+            .expect("OpenAPI error");
+    }
 }
@@ -0,0 +1,169 @@
+use crate::http_server::routes::middlewares;
+use crate::DependencyManager;
+use mithril_common::entities::PartyId;
+use serde::Deserialize;
+use std::sync::Arc;
+use warp::Filter;
+
+/// Query parameters of the signer statistics route
+#[derive(Debug, Deserialize)]
+pub struct SignerStatisticsQuery {
+    /// Party id of the signer
+    party_id: Option<PartyId>,
+
+    /// Epoch of the statistics
+    epoch: Option<u64>,
+}
+
+pub fn routes(
+    dependency_manager: Arc<DependencyManager>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    signer_statistics(dependency_manager)
+}
+
+/// GET /signers/statistics
+fn signer_statistics(
+    dependency_manager: Arc<DependencyManager>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("signers" / "statistics")
+        .and(warp::get())
+        .and(warp::query::<SignerStatisticsQuery>())
+        .and(middlewares::with_signer_statistics_store(
+            dependency_manager,
+        ))
+        .and_then(handlers::signer_statistics)
+}
+
+mod handlers {
+    use super::SignerStatisticsQuery;
+    use crate::http_server::routes::reply;
+    use crate::SignerStatisticsStore;
+    use mithril_common::entities::Epoch;
+    use slog_scope::{debug, warn};
+    use std::convert::Infallible;
+    use std::sync::Arc;
+    use warp::http::StatusCode;
+
+    /// Signer statistics, the most recent epoch first
+    pub async fn signer_statistics(
+        query: SignerStatisticsQuery,
+        signer_statistics_store: Arc<SignerStatisticsStore>,
+    ) -> Result<impl warp::Reply, Infallible> {
+        debug!("⇄ HTTP SERVER: signer_statistics/{:?}", query);
+
+        match signer_statistics_store
+            .get_list(query.party_id.as_ref(), query.epoch.map(Epoch))
+            .await
+        {
+            Ok(signer_statistics) => Ok(reply::json(&signer_statistics, StatusCode::OK)),
+            Err(err) => {
+                warn!("signer_statistics::error"; "error" => ?err);
+                Ok(reply::internal_server_error(err.to_string()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    const API_SPEC_FILE: &str = "../openapi.yaml";
+
+    use crate::http_server::SERVER_BASE_PATH;
+    use crate::initialize_dependencies;
+    use crate::SignerStatisticsStore;
+    use mithril_common::apispec::APISpec;
+    use mithril_common::entities::{Epoch, PartyId, SignerStatistics};
+    use mithril_common::store::adapter::FailStoreAdapter;
+    use mithril_common::store::RetentionPolicy;
+    use serde_json::Value::Null;
+    use std::collections::HashMap;
+    use warp::http::{Method, StatusCode};
+    use warp::test::request;
+
+    use super::*;
+
+    fn setup_router(
+        dependency_manager: Arc<DependencyManager>,
+    ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+        let cors = warp::cors()
+            .allow_any_origin()
+            .allow_headers(vec!["content-type"])
+            .allow_methods(vec![Method::GET, Method::POST, Method::OPTIONS]);
+
+        warp::any()
+            .and(warp::path(SERVER_BASE_PATH))
+            .and(routes(dependency_manager).with(cors))
+    }
+
+    #[tokio::test]
+    async fn test_signer_statistics_get_ok() {
+        let (dependency_manager, _) = initialize_dependencies().await;
+        let party_id = "party".to_string();
+        for epoch in [Epoch(1), Epoch(2)] {
+            dependency_manager
+                .signer_statistics_store
+                .record_registration(epoch, &party_id)
+                .await
+                .unwrap();
+        }
+        dependency_manager
+            .signer_statistics_store
+            .record_registration(Epoch(2), &"other_party".to_string())
+            .await
+            .unwrap();
+
+        let method = Method::GET.as_str();
+        let path = "/signers/statistics";
+
+        let response = request()
+            .method(method)
+            .path(&format!(
+                "/{}{}?party_id={}&epoch=2",
+                SERVER_BASE_PATH, path, party_id
+            ))
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        assert_eq!(StatusCode::OK, response.status());
+        let signer_statistics: Vec<SignerStatistics> =
+            serde_json::from_slice(response.body()).unwrap();
+        let mut expected_statistics = SignerStatistics::new(party_id, Epoch(2));
+        expected_statistics.registered = true;
+        assert_eq!(vec![expected_statistics], signer_statistics);
+        APISpec::from_file(API_SPEC_FILE)
+            .method(method)
+            .path(path)
+            .validate_request(&Null)
+            .unwrap()
+            .validate_response(&response)
+            .expect("OpenAPI error");
+    }
+
+    #[tokio::test]
+    async fn test_signer_statistics_get_ko_500() {
+        let (mut dependency_manager, _) = initialize_dependencies().await;
+        let signer_statistics_store = SignerStatisticsStore::new(
+            Box::new(FailStoreAdapter::<Epoch, HashMap<PartyId, SignerStatistics>>::new()),
+            RetentionPolicy::KeepAll,
+        );
+        dependency_manager.signer_statistics_store = Arc::new(signer_statistics_store);
+
+        let method = Method::GET.as_str();
+        let path = "/signers/statistics";
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{}{}", SERVER_BASE_PATH, path))
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, response.status());
+        APISpec::from_file(API_SPEC_FILE)
+            .method(method)
+            .path(path)
+            .validate_request(&Null)
+            .unwrap()
+            .validate_response(&response)
+            .expect("OpenAPI error");
+    }
+}
@@ -1,4 +1,5 @@
 use crate::http_server::routes::middlewares;
+use crate::snapshot_uploaders::SNAPSHOT_CHUNKS_DIRECTORY;
 use crate::DependencyManager;
 use std::sync::Arc;
 use warp::Filter;
@@ -8,6 +9,7 @@ pub fn routes(
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
     snapshots(dependency_manager.clone())
         .or(serve_snapshots_dir(dependency_manager.clone()))
+        .or(serve_snapshot_chunks_dir(dependency_manager.clone()))
         .or(snapshot_download(dependency_manager.clone()))
         .or(snapshot_digest(dependency_manager))
 }
@@ -44,6 +46,19 @@ fn serve_snapshots_dir(
         .and_then(handlers::ensure_downloaded_file_is_a_snapshot)
 }
 
+/// GET /snapshot_chunks/{filename}
+///
+/// Serve the content addressed chunks of the snapshot archives and their manifests.
+fn serve_snapshot_chunks_dir(
+    dependency_manager: Arc<DependencyManager>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let config = dependency_manager.config.clone();
+
+    warp::path("snapshot_chunks").and(warp::fs::dir(
+        config.snapshot_directory.join(SNAPSHOT_CHUNKS_DIRECTORY),
+    ))
+}
+
 /// GET /snapshot/digest
 fn snapshot_digest(
     dependency_manager: Arc<DependencyManager>,
@@ -120,11 +135,12 @@ mod handlers {
         match snapshot_store.get_snapshot_details(digest).await {
             Ok(Some(snapshot)) => {
                 let filename = format!(
-                    "{}-e{}-i{}.{}.tar.gz",
+                    "{}-e{}-i{}.{}.{}",
                     snapshot.beacon.network,
                     snapshot.beacon.epoch,
                     snapshot.beacon.immutable_file_number,
-                    snapshot.digest
+                    snapshot.digest,
+                    snapshot.compression_algorithm.tar_file_extension()
                 );
                 let snapshot_uri = format!(
                     "{}{}/snapshot_download/{}",
@@ -174,7 +190,7 @@ mod tests {
     use serde_json::Value::Null;
 
     use crate::initialize_dependencies;
-    use warp::http::Method;
+    use warp::http::{Method, StatusCode};
     use warp::test::request;
 
     use super::*;
@@ -252,6 +268,35 @@ mod tests {
             .expect("OpenAPI error");
     }
 
+    #[tokio::test]
+    async fn test_snapshot_chunks_get_ok() {
+        let snapshot_directory = tempfile::tempdir().unwrap();
+        let chunks_directory = snapshot_directory.path().join(SNAPSHOT_CHUNKS_DIRECTORY);
+        std::fs::create_dir_all(&chunks_directory).unwrap();
+        std::fs::write(chunks_directory.join("chunk-hash"), b"chunk content").unwrap();
+        let (mut dependency_manager, _) = initialize_dependencies().await;
+        dependency_manager.config.snapshot_directory = snapshot_directory.path().to_path_buf();
+        let router = setup_router(Arc::new(dependency_manager));
+
+        let response = request()
+            .method(Method::GET.as_str())
+            .path(&format!("/{}/snapshot_chunks/chunk-hash", SERVER_BASE_PATH))
+            .reply(&router)
+            .await;
+        assert_eq!(StatusCode::OK, response.status());
+        assert_eq!(b"chunk content".to_vec(), response.body().to_vec());
+
+        let response = request()
+            .method(Method::GET.as_str())
+            .path(&format!(
+                "/{}/snapshot_chunks/unknown-hash",
+                SERVER_BASE_PATH
+            ))
+            .reply(&router)
+            .await;
+        assert_eq!(StatusCode::NOT_FOUND, response.status());
+    }
+
     #[tokio::test]
     async fn test_snapshot_download_get_ok() {
         let fake_snapshot = fake_data::snapshots(1).first().unwrap().to_owned();
@@ -0,0 +1,182 @@
+use crate::http_server::routes::middlewares;
+use crate::DependencyManager;
+use std::sync::Arc;
+use warp::Filter;
+
+pub fn routes(
+    dependency_manager: Arc<DependencyManager>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    signing_stake_distribution(dependency_manager)
+}
+
+/// GET /stake-distribution
+fn signing_stake_distribution(
+    dependency_manager: Arc<DependencyManager>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("stake-distribution")
+        .and(warp::get())
+        .and(middlewares::with_multi_signer(dependency_manager))
+        .and_then(handlers::signing_stake_distribution)
+}
+
+mod handlers {
+    use crate::dependency::MultiSignerWrapper;
+    use crate::http_server::routes::reply;
+    use crate::ProtocolError;
+    use mithril_common::entities::{SignerWithStake, SigningStakeDistribution, StakeDistribution};
+    use slog_scope::{debug, warn};
+    use std::convert::Infallible;
+    use warp::http::StatusCode;
+
+    fn to_stake_distribution(signers_with_stake: &[SignerWithStake]) -> StakeDistribution {
+        signers_with_stake
+            .iter()
+            .map(|signer| (signer.party_id.clone(), signer.stake))
+            .collect()
+    }
+
+    /// Signing stake distribution
+    pub async fn signing_stake_distribution(
+        multi_signer: MultiSignerWrapper,
+    ) -> Result<impl warp::Reply, Infallible> {
+        debug!("⇄ HTTP SERVER: signing_stake_distribution");
+
+        let multi_signer = multi_signer.read().await;
+        let signing_stake_distribution = async {
+            let beacon = multi_signer
+                .get_current_beacon()
+                .await
+                .ok_or_else(ProtocolError::UnavailableBeacon)?;
+            let signers = multi_signer.get_signers_with_stake().await?;
+            let next_signers = multi_signer.get_next_signers_with_stake().await?;
+
+            Ok::<_, ProtocolError>(SigningStakeDistribution {
+                epoch: beacon.epoch,
+                stake_distribution: to_stake_distribution(&signers),
+                aggregate_verification_key: multi_signer
+                    .compute_stake_distribution_aggregate_verification_key()
+                    .await?,
+                next_epoch: beacon.epoch + 1,
+                next_stake_distribution: to_stake_distribution(&next_signers),
+                next_aggregate_verification_key: multi_signer
+                    .compute_next_stake_distribution_aggregate_verification_key()
+                    .await?,
+            })
+        };
+
+        match signing_stake_distribution.await {
+            Ok(signing_stake_distribution) => {
+                Ok(reply::json(&signing_stake_distribution, StatusCode::OK))
+            }
+            Err(err) => {
+                warn!("signing_stake_distribution::error"; "error" => ?err);
+                Ok(reply::internal_server_error(err.to_string()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    const API_SPEC_FILE: &str = "../openapi.yaml";
+
+    use crate::http_server::SERVER_BASE_PATH;
+    use crate::initialize_dependencies;
+    use crate::multi_signer::MockMultiSigner;
+    use mithril_common::apispec::APISpec;
+    use mithril_common::entities::SigningStakeDistribution;
+    use mithril_common::fake_data;
+    use serde_json::Value::Null;
+    use tokio::sync::RwLock;
+    use warp::http::{Method, StatusCode};
+    use warp::test::request;
+
+    use super::*;
+
+    fn setup_router(
+        dependency_manager: Arc<DependencyManager>,
+    ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+        let cors = warp::cors()
+            .allow_any_origin()
+            .allow_headers(vec!["content-type"])
+            .allow_methods(vec![Method::GET, Method::POST, Method::OPTIONS]);
+
+        warp::any()
+            .and(warp::path(SERVER_BASE_PATH))
+            .and(routes(dependency_manager).with(cors))
+    }
+
+    #[tokio::test]
+    async fn test_stake_distribution_get_ok() {
+        let (dependency_manager, _) = initialize_dependencies().await;
+        let beacon = fake_data::beacon();
+        let signers = fake_data::signers_with_stakes(3);
+        dependency_manager
+            .simulate_genesis(
+                signers[0..2].to_vec(),
+                signers.clone(),
+                &fake_data::protocol_parameters(),
+            )
+            .await;
+        dependency_manager
+            .multi_signer
+            .write()
+            .await
+            .update_current_beacon(beacon.clone())
+            .await
+            .unwrap();
+
+        let method = Method::GET.as_str();
+        let path = "/stake-distribution";
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{}{}", SERVER_BASE_PATH, path))
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        let stake_distribution: SigningStakeDistribution =
+            serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(beacon.epoch, stake_distribution.epoch);
+        assert_eq!(2, stake_distribution.stake_distribution.len());
+        assert_eq!(3, stake_distribution.next_stake_distribution.len());
+        assert!(stake_distribution.aggregate_verification_key.is_some());
+        assert!(stake_distribution.next_aggregate_verification_key.is_some());
+
+        APISpec::from_file(API_SPEC_FILE)
+            .method(method)
+            .path(path)
+            .validate_request(&Null)
+            .unwrap()
+            .validate_response(&response)
+            .expect("OpenAPI error");
+    }
+
+    #[tokio::test]
+    async fn test_stake_distribution_get_ko_500() {
+        let mut mock_multi_signer = MockMultiSigner::new();
+        mock_multi_signer
+            .expect_get_current_beacon()
+            .return_once(|| None);
+        let (mut dependency_manager, _) = initialize_dependencies().await;
+        dependency_manager.multi_signer = Arc::new(RwLock::new(mock_multi_signer));
+
+        let method = Method::GET.as_str();
+        let path = "/stake-distribution";
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{}{}", SERVER_BASE_PATH, path))
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, response.status());
+        APISpec::from_file(API_SPEC_FILE)
+            .method(method)
+            .path(path)
+            .validate_request(&Null)
+            .unwrap()
+            .validate_response(&response)
+            .expect("OpenAPI error");
+    }
+}
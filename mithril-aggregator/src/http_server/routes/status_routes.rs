@@ -0,0 +1,252 @@
+use crate::http_server::routes::middlewares;
+use crate::DependencyManager;
+use std::sync::Arc;
+use warp::Filter;
+
+pub fn routes(
+    dependency_manager: Arc<DependencyManager>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    status(dependency_manager)
+}
+
+/// GET /status
+fn status(
+    dependency_manager: Arc<DependencyManager>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("status")
+        .and(warp::get())
+        .and(middlewares::with_runtime_status(dependency_manager.clone()))
+        .and(middlewares::with_certificate_pending_store(
+            dependency_manager.clone(),
+        ))
+        .and(middlewares::with_certificate_store(
+            dependency_manager.clone(),
+        ))
+        .and(middlewares::with_single_signature_store(dependency_manager))
+        .and_then(handlers::status)
+}
+
+mod handlers {
+    use crate::dependency::RuntimeStatusWrapper;
+    use crate::http_server::routes::reply;
+    use crate::store::SingleSignatureStorer;
+    use crate::{CertificatePendingStore, CertificateStore, SingleSignatureStore};
+    use mithril_common::entities::{
+        AggregatorStatus, LastCertificateStatus, PendingCertificateStatus, SingleSignatures,
+    };
+    use mithril_common::store::StoreError;
+    use slog_scope::{debug, warn};
+    use std::convert::Infallible;
+    use std::sync::Arc;
+    use warp::http::StatusCode;
+
+    /// Status
+    pub async fn status(
+        runtime_status: RuntimeStatusWrapper,
+        certificate_pending_store: Arc<CertificatePendingStore>,
+        certificate_store: Arc<CertificateStore>,
+        single_signature_store: Arc<SingleSignatureStore>,
+    ) -> Result<impl warp::Reply, Infallible> {
+        debug!("⇄ HTTP SERVER: status");
+
+        match build_status(
+            runtime_status,
+            certificate_pending_store,
+            certificate_store,
+            single_signature_store,
+        )
+        .await
+        {
+            Ok(status) => Ok(reply::json(&status, StatusCode::OK)),
+            Err(err) => {
+                warn!("status::error"; "error" => ?err);
+                Ok(reply::internal_server_error(err.to_string()))
+            }
+        }
+    }
+
+    /// The health of the chain observer is the one recorded by the runtime the last time it
+    /// read the chain, the node is not queried for each request.
+    async fn build_status(
+        runtime_status: RuntimeStatusWrapper,
+        certificate_pending_store: Arc<CertificatePendingStore>,
+        certificate_store: Arc<CertificateStore>,
+        single_signature_store: Arc<SingleSignatureStore>,
+    ) -> Result<AggregatorStatus, StoreError> {
+        let runtime_status = runtime_status.read().await.clone();
+
+        let pending_certificate = match certificate_pending_store.get().await? {
+            Some(certificate_pending) => {
+                let single_signatures = single_signature_store
+                    .get_single_signatures(&certificate_pending.get_signed_entity_type())
+                    .await?
+                    .unwrap_or_default();
+                let won_lotteries =
+                    SingleSignatures::count_won_lotteries(single_signatures.values());
+
+                Some(PendingCertificateStatus {
+                    signed_entity_type: certificate_pending.get_signed_entity_type(),
+                    signers: certificate_pending.signers.len() as u64,
+                    signatures: single_signatures.len() as u64,
+                    won_lotteries,
+                    quorum: certificate_pending.protocol_parameters.k,
+                    beacon: certificate_pending.beacon,
+                })
+            }
+            None => None,
+        };
+
+        let last_certificate =
+            certificate_store
+                .get_list(1)
+                .await?
+                .into_iter()
+                .next()
+                .map(|certificate| LastCertificateStatus {
+                    hash: certificate.hash,
+                    beacon: certificate.beacon,
+                    sealed_at: certificate.metadata.sealed_at,
+                });
+
+        Ok(AggregatorStatus {
+            state: runtime_status.state,
+            beacon: runtime_status.beacon,
+            pending_certificate,
+            last_certificate,
+            chain_observer: runtime_status.chain_observer,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    const API_SPEC_FILE: &str = "../openapi.yaml";
+
+    use crate::http_server::SERVER_BASE_PATH;
+    use crate::initialize_dependencies;
+    use crate::store::SingleSignatureStorer;
+    use crate::RuntimeStatus;
+    use mithril_common::apispec::APISpec;
+    use mithril_common::entities::{AggregatorStatus, ChainObserverStatus};
+    use mithril_common::fake_data;
+    use serde_json::Value::Null;
+    use warp::http::{Method, StatusCode};
+    use warp::test::request;
+
+    use super::*;
+
+    fn setup_router(
+        dependency_manager: Arc<DependencyManager>,
+    ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+        let cors = warp::cors()
+            .allow_any_origin()
+            .allow_headers(vec!["content-type"])
+            .allow_methods(vec![Method::GET, Method::POST, Method::OPTIONS]);
+
+        warp::any()
+            .and(warp::path(SERVER_BASE_PATH))
+            .and(routes(dependency_manager).with(cors))
+    }
+
+    #[tokio::test]
+    async fn test_status_get_ok_when_idle() {
+        let (dependency_manager, _) = initialize_dependencies().await;
+
+        let method = Method::GET.as_str();
+        let path = "/status";
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{}{}", SERVER_BASE_PATH, path))
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        let status: AggregatorStatus = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!("idle", status.state);
+        assert_eq!(None, status.pending_certificate);
+        assert_eq!(None, status.last_certificate);
+        assert!(
+            !status.chain_observer.healthy,
+            "the chain observer health is unknown until the runtime reads the chain"
+        );
+
+        APISpec::from_file(API_SPEC_FILE)
+            .method(method)
+            .path(path)
+            .validate_request(&Null)
+            .unwrap()
+            .validate_response(&response)
+            .expect("OpenAPI error");
+    }
+
+    #[tokio::test]
+    async fn test_status_get_ok_when_signing() {
+        let (dependency_manager, _) = initialize_dependencies().await;
+        let certificate_pending = fake_data::certificate_pending();
+        let beacon = certificate_pending.beacon.clone();
+        let chain_observer = ChainObserverStatus {
+            healthy: true,
+            epoch: Some(beacon.epoch),
+            error: None,
+        };
+        *dependency_manager.runtime_status.write().await = RuntimeStatus {
+            state: "signing".to_string(),
+            beacon: Some(beacon.clone()),
+            chain_observer: chain_observer.clone(),
+        };
+        dependency_manager
+            .certificate_pending_store
+            .save(certificate_pending.clone())
+            .await
+            .unwrap();
+        for (party_id, won_indexes) in [("1", vec![1, 4]), ("2", vec![4, 7])] {
+            let mut single_signatures = fake_data::single_signatures(won_indexes);
+            single_signatures.party_id = party_id.to_string();
+            dependency_manager
+                .single_signature_store
+                .save_single_signatures(
+                    &certificate_pending.get_signed_entity_type(),
+                    &single_signatures,
+                )
+                .await
+                .unwrap();
+        }
+        let certificate = fake_data::certificate("certificate-hash".to_string());
+        dependency_manager
+            .certificate_store
+            .save(certificate.clone())
+            .await
+            .unwrap();
+
+        let method = Method::GET.as_str();
+        let path = "/status";
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{}{}", SERVER_BASE_PATH, path))
+            .reply(&setup_router(Arc::new(dependency_manager)))
+            .await;
+
+        assert_eq!(StatusCode::OK, response.status());
+        let status: AggregatorStatus = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!("signing", status.state);
+        assert_eq!(Some(beacon), status.beacon);
+        let pending_certificate = status.pending_certificate.unwrap();
+        assert_eq!(2, pending_certificate.signatures);
+        assert_eq!(3, pending_certificate.won_lotteries);
+        assert_eq!(
+            certificate_pending.protocol_parameters.k,
+            pending_certificate.quorum
+        );
+        assert_eq!(certificate.hash, status.last_certificate.unwrap().hash);
+        assert_eq!(chain_observer, status.chain_observer);
+
+        APISpec::from_file(API_SPEC_FILE)
+            .method(method)
+            .path(path)
+            .validate_request(&Null)
+            .unwrap()
+            .validate_response(&response)
+            .expect("OpenAPI error");
+    }
+}
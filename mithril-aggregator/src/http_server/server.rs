@@ -6,6 +6,7 @@ use std::sync::Arc;
 use warp::Future;
 
 pub const SERVER_BASE_PATH: &str = "aggregator";
+pub const ADMIN_BASE_PATH: &str = "admin";
 
 /// Server
 pub struct Server {
@@ -11,10 +11,14 @@
 //! signed certificates.
 //! You can find more information on how it works reading the [documentation website](https://mithril.network/doc/mithril/mithril-network/aggregator).
 
+mod audit_log;
+mod certificate_bundle_cache;
 mod command_args;
 mod configuration;
 mod dependency;
+mod event_bus;
 mod http_server;
+mod metrics;
 mod multi_signer;
 mod runtime;
 mod snapshot_stores;
@@ -24,22 +28,32 @@ mod store;
 mod tools;
 
 pub use crate::configuration::{
-    Configuration, DefaultConfiguration, GenesisConfiguration, SnapshotStoreType,
-    SnapshotUploaderType,
+    AdminToken, Configuration, DefaultConfiguration, GcpAclPolicy, GenesisConfiguration,
+    SnapshotStoreType, SnapshotUploaderType,
 };
 pub use crate::multi_signer::{MultiSigner, MultiSignerImpl, ProtocolError};
 pub use crate::snapshot_stores::{LocalSnapshotStore, RemoteSnapshotStore, SnapshotStore};
+pub use audit_log::{AuditLog, AuditLogEntry, AuditLogError};
+pub use certificate_bundle_cache::CertificateBundleCache;
 pub use command_args::MainOpts;
 pub use dependency::DependencyManager;
+pub use event_bus::EventBus;
 pub use http_server::Server;
-pub use runtime::{AggregatorConfig, AggregatorRunner, AggregatorRunnerTrait, AggregatorRuntime};
+pub use metrics::{MetricsService, MetricsServiceError};
+pub use runtime::{
+    AggregatorConfig, AggregatorRunner, AggregatorRunnerTrait, AggregatorRuntime, AggregatorState,
+    RuntimeControl, RuntimeStatus,
+};
 pub use snapshot_uploaders::{
     DumbSnapshotUploader, LocalSnapshotUploader, RemoteSnapshotUploader, SnapshotUploader,
 };
-pub use snapshotter::{DumbSnapshotter, GzipSnapshotter, SnapshotError, Snapshotter};
+pub use snapshotter::{
+    DumbSnapshotter, GzipSnapshotter, SnapshotError, Snapshotter, ZstandardSnapshotter,
+};
 pub use store::{
-    CertificatePendingStore, CertificateStore, ProtocolParametersStore, ProtocolParametersStorer,
-    SingleSignatureStore, VerificationKeyStore, VerificationKeyStorer,
+    CertificateFilter, CertificatePendingStore, CertificateStore, FailedRoundStore,
+    ProtocolParametersStore, ProtocolParametersStorer, RuntimeState, RuntimeStateStore,
+    SignerStatisticsStore, SingleSignatureStore, VerificationKeyStore, VerificationKeyStorer,
 };
 
 #[cfg(test)]
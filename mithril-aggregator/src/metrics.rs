@@ -0,0 +1,237 @@
+use prometheus::{
+    Encoder, Histogram, HistogramOpts, IntCounter, IntCounterVec, IntGauge, Opts, Registry,
+    TextEncoder,
+};
+use std::time::Duration;
+use thiserror::Error;
+
+/// Prefix of the names of the metrics exposed by the aggregator
+const METRICS_PREFIX: &str = "mithril_aggregator";
+
+/// [MetricsService] related errors.
+#[derive(Error, Debug)]
+pub enum MetricsServiceError {
+    /// Error raised when a metric can not be created or registered
+    #[error("metric registration error: {0}")]
+    Registration(#[from] prometheus::Error),
+
+    /// Error raised when the metrics can not be encoded
+    #[error("metrics encoding error: {0}")]
+    Encoding(String),
+}
+
+/// Service that collects the metrics of the aggregator and exports them in the
+/// Prometheus text format
+pub struct MetricsService {
+    registry: Registry,
+    runtime_state_transitions: IntCounterVec,
+    runtime_cycle_duration: Histogram,
+    runtime_cycle_errors: IntCounter,
+    digest_computation_duration: Histogram,
+    snapshot_size: IntGauge,
+    snapshot_upload_duration: Histogram,
+    signer_registrations: IntCounter,
+    single_signatures_received: IntCounter,
+    single_signatures_rejected: IntCounter,
+    quorum_won_lotteries: IntGauge,
+    quorum_required_lotteries: IntGauge,
+    certificates_produced: IntCounterVec,
+}
+
+impl MetricsService {
+    /// MetricsService factory
+    pub fn new() -> Result<Self, MetricsServiceError> {
+        let registry = Registry::new_custom(Some(METRICS_PREFIX.to_string()), None)?;
+
+        let runtime_state_transitions = IntCounterVec::new(
+            Opts::new(
+                "runtime_state_transitions_total",
+                "Number of transitions between the states of the runtime",
+            ),
+            &["from", "to"],
+        )?;
+        registry.register(Box::new(runtime_state_transitions.clone()))?;
+        let runtime_cycle_duration = Histogram::with_opts(HistogramOpts::new(
+            "runtime_cycle_duration_seconds",
+            "Duration of the cycles of the runtime",
+        ))?;
+        registry.register(Box::new(runtime_cycle_duration.clone()))?;
+        let runtime_cycle_errors = IntCounter::new(
+            "runtime_cycle_errors_total",
+            "Number of cycles of the runtime that ended with an error",
+        )?;
+        registry.register(Box::new(runtime_cycle_errors.clone()))?;
+        let digest_computation_duration = Histogram::with_opts(HistogramOpts::new(
+            "digest_computation_duration_seconds",
+            "Duration of the computation of the immutable files digest",
+        ))?;
+        registry.register(Box::new(digest_computation_duration.clone()))?;
+        let snapshot_size = IntGauge::new(
+            "snapshot_size_bytes",
+            "Size of the last snapshot archive created",
+        )?;
+        registry.register(Box::new(snapshot_size.clone()))?;
+        let snapshot_upload_duration = Histogram::with_opts(
+            HistogramOpts::new(
+                "snapshot_upload_duration_seconds",
+                "Duration of the upload of the snapshot archives",
+            )
+            .buckets(vec![
+                1.0, 5.0, 15.0, 30.0, 60.0, 120.0, 300.0, 600.0, 1800.0,
+            ]),
+        )?;
+        registry.register(Box::new(snapshot_upload_duration.clone()))?;
+        let signer_registrations = IntCounter::new(
+            "signer_registrations_total",
+            "Number of successful signer registrations",
+        )?;
+        registry.register(Box::new(signer_registrations.clone()))?;
+        let single_signatures_received = IntCounter::new(
+            "single_signatures_received_total",
+            "Number of single signatures received",
+        )?;
+        registry.register(Box::new(single_signatures_received.clone()))?;
+        let single_signatures_rejected = IntCounter::new(
+            "single_signatures_rejected_total",
+            "Number of single signatures rejected",
+        )?;
+        registry.register(Box::new(single_signatures_rejected.clone()))?;
+        let quorum_won_lotteries = IntGauge::new(
+            "quorum_won_lotteries",
+            "Number of distinct lotteries won by the single signatures of the pending certificate",
+        )?;
+        registry.register(Box::new(quorum_won_lotteries.clone()))?;
+        let quorum_required_lotteries = IntGauge::new(
+            "quorum_required_lotteries",
+            "Number of lotteries required to create the multi-signature of the pending certificate",
+        )?;
+        registry.register(Box::new(quorum_required_lotteries.clone()))?;
+        let certificates_produced = IntCounterVec::new(
+            Opts::new(
+                "certificates_produced_total",
+                "Number of certificates produced",
+            ),
+            &["signed_entity_type"],
+        )?;
+        registry.register(Box::new(certificates_produced.clone()))?;
+
+        Ok(Self {
+            registry,
+            runtime_state_transitions,
+            runtime_cycle_duration,
+            runtime_cycle_errors,
+            digest_computation_duration,
+            snapshot_size,
+            snapshot_upload_duration,
+            signer_registrations,
+            single_signatures_received,
+            single_signatures_rejected,
+            quorum_won_lotteries,
+            quorum_required_lotteries,
+            certificates_produced,
+        })
+    }
+
+    /// Record a transition between two states of the runtime
+    pub fn record_state_transition(&self, from: &str, to: &str) {
+        self.runtime_state_transitions
+            .with_label_values(&[from, to])
+            .inc();
+    }
+
+    /// Record the duration of a cycle of the runtime
+    pub fn observe_cycle_duration(&self, duration: Duration) {
+        self.runtime_cycle_duration.observe(duration.as_secs_f64());
+    }
+
+    /// Record a cycle of the runtime that ended with an error
+    pub fn increment_cycle_errors(&self) {
+        self.runtime_cycle_errors.inc();
+    }
+
+    /// Record the duration of a digest computation
+    pub fn observe_digest_computation_duration(&self, duration: Duration) {
+        self.digest_computation_duration
+            .observe(duration.as_secs_f64());
+    }
+
+    /// Record the size of the last snapshot archive
+    pub fn set_snapshot_size(&self, size: u64) {
+        self.snapshot_size
+            .set(i64::try_from(size).unwrap_or(i64::MAX));
+    }
+
+    /// Record the duration of a snapshot archive upload
+    pub fn observe_snapshot_upload_duration(&self, duration: Duration) {
+        self.snapshot_upload_duration
+            .observe(duration.as_secs_f64());
+    }
+
+    /// Record a successful signer registration
+    pub fn increment_signer_registrations(&self) {
+        self.signer_registrations.inc();
+    }
+
+    /// Record a received single signature
+    pub fn increment_single_signatures_received(&self) {
+        self.single_signatures_received.inc();
+    }
+
+    /// Record a rejected single signature
+    pub fn increment_single_signatures_rejected(&self) {
+        self.single_signatures_rejected.inc();
+    }
+
+    /// Record the progress of the quorum of the pending certificate
+    pub fn set_quorum_progress(&self, won_lotteries: u64, required_lotteries: u64) {
+        self.quorum_won_lotteries
+            .set(i64::try_from(won_lotteries).unwrap_or(i64::MAX));
+        self.quorum_required_lotteries
+            .set(i64::try_from(required_lotteries).unwrap_or(i64::MAX));
+    }
+
+    /// Record a certificate produced for the given kind of signed entity
+    pub fn increment_certificates_produced(&self, signed_entity_type: &str) {
+        self.certificates_produced
+            .with_label_values(&[signed_entity_type])
+            .inc();
+    }
+
+    /// Export the metrics in the Prometheus text format
+    pub fn export(&self) -> Result<String, MetricsServiceError> {
+        let mut buffer = vec![];
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .map_err(|e| MetricsServiceError::Encoding(e.to_string()))?;
+
+        String::from_utf8(buffer).map_err(|e| MetricsServiceError::Encoding(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_metrics() {
+        let metrics_service = MetricsService::new().unwrap();
+        metrics_service.record_state_transition("idle", "ready");
+        metrics_service.increment_single_signatures_received();
+        metrics_service.increment_single_signatures_received();
+        metrics_service.set_quorum_progress(3, 5);
+        metrics_service.increment_certificates_produced("CardanoImmutableFilesFull");
+
+        let exported = metrics_service.export().unwrap();
+
+        assert!(exported.contains(
+            "mithril_aggregator_runtime_state_transitions_total{from=\"idle\",to=\"ready\"} 1"
+        ));
+        assert!(exported.contains("mithril_aggregator_single_signatures_received_total 2"));
+        assert!(exported.contains("mithril_aggregator_single_signatures_rejected_total 0"));
+        assert!(exported.contains("mithril_aggregator_quorum_won_lotteries 3"));
+        assert!(exported.contains("mithril_aggregator_quorum_required_lotteries 5"));
+        assert!(exported.contains(
+            "mithril_aggregator_certificates_produced_total{signed_entity_type=\"CardanoImmutableFilesFull\"} 1"
+        ));
+    }
+}
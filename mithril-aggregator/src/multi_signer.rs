@@ -23,7 +23,8 @@ use mithril_common::{
 
 use crate::store::{SingleSignatureStorer, VerificationKeyStorer};
 use crate::{
-    ProtocolParametersStore, ProtocolParametersStorer, SingleSignatureStore, VerificationKeyStore,
+    ProtocolParametersStore, ProtocolParametersStorer, SignerStatisticsStore, SingleSignatureStore,
+    VerificationKeyStore,
 };
 
 #[cfg(test)]
@@ -259,6 +260,9 @@ pub struct MultiSignerImpl {
 
     /// Chain observer
     chain_observer: Arc<dyn ChainObserver>,
+
+    /// Signer statistics store, the participation of the signers is not recorded if not set
+    signer_statistics_store: Option<Arc<SignerStatisticsStore>>,
 }
 
 impl MultiSignerImpl {
@@ -283,6 +287,67 @@ impl MultiSignerImpl {
             single_signature_store,
             protocol_parameters_store,
             chain_observer,
+            signer_statistics_store: None,
+        }
+    }
+
+    /// Record the participation of the signers in the given store.
+    pub fn with_signer_statistics_store(
+        mut self,
+        signer_statistics_store: Arc<SignerStatisticsStore>,
+    ) -> Self {
+        self.signer_statistics_store = Some(signer_statistics_store);
+        self
+    }
+
+    /// Entity signed by the given message at the given beacon, the single signatures are
+    /// stored per signed entity.
+    ///
+    /// A message that does not tell its signed entity certifies a snapshot, as a certificate
+    /// pending without signed entity type does.
+    fn signed_entity_type(
+        message: &entities::ProtocolMessage,
+        beacon: &entities::Beacon,
+    ) -> entities::SignedEntityType {
+        message.get_signed_entity_type(beacon).unwrap_or_else(|| {
+            entities::SignedEntityType::CardanoImmutableFilesFull(beacon.clone())
+        })
+    }
+
+    /// Record the registration of a signer in the signer statistics store if any.
+    ///
+    /// Statistics are informative only: a failure is logged and does not fail the registration.
+    async fn record_signer_registration(&self, epoch: entities::Epoch, party_id: &PartyId) {
+        if let Some(signer_statistics_store) = &self.signer_statistics_store {
+            if let Err(error) = signer_statistics_store
+                .record_registration(epoch, party_id)
+                .await
+            {
+                warn!("could not record the signer registration statistics"; "party_id" => party_id, "error" => ?error);
+            }
+        }
+    }
+
+    /// Record a single signature in the signer statistics store if any, with its delay since
+    /// the current message started to be signed.
+    ///
+    /// Statistics are informative only: a failure is logged and does not fail the registration.
+    async fn record_single_signature_statistics(
+        &self,
+        epoch: entities::Epoch,
+        signatures: &entities::SingleSignatures,
+    ) {
+        if let Some(signer_statistics_store) = &self.signer_statistics_store {
+            let latency_ms = self
+                .current_initiated_at
+                .map(|initiated_at| (Utc::now() - initiated_at).num_milliseconds().max(0) as u64)
+                .unwrap_or_default();
+            if let Err(error) = signer_statistics_store
+                .record_single_signature(epoch, signatures, latency_ms)
+                .await
+            {
+                warn!("could not record the single signature statistics"; "party_id" => &signatures.party_id, "error" => ?error);
+            }
         }
     }
 
@@ -577,7 +642,7 @@ impl MultiSigner for MultiSignerImpl {
             verification_key,
         )?;
         let mut signer_save = signer.to_owned();
-        signer_save.party_id = party_id_save;
+        signer_save.party_id = party_id_save.clone();
 
         match self
             .verification_key_store
@@ -585,7 +650,13 @@ impl MultiSigner for MultiSignerImpl {
             .await?
         {
             Some(_) => Err(ProtocolError::ExistingSigner()),
-            None => Ok(()),
+            None => {
+                // The signers recorded for an epoch sign during the epoch that retrieves them.
+                let signing_epoch = epoch.offset_by(-SIGNER_EPOCH_RETRIEVAL_OFFSET)?;
+                self.record_signer_registration(signing_epoch, &party_id_save)
+                    .await;
+                Ok(())
+            }
         }
     }
 
@@ -717,16 +788,21 @@ impl MultiSigner for MultiSignerImpl {
             .current_beacon
             .as_ref()
             .ok_or_else(ProtocolError::UnavailableBeacon)?;
+        let signed_entity_type = Self::signed_entity_type(message, beacon);
 
         match self
             .single_signature_store
-            .save_single_signatures(beacon, signatures)
+            .save_single_signatures(&signed_entity_type, signatures)
             .await?
         {
             Some(_) => Err(ProtocolError::ExistingSingleSignature(
                 signatures.party_id.clone(),
             )),
-            None => Ok(()),
+            None => {
+                self.record_single_signature_statistics(beacon.epoch, signatures)
+                    .await;
+                Ok(())
+            }
         }
     }
 
@@ -750,9 +826,10 @@ impl MultiSigner for MultiSignerImpl {
             .current_beacon
             .as_ref()
             .ok_or_else(ProtocolError::UnavailableBeacon)?;
+        let signed_entity_type = Self::signed_entity_type(message, beacon);
         let signatures: Vec<ProtocolSingleSignature> = self
             .single_signature_store
-            .get_single_signatures(beacon)
+            .get_single_signatures(&signed_entity_type)
             .await?
             .unwrap_or_default()
             .iter()
@@ -801,9 +878,14 @@ impl MultiSigner for MultiSignerImpl {
                 let initiated_at =
                     format!("{:?}", self.current_initiated_at.unwrap_or_else(Utc::now));
                 let sealed_at = format!("{:?}", Utc::now());
+                let protocol_message = self
+                    .get_current_message()
+                    .await
+                    .ok_or_else(ProtocolError::UnavailableMessage)?;
+                let signed_entity_type = Self::signed_entity_type(&protocol_message, &beacon);
                 let signatures_party_ids: Vec<PartyId> = self
                     .single_signature_store
-                    .get_single_signatures(&beacon)
+                    .get_single_signatures(&signed_entity_type)
                     .await?
                     .unwrap_or_default()
                     .into_iter()
@@ -822,10 +904,6 @@ impl MultiSigner for MultiSignerImpl {
                     sealed_at,
                     signers,
                 );
-                let protocol_message = self
-                    .get_current_message()
-                    .await
-                    .ok_or_else(ProtocolError::UnavailableMessage)?;
                 let aggregate_verification_key =
                     key_encode_hex(&self.avk.as_ref().unwrap()).map_err(ProtocolError::Codec)?;
                 let multi_signature =
@@ -856,7 +934,7 @@ mod tests {
     use mithril_common::crypto_helper::tests_setup::*;
     use mithril_common::fake_data;
     use mithril_common::store::adapter::MemoryAdapter;
-    use mithril_common::store::StakeStore;
+    use mithril_common::store::{RetentionPolicy, StakeStore};
 
     use std::collections::HashMap;
     use std::sync::Arc;
@@ -868,7 +946,7 @@ mod tests {
                 None,
             )
             .unwrap(),
-        ), None);
+        ), RetentionPolicy::KeepAll);
         let stake_store = StakeStore::new(
             Box::new(
                 MemoryAdapter::<entities::Epoch, HashMap<entities::PartyId, entities::Stake>>::new(
@@ -876,17 +954,17 @@ mod tests {
                 )
                 .unwrap(),
             ),
-            None,
+            RetentionPolicy::KeepAll,
         );
         let single_signature_store = SingleSignatureStore::new(
             Box::new(
                 MemoryAdapter::<
-                    entities::Beacon,
+                    entities::SignedEntityType,
                     HashMap<entities::PartyId, entities::SingleSignatures>,
                 >::new(None)
                 .unwrap(),
             ),
-            None,
+            RetentionPolicy::KeepAll,
         );
         let protocol_parameters_store = ProtocolParametersStore::new(
             Box::new(
@@ -905,7 +983,7 @@ mod tests {
                 ]))
                 .unwrap(),
             ),
-            None,
+            RetentionPolicy::KeepAll,
         );
         let chain_observer = FakeObserver::default();
         let mut multi_signer = MultiSignerImpl::new(
@@ -1107,6 +1185,92 @@ mod tests {
         assert_eq!(signers_all_expected, signers_all);
     }
 
+    #[tokio::test]
+    async fn test_multi_signer_records_signer_statistics() {
+        let signer_statistics_store = Arc::new(SignerStatisticsStore::new(
+            Box::new(MemoryAdapter::new(None).unwrap()),
+            RetentionPolicy::KeepAll,
+        ));
+        let mut multi_signer = setup_multi_signer()
+            .await
+            .with_signer_statistics_store(signer_statistics_store.clone());
+
+        let protocol_parameters = setup_protocol_parameters();
+        multi_signer
+            .update_protocol_parameters(&protocol_parameters)
+            .await
+            .expect("update protocol parameters failed");
+        let signers = setup_signers(5, &protocol_parameters);
+        let stake_distribution = &signers
+            .iter()
+            .map(|(signer_with_stake, _, _)| {
+                (
+                    signer_with_stake.party_id.to_owned(),
+                    signer_with_stake.stake,
+                )
+            })
+            .collect::<_>();
+        multi_signer
+            .update_stake_distribution(stake_distribution)
+            .await
+            .expect("update stake distribution failed");
+        for (signer_with_stake, _, _) in &signers {
+            multi_signer
+                .register_signer(&signer_with_stake.to_owned().into())
+                .await
+                .expect("register should have succeeded")
+        }
+
+        offset_epoch(
+            &mut multi_signer,
+            (SIGNER_EPOCH_RECORDING_OFFSET - SIGNER_EPOCH_RETRIEVAL_OFFSET) as i64,
+        )
+        .await;
+        let epoch = multi_signer.get_current_beacon().await.unwrap().epoch;
+        let message = setup_message();
+        multi_signer
+            .update_current_message(message.clone())
+            .await
+            .expect("update current message failed");
+        let (signer_with_stake, protocol_signer, _) = signers
+            .iter()
+            .find(|(_, protocol_signer, _)| {
+                protocol_signer
+                    .sign(message.compute_hash().as_bytes())
+                    .is_some()
+            })
+            .expect("at least one signer should win a lottery");
+        let signature = protocol_signer
+            .sign(message.compute_hash().as_bytes())
+            .unwrap();
+        let won_lotteries = signature.indexes.len() as u64;
+        multi_signer
+            .register_single_signature(&entities::SingleSignatures::new(
+                signer_with_stake.party_id.to_owned(),
+                key_encode_hex(&signature).unwrap(),
+                signature.indexes.clone(),
+            ))
+            .await
+            .expect("register single signature should not fail");
+
+        let signer_statistics = signer_statistics_store
+            .get_list(Some(&signer_with_stake.party_id), Some(epoch))
+            .await
+            .unwrap();
+        assert_eq!(1, signer_statistics.len());
+        assert!(signer_statistics[0].registered);
+        assert_eq!(1, signer_statistics[0].signatures_sent);
+        assert_eq!(won_lotteries, signer_statistics[0].won_lotteries);
+        assert_eq!(
+            signers.len(),
+            signer_statistics_store
+                .get_list(None, Some(epoch))
+                .await
+                .unwrap()
+                .len()
+        );
+    }
+
     #[tokio::test]
     async fn test_multi_signer_multi_signature_ok() {
         let beacon = fake_data::beacon();
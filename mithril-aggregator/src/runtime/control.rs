@@ -0,0 +1,121 @@
+use mithril_common::entities::Beacon;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tokio::sync::Notify;
+use tokio::time::{sleep, Duration};
+
+/// RuntimeControl holds the operations requested to the runtime from outside of it, ie: by
+/// the admin API, the runtime applies them at its next cycle.
+#[derive(Debug, Default)]
+pub struct RuntimeControl {
+    signing_paused: AtomicBool,
+    new_pending_certificate_requested: AtomicBool,
+    failed_snapshot_upload: Mutex<Option<Beacon>>,
+    wake_up: Notify,
+}
+
+impl RuntimeControl {
+    /// RuntimeControl factory
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Prevent the runtime from creating new pending certificates.
+    pub fn pause_signing(&self) {
+        self.signing_paused.store(true, Ordering::SeqCst);
+    }
+
+    /// Allow the runtime to create new pending certificates again.
+    pub fn resume_signing(&self) {
+        self.signing_paused.store(false, Ordering::SeqCst);
+        self.wake_up();
+    }
+
+    /// Is the creation of new pending certificates paused
+    pub fn is_signing_paused(&self) -> bool {
+        self.signing_paused.load(Ordering::SeqCst)
+    }
+
+    /// Ask the runtime to drop its pending certificate and create a new one.
+    pub fn request_new_pending_certificate(&self) {
+        self.new_pending_certificate_requested
+            .store(true, Ordering::SeqCst);
+        self.wake_up();
+    }
+
+    /// Return whether a new pending certificate has been requested and clear the request.
+    pub fn take_new_pending_certificate_request(&self) -> bool {
+        self.new_pending_certificate_requested
+            .swap(false, Ordering::SeqCst)
+    }
+
+    /// Record that the upload of the snapshot of the given beacon failed.
+    pub fn record_failed_snapshot_upload(&self, beacon: Beacon) {
+        *self.failed_snapshot_upload.lock().unwrap() = Some(beacon);
+    }
+
+    /// Clear the failed snapshot upload once an upload succeeded.
+    pub fn clear_failed_snapshot_upload(&self) {
+        *self.failed_snapshot_upload.lock().unwrap() = None;
+    }
+
+    /// Return the beacon of the last snapshot whose upload failed, if any.
+    pub fn get_failed_snapshot_upload(&self) -> Option<Beacon> {
+        self.failed_snapshot_upload.lock().unwrap().clone()
+    }
+
+    /// Ask the runtime to run its next cycle without waiting for the end of its sleep.
+    pub fn wake_up(&self) {
+        self.wake_up.notify_one();
+    }
+
+    /// Sleep for the given duration or until the runtime is woken up.
+    pub async fn sleep(&self, duration: Duration) {
+        tokio::select! {
+            _ = sleep(duration) => {},
+            _ = self.wake_up.notified() => {},
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_pending_certificate_request_is_taken_once() {
+        let runtime_control = RuntimeControl::new();
+        runtime_control.request_new_pending_certificate();
+
+        assert!(runtime_control.take_new_pending_certificate_request());
+        assert!(!runtime_control.take_new_pending_certificate_request());
+    }
+
+    #[test]
+    fn failed_snapshot_upload_is_kept_until_cleared() {
+        let runtime_control = RuntimeControl::new();
+        assert_eq!(None, runtime_control.get_failed_snapshot_upload());
+
+        runtime_control.record_failed_snapshot_upload(Beacon::new("devnet".to_string(), 1, 2));
+        assert_eq!(
+            Some(Beacon::new("devnet".to_string(), 1, 2)),
+            runtime_control.get_failed_snapshot_upload()
+        );
+
+        runtime_control.clear_failed_snapshot_upload();
+        assert_eq!(None, runtime_control.get_failed_snapshot_upload());
+    }
+
+    #[tokio::test]
+    async fn sleep_ends_when_woken_up() {
+        let runtime_control = RuntimeControl::new();
+        runtime_control.wake_up();
+
+        tokio::time::timeout(
+            Duration::from_secs(5),
+            runtime_control.sleep(Duration::from_secs(3600)),
+        )
+        .await
+        .expect("The sleep should have been interrupted by the wake up");
+    }
+}
@@ -1,7 +1,9 @@
+mod control;
 mod error;
 mod runner;
 mod state_machine;
 
+pub use control::RuntimeControl;
 pub use error::RuntimeError;
 pub use runner::{AggregatorConfig, AggregatorRunner, AggregatorRunnerTrait};
 pub use state_machine::*;
@@ -1,25 +1,32 @@
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use mithril_common::entities::Epoch;
-use slog_scope::{debug, info, warn};
+use slog_scope::{debug, error, info, warn};
+use std::collections::BTreeSet;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
 
+use mithril_common::certificate_chain::GenesisKeyRotation;
 use mithril_common::crypto_helper::ProtocolStakeDistribution;
 use mithril_common::entities::{
-    Beacon, Certificate, CertificatePending, ProtocolMessage, ProtocolMessagePartKey, Snapshot,
+    Beacon, Certificate, CertificatePending, FailedRound, ProtocolMessage, ProtocolMessagePartKey,
+    SignedEntityKind, SignedEntityType, SingleSignatures, Snapshot, SnapshotManifest,
 };
+use mithril_common::store::StorePruner;
 use mithril_common::CardanoNetwork;
 
 use crate::snapshot_uploaders::SnapshotLocation;
 use crate::snapshotter::OngoingSnapshot;
+use crate::store::{RuntimeState, SingleSignatureStorer};
 use crate::{DependencyManager, ProtocolError, SnapshotError};
 
 #[cfg(test)]
 use mockall::automock;
 
-use super::RuntimeError;
+use super::{AggregatorState, RuntimeError};
 
 /// Configuration structure dedicated to the AggregatorRuntime.
 #[derive(Debug, Clone)]
@@ -32,17 +39,66 @@ pub struct AggregatorConfig {
 
     /// DB directory to snapshot
     pub db_directory: PathBuf,
+
+    /// Kinds of entities to certify, in the order in which they are certified
+    pub signed_entity_kinds: BTreeSet<SignedEntityKind>,
+
+    /// Time after which a pending certificate expires, never if not set
+    pub pending_certificate_timeout: Option<Duration>,
+
+    /// Maximum number of incremental snapshots created after a full snapshot, only full
+    /// snapshots are created if zero
+    pub snapshot_max_increments: u64,
+
+    /// Size in Bytes of the chunks the snapshot archives are split into, the snapshot
+    /// archives are uploaded as a single file if not set
+    pub snapshot_chunk_size: Option<u64>,
 }
 
 impl AggregatorConfig {
-    /// Create a new instance of AggregatorConfig.
+    /// Create a new instance of AggregatorConfig that certifies the full Cardano immutable
+    /// files.
     pub fn new(interval: u64, network: CardanoNetwork, db_directory: &Path) -> Self {
         Self {
             interval,
             network,
             db_directory: db_directory.to_path_buf(),
+            signed_entity_kinds: BTreeSet::from([SignedEntityKind::CardanoImmutableFilesFull]),
+            pending_certificate_timeout: None,
+            snapshot_max_increments: 0,
+            snapshot_chunk_size: None,
         }
     }
+
+    /// Set the kinds of entities to certify.
+    pub fn with_signed_entity_kinds(
+        mut self,
+        signed_entity_kinds: BTreeSet<SignedEntityKind>,
+    ) -> Self {
+        self.signed_entity_kinds = signed_entity_kinds;
+        self
+    }
+
+    /// Set the time after which a pending certificate expires.
+    pub fn with_pending_certificate_timeout(
+        mut self,
+        pending_certificate_timeout: Option<Duration>,
+    ) -> Self {
+        self.pending_certificate_timeout = pending_certificate_timeout;
+        self
+    }
+
+    /// Set the maximum number of incremental snapshots created after a full snapshot.
+    pub fn with_snapshot_max_increments(mut self, snapshot_max_increments: u64) -> Self {
+        self.snapshot_max_increments = snapshot_max_increments;
+        self
+    }
+
+    /// Set the size of the chunks the snapshot archives are split into.
+    pub fn with_snapshot_chunk_size(mut self, snapshot_chunk_size: Option<u64>) -> Self {
+        self.snapshot_chunk_size = snapshot_chunk_size;
+        self
+    }
 }
 
 /// This trait is intended to allow mocking the AggregatorRunner in tests.
@@ -52,11 +108,11 @@ pub trait AggregatorRunnerTrait: Sync + Send {
     /// Return the current beacon from the chain
     async fn get_beacon_from_chain(&self) -> Result<Beacon, RuntimeError>;
 
-    /// Check if a certificate already have been issued for a given beacon.
-    async fn does_certificate_exist_for_beacon(
+    /// Return the first entity to certify at the given beacon that has no certificate yet.
+    async fn get_next_signed_entity_type(
         &self,
         beacon: &Beacon,
-    ) -> Result<bool, RuntimeError>;
+    ) -> Result<Option<SignedEntityType>, RuntimeError>;
 
     /// Check if a certificate chain is valid.
     async fn is_certificate_chain_valid(&self) -> Result<bool, RuntimeError>;
@@ -76,15 +132,25 @@ pub trait AggregatorRunnerTrait: Sync + Send {
         new_beacon: &Beacon,
     ) -> Result<(), RuntimeError>;
 
-    /// Set the message to sign in the multisigner. The digest is only one part
-    /// of the message, the next signing stake distribution must also be signed
-    /// as part of the message.
-    async fn update_message_in_multisigner(&self, digest: String) -> Result<(), RuntimeError>;
+    /// Compute the message that certifies the given entity. The entity is only one part of
+    /// the message, the next signing stake distribution must also be signed as part of the
+    /// message.
+    async fn compute_protocol_message(
+        &self,
+        signed_entity_type: &SignedEntityType,
+    ) -> Result<ProtocolMessage, RuntimeError>;
+
+    /// Set the message to sign in the multisigner.
+    async fn update_message_in_multisigner(
+        &self,
+        protocol_message: ProtocolMessage,
+    ) -> Result<(), RuntimeError>;
 
     /// Return the actual pending certificate from the multisigner.
     async fn create_new_pending_certificate_from_multisigner(
         &self,
         beacon: Beacon,
+        signed_entity_type: SignedEntityType,
     ) -> Result<CertificatePending, RuntimeError>;
 
     /// Store the given pending certificate.
@@ -99,14 +165,40 @@ pub trait AggregatorRunnerTrait: Sync + Send {
     /// Check if the multisigner has issued a multi-signature.
     async fn is_multisig_created(&self) -> Result<bool, RuntimeError>;
 
-    /// Create an archive of the cardano node db directory naming it after the given beacon.
+    /// Check if the pending certificate created at the given time has expired.
+    fn is_pending_certificate_expired(&self, started_at: &DateTime<Utc>) -> bool;
+
+    /// Save the failed round of the given expired pending certificate, with the single
+    /// signatures received for it.
+    async fn record_failed_round(
+        &self,
+        certificate_pending: &CertificatePending,
+        started_at: &DateTime<Utc>,
+    ) -> Result<FailedRound, RuntimeError>;
+
+    /// Create an archive of the cardano node db directory naming it after the given beacon
+    /// and the snapshot digest of the given protocol message.
+    ///
+    /// The archive only contains the immutable files added since the last snapshot if
+    /// incremental snapshots are enabled and the maximum number of increments after the last
+    /// full snapshot is not reached. The manifest of its chunks is built if the snapshot
+    /// archives are split in chunks.
     ///
     /// Returns the path of the created archive and the archive size as byte.
     async fn create_snapshot_archive(
         &self,
         beacon: &Beacon,
+        protocol_message: &ProtocolMessage,
     ) -> Result<OngoingSnapshot, RuntimeError>;
 
+    /// Remove the archive of a snapshot that will not be uploaded, ie: when its pending
+    /// certificate is dropped.
+    async fn drop_snapshot_archive(&self, ongoing_snapshot: &OngoingSnapshot);
+
+    /// Are the snapshot archives split in chunks, in which case the hash of the manifest
+    /// of their chunks is signed along the snapshot digest.
+    fn is_snapshot_chunking_enabled(&self) -> bool;
+
     /// Upload the snapshot at the given location using the configured uploader(s).
     ///
     /// **Important**: the snapshot is removed after the upload succeeded.
@@ -116,18 +208,33 @@ pub trait AggregatorRunnerTrait: Sync + Send {
     ) -> Result<Vec<SnapshotLocation>, RuntimeError>;
 
     /// Create a signed certificate.
-    async fn create_and_save_certificate(
-        &self,
-        beacon: &Beacon,
-    ) -> Result<Certificate, RuntimeError>;
+    async fn create_certificate(&self, beacon: &Beacon) -> Result<Certificate, RuntimeError>;
 
-    /// Create a snapshot and save it to the given locations.
-    async fn create_and_save_snapshot(
+    /// Create a snapshot of the given ongoing snapshot available at the given locations.
+    async fn create_snapshot(
         &self,
-        certificate: Certificate,
+        certificate: &Certificate,
         ongoing_snapshot: &OngoingSnapshot,
         remote_locations: Vec<String>,
     ) -> Result<Snapshot, RuntimeError>;
+
+    /// Save the given certificate and its snapshot if any and drop the pending certificate.
+    ///
+    /// This is done as a single unit of work: either all the stores are
+    /// updated or none of them is.
+    async fn seal_certificate(
+        &self,
+        certificate: Certificate,
+        snapshot: Option<Snapshot>,
+    ) -> Result<(), RuntimeError>;
+
+    /// Save the given state of the state machine along with the in-flight context of the
+    /// multisigner.
+    async fn save_runtime_state(&self, state: &AggregatorState) -> Result<(), RuntimeError>;
+
+    /// Restore the in-flight context of the multisigner from the last saved runtime state and
+    /// return the state of the state machine to resume from, if any.
+    async fn restore_runtime_state(&self) -> Result<Option<AggregatorState>, RuntimeError>;
 }
 
 /// The runner responsibility is to expose a code API for the state machine. It
@@ -160,18 +267,26 @@ impl AggregatorRunnerTrait for AggregatorRunner {
             .await?)
     }
 
-    async fn does_certificate_exist_for_beacon(
+    async fn get_next_signed_entity_type(
         &self,
         beacon: &Beacon,
-    ) -> Result<bool, RuntimeError> {
-        debug!("RUNNER: does_certificate_exist_for_beacon");
-        let certificate_exist = self
-            .dependencies
-            .certificate_store
-            .get_from_beacon(beacon)
-            .await?
-            .is_some();
-        Ok(certificate_exist)
+    ) -> Result<Option<SignedEntityType>, RuntimeError> {
+        debug!("RUNNER: get_next_signed_entity_type");
+        for signed_entity_kind in &self.config.signed_entity_kinds {
+            let signed_entity_type = signed_entity_kind.to_signed_entity_type(beacon);
+            let certificate_exist = self
+                .dependencies
+                .certificate_store
+                .get_from_signed_entity_type(&signed_entity_type)
+                .await?
+                .is_some();
+
+            if !certificate_exist {
+                return Ok(Some(signed_entity_type));
+            }
+        }
+
+        Ok(None)
     }
 
     async fn is_certificate_chain_valid(&self) -> Result<bool, RuntimeError> {
@@ -208,10 +323,14 @@ impl AggregatorRunnerTrait for AggregatorRunner {
         debug!(" > computing digest"; "cardano_db_directory" => self.config.db_directory.display());
 
         debug!(" > launching digester thread");
+        let started_at = Instant::now();
         let digest = digester
             .compute_digest(new_beacon)
             .await
             .map_err(|e| RuntimeError::General(e.into()))?;
+        self.dependencies
+            .metrics_service
+            .observe_digest_computation_duration(started_at.elapsed());
         debug!(" > computed digest: {}", digest);
 
         Ok(digest)
@@ -265,20 +384,48 @@ impl AggregatorRunnerTrait for AggregatorRunner {
             .await?)
     }
 
-    async fn update_message_in_multisigner(&self, digest: String) -> Result<(), RuntimeError> {
-        debug!("RUNNER: update message in multisigner");
-        let mut multi_signer = self.dependencies.multi_signer.write().await;
+    async fn compute_protocol_message(
+        &self,
+        signed_entity_type: &SignedEntityType,
+    ) -> Result<ProtocolMessage, RuntimeError> {
+        debug!("RUNNER: compute protocol message"; "signed_entity_type" => ?signed_entity_type);
         let mut protocol_message = ProtocolMessage::new();
-        protocol_message.set_message_part(ProtocolMessagePartKey::SnapshotDigest, digest);
+        match signed_entity_type {
+            SignedEntityType::MithrilStakeDistribution(epoch) => {
+                protocol_message.set_message_part(
+                    ProtocolMessagePartKey::MithrilStakeDistributionEpoch,
+                    epoch.to_string(),
+                );
+            }
+            SignedEntityType::CardanoImmutableFilesFull(beacon) => {
+                let digest = self.compute_digest(beacon).await?;
+                protocol_message.set_message_part(ProtocolMessagePartKey::SnapshotDigest, digest);
+            }
+        }
         protocol_message.set_message_part(
             ProtocolMessagePartKey::NextAggregateVerificationKey,
-            multi_signer
+            self.dependencies
+                .multi_signer
+                .read()
+                .await
                 .compute_next_stake_distribution_aggregate_verification_key()
                 .await
                 .map_err(RuntimeError::MultiSigner)?
                 .unwrap_or_default(),
         );
-        multi_signer
+
+        Ok(protocol_message)
+    }
+
+    async fn update_message_in_multisigner(
+        &self,
+        protocol_message: ProtocolMessage,
+    ) -> Result<(), RuntimeError> {
+        debug!("RUNNER: update message in multisigner");
+        self.dependencies
+            .multi_signer
+            .write()
+            .await
             .update_current_message(protocol_message)
             .await
             .map_err(RuntimeError::MultiSigner)
@@ -287,6 +434,7 @@ impl AggregatorRunnerTrait for AggregatorRunner {
     async fn create_new_pending_certificate_from_multisigner(
         &self,
         beacon: Beacon,
+        signed_entity_type: SignedEntityType,
     ) -> Result<CertificatePending, RuntimeError> {
         debug!("RUNNER: create new pending certificate from multisigner");
         let multi_signer = self.dependencies.multi_signer.read().await;
@@ -313,13 +461,29 @@ impl AggregatorRunnerTrait for AggregatorRunner {
                 RuntimeError::General("no next protocol parameters".to_string().into())
             })?;
 
-        let pending_certificate = CertificatePending::new(
+        let mut pending_certificate = CertificatePending::new(
             beacon,
             protocol_parameters.into(),
             next_protocol_parameters.into(),
             signers,
             next_signers.into_iter().map(|s| s.into()).collect(),
+            signed_entity_type,
         );
+        // The signers can't compute the hash of the manifest of the snapshot archive built
+        // by the aggregator, it is given to them along the pending certificate.
+        if let Some(snapshot_manifest_hash) =
+            multi_signer
+                .get_current_message()
+                .await
+                .and_then(|message| {
+                    message
+                        .get_message_part(&ProtocolMessagePartKey::SnapshotManifestHash)
+                        .cloned()
+                })
+        {
+            pending_certificate =
+                pending_certificate.with_snapshot_manifest_hash(snapshot_manifest_hash);
+        }
 
         Ok(pending_certificate)
     }
@@ -360,6 +524,10 @@ impl AggregatorRunnerTrait for AggregatorRunner {
             .create_multi_signature()
             .await?
             .is_some();
+        // The metrics are informative only, they must not fail the signing round.
+        if let Err(error) = self.update_quorum_progress_metrics().await {
+            warn!(" > could not update the quorum progress metrics"; "error" => ?error);
+        }
 
         if has_multisig {
             debug!(" > new multi-signature created");
@@ -369,34 +537,100 @@ impl AggregatorRunnerTrait for AggregatorRunner {
         Ok(has_multisig)
     }
 
+    fn is_pending_certificate_expired(&self, started_at: &DateTime<Utc>) -> bool {
+        match self.config.pending_certificate_timeout {
+            Some(timeout) => Utc::now()
+                .signed_duration_since(*started_at)
+                .to_std()
+                .map_or(false, |elapsed| elapsed >= timeout),
+            None => false,
+        }
+    }
+
+    async fn record_failed_round(
+        &self,
+        certificate_pending: &CertificatePending,
+        started_at: &DateTime<Utc>,
+    ) -> Result<FailedRound, RuntimeError> {
+        debug!("RUNNER: record failed round"; "beacon" => ?certificate_pending.beacon);
+        let mut single_signatures = self
+            .dependencies
+            .single_signature_store
+            .get_single_signatures(&certificate_pending.get_signed_entity_type())
+            .await?
+            .unwrap_or_default()
+            .into_values()
+            .collect::<Vec<_>>();
+        single_signatures.sort_by(|a, b| a.party_id.cmp(&b.party_id));
+        let failed_round = FailedRound::new(
+            certificate_pending,
+            &single_signatures,
+            started_at.to_rfc3339(),
+            Utc::now().to_rfc3339(),
+        );
+        self.dependencies
+            .failed_round_store
+            .save(failed_round.clone())
+            .await?;
+
+        Ok(failed_round)
+    }
+
     async fn create_snapshot_archive(
         &self,
         beacon: &Beacon,
+        protocol_message: &ProtocolMessage,
     ) -> Result<OngoingSnapshot, RuntimeError> {
         debug!("RUNNER: create snapshot archive");
 
         let snapshotter = self.dependencies.snapshotter.clone();
-        let protocol_message = self
-            .dependencies
-            .multi_signer
-            .read()
-            .await
-            .get_current_message()
-            .await
-            .ok_or_else(|| RuntimeError::General("no message found".to_string().into()))?;
         let snapshot_digest = protocol_message
             .get_message_part(&ProtocolMessagePartKey::SnapshotDigest)
             .ok_or_else(|| {
                 RuntimeError::General("no snapshot digest message part found".to_string().into())
             })?;
         let snapshot_name = format!(
-            "{}-e{}-i{}.{}.tar.gz",
-            beacon.network, beacon.epoch.0, beacon.immutable_file_number, snapshot_digest
+            "{}-e{}-i{}.{}.{}",
+            beacon.network,
+            beacon.epoch.0,
+            beacon.immutable_file_number,
+            snapshot_digest,
+            snapshotter.compression_algorithm().tar_file_extension()
         );
+        let snapshot_digest = snapshot_digest.to_owned();
+        let chunk_size = self.config.snapshot_chunk_size;
+        let base_snapshot = self.get_incremental_snapshot_base(beacon).await?;
         // spawn a separate thread to prevent blocking
         let ongoing_snapshot =
             tokio::task::spawn_blocking(move || -> Result<OngoingSnapshot, SnapshotError> {
-                snapshotter.snapshot(&snapshot_name)
+                let ongoing_snapshot = match base_snapshot {
+                    Some(base_snapshot) => {
+                        debug!(" > incremental snapshot"; "base_digest" => &base_snapshot.digest);
+                        snapshotter
+                            .snapshot_increment(
+                                &snapshot_name,
+                                base_snapshot.beacon.immutable_file_number,
+                            )?
+                            .with_base_digest(base_snapshot.digest)
+                    }
+                    None => snapshotter.snapshot(&snapshot_name)?,
+                };
+
+                match chunk_size {
+                    Some(chunk_size) => {
+                        let manifest = SnapshotManifest::from_archive(
+                            ongoing_snapshot.get_file_path(),
+                            chunk_size,
+                            snapshot_digest,
+                            snapshotter.compression_algorithm(),
+                        )
+                        .map_err(|e| SnapshotError::GeneralError(e.to_string()))?;
+                        debug!(" > chunked snapshot"; "chunks" => manifest.chunks.len());
+
+                        Ok(ongoing_snapshot.with_manifest(manifest))
+                    }
+                    None => Ok(ongoing_snapshot),
+                }
             })
             .await
             .map_err(|e| RuntimeError::General(e.into()))??;
@@ -406,17 +640,42 @@ impl AggregatorRunnerTrait for AggregatorRunner {
         Ok(ongoing_snapshot)
     }
 
+    fn is_snapshot_chunking_enabled(&self) -> bool {
+        self.config.snapshot_chunk_size.is_some()
+    }
+
+    async fn drop_snapshot_archive(&self, ongoing_snapshot: &OngoingSnapshot) {
+        debug!("RUNNER: drop snapshot archive");
+
+        if let Err(error) = tokio::fs::remove_file(ongoing_snapshot.get_file_path()).await {
+            warn!(" > Ongoing snapshot file removal failure: {}", error);
+        }
+    }
+
     async fn upload_snapshot_archive(
         &self,
         ongoing_snapshot: &OngoingSnapshot,
     ) -> Result<Vec<SnapshotLocation>, RuntimeError> {
         debug!("RUNNER: upload snapshot archive");
-        let location = self
-            .dependencies
-            .snapshot_uploader
-            .upload_snapshot(ongoing_snapshot.get_file_path())
-            .await
-            .map_err(RuntimeError::SnapshotUploader)?;
+        let started_at = Instant::now();
+        let location = match ongoing_snapshot.get_manifest() {
+            Some(manifest) => {
+                self.upload_snapshot_chunks(ongoing_snapshot.get_file_path(), manifest)
+                    .await?
+            }
+            None => self
+                .dependencies
+                .snapshot_uploader
+                .upload_snapshot(ongoing_snapshot.get_file_path())
+                .await
+                .map_err(RuntimeError::SnapshotUploader)?,
+        };
+        self.dependencies
+            .metrics_service
+            .observe_snapshot_upload_duration(started_at.elapsed());
+        self.dependencies
+            .metrics_service
+            .set_snapshot_size(*ongoing_snapshot.get_file_size());
 
         if let Err(error) = tokio::fs::remove_file(ongoing_snapshot.get_file_path()).await {
             warn!(
@@ -428,11 +687,8 @@ impl AggregatorRunnerTrait for AggregatorRunner {
         Ok(vec![location])
     }
 
-    async fn create_and_save_certificate(
-        &self,
-        beacon: &Beacon,
-    ) -> Result<Certificate, RuntimeError> {
-        debug!("RUNNER: create and save certificate");
+    async fn create_certificate(&self, beacon: &Beacon) -> Result<Certificate, RuntimeError> {
+        debug!("RUNNER: create certificate");
         let certificate_store = self.dependencies.certificate_store.clone();
         let latest_certificates = certificate_store.get_list(2).await?;
         let last_certificate = latest_certificates.get(0);
@@ -446,8 +702,14 @@ impl AggregatorRunnerTrait for AggregatorRunner {
                         last_certificate.beacon.epoch,
                     ));
                 }
-                // Check if last certificate is first certificate of its epoch
-                if penultimate_certificate.beacon.epoch != last_certificate.beacon.epoch {
+                // Check if last certificate is first certificate of its epoch, or restarts the
+                // chain with a genesis key rotation
+                if penultimate_certificate.beacon.epoch != last_certificate.beacon.epoch
+                    || matches!(
+                        GenesisKeyRotation::from_certificate(last_certificate),
+                        Ok(Some(_))
+                    )
+                {
                     &last_certificate.hash
                 } else {
                     &last_certificate.previous_hash
@@ -469,38 +731,337 @@ impl AggregatorRunnerTrait for AggregatorRunner {
                 &self.dependencies.genesis_verifier,
             )
             .await?;
-        let _ = certificate_store.save(certificate.clone()).await?;
 
         Ok(certificate)
     }
 
-    async fn create_and_save_snapshot(
+    async fn create_snapshot(
         &self,
-        certificate: Certificate,
+        certificate: &Certificate,
         ongoing_snapshot: &OngoingSnapshot,
         remote_locations: Vec<String>,
     ) -> Result<Snapshot, RuntimeError> {
-        debug!("RUNNER: create and save snapshot");
+        debug!("RUNNER: create snapshot");
         let snapshot_digest = certificate
             .protocol_message
             .get_message_part(&ProtocolMessagePartKey::SnapshotDigest)
             .ok_or_else(|| RuntimeError::General("message part not found".to_string().into()))?
             .to_owned();
-        let snapshot = Snapshot::new(
+        let mut snapshot = Snapshot::new(
             snapshot_digest,
-            certificate.beacon,
-            certificate.hash,
+            certificate.beacon.clone(),
+            certificate.hash.clone(),
             *ongoing_snapshot.get_file_size(),
             format!("{:?}", Utc::now()),
             remote_locations,
+            self.dependencies.snapshotter.compression_algorithm(),
         );
+        snapshot.base_digest = ongoing_snapshot.get_base_digest().cloned();
+        if let Some(manifest) = ongoing_snapshot.get_manifest() {
+            let manifest_hash = manifest.compute_hash();
+            if certificate
+                .protocol_message
+                .get_message_part(&ProtocolMessagePartKey::SnapshotManifestHash)
+                != Some(&manifest_hash)
+            {
+                return Err(RuntimeError::General(
+                    "the snapshot manifest is not the one signed by the certificate"
+                        .to_string()
+                        .into(),
+                ));
+            }
+            snapshot.manifest_hash = Some(manifest_hash);
+        }
 
-        self.dependencies
+        Ok(snapshot)
+    }
+
+    async fn seal_certificate(
+        &self,
+        certificate: Certificate,
+        snapshot: Option<Snapshot>,
+    ) -> Result<(), RuntimeError> {
+        debug!("RUNNER: seal certificate"; "certificate_hash" => &certificate.hash);
+        let signed_entity_kind = certificate
+            .signed_entity_type()
+            .map(|signed_entity_type| signed_entity_type.kind().to_string())
+            .unwrap_or_else(|| "Unknown".to_string());
+        let epoch = certificate.beacon.epoch;
+        let signers_party_ids = certificate
+            .metadata
+            .signers
+            .iter()
+            .map(|signer| signer.party_id.clone())
+            .collect::<Vec<_>>();
+        let transaction = self.dependencies.transaction_manager.begin().await?;
+
+        match transaction
+            .run(self.save_sealed_certificate(certificate, snapshot))
+            .await
+        {
+            Ok(()) => {
+                transaction.commit().await?;
+                self.dependencies
+                    .metrics_service
+                    .increment_certificates_produced(&signed_entity_kind);
+
+                // The certificate is sealed whatever happens while pruning.
+                match self.dependencies.certificate_store.prune().await {
+                    Ok(report) => {
+                        debug!(" > certificates pruned"; "pruned" => ?report.pruned_keys, "protected" => ?report.protected_keys)
+                    }
+                    Err(error) => warn!(" > could not prune certificates"; "error" => ?error),
+                }
+                if let Err(error) = self
+                    .dependencies
+                    .signer_statistics_store
+                    .record_certificate_inclusion(epoch, &signers_party_ids)
+                    .await
+                {
+                    warn!(" > could not record the signers inclusion statistics"; "error" => ?error);
+                }
+
+                Ok(())
+            }
+            Err(error) => {
+                warn!(" > certificate sealing failed, rolling back"; "error" => ?error);
+                // The sealing error is the one to report, whatever happens while rolling back.
+                if let Err(rollback_error) = transaction.rollback().await {
+                    error!(" > could not roll back the certificate sealing"; "error" => ?rollback_error);
+                }
+
+                Err(error)
+            }
+        }
+    }
+
+    async fn save_runtime_state(&self, state: &AggregatorState) -> Result<(), RuntimeError> {
+        debug!("RUNNER: save runtime state"; "state" => %state);
+        let multi_signer = self.dependencies.multi_signer.read().await;
+        let runtime_state = RuntimeState {
+            state: state.to_owned(),
+            current_beacon: multi_signer.get_current_beacon().await,
+            current_message: multi_signer.get_current_message().await,
+        };
+
+        Ok(self
+            .dependencies
+            .runtime_state_store
+            .save(runtime_state)
+            .await?)
+    }
+
+    async fn restore_runtime_state(&self) -> Result<Option<AggregatorState>, RuntimeError> {
+        debug!("RUNNER: restore runtime state");
+        let runtime_state = match self.dependencies.runtime_state_store.get().await? {
+            Some(runtime_state) => runtime_state,
+            None => return Ok(None),
+        };
+
+        {
+            let mut multi_signer = self.dependencies.multi_signer.write().await;
+            if let Some(beacon) = runtime_state.current_beacon {
+                multi_signer.update_current_beacon(beacon).await?;
+            }
+            if let Some(message) = runtime_state.current_message {
+                multi_signer.update_current_message(message).await?;
+            }
+        }
+
+        // The pending certificate is saved before the state machine enters SIGNING and is
+        // removed before it leaves it: if it is missing the aggregator stopped during the
+        // transition, the state machine resumes from READY to sign again what is not certified.
+        match runtime_state.state {
+            AggregatorState::Signing(_)
+                if self
+                    .dependencies
+                    .certificate_pending_store
+                    .get()
+                    .await?
+                    .is_none() =>
+            {
+                warn!(" > restore_runtime_state::no certificate pending in store, resuming from READY");
+                let beacon = runtime_state
+                    .state
+                    .get_beacon()
+                    .expect("a SIGNING state always has a beacon");
+
+                Ok(Some(AggregatorState::ready(beacon)))
+            }
+            state => Ok(Some(state)),
+        }
+    }
+}
+
+impl AggregatorRunner {
+    /// Record how many distinct lotteries are won by the single signatures received for the
+    /// pending certificate, compared to the number of lotteries required by the quorum.
+    async fn update_quorum_progress_metrics(&self) -> Result<(), RuntimeError> {
+        let signed_entity_type = match self.dependencies.certificate_pending_store.get().await? {
+            Some(certificate_pending) => certificate_pending.get_signed_entity_type(),
+            None => return Ok(()),
+        };
+        let multi_signer = self.dependencies.multi_signer.read().await;
+        let required_lotteries = match multi_signer.get_protocol_parameters().await? {
+            Some(protocol_parameters) => protocol_parameters.k,
+            None => return Ok(()),
+        };
+        let single_signatures = self
+            .dependencies
+            .single_signature_store
+            .get_single_signatures(&signed_entity_type)
+            .await?
+            .unwrap_or_default();
+        self.dependencies.metrics_service.set_quorum_progress(
+            SingleSignatures::count_won_lotteries(single_signatures.values()),
+            required_lotteries,
+        );
+
+        Ok(())
+    }
+
+    /// Drop the pending certificate then save the certificate and its snapshot if any.
+    ///
+    /// Stores that are not enrolled in a transaction can't be rolled back, so
+    /// if a mutation fails the previous ones are undone before returning the
+    /// error. The snapshot is saved last so it never has to be undone.
+    async fn save_sealed_certificate(
+        &self,
+        certificate: Certificate,
+        snapshot: Option<Snapshot>,
+    ) -> Result<(), RuntimeError> {
+        let certificate_pending_store = self.dependencies.certificate_pending_store.clone();
+        let certificate_store = self.dependencies.certificate_store.clone();
+        let certificate_hash = certificate.hash.clone();
+
+        let certificate_pending = certificate_pending_store.remove().await?;
+        if certificate_pending.is_none() {
+            warn!(" > seal_certificate::no certificate pending in store, did the previous loop crashed ?");
+        }
+
+        if let Err(error) = certificate_store.save(certificate).await {
+            self.restore_pending_certificate(certificate_pending).await;
+
+            return Err(error.into());
+        }
+
+        let snapshot = match snapshot {
+            Some(snapshot) => snapshot,
+            None => return Ok(()),
+        };
+        if let Err(error) = self
+            .dependencies
             .snapshot_store
-            .add_snapshot(snapshot.clone())
-            .await?;
+            .add_snapshot(snapshot)
+            .await
+        {
+            if let Err(e) = certificate_store.remove(&certificate_hash).await {
+                warn!(" > could not undo the certificate save"; "error" => ?e);
+            }
+            self.restore_pending_certificate(certificate_pending).await;
 
-        Ok(snapshot)
+            return Err(error.into());
+        }
+
+        Ok(())
+    }
+
+    /// Return the snapshot the snapshot of the given beacon is based on, if it is incremental.
+    ///
+    /// It is the latest snapshot, unless the maximum number of increments created since the
+    /// last full snapshot is reached.
+    async fn get_incremental_snapshot_base(
+        &self,
+        beacon: &Beacon,
+    ) -> Result<Option<Snapshot>, RuntimeError> {
+        if self.config.snapshot_max_increments == 0 {
+            return Ok(None);
+        }
+        let snapshot_store = &self.dependencies.snapshot_store;
+        let last_snapshot = match snapshot_store
+            .list_snapshots()
+            .await?
+            .into_iter()
+            .filter(|snapshot| {
+                snapshot.beacon.network == beacon.network
+                    && snapshot.beacon.immutable_file_number < beacon.immutable_file_number
+            })
+            .max_by_key(|snapshot| snapshot.beacon.immutable_file_number)
+        {
+            Some(snapshot) => snapshot,
+            None => return Ok(None),
+        };
+
+        let mut increments = 0;
+        let mut base_digest = last_snapshot.base_digest.clone();
+        while let Some(digest) = base_digest {
+            increments += 1;
+            if increments >= self.config.snapshot_max_increments {
+                return Ok(None);
+            }
+            base_digest = match snapshot_store.get_snapshot_details(digest.clone()).await? {
+                Some(snapshot) => snapshot.base_digest,
+                None => {
+                    warn!(" > base snapshot not found, creating a full snapshot"; "digest" => &digest);
+                    return Ok(None);
+                }
+            };
+        }
+
+        Ok(Some(last_snapshot))
+    }
+
+    /// Upload the chunks of the given archive listed in its manifest, then the manifest.
+    ///
+    /// The chunks that are already stored are skipped: they are either shared with a previous
+    /// snapshot or were uploaded by a previous attempt that failed.
+    async fn upload_snapshot_chunks(
+        &self,
+        archive_path: &Path,
+        manifest: &SnapshotManifest,
+    ) -> Result<SnapshotLocation, RuntimeError> {
+        let snapshot_uploader = &self.dependencies.snapshot_uploader;
+        let mut archive = tokio::fs::File::open(archive_path).await?;
+        let mut uploaded_chunks = 0;
+
+        for chunk in &manifest.chunks {
+            if snapshot_uploader
+                .has_snapshot_chunk(&chunk.hash)
+                .await
+                .map_err(RuntimeError::SnapshotUploader)?
+            {
+                archive
+                    .seek(std::io::SeekFrom::Current(chunk.size as i64))
+                    .await?;
+                continue;
+            }
+            let mut content = vec![0; chunk.size as usize];
+            archive.read_exact(&mut content).await?;
+            snapshot_uploader
+                .upload_snapshot_chunk(&chunk.hash, &content)
+                .await
+                .map_err(RuntimeError::SnapshotUploader)?;
+            uploaded_chunks += 1;
+        }
+        debug!(" > snapshot chunks uploaded"; "uploaded" => uploaded_chunks, "total" => manifest.chunks.len());
+
+        snapshot_uploader
+            .upload_snapshot_manifest(manifest)
+            .await
+            .map_err(RuntimeError::SnapshotUploader)
+    }
+
+    async fn restore_pending_certificate(&self, certificate_pending: Option<CertificatePending>) {
+        if let Some(certificate_pending) = certificate_pending {
+            if let Err(e) = self
+                .dependencies
+                .certificate_pending_store
+                .save(certificate_pending)
+                .await
+            {
+                warn!(" > could not restore the pending certificate"; "error" => ?e);
+            }
+        }
     }
 }
 
@@ -508,20 +1069,34 @@ impl AggregatorRunnerTrait for AggregatorRunner {
 pub mod tests {
     use crate::multi_signer::MockMultiSigner;
     use crate::runtime::RuntimeError;
+    use crate::snapshot_uploaders::MockSnapshotUploader;
     use crate::snapshotter::OngoingSnapshot;
-    use crate::ProtocolParametersStorer;
+    use crate::store::SingleSignatureStorer;
     use crate::{
         initialize_dependencies,
         runtime::{AggregatorRunner, AggregatorRunnerTrait},
     };
+    use crate::{
+        CertificatePendingStore, CertificateStore, LocalSnapshotStore, ProtocolParametersStorer,
+        SnapshotStore,
+    };
+    use chrono::Utc;
     use mithril_common::chain_observer::FakeObserver;
     use mithril_common::crypto_helper::tests_setup::setup_certificate_chain;
     use mithril_common::digesters::DumbImmutableFileObserver;
-    use mithril_common::entities::{Beacon, CertificatePending, Epoch, ProtocolMessage};
+    use mithril_common::entities::{
+        Beacon, CertificatePending, CompressionAlgorithm, Epoch, ProtocolMessage, SignedEntityKind,
+        SignedEntityType, SnapshotChunk, SnapshotManifest,
+    };
+    use mithril_common::store::adapter::{open_sqlite_connection, FailStoreAdapter, SQLiteAdapter};
+    use mithril_common::store::SQLiteTransactionManager;
     use mithril_common::{entities::ProtocolMessagePartKey, fake_data, store::StakeStorer};
     use mithril_common::{BeaconProviderImpl, CardanoNetwork};
+    use std::collections::BTreeSet;
+    use std::io::Write;
     use std::path::Path;
     use std::sync::Arc;
+    use std::time::Duration;
     use tempfile::NamedTempFile;
     use tokio::sync::RwLock;
 
@@ -547,7 +1122,7 @@ pub mod tests {
     }
 
     #[tokio::test]
-    async fn test_does_certificate_exist_for_beacon() {
+    async fn test_get_next_signed_entity_type() {
         let (dependencies, config) = initialize_dependencies().await;
         let certificate_store = dependencies.certificate_store.clone();
         let runner = AggregatorRunner::new(config, Arc::new(dependencies));
@@ -556,15 +1131,45 @@ pub mod tests {
         let mut certificate = fake_data::certificate("certificate_hash".to_string());
         certificate.beacon = beacon.clone();
 
-        assert!(!runner
-            .does_certificate_exist_for_beacon(&beacon)
-            .await
-            .unwrap());
+        assert_eq!(
+            Some(SignedEntityType::CardanoImmutableFilesFull(beacon.clone())),
+            runner.get_next_signed_entity_type(&beacon).await.unwrap()
+        );
         certificate_store.save(certificate).await.unwrap();
-        assert!(runner
-            .does_certificate_exist_for_beacon(&beacon)
-            .await
-            .unwrap());
+        assert_eq!(
+            None,
+            runner.get_next_signed_entity_type(&beacon).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_next_signed_entity_type_certifies_stake_distribution_first() {
+        let (dependencies, config) = initialize_dependencies().await;
+        let config = config.with_signed_entity_kinds(BTreeSet::from([
+            SignedEntityKind::CardanoImmutableFilesFull,
+            SignedEntityKind::MithrilStakeDistribution,
+        ]));
+        let certificate_store = dependencies.certificate_store.clone();
+        let runner = AggregatorRunner::new(config, Arc::new(dependencies));
+
+        let beacon = fake_data::beacon();
+        let mut certificate = fake_data::certificate("certificate_hash".to_string());
+        certificate.beacon = beacon.clone();
+        certificate.protocol_message = ProtocolMessage::new();
+        certificate.protocol_message.set_message_part(
+            ProtocolMessagePartKey::MithrilStakeDistributionEpoch,
+            beacon.epoch.to_string(),
+        );
+
+        assert_eq!(
+            Some(SignedEntityType::MithrilStakeDistribution(beacon.epoch)),
+            runner.get_next_signed_entity_type(&beacon).await.unwrap()
+        );
+        certificate_store.save(certificate).await.unwrap();
+        assert_eq!(
+            Some(SignedEntityType::CardanoImmutableFilesFull(beacon.clone())),
+            runner.get_next_signed_entity_type(&beacon).await.unwrap()
+        );
     }
 
     #[tokio::test]
@@ -686,8 +1291,12 @@ pub mod tests {
         )
         .await;
 
+        let signed_entity_type = SignedEntityType::MithrilStakeDistribution(beacon.epoch);
         let mut certificate = runner
-            .create_new_pending_certificate_from_multisigner(beacon.clone())
+            .create_new_pending_certificate_from_multisigner(
+                beacon.clone(),
+                signed_entity_type.clone(),
+            )
             .await
             .unwrap();
         certificate.signers.sort_by_key(|s| s.party_id.clone());
@@ -698,6 +1307,7 @@ pub mod tests {
             protocol_parameters,
             current_signers.into_iter().map(|s| s.into()).collect(),
             next_signers.into_iter().map(|s| s.into()).collect(),
+            signed_entity_type,
         );
         expected.signers.sort_by_key(|s| s.party_id.clone());
         expected.next_signers.sort_by_key(|s| s.party_id.clone());
@@ -705,6 +1315,87 @@ pub mod tests {
         assert_eq!(expected, certificate);
     }
 
+    #[tokio::test]
+    async fn test_create_new_pending_certificate_from_multisigner_with_manifest_hash() {
+        let (deps, config) = initialize_dependencies().await;
+        let deps = Arc::new(deps);
+        let runner = AggregatorRunner::new(config, deps.clone());
+        let beacon = runner.get_beacon_from_chain().await.unwrap();
+        runner.update_beacon(&beacon).await.unwrap();
+        let signers = fake_data::signers_with_stakes(5);
+        deps.simulate_genesis(
+            signers[1..3].to_vec(),
+            signers[2..5].to_vec(),
+            &fake_data::protocol_parameters(),
+        )
+        .await;
+        let mut message = ProtocolMessage::new();
+        message.set_message_part(
+            ProtocolMessagePartKey::SnapshotManifestHash,
+            "manifest-hash".to_string(),
+        );
+        runner.update_message_in_multisigner(message).await.unwrap();
+
+        let certificate = runner
+            .create_new_pending_certificate_from_multisigner(
+                beacon.clone(),
+                SignedEntityType::CardanoImmutableFilesFull(beacon),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            Some("manifest-hash".to_string()),
+            certificate.snapshot_manifest_hash
+        );
+    }
+
+    #[tokio::test]
+    async fn test_compute_protocol_message() {
+        let (deps, config) = initialize_dependencies().await;
+        let deps = Arc::new(deps);
+        let runner = AggregatorRunner::new(config, deps.clone());
+        let beacon = runner.get_beacon_from_chain().await.unwrap();
+        runner.update_beacon(&beacon).await.unwrap();
+        for epoch in [
+            beacon.epoch.offset_to_signer_retrieval_epoch().unwrap(),
+            beacon
+                .epoch
+                .offset_to_next_signer_retrieval_epoch()
+                .unwrap(),
+        ] {
+            deps.protocol_parameters_store
+                .save_protocol_parameters(epoch, fake_data::protocol_parameters())
+                .await
+                .unwrap();
+        }
+
+        let message = runner
+            .compute_protocol_message(&SignedEntityType::CardanoImmutableFilesFull(beacon.clone()))
+            .await
+            .expect("compute_protocol_message should not fail");
+        assert_eq!(
+            Some(&"digest".to_string()),
+            message.get_message_part(&ProtocolMessagePartKey::SnapshotDigest)
+        );
+        assert!(message
+            .get_message_part(&ProtocolMessagePartKey::NextAggregateVerificationKey)
+            .is_some());
+
+        let message = runner
+            .compute_protocol_message(&SignedEntityType::MithrilStakeDistribution(beacon.epoch))
+            .await
+            .expect("compute_protocol_message should not fail");
+        assert_eq!(
+            Some(&beacon.epoch.to_string()),
+            message.get_message_part(&ProtocolMessagePartKey::MithrilStakeDistributionEpoch)
+        );
+        assert_eq!(
+            None,
+            message.get_message_part(&ProtocolMessagePartKey::SnapshotDigest)
+        );
+    }
+
     #[tokio::test]
     async fn test_update_message_in_multisigner() {
         let (deps, config) = initialize_dependencies().await;
@@ -725,9 +1416,11 @@ pub mod tests {
                 .await
                 .unwrap();
         }
+        let mut protocol_message = ProtocolMessage::new();
+        protocol_message.set_message_part(ProtocolMessagePartKey::SnapshotDigest, digest);
 
         runner
-            .update_message_in_multisigner(digest)
+            .update_message_in_multisigner(protocol_message)
             .await
             .expect("update_message_in_multisigner should not fail");
         let message = deps
@@ -798,7 +1491,60 @@ pub mod tests {
     }
 
     #[tokio::test]
-    async fn test_create_and_save_certificate_ok() {
+    async fn test_is_pending_certificate_expired() {
+        let (deps, config) = initialize_dependencies().await;
+        let config = config.with_pending_certificate_timeout(Some(Duration::from_secs(60)));
+        let runner = AggregatorRunner::new(config, Arc::new(deps));
+
+        assert!(!runner.is_pending_certificate_expired(&Utc::now()));
+        assert!(
+            runner.is_pending_certificate_expired(&(Utc::now() - chrono::Duration::seconds(61)))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pending_certificate_never_expires_without_timeout() {
+        let (deps, config) = initialize_dependencies().await;
+        let runner = AggregatorRunner::new(config, Arc::new(deps));
+
+        assert!(!runner.is_pending_certificate_expired(&(Utc::now() - chrono::Duration::days(7))));
+    }
+
+    #[tokio::test]
+    async fn test_record_failed_round() {
+        let (deps, config) = initialize_dependencies().await;
+        let deps = Arc::new(deps);
+        let runner = AggregatorRunner::new(config, deps.clone());
+        let mut certificate_pending = fake_data::certificate_pending();
+        certificate_pending.signers = fake_data::signers(2);
+        let mut single_signatures = fake_data::single_signatures(vec![1, 4]);
+        single_signatures.party_id = certificate_pending.signers[0].party_id.clone();
+        deps.single_signature_store
+            .save_single_signatures(
+                &certificate_pending.get_signed_entity_type(),
+                &single_signatures,
+            )
+            .await
+            .unwrap();
+
+        let failed_round = runner
+            .record_failed_round(&certificate_pending, &Utc::now())
+            .await
+            .unwrap();
+
+        assert_eq!(2, failed_round.won_lotteries);
+        assert_eq!(
+            vec![certificate_pending.signers[1].party_id.clone()],
+            failed_round.missing_signers
+        );
+        assert_eq!(
+            vec![failed_round],
+            deps.failed_round_store.get_list(10).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_certificate_ok() {
         let (certificate_chain, _) = setup_certificate_chain(5, 1);
         let first_certificate = certificate_chain[0].clone();
         let beacon = first_certificate.beacon.clone();
@@ -818,12 +1564,12 @@ pub mod tests {
                 .expect("save certificate to store should not fail");
         }
 
-        let certificate = runner.create_and_save_certificate(&beacon).await;
-        certificate.expect("a certificate should have been created and saved");
+        let certificate = runner.create_certificate(&beacon).await;
+        certificate.expect("a certificate should have been created");
     }
 
     #[tokio::test]
-    async fn test_create_and_save_certificate_ko_epoch_gap() {
+    async fn test_create_certificate_ko_epoch_gap() {
         let (deps, config) = initialize_dependencies().await;
         let certificate_store = deps.certificate_store.clone();
         let runner = AggregatorRunner::new(config, Arc::new(deps));
@@ -839,7 +1585,7 @@ pub mod tests {
                 .expect("save certificate to store should not fail");
         }
 
-        let certificate = runner.create_and_save_certificate(&beacon).await;
+        let certificate = runner.create_certificate(&beacon).await;
         assert!(certificate.is_err());
         let err = certificate.unwrap_err();
         assert_eq!(
@@ -849,6 +1595,177 @@ pub mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_seal_certificate_ok() {
+        let (deps, config) = initialize_dependencies().await;
+        let deps = Arc::new(deps);
+        let runner = AggregatorRunner::new(config, deps.clone());
+        let certificate = fake_data::certificate("certificate_hash".to_string());
+        let snapshot = fake_data::snapshots(1)[0].clone();
+        deps.certificate_pending_store
+            .save(fake_data::certificate_pending())
+            .await
+            .unwrap();
+
+        runner
+            .seal_certificate(certificate.clone(), Some(snapshot.clone()))
+            .await
+            .expect("seal_certificate should not fail");
+
+        assert_eq!(
+            Some(certificate),
+            deps.certificate_store
+                .get_from_hash("certificate_hash")
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            Some(snapshot.clone()),
+            deps.snapshot_store
+                .get_snapshot_details(snapshot.digest)
+                .await
+                .unwrap()
+        );
+        assert_eq!(None, deps.certificate_pending_store.get().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_seal_certificate_without_snapshot_ok() {
+        let (deps, config) = initialize_dependencies().await;
+        let deps = Arc::new(deps);
+        let runner = AggregatorRunner::new(config, deps.clone());
+        let certificate = fake_data::certificate("certificate_hash".to_string());
+        deps.certificate_pending_store
+            .save(fake_data::certificate_pending())
+            .await
+            .unwrap();
+
+        runner
+            .seal_certificate(certificate.clone(), None)
+            .await
+            .expect("seal_certificate should not fail");
+
+        assert_eq!(
+            Some(certificate),
+            deps.certificate_store
+                .get_from_hash("certificate_hash")
+                .await
+                .unwrap()
+        );
+        assert!(deps
+            .snapshot_store
+            .list_snapshots()
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(None, deps.certificate_pending_store.get().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_seal_certificate_records_signers_inclusion() {
+        let (deps, config) = initialize_dependencies().await;
+        let deps = Arc::new(deps);
+        let runner = AggregatorRunner::new(config, deps.clone());
+        let certificate = fake_data::certificate("certificate_hash".to_string());
+        deps.certificate_pending_store
+            .save(fake_data::certificate_pending())
+            .await
+            .unwrap();
+
+        runner
+            .seal_certificate(certificate.clone(), None)
+            .await
+            .expect("seal_certificate should not fail");
+
+        let signer_statistics = deps
+            .signer_statistics_store
+            .get_list(None, Some(certificate.beacon.epoch))
+            .await
+            .unwrap();
+        assert_eq!(certificate.metadata.signers.len(), signer_statistics.len());
+        assert!(signer_statistics
+            .iter()
+            .all(|statistics| statistics.certificates_included == 1));
+    }
+
+    #[tokio::test]
+    async fn test_seal_certificate_undo_mutations_when_a_store_fails() {
+        let (mut deps, config) = initialize_dependencies().await;
+        deps.snapshot_store = Arc::new(LocalSnapshotStore::new(
+            Box::new(FailStoreAdapter::new()),
+            20,
+        ));
+        let deps = Arc::new(deps);
+        let runner = AggregatorRunner::new(config, deps.clone());
+        let certificate = fake_data::certificate("certificate_hash".to_string());
+        let snapshot = fake_data::snapshots(1)[0].clone();
+        let pending_certificate = fake_data::certificate_pending();
+        deps.certificate_pending_store
+            .save(pending_certificate.clone())
+            .await
+            .unwrap();
+
+        runner
+            .seal_certificate(certificate, Some(snapshot))
+            .await
+            .expect_err("seal_certificate should fail when the snapshot store fails");
+
+        assert_eq!(
+            None,
+            deps.certificate_store
+                .get_from_hash("certificate_hash")
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            Some(pending_certificate),
+            deps.certificate_pending_store.get().await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_seal_certificate_rollback_sqlite_stores_when_a_store_fails() {
+        let (mut deps, config) = initialize_dependencies().await;
+        let connection = open_sqlite_connection(None).unwrap();
+        deps.certificate_pending_store = Arc::new(CertificatePendingStore::new(Box::new(
+            SQLiteAdapter::with_connection("pending_certificate", connection.clone()).unwrap(),
+        )));
+        deps.certificate_store = Arc::new(CertificateStore::new(Box::new(
+            SQLiteAdapter::with_connection("certificate", connection.clone()).unwrap(),
+        )));
+        deps.snapshot_store = Arc::new(LocalSnapshotStore::new(
+            Box::new(FailStoreAdapter::new()),
+            20,
+        ));
+        deps.transaction_manager = Arc::new(SQLiteTransactionManager::new(connection));
+        let deps = Arc::new(deps);
+        let runner = AggregatorRunner::new(config, deps.clone());
+        let certificate = fake_data::certificate("certificate_hash".to_string());
+        let snapshot = fake_data::snapshots(1)[0].clone();
+        let pending_certificate = fake_data::certificate_pending();
+        deps.certificate_pending_store
+            .save(pending_certificate.clone())
+            .await
+            .unwrap();
+
+        runner
+            .seal_certificate(certificate, Some(snapshot))
+            .await
+            .expect_err("seal_certificate should fail when the snapshot store fails");
+
+        assert_eq!(
+            None,
+            deps.certificate_store
+                .get_from_hash("certificate_hash")
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            Some(pending_certificate),
+            deps.certificate_pending_store.get().await.unwrap()
+        );
+    }
+
     #[tokio::test]
     async fn test_remove_snapshot_archive_after_upload() {
         let (deps, config) = initialize_dependencies().await;
@@ -876,16 +1793,11 @@ pub mod tests {
             ProtocolMessagePartKey::SnapshotDigest,
             "test+digest".to_string(),
         );
-        let mut mock_multi_signer = MockMultiSigner::new();
-        mock_multi_signer
-            .expect_get_current_message()
-            .return_once(move || Some(message));
-        let (mut deps, config) = initialize_dependencies().await;
-        deps.multi_signer = Arc::new(RwLock::new(mock_multi_signer));
+        let (deps, config) = initialize_dependencies().await;
         let runner = AggregatorRunner::new(config, Arc::new(deps));
 
         let ongoing_snapshot = runner
-            .create_snapshot_archive(&beacon)
+            .create_snapshot_archive(&beacon, &message)
             .await
             .expect("create_snapshot_archive should not fail");
 
@@ -900,4 +1812,133 @@ pub mod tests {
             ongoing_snapshot.get_file_path()
         );
     }
+
+    #[tokio::test]
+    async fn test_create_snapshot_archive_based_on_last_snapshot_until_max_increments() {
+        let beacon = Beacon::new("network".to_string(), 20, 145);
+        let mut message = ProtocolMessage::new();
+        message.set_message_part(
+            ProtocolMessagePartKey::SnapshotDigest,
+            "test+digest".to_string(),
+        );
+        let mut full_snapshot = fake_data::snapshots(1)[0].clone();
+        full_snapshot.digest = "full".to_string();
+        full_snapshot.beacon = Beacon::new("network".to_string(), 19, 100);
+        let mut incremental_snapshot = full_snapshot.clone();
+        incremental_snapshot.digest = "increment".to_string();
+        incremental_snapshot.beacon = Beacon::new("network".to_string(), 19, 120);
+        incremental_snapshot.base_digest = Some("full".to_string());
+
+        for (snapshot_max_increments, expected_base_digest) in
+            [(0, None), (1, None), (2, Some("increment".to_string()))]
+        {
+            let (deps, config) = initialize_dependencies().await;
+            for snapshot in [&full_snapshot, &incremental_snapshot] {
+                deps.snapshot_store
+                    .add_snapshot(snapshot.clone())
+                    .await
+                    .unwrap();
+            }
+            let config = config.with_snapshot_max_increments(snapshot_max_increments);
+            let runner = AggregatorRunner::new(config, Arc::new(deps));
+
+            let ongoing_snapshot = runner
+                .create_snapshot_archive(&beacon, &message)
+                .await
+                .expect("create_snapshot_archive should not fail");
+
+            assert_eq!(
+                expected_base_digest.as_ref(),
+                ongoing_snapshot.get_base_digest(),
+                "unexpected base with {} max increments",
+                snapshot_max_increments
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_upload_snapshot_archive_only_uploads_missing_chunks() {
+        let content = b"0123456789abcdefghijklmnopqrstuvwxyz";
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(content).unwrap();
+        let manifest = SnapshotManifest::from_archive(
+            file.path(),
+            16,
+            "digest".to_string(),
+            CompressionAlgorithm::Gzip,
+        )
+        .unwrap();
+        let stored_chunk_hash = manifest.chunks[0].hash.clone();
+        let missing_chunks = vec![
+            (manifest.chunks[1].hash.clone(), content[16..32].to_vec()),
+            (manifest.chunks[2].hash.clone(), content[32..].to_vec()),
+        ];
+        let mut mock_snapshot_uploader = MockSnapshotUploader::new();
+        mock_snapshot_uploader
+            .expect_has_snapshot_chunk()
+            .returning(move |chunk_hash| Ok(chunk_hash == stored_chunk_hash));
+        mock_snapshot_uploader
+            .expect_upload_snapshot_chunk()
+            .withf(move |chunk_hash, content| {
+                missing_chunks.contains(&(chunk_hash.to_string(), content.to_vec()))
+            })
+            .times(2)
+            .returning(|_, _| Ok(()));
+        mock_snapshot_uploader
+            .expect_upload_snapshot_manifest()
+            .times(1)
+            .returning(|_| Ok("http://whatev.er/digest.manifest.json".to_string()));
+        let (mut deps, config) = initialize_dependencies().await;
+        deps.snapshot_uploader = Arc::new(mock_snapshot_uploader);
+        let runner = AggregatorRunner::new(config, Arc::new(deps));
+        let snapshot = OngoingSnapshot::new(file.path().to_path_buf(), content.len() as u64)
+            .with_manifest(manifest);
+
+        let locations = runner
+            .upload_snapshot_archive(&snapshot)
+            .await
+            .expect("Snapshot upload should not fail");
+
+        assert_eq!(
+            vec!["http://whatev.er/digest.manifest.json".to_string()],
+            locations
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_snapshot_with_manifest_hash() {
+        let manifest = SnapshotManifest::new(
+            "digest".to_string(),
+            CompressionAlgorithm::Gzip,
+            vec![SnapshotChunk::new("chunk-hash".to_string(), 7331)],
+        );
+        let mut certificate = fake_data::certificate("certificate-hash".to_string());
+        certificate.protocol_message.set_message_part(
+            ProtocolMessagePartKey::SnapshotManifestHash,
+            manifest.compute_hash(),
+        );
+        certificate.signed_message = certificate.protocol_message.compute_hash();
+        let (deps, config) = initialize_dependencies().await;
+        let runner = AggregatorRunner::new(config, Arc::new(deps));
+        let ongoing_snapshot = OngoingSnapshot::new(Path::new("archive").to_path_buf(), 7331)
+            .with_manifest(manifest.clone());
+
+        let snapshot = runner
+            .create_snapshot(&certificate, &ongoing_snapshot, vec![])
+            .await
+            .expect("create_snapshot should not fail");
+        assert_eq!(Some(manifest.compute_hash()), snapshot.manifest_hash);
+
+        let ongoing_snapshot = OngoingSnapshot::new(Path::new("archive").to_path_buf(), 7331)
+            .with_manifest(SnapshotManifest {
+                digest: "another-digest".to_string(),
+                ..manifest
+            });
+        runner
+            .create_snapshot(&certificate, &ongoing_snapshot, vec![])
+            .await
+            .expect_err(
+                "create_snapshot should fail when the manifest does not match the certificate",
+            );
+    }
 }
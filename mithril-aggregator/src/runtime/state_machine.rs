@@ -1,28 +1,42 @@
-use super::{AggregatorRunnerTrait, RuntimeError};
+use super::{AggregatorRunnerTrait, RuntimeControl, RuntimeError};
+use crate::dependency::RuntimeStatusWrapper;
+use crate::snapshotter::OngoingSnapshot;
+use crate::{EventBus, MetricsService};
 
-use mithril_common::entities::{Beacon, CertificatePending};
+use chrono::{DateTime, Utc};
+use mithril_common::entities::{
+    AggregatorEvent, Beacon, Certificate, CertificatePending, ChainObserverStatus, ProtocolMessage,
+    ProtocolMessagePartKey, SignedEntityType,
+};
+use serde::{Deserialize, Serialize};
 use slog_scope::{error, info, trace, warn};
 use std::fmt::Display;
 use std::sync::Arc;
-use tokio::time::{sleep, Duration};
+use std::time::Instant;
+use tokio::time::Duration;
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct IdleState {
     current_beacon: Option<Beacon>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ReadyState {
     current_beacon: Beacon,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct SigningState {
     current_beacon: Beacon,
     certificate_pending: CertificatePending,
+    #[serde(default = "Utc::now")]
+    started_at: DateTime<Utc>,
+    /// Snapshot archive built for the pending certificate, if it certifies a snapshot.
+    #[serde(default)]
+    ongoing_snapshot: Option<OngoingSnapshot>,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum AggregatorState {
     Idle(IdleState),
     Ready(ReadyState),
@@ -46,6 +60,55 @@ impl Display for AggregatorState {
     }
 }
 
+impl AggregatorState {
+    /// Create an `IDLE` state with the given beacon.
+    pub fn idle(current_beacon: Option<Beacon>) -> Self {
+        AggregatorState::Idle(IdleState { current_beacon })
+    }
+
+    /// Create a `READY` state with the given beacon.
+    pub fn ready(current_beacon: Beacon) -> Self {
+        AggregatorState::Ready(ReadyState { current_beacon })
+    }
+
+    /// Return the beacon of the state if any.
+    pub fn get_beacon(&self) -> Option<Beacon> {
+        match self {
+            AggregatorState::Idle(state) => state.current_beacon.clone(),
+            AggregatorState::Ready(state) => Some(state.current_beacon.clone()),
+            AggregatorState::Signing(state) => Some(state.current_beacon.clone()),
+        }
+    }
+}
+
+/// Status of the state machine, published by the runtime after each cycle so that it can be
+/// reported outside of it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RuntimeStatus {
+    /// name of the state of the state machine
+    pub state: String,
+
+    /// beacon of the state of the state machine
+    pub beacon: Option<Beacon>,
+
+    /// health of the chain observer the last time the state machine read the chain
+    pub chain_observer: ChainObserverStatus,
+}
+
+impl Default for RuntimeStatus {
+    fn default() -> Self {
+        Self {
+            state: "idle".to_string(),
+            beacon: None,
+            chain_observer: ChainObserverStatus {
+                healthy: false,
+                epoch: None,
+                error: Some("the chain has not been read yet".to_string()),
+            },
+        }
+    }
+}
+
 /// The AggregatorRuntime responsibility is to create a state machine to handle
 /// all actions required by the process of getting multi-signatures.
 /// See the [documentation](https://mithril.network/doc/mithril/mithril-network/aggregator#under-the-hood) for more explanations about the Aggregator state machine.
@@ -58,6 +121,21 @@ pub struct AggregatorRuntime {
 
     /// specific runner for this state machine
     runner: Arc<dyn AggregatorRunnerTrait>,
+
+    /// metrics of the state machine
+    metrics_service: Arc<MetricsService>,
+
+    /// status of the state machine shared with the HTTP server
+    runtime_status: RuntimeStatusWrapper,
+
+    /// health of the chain observer the last time the chain was read
+    chain_observer_status: ChainObserverStatus,
+
+    /// bus where the lifecycle events of the state machine are published
+    event_bus: Arc<EventBus>,
+
+    /// operations requested to the state machine from outside of it
+    runtime_control: Arc<RuntimeControl>,
 }
 
 impl AggregatorRuntime {
@@ -66,29 +144,52 @@ impl AggregatorRuntime {
         state_sleep: Duration,
         init_state: Option<AggregatorState>,
         runner: Arc<dyn AggregatorRunnerTrait>,
+        metrics_service: Arc<MetricsService>,
+        runtime_status: RuntimeStatusWrapper,
+        event_bus: Arc<EventBus>,
+        runtime_control: Arc<RuntimeControl>,
     ) -> Result<Self, RuntimeError> {
         info!("initializing runtime");
 
-        let state = if init_state.is_none() {
-            trace!("idle state, no current beacon");
-            AggregatorState::Idle(IdleState {
-                current_beacon: None,
-            })
-        } else {
-            trace!("got initial state from caller");
-            init_state.unwrap()
+        let state = match init_state {
+            Some(state) => {
+                trace!("got initial state from caller");
+                state
+            }
+            None => match runner.restore_runtime_state().await? {
+                Some(state) => {
+                    info!("resuming from the saved state"; "state" => %state);
+                    state
+                }
+                None => {
+                    trace!("idle state, no current beacon");
+                    AggregatorState::idle(None)
+                }
+            },
         };
 
-        Ok::<Self, RuntimeError>(Self {
+        let runtime = Self {
             state_sleep,
             state,
             runner,
-        })
+            metrics_service,
+            runtime_status,
+            chain_observer_status: RuntimeStatus::default().chain_observer,
+            event_bus,
+            runtime_control,
+        };
+        runtime.publish_status().await;
+
+        Ok::<Self, RuntimeError>(runtime)
     }
 
     /// Return the actual state of the state machine.
     pub fn get_state(&self) -> String {
-        match self.state {
+        Self::state_name(&self.state)
+    }
+
+    fn state_name(state: &AggregatorState) -> String {
+        match state {
             AggregatorState::Idle(_) => "idle".to_string(),
             AggregatorState::Ready(_) => "ready".to_string(),
             AggregatorState::Signing(_) => "signing".to_string(),
@@ -108,18 +209,79 @@ impl AggregatorRuntime {
                 "… Cycle finished, Sleeping for {} ms",
                 self.state_sleep.as_millis()
             );
-            sleep(self.state_sleep).await;
+            self.runtime_control.sleep(self.state_sleep).await;
         }
     }
 
     /// Perform one tick of the state machine.
+    ///
+    /// The state is saved each time it changes so the runtime can resume from it after a
+    /// restart.
     pub async fn cycle(&mut self) -> Result<(), RuntimeError> {
+        let started_at = Instant::now();
+        let previous_state = self.state.clone();
+        let mut result = self.execute_cycle().await;
+        if self.state != previous_state {
+            if let Err(error) = self.runner.save_runtime_state(&self.state).await {
+                warn!("STATE MACHINE: could not save the state"; "error" => ?error);
+                result = result.and(Err(error));
+            }
+        }
+
+        self.metrics_service
+            .observe_cycle_duration(started_at.elapsed());
+        let previous_state = Self::state_name(&previous_state);
+        let state = self.get_state();
+        if state != previous_state {
+            self.metrics_service
+                .record_state_transition(&previous_state, &state);
+        }
+        if result.is_err() {
+            self.metrics_service.increment_cycle_errors();
+        }
+        self.publish_status().await;
+
+        result
+    }
+
+    /// Share the current state of the state machine with the HTTP server.
+    async fn publish_status(&self) {
+        *self.runtime_status.write().await = RuntimeStatus {
+            state: self.get_state(),
+            beacon: self.state.get_beacon(),
+            chain_observer: self.chain_observer_status.clone(),
+        };
+    }
+
+    /// Read the current beacon from the chain and keep the health of the chain observer, so
+    /// that it is published along the status without querying the node again.
+    async fn read_beacon_from_chain(&mut self) -> Result<Beacon, RuntimeError> {
+        let result = self.runner.get_beacon_from_chain().await;
+        self.chain_observer_status = match &result {
+            Ok(beacon) => ChainObserverStatus {
+                healthy: true,
+                epoch: Some(beacon.epoch),
+                error: None,
+            },
+            Err(error) => ChainObserverStatus {
+                healthy: false,
+                epoch: None,
+                error: Some(error.to_string()),
+            },
+        };
+
+        result
+    }
+
+    async fn execute_cycle(&mut self) -> Result<(), RuntimeError> {
         info!("================================================================================");
         info!("STATE MACHINE: new cycle: {}", self.state);
+        let new_pending_certificate_requested =
+            self.runtime_control.take_new_pending_certificate_request();
 
         match self.state.clone() {
             AggregatorState::Idle(state) => {
-                let chain_beacon = self.runner.get_beacon_from_chain().await?;
+                let chain_beacon = self.read_beacon_from_chain().await?;
 
                 if state.current_beacon.is_none()
                     || chain_beacon
@@ -149,7 +311,7 @@ impl AggregatorRuntime {
                 }
             }
             AggregatorState::Ready(state) => {
-                let chain_beacon: Beacon = self.runner.get_beacon_from_chain().await?;
+                let chain_beacon: Beacon = self.read_beacon_from_chain().await?;
 
                 if chain_beacon
                     .compare_to_older(&state.current_beacon)?
@@ -160,30 +322,32 @@ impl AggregatorRuntime {
                     self.state = AggregatorState::Idle(IdleState {
                         current_beacon: Some(state.current_beacon),
                     });
-                } else if self
+                } else if self.runtime_control.is_signing_paused() {
+                    info!(" ⋅ signing is paused, waiting…");
+                } else if let Some(signed_entity_type) = self
                     .runner
-                    .does_certificate_exist_for_beacon(&state.current_beacon)
+                    .get_next_signed_entity_type(&state.current_beacon)
                     .await?
                 {
+                    // transition READY > SIGNING
+                    info!("→ transitioning to SIGNING"; "signed_entity_type" => ?signed_entity_type);
+                    let new_state = self
+                        .transition_from_ready_to_signing(state.current_beacon, signed_entity_type)
+                        .await?;
+                    self.state = AggregatorState::Signing(new_state);
+                } else {
                     // READY > READY
                     info!(
-                        " ⋅ a certificate already exists for this beacon, waiting…";
+                        " ⋅ a certificate already exists for each signed entity of this beacon, waiting…";
                         "beacon" => ?state.current_beacon
                     );
                     self.state = AggregatorState::Ready(ReadyState {
                         current_beacon: chain_beacon,
                     });
-                } else {
-                    // transition READY > SIGNING
-                    info!("→ transitioning to SIGNING");
-                    let new_state = self
-                        .transition_from_ready_to_signing(state.current_beacon)
-                        .await?;
-                    self.state = AggregatorState::Signing(new_state);
                 }
             }
             AggregatorState::Signing(state) => {
-                let chain_beacon: Beacon = self.runner.get_beacon_from_chain().await?;
+                let chain_beacon: Beacon = self.read_beacon_from_chain().await?;
 
                 if chain_beacon
                     .compare_to_older(&state.current_beacon)?
@@ -194,11 +358,25 @@ impl AggregatorRuntime {
                         .transition_from_signing_to_idle_new_beacon(state)
                         .await?;
                     self.state = AggregatorState::Idle(new_state);
+                } else if new_pending_certificate_requested {
+                    info!("→ a new pending certificate has been requested, transitioning to READY");
+                    self.runner.drop_pending_certificate().await?;
+                    self.drop_snapshot_archive(&state).await;
+                    self.state = AggregatorState::Ready(ReadyState {
+                        current_beacon: state.current_beacon,
+                    });
                 } else if self.runner.is_multisig_created().await? {
-                    info!("→ a multi-signature have been created, build a snapshot & a certificate and transitioning back to IDLE");
-                    let new_state = self
-                        .transition_from_signing_to_idle_multisignature(state)
-                        .await?;
+                    info!("→ a multi-signature have been created, build a snapshot & a certificate and transitioning back to READY or IDLE");
+                    self.event_bus.publish(AggregatorEvent::QuorumReached {
+                        beacon: state.current_beacon.clone(),
+                    });
+                    self.state = self.transition_from_signing_multisignature(state).await?;
+                } else if self
+                    .runner
+                    .is_pending_certificate_expired(&state.started_at)
+                {
+                    info!("→ the pending certificate has expired, recording the failed round and transitioning to IDLE");
+                    let new_state = self.transition_from_signing_to_idle_expired(state).await?;
                     self.state = AggregatorState::Idle(new_state);
                 } else {
                     info!(" ⋅ not enough signature yet to aggregate a multi-signature, waiting…");
@@ -225,6 +403,9 @@ impl AggregatorRuntime {
             self.runner
                 .update_protocol_parameters_in_multisigner(&new_beacon)
                 .await?;
+            self.event_bus.publish(AggregatorEvent::EpochChanged {
+                epoch: new_beacon.epoch,
+            });
         }
 
         let is_chain_valid = self.runner.is_certificate_chain_valid().await?;
@@ -234,44 +415,158 @@ impl AggregatorRuntime {
         Ok(is_chain_valid)
     }
 
+    /// Perform a transition from `SIGNING` state when a new multi-signature is issued.
+    ///
+    /// The stake distribution and the snapshot of a beacon are both certified, so the
+    /// state machine goes back to `READY` on the same beacon after a stake distribution
+    /// certificate and to `IDLE` after a snapshot certificate.
+    async fn transition_from_signing_multisignature(
+        &self,
+        state: SigningState,
+    ) -> Result<AggregatorState, RuntimeError> {
+        trace!("launching transition from SIGNING state after a multi-signature");
+        let signed_entity_type = state.certificate_pending.get_signed_entity_type();
+        match &signed_entity_type {
+            SignedEntityType::MithrilStakeDistribution(_) => {
+                let certificate = self
+                    .runner
+                    .create_certificate(&state.current_beacon)
+                    .await?;
+                let sealed_event =
+                    Self::certificate_sealed_event(&certificate, signed_entity_type.clone());
+                self.runner.seal_certificate(certificate, None).await?;
+                self.event_bus.publish(sealed_event);
+
+                Ok(AggregatorState::ready(state.current_beacon))
+            }
+            SignedEntityType::CardanoImmutableFilesFull(_) => {
+                let certificate = self
+                    .runner
+                    .create_certificate(&state.current_beacon)
+                    .await?;
+                match &state.ongoing_snapshot {
+                    Some(ongoing_snapshot) => {
+                        self.publish_snapshot(
+                            &state.current_beacon,
+                            signed_entity_type.clone(),
+                            certificate,
+                            ongoing_snapshot,
+                        )
+                        .await?;
+                    }
+                    None => {
+                        // Without chunks, the snapshot archive is only built once the quorum
+                        // is reached so that no archive is built for a failed round.
+                        let ongoing_snapshot = self
+                            .runner
+                            .create_snapshot_archive(
+                                &state.current_beacon,
+                                &certificate.protocol_message,
+                            )
+                            .await?;
+                        if let Err(error) = self
+                            .publish_snapshot(
+                                &state.current_beacon,
+                                signed_entity_type.clone(),
+                                certificate,
+                                &ongoing_snapshot,
+                            )
+                            .await
+                        {
+                            self.runner.drop_snapshot_archive(&ongoing_snapshot).await;
+                            return Err(error);
+                        }
+                    }
+                }
+
+                Ok(AggregatorState::idle(Some(state.current_beacon)))
+            }
+        }
+    }
+
+    /// Build the event published once the given certificate is sealed.
+    fn certificate_sealed_event(
+        certificate: &Certificate,
+        signed_entity_type: SignedEntityType,
+    ) -> AggregatorEvent {
+        AggregatorEvent::CertificateSealed {
+            hash: certificate.hash.clone(),
+            beacon: certificate.beacon.clone(),
+            signed_entity_type,
+        }
+    }
+
+    /// Upload the given snapshot archive, then seal the given certificate along its snapshot.
+    async fn publish_snapshot(
+        &self,
+        beacon: &Beacon,
+        signed_entity_type: SignedEntityType,
+        certificate: Certificate,
+        ongoing_snapshot: &OngoingSnapshot,
+    ) -> Result<(), RuntimeError> {
+        let locations = match self.runner.upload_snapshot_archive(ongoing_snapshot).await {
+            Ok(locations) => {
+                self.runtime_control.clear_failed_snapshot_upload();
+                locations
+            }
+            Err(error) => {
+                self.runtime_control
+                    .record_failed_snapshot_upload(beacon.clone());
+                return Err(error);
+            }
+        };
+        let snapshot = self
+            .runner
+            .create_snapshot(&certificate, ongoing_snapshot, locations)
+            .await?;
+        let sealed_event = Self::certificate_sealed_event(&certificate, signed_entity_type);
+        let published_event = AggregatorEvent::SnapshotPublished {
+            digest: snapshot.digest.clone(),
+            beacon: snapshot.beacon.clone(),
+        };
+        self.runner
+            .seal_certificate(certificate, Some(snapshot))
+            .await?;
+        self.event_bus.publish(sealed_event);
+        self.event_bus.publish(published_event);
+
+        Ok(())
+    }
+
     /// Perform a transition from `SIGNING` state to `IDLE` state when a new
-    /// multi-signature is issued.
-    async fn transition_from_signing_to_idle_multisignature(
+    /// beacon is detected.
+    async fn transition_from_signing_to_idle_new_beacon(
         &self,
         state: SigningState,
     ) -> Result<IdleState, RuntimeError> {
         trace!("launching transition from SIGNING to IDLE state");
         self.runner.drop_pending_certificate().await?;
-        let ongoing_snapshot = self
-            .runner
-            .create_snapshot_archive(&state.current_beacon)
-            .await?;
-        let locations = self
-            .runner
-            .upload_snapshot_archive(&ongoing_snapshot)
-            .await?;
-        let certificate = self
-            .runner
-            .create_and_save_certificate(&state.current_beacon)
-            .await?;
-        let _ = self
-            .runner
-            .create_and_save_snapshot(certificate, &ongoing_snapshot, locations)
-            .await?;
+        self.drop_snapshot_archive(&state).await;
 
         Ok(IdleState {
             current_beacon: Some(state.current_beacon),
         })
     }
 
-    /// Perform a transition from `SIGNING` state to `IDLE` state when a new
-    /// beacon is detected.
-    async fn transition_from_signing_to_idle_new_beacon(
+    /// Perform a transition from `SIGNING` state to `IDLE` state when the pending
+    /// certificate has expired before its quorum was reached.
+    async fn transition_from_signing_to_idle_expired(
         &self,
         state: SigningState,
     ) -> Result<IdleState, RuntimeError> {
         trace!("launching transition from SIGNING to IDLE state");
+        let failed_round = self
+            .runner
+            .record_failed_round(&state.certificate_pending, &state.started_at)
+            .await?;
+        warn!(
+            " > quorum not reached before the pending certificate expired";
+            "won_lotteries" => failed_round.won_lotteries,
+            "quorum" => failed_round.quorum,
+            "missing_signers" => ?failed_round.missing_signers
+        );
         self.runner.drop_pending_certificate().await?;
+        self.drop_snapshot_archive(&state).await;
 
         Ok(IdleState {
             current_beacon: Some(state.current_beacon),
@@ -279,31 +574,93 @@ impl AggregatorRuntime {
     }
 
     /// Perform a transition from `READY` state to `SIGNING` state when a new
-    /// beacon is detected.
+    /// beacon is detected or when an entity of the beacon is not certified yet.
     async fn transition_from_ready_to_signing(
         &mut self,
         new_beacon: Beacon,
+        signed_entity_type: SignedEntityType,
     ) -> Result<SigningState, RuntimeError> {
         trace!("launching transition from READY to SIGNING state");
         self.runner.update_beacon(&new_beacon).await?;
 
-        let digester_result = self.runner.compute_digest(&new_beacon).await?;
+        let mut protocol_message = self
+            .runner
+            .compute_protocol_message(&signed_entity_type)
+            .await?;
+        // When the snapshot archives are split in chunks, the archive is built before the
+        // pending certificate is created so that the hash of its manifest is part of the
+        // signed message. Otherwise it is only built once the quorum is reached.
+        let ongoing_snapshot = match &signed_entity_type {
+            SignedEntityType::CardanoImmutableFilesFull(_)
+                if self.runner.is_snapshot_chunking_enabled() =>
+            {
+                let ongoing_snapshot = self
+                    .runner
+                    .create_snapshot_archive(&new_beacon, &protocol_message)
+                    .await?;
+                if let Some(manifest) = ongoing_snapshot.get_manifest() {
+                    protocol_message.set_message_part(
+                        ProtocolMessagePartKey::SnapshotManifestHash,
+                        manifest.compute_hash(),
+                    );
+                }
+                Some(ongoing_snapshot)
+            }
+            _ => None,
+        };
+        let certificate_pending = match self
+            .create_pending_certificate(&new_beacon, signed_entity_type, protocol_message)
+            .await
+        {
+            Ok(certificate_pending) => certificate_pending,
+            Err(error) => {
+                if let Some(ongoing_snapshot) = &ongoing_snapshot {
+                    self.runner.drop_snapshot_archive(ongoing_snapshot).await;
+                }
+                return Err(error);
+            }
+        };
+        self.event_bus
+            .publish(AggregatorEvent::PendingCertificateCreated {
+                beacon: certificate_pending.beacon.clone(),
+                signed_entity_type: certificate_pending.get_signed_entity_type(),
+            });
+        let state = SigningState {
+            current_beacon: new_beacon,
+            certificate_pending,
+            started_at: Utc::now(),
+            ongoing_snapshot,
+        };
+
+        Ok(state)
+    }
+
+    /// Create and save the pending certificate of the given protocol message.
+    async fn create_pending_certificate(
+        &self,
+        beacon: &Beacon,
+        signed_entity_type: SignedEntityType,
+        protocol_message: ProtocolMessage,
+    ) -> Result<CertificatePending, RuntimeError> {
         self.runner
-            .update_message_in_multisigner(digester_result)
+            .update_message_in_multisigner(protocol_message)
             .await?;
         let certificate_pending = self
             .runner
-            .create_new_pending_certificate_from_multisigner(new_beacon.clone())
+            .create_new_pending_certificate_from_multisigner(beacon.clone(), signed_entity_type)
             .await?;
         self.runner
             .save_pending_certificate(certificate_pending.clone())
             .await?;
-        let state = SigningState {
-            current_beacon: new_beacon,
-            certificate_pending,
-        };
 
-        Ok(state)
+        Ok(certificate_pending)
+    }
+
+    /// Remove the snapshot archive built for the pending certificate of the given state, if any.
+    async fn drop_snapshot_archive(&self, state: &SigningState) {
+        if let Some(ongoing_snapshot) = &state.ongoing_snapshot {
+            self.runner.drop_snapshot_archive(ongoing_snapshot).await;
+        }
     }
 }
 
@@ -311,20 +668,106 @@ impl AggregatorRuntime {
 mod tests {
     use std::path::Path;
 
-    use crate::snapshotter::OngoingSnapshot;
-
     use super::super::runner::MockAggregatorRunner;
     use super::*;
+    use mithril_common::entities::{
+        CompressionAlgorithm, FailedRound, Snapshot, SnapshotChunk, SnapshotManifest,
+    };
     use mithril_common::fake_data;
     use mockall::predicate;
+    use tokio::sync::RwLock;
 
     async fn init_runtime(
         init_state: Option<AggregatorState>,
         runner: MockAggregatorRunner,
     ) -> AggregatorRuntime {
-        AggregatorRuntime::new(Duration::from_millis(100), init_state, Arc::new(runner))
+        init_runtime_with_metrics(init_state, runner, Arc::new(MetricsService::new().unwrap()))
             .await
-            .unwrap()
+    }
+
+    async fn init_runtime_with_metrics(
+        init_state: Option<AggregatorState>,
+        runner: MockAggregatorRunner,
+        metrics_service: Arc<MetricsService>,
+    ) -> AggregatorRuntime {
+        AggregatorRuntime::new(
+            Duration::from_millis(100),
+            init_state,
+            Arc::new(runner),
+            metrics_service,
+            Arc::new(RwLock::new(RuntimeStatus::default())),
+            Arc::new(EventBus::default()),
+            Arc::new(RuntimeControl::new()),
+        )
+        .await
+        .unwrap()
+    }
+
+    async fn init_runtime_with_control(
+        init_state: Option<AggregatorState>,
+        runner: MockAggregatorRunner,
+        runtime_control: Arc<RuntimeControl>,
+    ) -> AggregatorRuntime {
+        AggregatorRuntime::new(
+            Duration::from_millis(100),
+            init_state,
+            Arc::new(runner),
+            Arc::new(MetricsService::new().unwrap()),
+            Arc::new(RwLock::new(RuntimeStatus::default())),
+            Arc::new(EventBus::default()),
+            runtime_control,
+        )
+        .await
+        .unwrap()
+    }
+
+    async fn init_runtime_with_event_bus(
+        init_state: Option<AggregatorState>,
+        runner: MockAggregatorRunner,
+        event_bus: Arc<EventBus>,
+    ) -> AggregatorRuntime {
+        AggregatorRuntime::new(
+            Duration::from_millis(100),
+            init_state,
+            Arc::new(runner),
+            Arc::new(MetricsService::new().unwrap()),
+            Arc::new(RwLock::new(RuntimeStatus::default())),
+            event_bus,
+            Arc::new(RuntimeControl::new()),
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    pub async fn runtime_starts_idle_when_no_state_saved() {
+        let mut runner = MockAggregatorRunner::new();
+        runner
+            .expect_restore_runtime_state()
+            .once()
+            .returning(|| Ok(None));
+        let runtime = init_runtime(None, runner).await;
+
+        assert_eq!(AggregatorState::idle(None), runtime.state);
+    }
+
+    #[tokio::test]
+    pub async fn runtime_resumes_from_saved_state() {
+        let mut runner = MockAggregatorRunner::new();
+        let state = AggregatorState::Signing(SigningState {
+            current_beacon: fake_data::beacon(),
+            certificate_pending: fake_data::certificate_pending(),
+            started_at: Utc::now(),
+            ongoing_snapshot: None,
+        });
+        let saved_state = state.clone();
+        runner
+            .expect_restore_runtime_state()
+            .once()
+            .returning(move || Ok(Some(saved_state.clone())));
+        let runtime = init_runtime(None, runner).await;
+
+        assert_eq!(state, runtime.state);
     }
 
     #[tokio::test]
@@ -388,6 +831,10 @@ mod tests {
     #[tokio::test]
     pub async fn idle_check_certificate_chain_is_valid() {
         let mut runner = MockAggregatorRunner::new();
+        runner
+            .expect_save_runtime_state()
+            .once()
+            .returning(|_| Ok(()));
         runner
             .expect_get_beacon_from_chain()
             .once()
@@ -411,22 +858,88 @@ mod tests {
             .expect_is_certificate_chain_valid()
             .once()
             .returning(|| Ok(true));
+        let metrics_service = Arc::new(MetricsService::new().unwrap());
 
-        let mut runtime = init_runtime(
+        let mut runtime = init_runtime_with_metrics(
             Some(AggregatorState::Idle(IdleState {
                 current_beacon: None,
             })),
             runner,
+            metrics_service.clone(),
         )
         .await;
         runtime.cycle().await.unwrap();
 
         assert_eq!("ready".to_string(), runtime.get_state());
+        let metrics = metrics_service.export().unwrap();
+        assert!(metrics.contains(
+            "mithril_aggregator_runtime_state_transitions_total{from=\"idle\",to=\"ready\"} 1"
+        ));
+        assert!(metrics.contains("mithril_aggregator_runtime_cycle_duration_seconds_count 1"));
+    }
+
+    #[tokio::test]
+    pub async fn runtime_status_is_published_after_each_cycle() {
+        let mut runner = MockAggregatorRunner::new();
+        runner
+            .expect_save_runtime_state()
+            .once()
+            .returning(|_| Ok(()));
+        let beacon = fake_data::beacon();
+        let new_beacon = Beacon {
+            epoch: beacon.epoch + 1,
+            ..beacon.clone()
+        };
+        runner
+            .expect_get_beacon_from_chain()
+            .once()
+            .returning(move || Ok(new_beacon.clone()));
+        let runtime_status = Arc::new(RwLock::new(RuntimeStatus::default()));
+        let mut runtime = AggregatorRuntime::new(
+            Duration::from_millis(100),
+            Some(AggregatorState::Ready(ReadyState {
+                current_beacon: beacon.clone(),
+            })),
+            Arc::new(runner),
+            Arc::new(MetricsService::new().unwrap()),
+            runtime_status.clone(),
+            Arc::new(EventBus::default()),
+            Arc::new(RuntimeControl::new()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            RuntimeStatus {
+                state: "ready".to_string(),
+                beacon: Some(beacon.clone()),
+                ..RuntimeStatus::default()
+            },
+            *runtime_status.read().await
+        );
+
+        runtime.cycle().await.unwrap();
+
+        assert_eq!(
+            RuntimeStatus {
+                state: "idle".to_string(),
+                beacon: Some(beacon.clone()),
+                chain_observer: ChainObserverStatus {
+                    healthy: true,
+                    epoch: Some(beacon.epoch + 1),
+                    error: None,
+                },
+            },
+            *runtime_status.read().await
+        );
     }
 
     #[tokio::test]
     pub async fn ready_new_epoch_detected() {
         let mut runner = MockAggregatorRunner::new();
+        runner
+            .expect_save_runtime_state()
+            .once()
+            .returning(|_| Ok(()));
         let beacon = fake_data::beacon();
         let new_beacon = Beacon {
             epoch: beacon.epoch + 1,
@@ -451,6 +964,10 @@ mod tests {
     #[tokio::test]
     pub async fn ready_certificate_already_exist_for_beacon() {
         let mut runner = MockAggregatorRunner::new();
+        runner
+            .expect_save_runtime_state()
+            .once()
+            .returning(|_| Ok(()));
         let beacon = fake_data::beacon();
         let next_beacon = Beacon {
             immutable_file_number: beacon.immutable_file_number + 1,
@@ -462,9 +979,9 @@ mod tests {
             .once()
             .returning(move || Ok(next_beacon.clone()));
         runner
-            .expect_does_certificate_exist_for_beacon()
+            .expect_get_next_signed_entity_type()
             .once()
-            .returning(|_| Ok(true));
+            .returning(|_| Ok(None));
         let mut runtime = init_runtime(
             Some(AggregatorState::Ready(ReadyState {
                 current_beacon: beacon.clone(),
@@ -483,36 +1000,101 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    pub async fn ready_signing_paused() {
+        let mut runner = MockAggregatorRunner::new();
+        runner
+            .expect_get_beacon_from_chain()
+            .once()
+            .returning(|| Ok(fake_data::beacon()));
+        runner.expect_get_next_signed_entity_type().never();
+        let runtime_control = Arc::new(RuntimeControl::new());
+        runtime_control.pause_signing();
+        let mut runtime = init_runtime_with_control(
+            Some(AggregatorState::Ready(ReadyState {
+                current_beacon: fake_data::beacon(),
+            })),
+            runner,
+            runtime_control,
+        )
+        .await;
+        runtime.cycle().await.unwrap();
+
+        assert_eq!("ready".to_string(), runtime.get_state());
+    }
+
     #[tokio::test]
     pub async fn ready_certificate_does_not_exist_for_beacon() {
         let mut runner = MockAggregatorRunner::new();
+        runner
+            .expect_save_runtime_state()
+            .once()
+            .returning(|_| Ok(()));
         runner
             .expect_get_beacon_from_chain()
             .once()
             .returning(|| Ok(fake_data::beacon()));
         runner
-            .expect_does_certificate_exist_for_beacon()
+            .expect_get_next_signed_entity_type()
             .once()
-            .returning(|_| Ok(false));
+            .returning(|beacon| {
+                Ok(Some(SignedEntityType::CardanoImmutableFilesFull(
+                    beacon.clone(),
+                )))
+            });
         runner
-            .expect_compute_digest()
+            .expect_compute_protocol_message()
+            .with(predicate::eq(SignedEntityType::CardanoImmutableFilesFull(
+                fake_data::beacon(),
+            )))
             .once()
-            .returning(|_| Ok("whatever".to_string()));
+            .returning(|_| Ok(ProtocolMessage::new()));
         runner
             .expect_update_beacon()
             .with(predicate::eq(fake_data::beacon()))
             .once()
             .returning(|_| Ok(()));
+        runner
+            .expect_is_snapshot_chunking_enabled()
+            .once()
+            .returning(|| true);
+        let manifest = SnapshotManifest::new(
+            "digest".to_string(),
+            CompressionAlgorithm::Gzip,
+            vec![SnapshotChunk::new("chunk-hash".to_string(), 1234)],
+        );
+        let ongoing_snapshot =
+            OngoingSnapshot::new(Path::new("/tmp/archive.zip").to_path_buf(), 1234)
+                .with_manifest(manifest.clone());
+        let expected_ongoing_snapshot = ongoing_snapshot.clone();
+        runner
+            .expect_create_snapshot_archive()
+            .with(
+                predicate::eq(fake_data::beacon()),
+                predicate::eq(ProtocolMessage::new()),
+            )
+            .once()
+            .returning(move |_, _| Ok(ongoing_snapshot.clone()));
+        let mut expected_message = ProtocolMessage::new();
+        expected_message.set_message_part(
+            ProtocolMessagePartKey::SnapshotManifestHash,
+            manifest.compute_hash(),
+        );
         runner
             .expect_update_message_in_multisigner()
-            .with(predicate::eq("whatever".to_string()))
+            .with(predicate::eq(expected_message))
             .once()
             .returning(|_| Ok(()));
         runner
             .expect_create_new_pending_certificate_from_multisigner()
-            .with(predicate::eq(fake_data::beacon()))
+            .with(
+                predicate::eq(fake_data::beacon()),
+                predicate::eq(SignedEntityType::CardanoImmutableFilesFull(
+                    fake_data::beacon(),
+                )),
+            )
             .once()
-            .returning(|_| Ok(fake_data::certificate_pending()));
+            .returning(|_, _| Ok(fake_data::certificate_pending()));
         runner
             .expect_save_pending_certificate()
             .once()
@@ -528,11 +1110,136 @@ mod tests {
         runtime.cycle().await.unwrap();
 
         assert_eq!("signing".to_string(), runtime.get_state());
-    }
+        match runtime.state {
+            AggregatorState::Signing(state) => {
+                assert_eq!(Some(expected_ongoing_snapshot), state.ongoing_snapshot)
+            }
+            state => panic!("unexpected state: {state:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn ready_snapshot_archive_is_dropped_when_pending_certificate_creation_fails() {
+        let mut runner = MockAggregatorRunner::new();
+        runner
+            .expect_get_beacon_from_chain()
+            .once()
+            .returning(|| Ok(fake_data::beacon()));
+        runner
+            .expect_get_next_signed_entity_type()
+            .once()
+            .returning(|beacon| {
+                Ok(Some(SignedEntityType::CardanoImmutableFilesFull(
+                    beacon.clone(),
+                )))
+            });
+        runner
+            .expect_compute_protocol_message()
+            .once()
+            .returning(|_| Ok(ProtocolMessage::new()));
+        runner.expect_update_beacon().once().returning(|_| Ok(()));
+        runner
+            .expect_is_snapshot_chunking_enabled()
+            .once()
+            .returning(|| true);
+        runner
+            .expect_create_snapshot_archive()
+            .once()
+            .returning(|_, _| {
+                Ok(OngoingSnapshot::new(
+                    Path::new("/tmp/archive.zip").to_path_buf(),
+                    1234,
+                ))
+            });
+        runner
+            .expect_update_message_in_multisigner()
+            .once()
+            .returning(|_| Err(RuntimeError::General("an error".to_string().into())));
+        runner
+            .expect_drop_snapshot_archive()
+            .once()
+            .returning(|_| ());
+
+        let mut runtime = init_runtime(
+            Some(AggregatorState::Ready(ReadyState {
+                current_beacon: fake_data::beacon(),
+            })),
+            runner,
+        )
+        .await;
+        runtime
+            .cycle()
+            .await
+            .expect_err("the cycle should fail when the pending certificate can't be created");
+
+        assert_eq!("ready".to_string(), runtime.get_state());
+    }
+
+    #[tokio::test]
+    async fn ready_snapshot_archive_is_not_built_without_chunks() {
+        let mut runner = MockAggregatorRunner::new();
+        runner
+            .expect_save_runtime_state()
+            .once()
+            .returning(|_| Ok(()));
+        runner
+            .expect_get_beacon_from_chain()
+            .once()
+            .returning(|| Ok(fake_data::beacon()));
+        runner
+            .expect_get_next_signed_entity_type()
+            .once()
+            .returning(|beacon| {
+                Ok(Some(SignedEntityType::CardanoImmutableFilesFull(
+                    beacon.clone(),
+                )))
+            });
+        runner
+            .expect_compute_protocol_message()
+            .once()
+            .returning(|_| Ok(ProtocolMessage::new()));
+        runner.expect_update_beacon().once().returning(|_| Ok(()));
+        runner
+            .expect_is_snapshot_chunking_enabled()
+            .once()
+            .returning(|| false);
+        runner.expect_create_snapshot_archive().never();
+        runner
+            .expect_update_message_in_multisigner()
+            .with(predicate::eq(ProtocolMessage::new()))
+            .once()
+            .returning(|_| Ok(()));
+        runner
+            .expect_create_new_pending_certificate_from_multisigner()
+            .once()
+            .returning(|_, _| Ok(fake_data::certificate_pending()));
+        runner
+            .expect_save_pending_certificate()
+            .once()
+            .returning(|_| Ok(()));
+
+        let mut runtime = init_runtime(
+            Some(AggregatorState::Ready(ReadyState {
+                current_beacon: fake_data::beacon(),
+            })),
+            runner,
+        )
+        .await;
+        runtime.cycle().await.unwrap();
+
+        match runtime.state {
+            AggregatorState::Signing(state) => assert_eq!(None, state.ongoing_snapshot),
+            state => panic!("unexpected state: {state:?}"),
+        }
+    }
 
     #[tokio::test]
     async fn signing_changing_beacon_to_idle() {
         let mut runner = MockAggregatorRunner::new();
+        runner
+            .expect_save_runtime_state()
+            .once()
+            .returning(|_| Ok(()));
         runner
             .expect_get_beacon_from_chain()
             .once()
@@ -541,6 +1248,13 @@ mod tests {
             .expect_drop_pending_certificate()
             .once()
             .returning(|| Ok(Some(fake_data::certificate_pending())));
+        let ongoing_snapshot =
+            OngoingSnapshot::new(Path::new("/tmp/archive.zip").to_path_buf(), 1234);
+        runner
+            .expect_drop_snapshot_archive()
+            .with(predicate::eq(ongoing_snapshot.clone()))
+            .once()
+            .returning(|_| ());
 
         let state = SigningState {
             // this current beacon must be outdated so the state machine will
@@ -552,6 +1266,8 @@ mod tests {
                 beacon
             },
             certificate_pending: fake_data::certificate_pending(),
+            started_at: Utc::now(),
+            ongoing_snapshot: Some(ongoing_snapshot),
         };
         let mut runtime = init_runtime(Some(AggregatorState::Signing(state)), runner).await;
         runtime.cycle().await.unwrap();
@@ -559,9 +1275,52 @@ mod tests {
         assert_eq!("idle".to_string(), runtime.get_state());
     }
 
+    #[tokio::test]
+    async fn signing_new_pending_certificate_requested() {
+        let mut runner = MockAggregatorRunner::new();
+        runner
+            .expect_save_runtime_state()
+            .once()
+            .returning(|_| Ok(()));
+        runner
+            .expect_get_beacon_from_chain()
+            .once()
+            .returning(|| Ok(fake_data::beacon()));
+        runner
+            .expect_drop_pending_certificate()
+            .once()
+            .returning(|| Ok(Some(fake_data::certificate_pending())));
+        runner.expect_is_multisig_created().never();
+        let runtime_control = Arc::new(RuntimeControl::new());
+        runtime_control.request_new_pending_certificate();
+
+        let state = SigningState {
+            current_beacon: fake_data::beacon(),
+            certificate_pending: fake_data::certificate_pending(),
+            started_at: Utc::now(),
+            ongoing_snapshot: None,
+        };
+        let mut runtime = init_runtime_with_control(
+            Some(AggregatorState::Signing(state)),
+            runner,
+            runtime_control.clone(),
+        )
+        .await;
+        runtime.cycle().await.unwrap();
+
+        assert_eq!(
+            AggregatorState::Ready(ReadyState {
+                current_beacon: fake_data::beacon(),
+            }),
+            runtime.state
+        );
+        assert!(!runtime_control.take_new_pending_certificate_request());
+    }
+
     #[tokio::test]
     async fn signing_multisig_is_not_created() {
         let mut runner = MockAggregatorRunner::new();
+        runner.expect_save_runtime_state().never();
         runner
             .expect_get_beacon_from_chain()
             .once()
@@ -570,9 +1329,15 @@ mod tests {
             .expect_is_multisig_created()
             .once()
             .returning(|| Ok(false));
+        runner
+            .expect_is_pending_certificate_expired()
+            .once()
+            .returning(|_| false);
         let state = SigningState {
             current_beacon: fake_data::beacon(),
             certificate_pending: fake_data::certificate_pending(),
+            started_at: Utc::now(),
+            ongoing_snapshot: None,
         };
         let mut runtime = init_runtime(Some(AggregatorState::Signing(state)), runner).await;
         runtime.cycle().await.unwrap();
@@ -581,8 +1346,12 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn signing_multisig_is_created() {
+    async fn signing_pending_certificate_expired() {
         let mut runner = MockAggregatorRunner::new();
+        runner
+            .expect_save_runtime_state()
+            .once()
+            .returning(|_| Ok(()));
         runner
             .expect_get_beacon_from_chain()
             .once()
@@ -590,15 +1359,199 @@ mod tests {
         runner
             .expect_is_multisig_created()
             .once()
-            .returning(|| Ok(true));
+            .returning(|| Ok(false));
+        runner
+            .expect_is_pending_certificate_expired()
+            .once()
+            .returning(|_| true);
+        runner
+            .expect_record_failed_round()
+            .with(
+                predicate::eq(fake_data::certificate_pending()),
+                predicate::always(),
+            )
+            .once()
+            .returning(|certificate_pending, started_at| {
+                Ok(FailedRound::new(
+                    certificate_pending,
+                    &[],
+                    started_at.to_rfc3339(),
+                    Utc::now().to_rfc3339(),
+                ))
+            });
         runner
             .expect_drop_pending_certificate()
             .once()
             .returning(|| Ok(Some(fake_data::certificate_pending())));
+        let state = SigningState {
+            current_beacon: fake_data::beacon(),
+            certificate_pending: fake_data::certificate_pending(),
+            started_at: Utc::now(),
+            ongoing_snapshot: None,
+        };
+        let mut runtime = init_runtime(Some(AggregatorState::Signing(state)), runner).await;
+        runtime.cycle().await.unwrap();
+
+        assert_eq!(
+            AggregatorState::idle(Some(fake_data::beacon())),
+            runtime.state
+        );
+    }
+
+    #[tokio::test]
+    async fn signing_multisig_is_created() {
+        let mut runner = MockAggregatorRunner::new();
+        runner
+            .expect_save_runtime_state()
+            .once()
+            .returning(|_| Ok(()));
+        runner
+            .expect_get_beacon_from_chain()
+            .once()
+            .returning(|| Ok(fake_data::beacon()));
+        runner
+            .expect_is_multisig_created()
+            .once()
+            .returning(|| Ok(true));
+        runner
+            .expect_upload_snapshot_archive()
+            .once()
+            .returning(|_path| Ok(vec!["locA".to_string(), "locB".to_string()]));
+        runner
+            .expect_create_certificate()
+            .once()
+            .returning(|_| Ok(fake_data::certificate("whatever".to_string())));
+        runner
+            .expect_create_snapshot()
+            .once()
+            .returning(|_, _, _| Ok(fake_data::snapshots(1)[0].clone()));
+        runner
+            .expect_seal_certificate()
+            .withf(|_, snapshot| snapshot.is_some())
+            .once()
+            .returning(|_, _| Ok(()));
+
+        let state = SigningState {
+            current_beacon: fake_data::beacon(),
+            certificate_pending: fake_data::certificate_pending(),
+            started_at: Utc::now(),
+            ongoing_snapshot: Some(OngoingSnapshot::new(
+                Path::new("/tmp/archive.zip").to_path_buf(),
+                1234,
+            )),
+        };
+        let event_bus = Arc::new(EventBus::default());
+        let mut events = event_bus.subscribe();
+        let mut runtime =
+            init_runtime_with_event_bus(Some(AggregatorState::Signing(state)), runner, event_bus)
+                .await;
+        runtime.cycle().await.unwrap();
+
+        assert_eq!("idle".to_string(), runtime.get_state());
+        let certificate = fake_data::certificate("whatever".to_string());
+        let snapshot = fake_data::snapshots(1)[0].clone();
+        assert_eq!(
+            AggregatorEvent::QuorumReached {
+                beacon: fake_data::beacon()
+            },
+            events.try_recv().unwrap()
+        );
+        assert_eq!(
+            AggregatorEvent::CertificateSealed {
+                hash: certificate.hash,
+                beacon: certificate.beacon,
+                signed_entity_type: fake_data::certificate_pending().get_signed_entity_type(),
+            },
+            events.try_recv().unwrap()
+        );
+        assert_eq!(
+            AggregatorEvent::SnapshotPublished {
+                digest: snapshot.digest,
+                beacon: snapshot.beacon,
+            },
+            events.try_recv().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn signing_multisig_failed_upload_is_recorded() {
+        let mut runner = MockAggregatorRunner::new();
+        runner
+            .expect_get_beacon_from_chain()
+            .once()
+            .returning(|| Ok(fake_data::beacon()));
+        runner
+            .expect_is_multisig_created()
+            .once()
+            .returning(|| Ok(true));
+        runner
+            .expect_upload_snapshot_archive()
+            .once()
+            .returning(|_path| Err(RuntimeError::SnapshotUploader("timeout".to_string())));
+        runner
+            .expect_create_certificate()
+            .once()
+            .returning(|_| Ok(fake_data::certificate("whatever".to_string())));
+        runner.expect_seal_certificate().never();
+        runner.expect_drop_snapshot_archive().never();
+
+        let state = SigningState {
+            current_beacon: fake_data::beacon(),
+            certificate_pending: fake_data::certificate_pending(),
+            started_at: Utc::now(),
+            ongoing_snapshot: Some(OngoingSnapshot::new(
+                Path::new("/tmp/archive.zip").to_path_buf(),
+                1234,
+            )),
+        };
+        let runtime_control = Arc::new(RuntimeControl::new());
+        let mut runtime = init_runtime_with_control(
+            Some(AggregatorState::Signing(state)),
+            runner,
+            runtime_control.clone(),
+        )
+        .await;
+        runtime
+            .cycle()
+            .await
+            .expect_err("the cycle should fail when the upload fails");
+
+        assert_eq!("signing".to_string(), runtime.get_state());
+        assert_eq!(
+            Some(fake_data::beacon()),
+            runtime_control.get_failed_snapshot_upload()
+        );
+    }
+
+    #[tokio::test]
+    async fn signing_multisig_snapshot_archive_is_built_after_quorum_without_chunks() {
+        let mut runner = MockAggregatorRunner::new();
+        runner
+            .expect_save_runtime_state()
+            .once()
+            .returning(|_| Ok(()));
+        runner
+            .expect_get_beacon_from_chain()
+            .once()
+            .returning(|| Ok(fake_data::beacon()));
+        runner
+            .expect_is_multisig_created()
+            .once()
+            .returning(|| Ok(true));
+        let certificate = fake_data::certificate("whatever".to_string());
+        let expected_message = certificate.protocol_message.clone();
+        runner
+            .expect_create_certificate()
+            .once()
+            .returning(move |_| Ok(certificate.clone()));
         runner
             .expect_create_snapshot_archive()
+            .with(
+                predicate::eq(fake_data::beacon()),
+                predicate::eq(expected_message),
+            )
             .once()
-            .returning(|_| {
+            .returning(|_, _| {
                 Ok(OngoingSnapshot::new(
                     Path::new("/tmp/archive.zip").to_path_buf(),
                     1234,
@@ -607,23 +1560,123 @@ mod tests {
         runner
             .expect_upload_snapshot_archive()
             .once()
-            .returning(|_path| Ok(vec!["locA".to_string(), "locB".to_string()]));
+            .returning(|_path| Ok(vec!["locA".to_string()]));
         runner
-            .expect_create_and_save_certificate()
+            .expect_create_snapshot()
             .once()
-            .returning(|_| Ok(fake_data::certificate("whatever".to_string())));
+            .returning(|_, _, _| Ok(fake_data::snapshots(1)[0].clone()));
         runner
-            .expect_create_and_save_snapshot()
+            .expect_seal_certificate()
+            .withf(|_, snapshot| snapshot.is_some())
             .once()
-            .returning(|_, _, _| Ok(fake_data::snapshots(1)[0].clone()));
+            .returning(|_, _| Ok(()));
+        runner.expect_drop_snapshot_archive().never();
 
         let state = SigningState {
             current_beacon: fake_data::beacon(),
             certificate_pending: fake_data::certificate_pending(),
+            started_at: Utc::now(),
+            ongoing_snapshot: None,
         };
         let mut runtime = init_runtime(Some(AggregatorState::Signing(state)), runner).await;
         runtime.cycle().await.unwrap();
 
         assert_eq!("idle".to_string(), runtime.get_state());
     }
+
+    #[tokio::test]
+    async fn signing_multisig_snapshot_archive_built_after_quorum_is_dropped_when_upload_fails() {
+        let mut runner = MockAggregatorRunner::new();
+        runner
+            .expect_get_beacon_from_chain()
+            .once()
+            .returning(|| Ok(fake_data::beacon()));
+        runner
+            .expect_is_multisig_created()
+            .once()
+            .returning(|| Ok(true));
+        runner
+            .expect_create_certificate()
+            .once()
+            .returning(|_| Ok(fake_data::certificate("whatever".to_string())));
+        runner
+            .expect_create_snapshot_archive()
+            .once()
+            .returning(|_, _| {
+                Ok(OngoingSnapshot::new(
+                    Path::new("/tmp/archive.zip").to_path_buf(),
+                    1234,
+                ))
+            });
+        runner
+            .expect_upload_snapshot_archive()
+            .once()
+            .returning(|_path| Err(RuntimeError::SnapshotUploader("timeout".to_string())));
+        runner
+            .expect_drop_snapshot_archive()
+            .once()
+            .returning(|_| ());
+
+        let state = SigningState {
+            current_beacon: fake_data::beacon(),
+            certificate_pending: fake_data::certificate_pending(),
+            started_at: Utc::now(),
+            ongoing_snapshot: None,
+        };
+        let mut runtime = init_runtime(Some(AggregatorState::Signing(state)), runner).await;
+        runtime
+            .cycle()
+            .await
+            .expect_err("the cycle should fail when the upload fails");
+
+        assert_eq!("signing".to_string(), runtime.get_state());
+    }
+
+    #[tokio::test]
+    async fn signing_multisig_is_created_for_stake_distribution() {
+        let mut runner = MockAggregatorRunner::new();
+        runner
+            .expect_save_runtime_state()
+            .once()
+            .returning(|_| Ok(()));
+        runner
+            .expect_get_beacon_from_chain()
+            .once()
+            .returning(|| Ok(fake_data::beacon()));
+        runner
+            .expect_is_multisig_created()
+            .once()
+            .returning(|| Ok(true));
+        runner
+            .expect_create_certificate()
+            .once()
+            .returning(|_| Ok(fake_data::certificate("whatever".to_string())));
+        runner
+            .expect_seal_certificate()
+            .with(
+                predicate::eq(fake_data::certificate("whatever".to_string())),
+                predicate::eq(None::<Snapshot>),
+            )
+            .once()
+            .returning(|_, _| Ok(()));
+
+        let mut certificate_pending = fake_data::certificate_pending();
+        certificate_pending.signed_entity_type = Some(SignedEntityType::MithrilStakeDistribution(
+            fake_data::beacon().epoch,
+        ));
+        let state = SigningState {
+            current_beacon: fake_data::beacon(),
+            certificate_pending,
+            started_at: Utc::now(),
+            ongoing_snapshot: None,
+        };
+        let mut runtime = init_runtime(Some(AggregatorState::Signing(state)), runner).await;
+        runtime.cycle().await.unwrap();
+
+        assert_eq!(
+            AggregatorState::ready(fake_data::beacon()),
+            runtime.state,
+            "the snapshot of the beacon must still be certified after its stake distribution"
+        );
+    }
 }
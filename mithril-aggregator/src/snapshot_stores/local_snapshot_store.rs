@@ -75,7 +75,7 @@ mod tests {
     use super::LocalSnapshotStore;
     use crate::SnapshotStore;
 
-    use mithril_common::entities::Snapshot;
+    use mithril_common::entities::{CompressionAlgorithm, Snapshot};
     use mithril_common::fake_data;
     use mithril_common::store::adapter::DumbStoreAdapter;
 
@@ -88,6 +88,9 @@ mod tests {
             size: 0,
             created_at: "abc".to_string(),
             locations: vec!["abc".to_string()],
+            compression_algorithm: CompressionAlgorithm::Gzip,
+            base_digest: None,
+            manifest_hash: None,
         };
         let list_snapshots_max_items = 5;
         let adapter: DumbStoreAdapter<String, Snapshot> = DumbStoreAdapter::new();
@@ -109,6 +112,9 @@ mod tests {
             size: 0,
             created_at: "abc".to_string(),
             locations: vec!["abc".to_string()],
+            compression_algorithm: CompressionAlgorithm::Gzip,
+            base_digest: None,
+            manifest_hash: None,
         };
         let list_snapshots_max_items = 5;
         let adapter: DumbStoreAdapter<String, Snapshot> = DumbStoreAdapter::new();
@@ -3,7 +3,7 @@ mod remote_snapshot_store;
 mod snapshot_store;
 
 pub use local_snapshot_store::LocalSnapshotStore;
-pub use remote_snapshot_store::RemoteSnapshotStore;
+pub use remote_snapshot_store::{RemoteSnapshotStore, SNAPSHOTS_MANIFEST_FILENAME};
 pub use snapshot_store::SnapshotStore;
 pub use snapshot_store::SnapshotStoreError;
 
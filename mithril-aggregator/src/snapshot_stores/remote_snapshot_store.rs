@@ -10,6 +10,9 @@ use slog_scope::info;
 use std::fs::File;
 use std::path::Path;
 
+/// Name of the manifest file listing the snapshots of a remote snapshot store
+pub const SNAPSHOTS_MANIFEST_FILENAME: &str = "snapshots.json";
+
 /// GoogleCloudPlatformSnapshotStore is a snapshot store working using Google Cloud Platform services
 pub struct RemoteSnapshotStore {
     file_uploader: Box<dyn RemoteFileUploader>,
@@ -70,7 +73,7 @@ impl SnapshotStore for RemoteSnapshotStore {
         );
 
         let snapshots = vec![snapshot];
-        let manifest_to_upload_path = Path::new("snapshots.json");
+        let manifest_to_upload_path = Path::new(SNAPSHOTS_MANIFEST_FILENAME);
         serde_json::to_writer(&File::create(manifest_to_upload_path).unwrap(), &snapshots).unwrap();
 
         self.file_uploader
@@ -86,6 +89,7 @@ impl SnapshotStore for RemoteSnapshotStore {
 mod tests {
     use super::*;
     use httpmock::prelude::*;
+    use mithril_common::entities::CompressionAlgorithm;
     use mithril_common::fake_data;
     use serde_json::json;
 
@@ -180,6 +184,9 @@ mod tests {
             size: 0,
             created_at: "abc".to_string(),
             locations: vec!["abc".to_string()],
+            compression_algorithm: CompressionAlgorithm::Gzip,
+            base_digest: None,
+            manifest_hash: None,
         };
 
         snapshot_store
@@ -203,6 +210,9 @@ mod tests {
             size: 0,
             created_at: "abc".to_string(),
             locations: vec!["abc".to_string()],
+            compression_algorithm: CompressionAlgorithm::Gzip,
+            base_digest: None,
+            manifest_hash: None,
         };
 
         let result = snapshot_store.add_snapshot(snapshot).await;
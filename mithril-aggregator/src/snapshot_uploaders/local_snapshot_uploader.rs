@@ -3,9 +3,14 @@ use crate::snapshot_uploaders::{SnapshotLocation, SnapshotUploader};
 use crate::tools;
 
 use async_trait::async_trait;
+use mithril_common::entities::SnapshotManifest;
 use slog_scope::debug;
 use std::path::{Path, PathBuf};
 
+/// Name of the folder of the target location where the chunks of the snapshot archives are
+/// stored
+pub const SNAPSHOT_CHUNKS_DIRECTORY: &str = "chunks";
+
 /// LocalSnapshotUploader is a snapshot uploader working using local files
 pub struct LocalSnapshotUploader {
     /// Snapshot server listening IP
@@ -24,6 +29,30 @@ impl LocalSnapshotUploader {
             target_location: target_location.to_path_buf(),
         }
     }
+
+    /// Folder where the chunks of the snapshot archives and their manifests are stored
+    fn chunks_location(&self) -> PathBuf {
+        self.target_location.join(SNAPSHOT_CHUNKS_DIRECTORY)
+    }
+
+    /// Write a file of the chunks folder, the content is written in a temporary file first so
+    /// that an interrupted upload never leaves a partial chunk behind
+    async fn write_chunks_file(&self, filename: &str, content: &[u8]) -> Result<(), String> {
+        let chunks_location = self.chunks_location();
+        tokio::fs::create_dir_all(&chunks_location)
+            .await
+            .map_err(|e| format!("Snapshot chunks directory creation failure: {}", e))?;
+        let target_path = chunks_location.join(filename);
+        let part_path = chunks_location.join(format!("{}.part", filename));
+        tokio::fs::write(&part_path, content)
+            .await
+            .map_err(|e| format!("Snapshot chunk write failure: {}", e))?;
+        tokio::fs::rename(&part_path, &target_path)
+            .await
+            .map_err(|e| format!("Snapshot chunk write failure: {}", e))?;
+
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -45,13 +74,40 @@ impl SnapshotUploader for LocalSnapshotUploader {
 
         Ok(location)
     }
+
+    async fn has_snapshot_chunk(&self, chunk_hash: &str) -> Result<bool, String> {
+        Ok(self.chunks_location().join(chunk_hash).exists())
+    }
+
+    async fn upload_snapshot_chunk(&self, chunk_hash: &str, content: &[u8]) -> Result<(), String> {
+        self.write_chunks_file(chunk_hash, content).await
+    }
+
+    async fn upload_snapshot_manifest(
+        &self,
+        manifest: &SnapshotManifest,
+    ) -> Result<SnapshotLocation, String> {
+        let manifest_name = format!("{}.manifest.json", manifest.digest);
+        let content = serde_json::to_vec(manifest)
+            .map_err(|e| format!("Snapshot manifest serialization failure: {}", e))?;
+        self.write_chunks_file(&manifest_name, &content).await?;
+        let location = format!(
+            "{}{}/snapshot_chunks/{}",
+            self.snapshot_server_url,
+            http_server::SERVER_BASE_PATH,
+            manifest_name
+        );
+
+        Ok(location)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::LocalSnapshotUploader;
+    use super::{LocalSnapshotUploader, SNAPSHOT_CHUNKS_DIRECTORY};
     use crate::http_server;
     use crate::snapshot_uploaders::SnapshotUploader;
+    use mithril_common::entities::{CompressionAlgorithm, SnapshotChunk, SnapshotManifest};
     use std::fs::File;
     use std::io::Write;
     use std::path::{Path, PathBuf};
@@ -105,4 +161,66 @@ mod tests {
             .join(archive.file_name().unwrap())
             .exists());
     }
+
+    #[tokio::test]
+    async fn should_store_chunks_under_their_hash() {
+        let target_dir = tempdir().unwrap();
+        let content = b"I swear, this is a chunk of an archive.";
+        let chunk_hash = SnapshotChunk::compute_hash(content);
+        let uploader =
+            LocalSnapshotUploader::new("http://test.com:8080/".to_string(), target_dir.path());
+
+        assert_eq!(Ok(false), uploader.has_snapshot_chunk(&chunk_hash).await);
+        uploader
+            .upload_snapshot_chunk(&chunk_hash, content)
+            .await
+            .unwrap();
+
+        assert_eq!(Ok(true), uploader.has_snapshot_chunk(&chunk_hash).await);
+        assert_eq!(
+            content.to_vec(),
+            std::fs::read(
+                target_dir
+                    .path()
+                    .join(SNAPSHOT_CHUNKS_DIRECTORY)
+                    .join(&chunk_hash)
+            )
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn should_store_manifest_next_to_the_chunks() {
+        let target_dir = tempdir().unwrap();
+        let url = "http://test.com:8080/".to_string();
+        let digest = "41e27b9ed5a32531b95b2b7ff3c0757591a06a337efaf19a524a998e348028e7";
+        let manifest = SnapshotManifest::new(
+            digest.to_string(),
+            CompressionAlgorithm::Gzip,
+            vec![SnapshotChunk::new("chunk-hash-1".to_string(), 10)],
+        );
+        let expected_location = format!(
+            "{}{}/snapshot_chunks/{}.manifest.json",
+            url,
+            http_server::SERVER_BASE_PATH,
+            &digest
+        );
+        let uploader = LocalSnapshotUploader::new(url, target_dir.path());
+
+        assert_eq!(
+            Ok(expected_location),
+            uploader.upload_snapshot_manifest(&manifest).await,
+        );
+        let stored_manifest: SnapshotManifest = serde_json::from_slice(
+            &std::fs::read(
+                target_dir
+                    .path()
+                    .join(SNAPSHOT_CHUNKS_DIRECTORY)
+                    .join(format!("{}.manifest.json", digest)),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(manifest, stored_manifest);
+    }
 }
@@ -4,7 +4,7 @@ mod remote_snapshot_uploader;
 mod snapshot_uploader;
 
 pub use dumb_snapshot_uploader::*;
-pub use local_snapshot_uploader::LocalSnapshotUploader;
+pub use local_snapshot_uploader::{LocalSnapshotUploader, SNAPSHOT_CHUNKS_DIRECTORY};
 pub use remote_snapshot_uploader::RemoteSnapshotUploader;
 pub use snapshot_uploader::SnapshotLocation;
 pub use snapshot_uploader::SnapshotUploader;
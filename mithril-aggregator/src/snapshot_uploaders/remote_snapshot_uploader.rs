@@ -1,36 +1,76 @@
 use crate::snapshot_uploaders::{SnapshotLocation, SnapshotUploader};
 use crate::tools::RemoteFileUploader;
+use mithril_common::entities::SnapshotManifest;
 use std::path::Path;
 
 use async_trait::async_trait;
-use slog_scope::debug;
+use slog_scope::{debug, warn};
 
-/// GCPSnapshotUploader is a snapshot uploader working using Google Cloud Platform services
+/// RemoteSnapshotUploader is a snapshot uploader working using a remote file storage, ie:
+/// Google Cloud Platform or an S3 compatible object storage
 pub struct RemoteSnapshotUploader {
     file_uploader: Box<dyn RemoteFileUploader>,
 }
 
 impl RemoteSnapshotUploader {
-    /// GCPSnapshotUploader factory
+    /// RemoteSnapshotUploader factory
     pub fn new(file_uploader: Box<dyn RemoteFileUploader>) -> Self {
-        debug!("New GCPSnapshotUploader created");
+        debug!("New RemoteSnapshotUploader created");
         Self { file_uploader }
     }
+
+    /// Upload a content under the given name through a temporary file
+    async fn upload_content(&self, filename: &str, content: &[u8]) -> Result<(), String> {
+        let upload_directory = std::env::temp_dir().join("mithril_snapshot_chunks");
+        tokio::fs::create_dir_all(&upload_directory)
+            .await
+            .map_err(|e| format!("Snapshot chunks directory creation failure: {}", e))?;
+        let filepath = upload_directory.join(filename);
+        tokio::fs::write(&filepath, content)
+            .await
+            .map_err(|e| format!("Snapshot chunk write failure: {}", e))?;
+        let result = self.file_uploader.upload_file(&filepath).await;
+        if let Err(error) = tokio::fs::remove_file(&filepath).await {
+            warn!(
+                " > Post upload snapshot chunk file removal failure: {}",
+                error
+            );
+        }
+
+        result
+    }
 }
 
 #[async_trait]
 impl SnapshotUploader for RemoteSnapshotUploader {
     async fn upload_snapshot(&self, snapshot_filepath: &Path) -> Result<SnapshotLocation, String> {
         let archive_name = snapshot_filepath.file_name().unwrap().to_str().unwrap();
-        let location = format!(
-            "https://storage.googleapis.com/cardano-testnet/{}",
-            archive_name
-        );
+        let location = self.file_uploader.get_location(archive_name);
 
         self.file_uploader.upload_file(snapshot_filepath).await?;
 
         Ok(location)
     }
+
+    async fn has_snapshot_chunk(&self, chunk_hash: &str) -> Result<bool, String> {
+        self.file_uploader.file_exists(chunk_hash).await
+    }
+
+    async fn upload_snapshot_chunk(&self, chunk_hash: &str, content: &[u8]) -> Result<(), String> {
+        self.upload_content(chunk_hash, content).await
+    }
+
+    async fn upload_snapshot_manifest(
+        &self,
+        manifest: &SnapshotManifest,
+    ) -> Result<SnapshotLocation, String> {
+        let manifest_name = format!("{}.manifest.json", manifest.digest);
+        let content = serde_json::to_vec(manifest)
+            .map_err(|e| format!("Snapshot manifest serialization failure: {}", e))?;
+        self.upload_content(&manifest_name, &content).await?;
+
+        Ok(self.file_uploader.get_location(&manifest_name))
+    }
 }
 
 #[cfg(test)]
@@ -38,12 +78,20 @@ mod tests {
     use super::RemoteSnapshotUploader;
     use crate::snapshot_uploaders::SnapshotUploader;
     use crate::tools::MockRemoteFileUploader;
+    use mithril_common::entities::{CompressionAlgorithm, SnapshotManifest};
+    use mockall::predicate;
     use std::path::Path;
 
     #[tokio::test]
     async fn test_upload_snapshot_ok() {
         let mut file_uploader = MockRemoteFileUploader::new();
         file_uploader.expect_upload_file().return_const(Ok(()));
+        file_uploader.expect_get_location().returning(|filename| {
+            format!(
+                "https://storage.googleapis.com/cardano-testnet/{}",
+                filename
+            )
+        });
         let snapshot_uploader = RemoteSnapshotUploader::new(Box::new(file_uploader));
         let snapshot_filepath = Path::new("test/snapshot.xxx.tar.gz");
         let expected_location =
@@ -61,10 +109,66 @@ mod tests {
         file_uploader
             .expect_upload_file()
             .return_const(Err("unexpected error".to_string()));
+        file_uploader.expect_get_location().returning(|filename| {
+            format!(
+                "https://storage.googleapis.com/cardano-testnet/{}",
+                filename
+            )
+        });
         let snapshot_uploader = RemoteSnapshotUploader::new(Box::new(file_uploader));
         let snapshot_filepath = Path::new("test/snapshot.xxx.tar.gz");
 
         let result = snapshot_uploader.upload_snapshot(snapshot_filepath).await;
         assert_eq!(Err("unexpected error".to_string()), result);
     }
+
+    #[tokio::test]
+    async fn test_upload_snapshot_chunk_under_its_hash() {
+        let mut file_uploader = MockRemoteFileUploader::new();
+        file_uploader
+            .expect_file_exists()
+            .with(predicate::eq("chunk-hash-1"))
+            .return_const(Ok(false));
+        file_uploader
+            .expect_upload_file()
+            .withf(|filepath| filepath.file_name().unwrap() == "chunk-hash-1")
+            .times(1)
+            .return_const(Ok(()));
+        let snapshot_uploader = RemoteSnapshotUploader::new(Box::new(file_uploader));
+
+        assert_eq!(
+            Ok(false),
+            snapshot_uploader.has_snapshot_chunk("chunk-hash-1").await
+        );
+        snapshot_uploader
+            .upload_snapshot_chunk("chunk-hash-1", b"chunk content")
+            .await
+            .expect("uploading a snapshot chunk should not fail");
+    }
+
+    #[tokio::test]
+    async fn test_upload_snapshot_manifest_ok() {
+        let mut file_uploader = MockRemoteFileUploader::new();
+        file_uploader
+            .expect_upload_file()
+            .withf(|filepath| filepath.file_name().unwrap() == "digest-123.manifest.json")
+            .times(1)
+            .return_const(Ok(()));
+        file_uploader.expect_get_location().returning(|filename| {
+            format!(
+                "https://storage.googleapis.com/cardano-testnet/{}",
+                filename
+            )
+        });
+        let snapshot_uploader = RemoteSnapshotUploader::new(Box::new(file_uploader));
+        let manifest =
+            SnapshotManifest::new("digest-123".to_string(), CompressionAlgorithm::Gzip, vec![]);
+        let expected_location =
+            "https://storage.googleapis.com/cardano-testnet/digest-123.manifest.json".to_string();
+
+        assert_eq!(
+            Ok(expected_location),
+            snapshot_uploader.upload_snapshot_manifest(&manifest).await
+        );
+    }
 }
@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use mithril_common::entities::SnapshotManifest;
 use std::path::Path;
 
 #[cfg(test)]
@@ -12,4 +13,16 @@ pub type SnapshotLocation = String;
 pub trait SnapshotUploader: Sync + Send {
     /// Upload a snapshot
     async fn upload_snapshot(&self, snapshot_filepath: &Path) -> Result<SnapshotLocation, String>;
+
+    /// Check if a chunk of a snapshot archive is already stored
+    async fn has_snapshot_chunk(&self, chunk_hash: &str) -> Result<bool, String>;
+
+    /// Upload a chunk of a snapshot archive, it is stored under its hash
+    async fn upload_snapshot_chunk(&self, chunk_hash: &str, content: &[u8]) -> Result<(), String>;
+
+    /// Upload the manifest of a chunked snapshot, it is stored next to the chunks
+    async fn upload_snapshot_manifest(
+        &self,
+        manifest: &SnapshotManifest,
+    ) -> Result<SnapshotLocation, String>;
 }
@@ -1,9 +1,12 @@
 use flate2::write::GzEncoder;
 use flate2::Compression;
+use mithril_common::digesters::ImmutableFile;
+use mithril_common::entities::{CompressionAlgorithm, ImmutableFileNumber, SnapshotManifest};
+use serde::{Deserialize, Serialize};
 use slog_scope::info;
 use std::error::Error as StdError;
 use std::fs::File;
-use std::io;
+use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 use std::sync::RwLock;
 use thiserror::Error;
@@ -12,6 +15,17 @@ use thiserror::Error;
 pub trait Snapshotter: Sync + Send {
     /// Create a new snapshot with the given archive name.
     fn snapshot(&self, archive_name: &str) -> Result<OngoingSnapshot, SnapshotError>;
+
+    /// Create a new incremental snapshot with the given archive name, it only contains the
+    /// immutable files numbered after the given immutable file number of its base snapshot.
+    fn snapshot_increment(
+        &self,
+        archive_name: &str,
+        base_immutable_file_number: ImmutableFileNumber,
+    ) -> Result<OngoingSnapshot, SnapshotError>;
+
+    /// Compression algorithm of the snapshots archives.
+    fn compression_algorithm(&self) -> CompressionAlgorithm;
 }
 
 /// Gzip Snapshotter create a compressed file.
@@ -21,18 +35,56 @@ pub struct GzipSnapshotter {
 
     /// Directory to store ongoing snapshot
     ongoing_snapshot_directory: PathBuf,
+
+    /// Compression level of the archive
+    compression_level: Compression,
+}
+
+/// Zstandard Snapshotter create a compressed file, using several threads if configured to.
+pub struct ZstandardSnapshotter {
+    /// DB directory to snapshot
+    db_directory: PathBuf,
+
+    /// Directory to store ongoing snapshot
+    ongoing_snapshot_directory: PathBuf,
+
+    /// Compression level of the archive
+    compression_level: i32,
+
+    /// Number of threads used to compress the archive
+    threads: u32,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct OngoingSnapshot {
     filepath: PathBuf,
     filesize: u64,
+    base_digest: Option<String>,
+    manifest: Option<SnapshotManifest>,
 }
 
 impl OngoingSnapshot {
     pub fn new(filepath: PathBuf, filesize: u64) -> Self {
-        Self { filepath, filesize }
+        Self {
+            filepath,
+            filesize,
+            base_digest: None,
+            manifest: None,
+        }
     }
+
+    /// Set the digest of the snapshot this incremental snapshot is based on.
+    pub fn with_base_digest(mut self, base_digest: String) -> Self {
+        self.base_digest = Some(base_digest);
+        self
+    }
+
+    /// Set the manifest of the chunks the archive is split into.
+    pub fn with_manifest(mut self, manifest: SnapshotManifest) -> Self {
+        self.manifest = Some(manifest);
+        self
+    }
+
     pub fn get_file_path(&self) -> &PathBuf {
         &self.filepath
     }
@@ -40,6 +92,22 @@ impl OngoingSnapshot {
     pub fn get_file_size(&self) -> &u64 {
         &self.filesize
     }
+
+    pub fn get_base_digest(&self) -> Option<&String> {
+        self.base_digest.as_ref()
+    }
+
+    pub fn get_manifest(&self) -> Option<&SnapshotManifest> {
+        self.manifest.as_ref()
+    }
+
+    fn from_archive(filepath: PathBuf) -> Result<Self, SnapshotError> {
+        let filesize = std::fs::metadata(&filepath)
+            .map_err(|e| SnapshotError::GeneralError(e.to_string()))?
+            .len();
+
+        Ok(Self::new(filepath, filesize))
+    }
 }
 
 /// Snapshotter error type.
@@ -60,12 +128,23 @@ pub enum SnapshotError {
 
 impl Snapshotter for GzipSnapshotter {
     fn snapshot(&self, archive_name: &str) -> Result<OngoingSnapshot, SnapshotError> {
-        let filepath = self.create_archive(archive_name)?;
-        let filesize = std::fs::metadata(&filepath)
-            .map_err(|e| SnapshotError::GeneralError(e.to_string()))?
-            .len();
+        let filepath = self.create_archive(archive_name, None)?;
+
+        OngoingSnapshot::from_archive(filepath)
+    }
+
+    fn snapshot_increment(
+        &self,
+        archive_name: &str,
+        base_immutable_file_number: ImmutableFileNumber,
+    ) -> Result<OngoingSnapshot, SnapshotError> {
+        let filepath = self.create_archive(archive_name, Some(base_immutable_file_number))?;
+
+        OngoingSnapshot::from_archive(filepath)
+    }
 
-        Ok(OngoingSnapshot { filepath, filesize })
+    fn compression_algorithm(&self) -> CompressionAlgorithm {
+        CompressionAlgorithm::Gzip
     }
 }
 
@@ -75,10 +154,21 @@ impl GzipSnapshotter {
         Self {
             db_directory,
             ongoing_snapshot_directory,
+            compression_level: Compression::default(),
         }
     }
 
-    fn create_archive(&self, archive_name: &str) -> Result<PathBuf, SnapshotError> {
+    /// Set the compression level of the archives, from 0 (no compression) to 9.
+    pub fn with_compression_level(mut self, compression_level: u32) -> Self {
+        self.compression_level = Compression::new(compression_level);
+        self
+    }
+
+    fn create_archive(
+        &self,
+        archive_name: &str,
+        base_immutable_file_number: Option<ImmutableFileNumber>,
+    ) -> Result<PathBuf, SnapshotError> {
         let path = self.ongoing_snapshot_directory.join(archive_name);
         info!(
             "compressing {} into {}",
@@ -87,21 +177,142 @@ impl GzipSnapshotter {
         );
 
         let tar_gz = File::create(&path).map_err(SnapshotError::CreateArchiveError)?;
-        let enc = GzEncoder::new(tar_gz, Compression::default());
-        let mut tar = tar::Builder::new(enc);
+        let enc = GzEncoder::new(tar_gz, self.compression_level);
+        let mut gz = append_db_directory(&self.db_directory, enc, base_immutable_file_number)?;
+        gz.try_finish().map_err(SnapshotError::CreateArchiveError)?;
 
-        tar.append_dir_all(".", &self.db_directory)
-            .map_err(SnapshotError::CreateArchiveError)?;
+        Ok(path)
+    }
+}
 
-        let mut gz = tar
-            .into_inner()
+impl Snapshotter for ZstandardSnapshotter {
+    fn snapshot(&self, archive_name: &str) -> Result<OngoingSnapshot, SnapshotError> {
+        let filepath = self.create_archive(archive_name, None)?;
+
+        OngoingSnapshot::from_archive(filepath)
+    }
+
+    fn snapshot_increment(
+        &self,
+        archive_name: &str,
+        base_immutable_file_number: ImmutableFileNumber,
+    ) -> Result<OngoingSnapshot, SnapshotError> {
+        let filepath = self.create_archive(archive_name, Some(base_immutable_file_number))?;
+
+        OngoingSnapshot::from_archive(filepath)
+    }
+
+    fn compression_algorithm(&self) -> CompressionAlgorithm {
+        CompressionAlgorithm::Zstandard
+    }
+}
+
+impl ZstandardSnapshotter {
+    /// Snapshotter factory
+    pub fn new(db_directory: PathBuf, ongoing_snapshot_directory: PathBuf) -> Self {
+        Self {
+            db_directory,
+            ongoing_snapshot_directory,
+            compression_level: zstd::DEFAULT_COMPRESSION_LEVEL,
+            threads: 1,
+        }
+    }
+
+    /// Set the compression level of the archives, see [zstd::compression_level_range].
+    pub fn with_compression_level(mut self, compression_level: i32) -> Self {
+        self.compression_level = compression_level;
+        self
+    }
+
+    /// Set the number of threads used to compress the archives.
+    pub fn with_threads(mut self, threads: u32) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    fn create_archive(
+        &self,
+        archive_name: &str,
+        base_immutable_file_number: Option<ImmutableFileNumber>,
+    ) -> Result<PathBuf, SnapshotError> {
+        let path = self.ongoing_snapshot_directory.join(archive_name);
+        info!(
+            "compressing {} into {} with {} thread(s)",
+            self.db_directory.display(),
+            path.display(),
+            self.threads
+        );
+
+        let tar_zst = File::create(&path).map_err(SnapshotError::CreateArchiveError)?;
+        let mut enc = zstd::Encoder::new(tar_zst, self.compression_level)
             .map_err(SnapshotError::CreateArchiveError)?;
-        gz.try_finish().map_err(SnapshotError::CreateArchiveError)?;
+        if self.threads > 1 {
+            enc.multithread(self.threads)
+                .map_err(SnapshotError::CreateArchiveError)?;
+        }
+        let zst = append_db_directory(&self.db_directory, enc, base_immutable_file_number)?;
+        zst.finish().map_err(SnapshotError::CreateArchiveError)?;
 
         Ok(path)
     }
 }
 
+/// Write a tar archive of the given DB directory into the given writer, return the writer.
+///
+/// If a base immutable file number is given, only the immutable files numbered after it are
+/// archived.
+fn append_db_directory<W: Write>(
+    db_directory: &Path,
+    writer: W,
+    base_immutable_file_number: Option<ImmutableFileNumber>,
+) -> Result<W, SnapshotError> {
+    let mut tar = tar::Builder::new(writer);
+    match base_immutable_file_number {
+        None => tar
+            .append_dir_all(".", db_directory)
+            .map_err(SnapshotError::CreateArchiveError)?,
+        Some(base_immutable_file_number) => {
+            for immutable_file in
+                list_immutable_files_after(db_directory, base_immutable_file_number)?
+            {
+                let archive_path = immutable_file
+                    .path
+                    .strip_prefix(db_directory)
+                    .map_err(|e| SnapshotError::GeneralError(e.to_string()))?;
+                tar.append_path_with_name(&immutable_file.path, archive_path)
+                    .map_err(SnapshotError::CreateArchiveError)?;
+            }
+        }
+    }
+
+    tar.into_inner().map_err(SnapshotError::CreateArchiveError)
+}
+
+/// List the immutable files of the given DB directory numbered after the given immutable file
+/// number, including the ones that are not completed yet.
+fn list_immutable_files_after(
+    db_directory: &Path,
+    immutable_file_number: ImmutableFileNumber,
+) -> Result<Vec<ImmutableFile>, SnapshotError> {
+    let mut immutable_files = vec![];
+    for entry in std::fs::read_dir(db_directory.join("immutable"))
+        .map_err(SnapshotError::CreateArchiveError)?
+    {
+        let path = entry.map_err(SnapshotError::CreateArchiveError)?.path();
+        if !path.is_file() {
+            continue;
+        }
+        if let Ok(immutable_file) = ImmutableFile::new(path) {
+            if immutable_file.number > immutable_file_number {
+                immutable_files.push(immutable_file);
+            }
+        }
+    }
+    immutable_files.sort();
+
+    Ok(immutable_files)
+}
+
 /// Snapshotter that does nothing. It is mainly used for test purposes.
 pub struct DumbSnapshotter {
     last_snapshot: RwLock<Option<OngoingSnapshot>>,
@@ -142,19 +353,135 @@ impl Snapshotter for DumbSnapshotter {
             .last_snapshot
             .write()
             .map_err(|e| SnapshotError::UploadFileError(e.to_string()))?;
-        let snapshot = OngoingSnapshot {
-            filepath: Path::new(archive_name).to_path_buf(),
-            filesize: 0,
-        };
+        let snapshot = OngoingSnapshot::new(Path::new(archive_name).to_path_buf(), 0);
         *value = Some(snapshot.clone());
 
         Ok(snapshot)
     }
+
+    fn snapshot_increment(
+        &self,
+        archive_name: &str,
+        _base_immutable_file_number: ImmutableFileNumber,
+    ) -> Result<OngoingSnapshot, SnapshotError> {
+        self.snapshot(archive_name)
+    }
+
+    fn compression_algorithm(&self) -> CompressionAlgorithm {
+        CompressionAlgorithm::Gzip
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::io::Read;
+
+    fn setup_db_directory(name: &str) -> (PathBuf, PathBuf) {
+        let test_dir = std::env::temp_dir()
+            .join("mithril_test")
+            .join("snapshotter")
+            .join(name);
+        if test_dir.exists() {
+            std::fs::remove_dir_all(&test_dir).unwrap();
+        }
+        let db_directory = test_dir.join("db");
+        let ongoing_snapshot_directory = test_dir.join("pending_snapshot");
+        std::fs::create_dir_all(db_directory.join("immutable")).unwrap();
+        std::fs::create_dir_all(&ongoing_snapshot_directory).unwrap();
+        std::fs::write(
+            db_directory.join("immutable").join("00001.chunk"),
+            "1234567890".repeat(1024),
+        )
+        .unwrap();
+
+        (db_directory, ongoing_snapshot_directory)
+    }
+
+    fn unpack_archive<R: Read>(reader: R) -> Vec<PathBuf> {
+        let mut archive = tar::Archive::new(reader);
+        archive
+            .entries()
+            .unwrap()
+            .map(|entry| entry.unwrap().path().unwrap().to_path_buf())
+            .collect()
+    }
+
+    #[test]
+    fn test_gzip_snapshotter() {
+        let (db_directory, ongoing_snapshot_directory) = setup_db_directory("gzip");
+        let snapshotter = GzipSnapshotter::new(db_directory, ongoing_snapshot_directory)
+            .with_compression_level(9);
+
+        let snapshot = snapshotter
+            .snapshot("snapshot.tar.gz")
+            .expect("Gzip snapshotter::snapshot should not fail.");
+
+        let archive = File::open(snapshot.get_file_path()).unwrap();
+        let entries = unpack_archive(flate2::read::GzDecoder::new(archive));
+        assert!(entries.contains(&Path::new("immutable/00001.chunk").to_path_buf()));
+    }
+
+    #[test]
+    fn test_zstandard_snapshotter_with_several_threads() {
+        let (db_directory, ongoing_snapshot_directory) = setup_db_directory("zstandard");
+        let snapshotter = ZstandardSnapshotter::new(db_directory, ongoing_snapshot_directory)
+            .with_compression_level(19)
+            .with_threads(2);
+
+        let snapshot = snapshotter
+            .snapshot("snapshot.tar.zst")
+            .expect("Zstandard snapshotter::snapshot should not fail.");
+
+        let mut archive = File::open(snapshot.get_file_path()).unwrap();
+        let mut magic_bytes = [0; 4];
+        archive.read_exact(&mut magic_bytes).unwrap();
+        assert_eq!(
+            Some(CompressionAlgorithm::Zstandard),
+            CompressionAlgorithm::from_magic_bytes(&magic_bytes)
+        );
+        let archive = File::open(snapshot.get_file_path()).unwrap();
+        let entries = unpack_archive(zstd::Decoder::new(archive).unwrap());
+        assert!(entries.contains(&Path::new("immutable/00001.chunk").to_path_buf()));
+    }
+
+    #[test]
+    fn test_snapshot_increment_only_contains_immutable_files_after_base() {
+        let (db_directory, ongoing_snapshot_directory) = setup_db_directory("increment");
+        for number in 2..=3 {
+            for extension in ["chunk", "primary", "secondary"] {
+                std::fs::write(
+                    db_directory
+                        .join("immutable")
+                        .join(format!("{:05}.{}", number, extension)),
+                    "data",
+                )
+                .unwrap();
+            }
+        }
+        std::fs::create_dir_all(db_directory.join("ledger")).unwrap();
+        std::fs::write(db_directory.join("ledger").join("437"), "ledger").unwrap();
+        let snapshotter = GzipSnapshotter::new(db_directory, ongoing_snapshot_directory);
+
+        let snapshot = snapshotter
+            .snapshot_increment("snapshot.tar.gz", 1)
+            .expect("Gzip snapshotter::snapshot_increment should not fail.");
+
+        let archive = File::open(snapshot.get_file_path()).unwrap();
+        let mut entries = unpack_archive(flate2::read::GzDecoder::new(archive));
+        entries.sort();
+        assert_eq!(
+            vec![
+                PathBuf::from("immutable/00002.chunk"),
+                PathBuf::from("immutable/00002.primary"),
+                PathBuf::from("immutable/00002.secondary"),
+                PathBuf::from("immutable/00003.chunk"),
+                PathBuf::from("immutable/00003.primary"),
+                PathBuf::from("immutable/00003.secondary"),
+            ],
+            entries
+        );
+    }
 
     #[test]
     fn test_dumb_snapshotter() {
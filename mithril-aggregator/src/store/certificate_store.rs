@@ -1,22 +1,110 @@
 use async_trait::async_trait;
+use std::{collections::HashSet, sync::Arc};
 use tokio::sync::RwLock;
 
 use mithril_common::certificate_chain::{CertificateRetriever, CertificateRetrieverError};
-use mithril_common::entities::{Beacon, Certificate};
-use mithril_common::store::{adapter::StoreAdapter, StoreError};
+use mithril_common::entities::{
+    Beacon, Certificate, Epoch, ImmutableFileNumber, PartyId, ProtocolMessagePartKey,
+    SignedEntityType,
+};
+use mithril_common::store::{
+    adapter::{AdapterError, RecordCondition, RecordConditionParameter, StoreAdapter},
+    RetentionPolicy, StoreError, StorePruner,
+};
+
+use crate::SnapshotStore;
 
 type Adapter = Box<dyn StoreAdapter<Key = String, Record = Certificate>>;
 
+/// Criteria that the certificates returned by [CertificateStore::search] must meet.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CertificateFilter {
+    /// Lowest epoch of the certificates, included
+    pub epoch_from: Option<Epoch>,
+
+    /// Highest epoch of the certificates, included
+    pub epoch_to: Option<Epoch>,
+
+    /// Immutable file number of the certificates
+    pub immutable_file_number: Option<ImmutableFileNumber>,
+
+    /// Party id of a signer of the certificates
+    pub signer_party_id: Option<PartyId>,
+}
+
+impl CertificateFilter {
+    /// Condition met by the records of the certificates meeting the criteria of the filter,
+    /// evaluated by the query of the SQLite adapter.
+    pub fn to_record_condition(&self) -> RecordCondition<Certificate> {
+        let mut condition = RecordCondition::all();
+
+        if let Some(epoch) = self.epoch_from {
+            condition = condition.and(
+                "json_extract(record.value, '$.beacon.epoch') >= ?",
+                vec![RecordConditionParameter::Integer(epoch.0 as i64)],
+                move |certificate: &Certificate| certificate.beacon.epoch >= epoch,
+            );
+        }
+        if let Some(epoch) = self.epoch_to {
+            condition = condition.and(
+                "json_extract(record.value, '$.beacon.epoch') <= ?",
+                vec![RecordConditionParameter::Integer(epoch.0 as i64)],
+                move |certificate: &Certificate| certificate.beacon.epoch <= epoch,
+            );
+        }
+        if let Some(number) = self.immutable_file_number {
+            condition = condition.and(
+                "json_extract(record.value, '$.beacon.immutable_file_number') = ?",
+                vec![RecordConditionParameter::Integer(number as i64)],
+                move |certificate: &Certificate| certificate.beacon.immutable_file_number == number,
+            );
+        }
+        if let Some(party_id) = self.signer_party_id.clone() {
+            condition = condition.and(
+                "exists (select 1 from json_each(record.value, '$.metadata.signers') as signer \
+                where json_extract(signer.value, '$.party_id') = ?)",
+                vec![RecordConditionParameter::Text(party_id.clone())],
+                move |certificate: &Certificate| {
+                    certificate
+                        .metadata
+                        .signers
+                        .iter()
+                        .any(|signer| signer.party_id == party_id)
+                },
+            );
+        }
+
+        condition
+    }
+}
+
 /// Store for issued certificates.
+///
+/// A certificate is never pruned while it is the previous certificate of
+/// another one or while the snapshot it certifies is still available.
 pub struct CertificateStore {
     adapter: RwLock<Adapter>,
+    retention_policy: RetentionPolicy<String, Certificate>,
+    snapshot_store: Option<Arc<dyn SnapshotStore>>,
 }
 
 impl CertificateStore {
-    /// Create a new instance.
+    /// Create a new instance that keeps all the certificates.
     pub fn new(adapter: Adapter) -> Self {
+        Self::with_retention(adapter, RetentionPolicy::KeepAll, None)
+    }
+
+    /// Create a new instance pruned with the given retention policy, the
+    /// snapshot store is used to check if a certificate is still referenced.
+    pub fn with_retention(
+        adapter: Adapter,
+        retention_policy: RetentionPolicy<String, Certificate>,
+        snapshot_store: Option<Arc<dyn SnapshotStore>>,
+    ) -> Self {
         Self {
             adapter: RwLock::new(adapter),
+            retention_policy,
+            snapshot_store,
         }
     }
 
@@ -43,6 +131,17 @@ impl CertificateStore {
         Ok(iterator.find(|cert| beacon == &cert.beacon))
     }
 
+    /// Fetch a saved certificate that certifies the given signed entity if any.
+    pub async fn get_from_signed_entity_type(
+        &self,
+        signed_entity_type: &SignedEntityType,
+    ) -> Result<Option<Certificate>, StoreError> {
+        let adapter = self.adapter.read().await;
+        let mut iterator = adapter.get_iter().await?;
+
+        Ok(iterator.find(|cert| cert.signed_entity_type().as_ref() == Some(signed_entity_type)))
+    }
+
     /// Save the given certificate.
     pub async fn save(&self, certificate: Certificate) -> Result<(), StoreError> {
         self.adapter
@@ -54,6 +153,13 @@ impl CertificateStore {
         Ok(())
     }
 
+    /// Remove the certificate with the given hash and return it if it existed.
+    pub async fn remove(&self, hash: &str) -> Result<Option<Certificate>, StoreError> {
+        let record = self.adapter.write().await.remove(&hash.to_string()).await?;
+
+        Ok(record)
+    }
+
     /// Return the list of the `last_n` saved certificates sorted by creation
     /// time the most recent first.
     pub async fn get_list(&self, last_n: usize) -> Result<Vec<Certificate>, StoreError> {
@@ -62,6 +168,86 @@ impl CertificateStore {
 
         Ok(result)
     }
+
+    /// Return a page of at most `limit` certificates meeting the criteria of the filter,
+    /// skipping the `offset` first ones, sorted by creation time the most recent first.
+    ///
+    /// The total number of certificates meeting the criteria is returned along the page.
+    pub async fn search(
+        &self,
+        filter: &CertificateFilter,
+        offset: usize,
+        limit: usize,
+    ) -> Result<(Vec<Certificate>, usize), StoreError> {
+        let (records, total) = self
+            .adapter
+            .read()
+            .await
+            .get_records_page(&filter.to_record_condition(), offset, limit)
+            .await?;
+
+        Ok((
+            records
+                .into_iter()
+                .map(|(_, certificate)| certificate)
+                .collect(),
+            total,
+        ))
+    }
+}
+
+#[async_trait]
+impl StorePruner for CertificateStore {
+    type Key = String;
+    type Record = Certificate;
+
+    fn get_adapter(
+        &self,
+    ) -> &RwLock<Box<dyn StoreAdapter<Key = Self::Key, Record = Self::Record>>> {
+        &self.adapter
+    }
+
+    fn get_retention_policy(&self) -> &RetentionPolicy<Self::Key, Self::Record> {
+        &self.retention_policy
+    }
+
+    fn get_record_epoch(&self, _key: &Self::Key, record: &Self::Record) -> Option<Epoch> {
+        Some(record.beacon.epoch)
+    }
+
+    fn get_referenced_keys(&self, records: &[(Self::Key, Self::Record)]) -> HashSet<Self::Key> {
+        records
+            .iter()
+            .map(|(_, certificate)| certificate.previous_hash.clone())
+            .collect()
+    }
+
+    async fn is_referenced(
+        &self,
+        key: &Self::Key,
+        record: &Self::Record,
+        referenced_keys: &HashSet<Self::Key>,
+    ) -> Result<bool, StoreError> {
+        if referenced_keys.contains(key) {
+            return Ok(true);
+        }
+
+        let snapshot_digest = record
+            .protocol_message
+            .get_message_part(&ProtocolMessagePartKey::SnapshotDigest);
+
+        match (&self.snapshot_store, snapshot_digest) {
+            (Some(snapshot_store), Some(snapshot_digest)) => {
+                let snapshot = snapshot_store
+                    .get_snapshot_details(snapshot_digest.to_owned())
+                    .await
+                    .map_err(|e| AdapterError::QueryError(e.into()))?;
+
+                Ok(snapshot.map_or(false, |snapshot| &snapshot.certificate_hash == key))
+            }
+            _ => Ok(false),
+        }
+    }
 }
 
 #[async_trait]
@@ -83,9 +269,11 @@ mod test {
     use super::*;
 
     use mithril_common::fake_data::{self};
-    use mithril_common::store::adapter::MemoryAdapter;
+    use mithril_common::store::adapter::{MemoryAdapter, SQLiteAdapter};
 
-    async fn get_certificate_store(size: u64) -> CertificateStore {
+    use crate::snapshot_stores::MockSnapshotStore;
+
+    fn build_certificates(size: u64) -> Vec<(String, Certificate)> {
         let mut beacon = Beacon::new("devnet".to_string(), 1, 1);
         let mut certificates = vec![];
 
@@ -98,8 +286,21 @@ mod test {
             certificates.push((certificate.hash.clone(), certificate));
         }
 
+        certificates
+    }
+
+    async fn get_certificate_store(size: u64) -> CertificateStore {
         let adapter: MemoryAdapter<String, Certificate> =
-            MemoryAdapter::new(Some(certificates)).unwrap();
+            MemoryAdapter::new(Some(build_certificates(size))).unwrap();
+        CertificateStore::new(Box::new(adapter))
+    }
+
+    async fn get_sqlite_certificate_store(size: u64) -> CertificateStore {
+        let mut adapter: SQLiteAdapter<String, Certificate> =
+            SQLiteAdapter::new("certificate", None).unwrap();
+        for (hash, certificate) in build_certificates(size) {
+            adapter.store_record(&hash, &certificate).await.unwrap();
+        }
         CertificateStore::new(Box::new(adapter))
     }
 
@@ -151,6 +352,16 @@ mod test {
         assert_eq!("whatever".to_string(), certificate.previous_hash);
     }
 
+    #[tokio::test]
+    async fn remove_certificate() {
+        let store = get_certificate_store(2).await;
+        let certificate = store.remove("cert_01").await.unwrap();
+
+        assert_eq!("cert_01".to_string(), certificate.unwrap().hash);
+        assert!(store.get_from_hash("cert_01").await.unwrap().is_none());
+        assert!(store.remove("cert_01").await.unwrap().is_none());
+    }
+
     #[tokio::test]
     async fn get_from_beacon() {
         let store = get_certificate_store(12).await;
@@ -171,4 +382,127 @@ mod test {
         let beacon = Beacon::new("devnet".to_string(), 1, 9);
         assert_eq!(None, store.get_from_beacon(&beacon).await.unwrap());
     }
+
+    #[tokio::test]
+    async fn get_from_signed_entity_type() {
+        let store = get_certificate_store(12).await;
+
+        let beacon = Beacon::new("devnet".to_string(), 1, 9);
+        let certificate = store
+            .get_from_signed_entity_type(&SignedEntityType::CardanoImmutableFilesFull(
+                beacon.clone(),
+            ))
+            .await
+            .unwrap()
+            .expect("a certificate should have been found");
+        assert_eq!(beacon, certificate.beacon);
+        assert_eq!(
+            None,
+            store
+                .get_from_signed_entity_type(&SignedEntityType::MithrilStakeDistribution(
+                    beacon.epoch
+                ))
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn search_with_filter_and_pagination() {
+        assert_search_with_filter_and_pagination(get_certificate_store(12).await).await;
+    }
+
+    #[tokio::test]
+    async fn search_with_filter_and_pagination_in_sqlite() {
+        assert_search_with_filter_and_pagination(get_sqlite_certificate_store(12).await).await;
+    }
+
+    async fn assert_search_with_filter_and_pagination(store: CertificateStore) {
+        let (certificates, total) = store
+            .search(&CertificateFilter::default(), 2, 3)
+            .await
+            .unwrap();
+        assert_eq!(12, total);
+        assert_eq!(
+            vec!["cert_09", "cert_08", "cert_07"],
+            certificates
+                .iter()
+                .map(|certificate| certificate.hash.as_str())
+                .collect::<Vec<_>>()
+        );
+
+        let filter = CertificateFilter {
+            immutable_file_number: Some(5),
+            ..CertificateFilter::default()
+        };
+        let (certificates, total) = store.search(&filter, 0, 10).await.unwrap();
+        assert_eq!(1, total);
+        assert_eq!("cert_04", certificates[0].hash);
+
+        let filter = CertificateFilter {
+            epoch_from: Some(Epoch(2)),
+            ..CertificateFilter::default()
+        };
+        assert_eq!((vec![], 0), store.search(&filter, 0, 10).await.unwrap());
+
+        let signer_party_id = fake_data::signers_with_stakes(5)[0].party_id.clone();
+        let filter = CertificateFilter {
+            epoch_from: Some(Epoch(1)),
+            epoch_to: Some(Epoch(1)),
+            signer_party_id: Some(signer_party_id),
+            ..CertificateFilter::default()
+        };
+        assert_eq!(12, store.search(&filter, 0, 10).await.unwrap().1);
+
+        let filter = CertificateFilter {
+            signer_party_id: Some("unknown".to_string()),
+            ..CertificateFilter::default()
+        };
+        assert_eq!(0, store.search(&filter, 0, 10).await.unwrap().1);
+    }
+
+    #[tokio::test]
+    async fn prune_keeps_referenced_certificates() {
+        let mut certificates = vec![];
+        for ix in 0..4 {
+            let mut certificate = fake_data::certificate(format!("cert_{}", ix));
+            certificate.previous_hash = match ix {
+                3 => "cert_1".to_string(),
+                _ => "genesis".to_string(),
+            };
+            certificates.push((certificate.hash.clone(), certificate));
+        }
+        let snapshot_digest = certificates[0]
+            .1
+            .protocol_message
+            .get_message_part(&ProtocolMessagePartKey::SnapshotDigest)
+            .unwrap()
+            .to_owned();
+        let mut snapshot = fake_data::snapshots(1)[0].clone();
+        snapshot.certificate_hash = "cert_0".to_string();
+        let mut snapshot_store = MockSnapshotStore::new();
+        snapshot_store
+            .expect_get_snapshot_details()
+            .returning(move |digest| Ok((digest == snapshot_digest).then(|| snapshot.clone())));
+        let adapter: MemoryAdapter<String, Certificate> =
+            MemoryAdapter::new(Some(certificates)).unwrap();
+        let store = CertificateStore::with_retention(
+            Box::new(adapter),
+            RetentionPolicy::Count(1),
+            Some(Arc::new(snapshot_store)),
+        );
+
+        let report = store.get_prune_report().await.unwrap();
+        assert_eq!(vec!["cert_2".to_string()], report.pruned_keys);
+
+        store.prune().await.unwrap();
+        assert!(store.get_from_hash("cert_2").await.unwrap().is_none());
+        for hash in ["cert_0", "cert_1", "cert_3"] {
+            assert!(
+                store.get_from_hash(hash).await.unwrap().is_some(),
+                "certificate {} should have been kept",
+                hash
+            );
+        }
+    }
 }
@@ -0,0 +1,129 @@
+use async_trait::async_trait;
+use tokio::sync::RwLock;
+
+use mithril_common::entities::{Epoch, FailedRound};
+use mithril_common::store::{adapter::StoreAdapter, RetentionPolicy, StoreError, StorePruner};
+
+type Adapter = Box<dyn StoreAdapter<Key = String, Record = FailedRound>>;
+
+/// Store for the [FailedRound] recorded when a pending certificate expires.
+pub struct FailedRoundStore {
+    adapter: RwLock<Adapter>,
+    retention_policy: RetentionPolicy<String, FailedRound>,
+}
+
+impl FailedRoundStore {
+    /// Create a new instance.
+    pub fn new(adapter: Adapter, retention_policy: RetentionPolicy<String, FailedRound>) -> Self {
+        Self {
+            adapter: RwLock::new(adapter),
+            retention_policy,
+        }
+    }
+
+    /// Save the given [FailedRound].
+    pub async fn save(&self, failed_round: FailedRound) -> Result<(), StoreError> {
+        let key = format!(
+            "{}-{}",
+            failed_round.signed_entity_type, failed_round.started_at
+        );
+        self.adapter
+            .write()
+            .await
+            .store_record(&key, &failed_round)
+            .await?;
+        self.prune().await?;
+
+        Ok(())
+    }
+
+    /// Return the list of the `last_n` saved failed rounds, the most recent first.
+    pub async fn get_list(&self, last_n: usize) -> Result<Vec<FailedRound>, StoreError> {
+        let records = self.adapter.read().await.get_last_n_records(last_n).await?;
+
+        Ok(records
+            .into_iter()
+            .map(|(_, failed_round)| failed_round)
+            .collect())
+    }
+}
+
+#[async_trait]
+impl StorePruner for FailedRoundStore {
+    type Key = String;
+    type Record = FailedRound;
+
+    fn get_adapter(
+        &self,
+    ) -> &RwLock<Box<dyn StoreAdapter<Key = Self::Key, Record = Self::Record>>> {
+        &self.adapter
+    }
+
+    fn get_retention_policy(&self) -> &RetentionPolicy<Self::Key, Self::Record> {
+        &self.retention_policy
+    }
+
+    fn get_record_epoch(&self, _key: &Self::Key, record: &Self::Record) -> Option<Epoch> {
+        Some(record.signed_entity_type.get_epoch())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use mithril_common::fake_data;
+    use mithril_common::store::adapter::MemoryAdapter;
+
+    fn failed_round(started_at: &str) -> FailedRound {
+        FailedRound::new(
+            &fake_data::certificate_pending(),
+            &[],
+            started_at.to_string(),
+            "2022-10-18T12:00:00Z".to_string(),
+        )
+    }
+
+    fn init_store(retention_policy: RetentionPolicy<String, FailedRound>) -> FailedRoundStore {
+        FailedRoundStore::new(
+            Box::new(MemoryAdapter::new(None).unwrap()),
+            retention_policy,
+        )
+    }
+
+    #[tokio::test]
+    async fn get_list_returns_the_most_recent_failed_rounds_first() {
+        let store = init_store(RetentionPolicy::KeepAll);
+        let failed_rounds = vec![
+            failed_round("2022-10-18T10:00:00Z"),
+            failed_round("2022-10-18T11:00:00Z"),
+            failed_round("2022-10-18T12:00:00Z"),
+        ];
+        for failed_round in &failed_rounds {
+            store.save(failed_round.clone()).await.unwrap();
+        }
+
+        assert_eq!(
+            vec![failed_rounds[2].clone(), failed_rounds[1].clone()],
+            store.get_list(2).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn save_prunes_the_failed_rounds_according_to_the_retention_policy() {
+        let store = init_store(RetentionPolicy::Count(2));
+        let failed_rounds = vec![
+            failed_round("2022-10-18T10:00:00Z"),
+            failed_round("2022-10-18T11:00:00Z"),
+            failed_round("2022-10-18T12:00:00Z"),
+        ];
+        for failed_round in &failed_rounds {
+            store.save(failed_round.clone()).await.unwrap();
+        }
+
+        assert_eq!(
+            vec![failed_rounds[2].clone(), failed_rounds[1].clone()],
+            store.get_list(10).await.unwrap()
+        );
+    }
+}
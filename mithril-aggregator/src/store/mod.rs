@@ -1,11 +1,17 @@
 mod certificate_store;
+mod failed_round_store;
 mod pending_certificate_store;
 mod protocol_parameters_store;
+mod runtime_state_store;
+mod signer_statistics_store;
 mod single_signature_store;
 mod verification_key_store;
 
-pub use certificate_store::CertificateStore;
+pub use certificate_store::{CertificateFilter, CertificateStore};
+pub use failed_round_store::FailedRoundStore;
 pub use pending_certificate_store::CertificatePendingStore;
 pub use protocol_parameters_store::{ProtocolParametersStore, ProtocolParametersStorer};
+pub use runtime_state_store::{RuntimeState, RuntimeStateStore};
+pub use signer_statistics_store::SignerStatisticsStore;
 pub use single_signature_store::{SingleSignatureStore, SingleSignatureStorer};
 pub use verification_key_store::{VerificationKeyStore, VerificationKeyStorer};
@@ -56,7 +56,7 @@ impl CertificatePendingStore {
 mod test {
     use super::*;
 
-    use mithril_common::entities::Beacon;
+    use mithril_common::entities::{Beacon, SignedEntityType};
     use mithril_common::fake_data;
     use mithril_common::store::adapter::DumbStoreAdapter;
 
@@ -71,6 +71,7 @@ mod test {
                 fake_data::protocol_parameters(),
                 fake_data::signers(4),
                 fake_data::signers(5),
+                SignedEntityType::CardanoImmutableFilesFull(beacon.clone()),
             );
             adapter
                 .store_record(&KEY.to_string(), &certificate_pending)
@@ -101,11 +102,12 @@ mod test {
         let store = get_certificate_pending_store(false).await;
         let beacon = Beacon::new("testnet".to_string(), 0, 1);
         let certificate_pending = CertificatePending::new(
-            beacon,
+            beacon.clone(),
             fake_data::protocol_parameters(),
             fake_data::protocol_parameters(),
             fake_data::signers(1),
             fake_data::signers(2),
+            SignedEntityType::CardanoImmutableFilesFull(beacon),
         );
 
         assert!(store.save(certificate_pending).await.is_ok());
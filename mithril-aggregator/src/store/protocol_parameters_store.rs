@@ -2,7 +2,7 @@ use async_trait::async_trait;
 use tokio::sync::RwLock;
 
 use mithril_common::entities::{Epoch, ProtocolParameters};
-use mithril_common::store::{adapter::StoreAdapter, StoreError, StorePruner};
+use mithril_common::store::{adapter::StoreAdapter, RetentionPolicy, StoreError, StorePruner};
 
 type Adapter = Box<dyn StoreAdapter<Key = Epoch, Record = ProtocolParameters>>;
 
@@ -25,15 +25,18 @@ pub trait ProtocolParametersStorer {
 /// `ProtocolParameter` store.
 pub struct ProtocolParametersStore {
     adapter: RwLock<Adapter>,
-    retention_len: Option<usize>,
+    retention_policy: RetentionPolicy<Epoch, ProtocolParameters>,
 }
 
 impl ProtocolParametersStore {
     /// Create an instance of `ProtocolParameterStore`.
-    pub fn new(adapter: Adapter, retention_len: Option<usize>) -> Self {
+    pub fn new(
+        adapter: Adapter,
+        retention_policy: RetentionPolicy<Epoch, ProtocolParameters>,
+    ) -> Self {
         Self {
             adapter: RwLock::new(adapter),
-            retention_len,
+            retention_policy,
         }
     }
 }
@@ -49,8 +52,12 @@ impl StorePruner for ProtocolParametersStore {
         &self.adapter
     }
 
-    fn get_max_records(&self) -> Option<usize> {
-        self.retention_len
+    fn get_retention_policy(&self) -> &RetentionPolicy<Self::Key, Self::Record> {
+        &self.retention_policy
+    }
+
+    fn get_record_epoch(&self, key: &Self::Key, _record: &Self::Record) -> Option<Epoch> {
+        Some(*key)
     }
 }
 
@@ -109,7 +116,7 @@ mod tests {
         };
         let adapter: MemoryAdapter<Epoch, ProtocolParameters> = MemoryAdapter::new(values).unwrap();
 
-        ProtocolParametersStore::new(Box::new(adapter), retention_len)
+        ProtocolParametersStore::new(Box::new(adapter), retention_len.into())
     }
 
     #[tokio::test]
@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use mithril_common::entities::{Beacon, ProtocolMessage};
+use mithril_common::store::{adapter::StoreAdapter, StoreError};
+
+use crate::runtime::AggregatorState;
+
+type Adapter = Box<dyn StoreAdapter<Key = String, Record = RuntimeState>>;
+
+const KEY: &str = "runtime_state";
+
+/// State of the runtime saved at each transition of the state machine so it can be resumed
+/// after a restart.
+///
+/// The in-flight context of the multi-signer is saved in the same record than the state of the
+/// state machine so both are always consistent. The multi-signature is not part of it since it
+/// is computed again from the single signatures store.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RuntimeState {
+    /// State of the state machine
+    pub state: AggregatorState,
+
+    /// Beacon of the multi-signer
+    pub current_beacon: Option<Beacon>,
+
+    /// Message being signed by the multi-signer
+    pub current_message: Option<ProtocolMessage>,
+}
+
+/// Store for the [RuntimeState].
+pub struct RuntimeStateStore {
+    adapter: RwLock<Adapter>,
+}
+
+impl RuntimeStateStore {
+    /// Create a new instance.
+    pub fn new(adapter: Adapter) -> Self {
+        Self {
+            adapter: RwLock::new(adapter),
+        }
+    }
+
+    /// Fetch the last saved [RuntimeState] if any.
+    pub async fn get(&self) -> Result<Option<RuntimeState>, StoreError> {
+        let record = self
+            .adapter
+            .read()
+            .await
+            .get_record(&KEY.to_string())
+            .await?;
+        Ok(record)
+    }
+
+    /// Save the given [RuntimeState], replacing the previous one.
+    pub async fn save(&self, runtime_state: RuntimeState) -> Result<(), StoreError> {
+        Ok(self
+            .adapter
+            .write()
+            .await
+            .store_record(&KEY.to_string(), &runtime_state)
+            .await?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use mithril_common::fake_data;
+    use mithril_common::store::adapter::DumbStoreAdapter;
+
+    #[tokio::test]
+    async fn get_runtime_state_when_none_saved() {
+        let store = RuntimeStateStore::new(Box::new(DumbStoreAdapter::new()));
+
+        assert!(store.get().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn save_runtime_state_replaces_previous_one() {
+        let store = RuntimeStateStore::new(Box::new(DumbStoreAdapter::new()));
+        let runtime_state = RuntimeState {
+            state: AggregatorState::idle(None),
+            current_beacon: None,
+            current_message: None,
+        };
+        store.save(runtime_state).await.unwrap();
+        let runtime_state = RuntimeState {
+            state: AggregatorState::idle(Some(fake_data::beacon())),
+            current_beacon: Some(fake_data::beacon()),
+            current_message: Some(ProtocolMessage::new()),
+        };
+        store.save(runtime_state.clone()).await.unwrap();
+
+        assert_eq!(Some(runtime_state), store.get().await.unwrap());
+    }
+}
@@ -0,0 +1,265 @@
+use async_trait::async_trait;
+use mithril_common::store::{RetentionPolicy, StorePruner};
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+
+use mithril_common::entities::{Epoch, PartyId, SignerStatistics, SingleSignatures};
+use mithril_common::store::{adapter::StoreAdapter, StoreError};
+
+type Adapter = Box<dyn StoreAdapter<Key = Epoch, Record = HashMap<PartyId, SignerStatistics>>>;
+
+/// Store for the [SignerStatistics], indexed by [Epoch] then by [PartyId].
+pub struct SignerStatisticsStore {
+    adapter: RwLock<Adapter>,
+    retention_policy: RetentionPolicy<Epoch, HashMap<PartyId, SignerStatistics>>,
+}
+
+impl SignerStatisticsStore {
+    /// Create a new instance.
+    pub fn new(
+        adapter: Adapter,
+        retention_policy: RetentionPolicy<Epoch, HashMap<PartyId, SignerStatistics>>,
+    ) -> Self {
+        Self {
+            adapter: RwLock::new(adapter),
+            retention_policy,
+        }
+    }
+
+    /// Record that the signer registered to sign during the given [Epoch].
+    pub async fn record_registration(
+        &self,
+        epoch: Epoch,
+        party_id: &PartyId,
+    ) -> Result<(), StoreError> {
+        self.update(epoch, &[party_id.to_owned()], |statistics| {
+            statistics.registered = true
+        })
+        .await
+    }
+
+    /// Record a single signature sent during the given [Epoch], `latency_ms` after the
+    /// creation of the pending certificate it signs.
+    pub async fn record_single_signature(
+        &self,
+        epoch: Epoch,
+        single_signatures: &SingleSignatures,
+        latency_ms: u64,
+    ) -> Result<(), StoreError> {
+        let won_lotteries = single_signatures.won_indexes.len() as u64;
+        self.update(
+            epoch,
+            &[single_signatures.party_id.to_owned()],
+            |statistics| statistics.record_single_signature(won_lotteries, latency_ms),
+        )
+        .await
+    }
+
+    /// Record that the single signatures of the given signers are included in a
+    /// certificate of the given [Epoch].
+    pub async fn record_certificate_inclusion(
+        &self,
+        epoch: Epoch,
+        party_ids: &[PartyId],
+    ) -> Result<(), StoreError> {
+        self.update(epoch, party_ids, |statistics| {
+            statistics.certificates_included += 1
+        })
+        .await
+    }
+
+    /// Return the saved [SignerStatistics], optionally restricted to a signer and to an
+    /// [Epoch], the most recent epoch first.
+    pub async fn get_list(
+        &self,
+        party_id: Option<&PartyId>,
+        epoch: Option<Epoch>,
+    ) -> Result<Vec<SignerStatistics>, StoreError> {
+        let records = match epoch {
+            Some(epoch) => self
+                .adapter
+                .read()
+                .await
+                .get_record(&epoch)
+                .await?
+                .map(|record| vec![record])
+                .unwrap_or_default(),
+            None => self
+                .adapter
+                .read()
+                .await
+                .get_all_records()
+                .await?
+                .into_iter()
+                .map(|(_, record)| record)
+                .collect(),
+        };
+        let mut statistics = records
+            .into_iter()
+            .flat_map(|record| record.into_values())
+            .filter(|statistics| party_id.map_or(true, |party_id| &statistics.party_id == party_id))
+            .collect::<Vec<_>>();
+        statistics.sort_by(|a, b| {
+            b.epoch
+                .cmp(&a.epoch)
+                .then_with(|| a.party_id.cmp(&b.party_id))
+        });
+
+        Ok(statistics)
+    }
+
+    /// Apply `record` to the statistics of the given signers for the given [Epoch].
+    ///
+    /// The adapter is locked for writing during the whole update so statistics recorded
+    /// concurrently are not lost. The store is only pruned when the statistics of a new
+    /// [Epoch] are recorded, not on every recorded signature.
+    async fn update<F>(
+        &self,
+        epoch: Epoch,
+        party_ids: &[PartyId],
+        record: F,
+    ) -> Result<(), StoreError>
+    where
+        F: Fn(&mut SignerStatistics),
+    {
+        let is_new_epoch = {
+            let mut adapter = self.adapter.write().await;
+            let previous_statistics = adapter.get_record(&epoch).await?;
+            let is_new_epoch = previous_statistics.is_none();
+            let mut statistics = previous_statistics.unwrap_or_default();
+            for party_id in party_ids {
+                record(
+                    statistics
+                        .entry(party_id.to_owned())
+                        .or_insert_with(|| SignerStatistics::new(party_id.to_owned(), epoch)),
+                );
+            }
+            adapter.store_record(&epoch, &statistics).await?;
+
+            is_new_epoch
+        };
+        if is_new_epoch {
+            self.prune().await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl StorePruner for SignerStatisticsStore {
+    type Key = Epoch;
+    type Record = HashMap<PartyId, SignerStatistics>;
+
+    fn get_adapter(
+        &self,
+    ) -> &RwLock<Box<dyn StoreAdapter<Key = Self::Key, Record = Self::Record>>> {
+        &self.adapter
+    }
+
+    fn get_retention_policy(&self) -> &RetentionPolicy<Self::Key, Self::Record> {
+        &self.retention_policy
+    }
+
+    fn get_record_epoch(&self, key: &Self::Key, _record: &Self::Record) -> Option<Epoch> {
+        Some(*key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use mithril_common::store::adapter::MemoryAdapter;
+
+    fn init_store(
+        retention_policy: RetentionPolicy<Epoch, HashMap<PartyId, SignerStatistics>>,
+    ) -> SignerStatisticsStore {
+        SignerStatisticsStore::new(
+            Box::new(MemoryAdapter::new(None).unwrap()),
+            retention_policy,
+        )
+    }
+
+    #[tokio::test]
+    async fn record_participation_of_signers_per_epoch() {
+        let store = init_store(RetentionPolicy::KeepAll);
+        let party_1 = "party_1".to_string();
+        let party_2 = "party_2".to_string();
+        store.record_registration(Epoch(2), &party_1).await.unwrap();
+        store
+            .record_single_signature(
+                Epoch(2),
+                &SingleSignatures::new(party_1.clone(), "signature".to_string(), vec![1, 4]),
+                250,
+            )
+            .await
+            .unwrap();
+        store
+            .record_single_signature(
+                Epoch(2),
+                &SingleSignatures::new(party_2.clone(), "signature".to_string(), vec![3]),
+                800,
+            )
+            .await
+            .unwrap();
+        store
+            .record_certificate_inclusion(Epoch(2), &[party_1.clone(), party_2.clone()])
+            .await
+            .unwrap();
+        store.record_registration(Epoch(3), &party_1).await.unwrap();
+
+        let mut expected_party_1_epoch_2 = SignerStatistics::new(party_1.clone(), Epoch(2));
+        expected_party_1_epoch_2.registered = true;
+        expected_party_1_epoch_2.record_single_signature(2, 250);
+        expected_party_1_epoch_2.certificates_included = 1;
+        let mut expected_party_2_epoch_2 = SignerStatistics::new(party_2.clone(), Epoch(2));
+        expected_party_2_epoch_2.record_single_signature(1, 800);
+        expected_party_2_epoch_2.certificates_included = 1;
+        let mut expected_party_1_epoch_3 = SignerStatistics::new(party_1.clone(), Epoch(3));
+        expected_party_1_epoch_3.registered = true;
+
+        assert_eq!(
+            vec![
+                expected_party_1_epoch_3.clone(),
+                expected_party_1_epoch_2.clone(),
+                expected_party_2_epoch_2.clone(),
+            ],
+            store.get_list(None, None).await.unwrap()
+        );
+        assert_eq!(
+            vec![expected_party_1_epoch_3, expected_party_1_epoch_2.clone()],
+            store.get_list(Some(&party_1), None).await.unwrap()
+        );
+        assert_eq!(
+            vec![expected_party_1_epoch_2],
+            store
+                .get_list(Some(&party_1), Some(Epoch(2)))
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn statistics_of_old_epochs_are_pruned() {
+        let store = init_store(RetentionPolicy::EpochWindow(2));
+        let party_id = "party".to_string();
+        for epoch in 1..=3 {
+            store
+                .record_registration(Epoch(epoch), &party_id)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(
+            vec![Epoch(3), Epoch(2)],
+            store
+                .get_list(None, None)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|statistics| statistics.epoch)
+                .collect::<Vec<_>>()
+        );
+    }
+}
@@ -1,49 +1,56 @@
 use async_trait::async_trait;
-use mithril_common::store::StorePruner;
+use mithril_common::store::{RetentionPolicy, StorePruner};
 use std::collections::HashMap;
 use tokio::sync::RwLock;
 
-use mithril_common::entities::{Beacon, PartyId, SingleSignatures};
+use mithril_common::entities::{Epoch, PartyId, SignedEntityType, SingleSignatures};
 use mithril_common::store::{adapter::StoreAdapter, StoreError};
 
-type Adapter = Box<dyn StoreAdapter<Key = Beacon, Record = HashMap<PartyId, SingleSignatures>>>;
+type Adapter =
+    Box<dyn StoreAdapter<Key = SignedEntityType, Record = HashMap<PartyId, SingleSignatures>>>;
 
 /// Trait for mocking [SingleSignatureStore].
 #[async_trait]
 pub trait SingleSignatureStorer {
-    /// Save the given [SingleSignatures] for the given [Beacon].
+    /// Save the given [SingleSignatures] for the given [SignedEntityType].
     async fn save_single_signatures(
         &self,
-        beacon: &Beacon,
+        signed_entity_type: &SignedEntityType,
         single_signature: &SingleSignatures,
     ) -> Result<Option<SingleSignatures>, StoreError>;
 
-    /// Get the [SingleSignatures] for the given [Beacon] if any.
+    /// Get the [SingleSignatures] for the given [SignedEntityType] if any.
     async fn get_single_signatures(
         &self,
-        beacon: &Beacon,
+        signed_entity_type: &SignedEntityType,
     ) -> Result<Option<HashMap<PartyId, SingleSignatures>>, StoreError>;
 }
 
 /// Store for [SingleSignatures].
+///
+/// The signatures are kept per signed entity since several entities may be signed for a same
+/// beacon.
 pub struct SingleSignatureStore {
     adapter: RwLock<Adapter>,
-    retention_limit: Option<usize>,
+    retention_policy: RetentionPolicy<SignedEntityType, HashMap<PartyId, SingleSignatures>>,
 }
 
 impl SingleSignatureStore {
     /// Create a new instance.
-    pub fn new(adapter: Adapter, retention_limit: Option<usize>) -> Self {
+    pub fn new(
+        adapter: Adapter,
+        retention_policy: RetentionPolicy<SignedEntityType, HashMap<PartyId, SingleSignatures>>,
+    ) -> Self {
         Self {
             adapter: RwLock::new(adapter),
-            retention_limit,
+            retention_policy,
         }
     }
 }
 
 #[async_trait]
 impl StorePruner for SingleSignatureStore {
-    type Key = Beacon;
+    type Key = SignedEntityType;
     type Record = HashMap<PartyId, SingleSignatures>;
 
     fn get_adapter(
@@ -52,8 +59,12 @@ impl StorePruner for SingleSignatureStore {
         &self.adapter
     }
 
-    fn get_max_records(&self) -> Option<usize> {
-        self.retention_limit
+    fn get_retention_policy(&self) -> &RetentionPolicy<Self::Key, Self::Record> {
+        &self.retention_policy
+    }
+
+    fn get_record_epoch(&self, key: &Self::Key, _record: &Self::Record) -> Option<Epoch> {
+        Some(key.get_epoch())
     }
 }
 
@@ -61,14 +72,14 @@ impl StorePruner for SingleSignatureStore {
 impl SingleSignatureStorer for SingleSignatureStore {
     async fn save_single_signatures(
         &self,
-        beacon: &Beacon,
+        signed_entity_type: &SignedEntityType,
         single_signatures: &SingleSignatures,
     ) -> Result<Option<SingleSignatures>, StoreError> {
         let mut single_signatures_per_party_id = self
             .adapter
             .read()
             .await
-            .get_record(beacon)
+            .get_record(signed_entity_type)
             .await?
             .unwrap_or_default();
 
@@ -79,7 +90,7 @@ impl SingleSignatureStorer for SingleSignatureStore {
         self.adapter
             .write()
             .await
-            .store_record(beacon, &single_signatures_per_party_id)
+            .store_record(signed_entity_type, &single_signatures_per_party_id)
             .await?;
         self.prune().await?;
 
@@ -88,9 +99,14 @@ impl SingleSignatureStorer for SingleSignatureStore {
 
     async fn get_single_signatures(
         &self,
-        beacon: &Beacon,
+        signed_entity_type: &SignedEntityType,
     ) -> Result<Option<HashMap<PartyId, SingleSignatures>>, StoreError> {
-        let record = self.adapter.read().await.get_record(beacon).await?;
+        let record = self
+            .adapter
+            .read()
+            .await
+            .get_record(signed_entity_type)
+            .await?;
         Ok(record)
     }
 }
@@ -98,15 +114,24 @@ impl SingleSignatureStorer for SingleSignatureStore {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use mithril_common::entities::Beacon;
     use mithril_common::store::adapter::MemoryAdapter;
 
+    fn immutable_files_full(epoch: u64, immutable_file_number: u64) -> SignedEntityType {
+        SignedEntityType::CardanoImmutableFilesFull(Beacon::new(
+            "devnet".to_string(),
+            epoch,
+            immutable_file_number,
+        ))
+    }
+
     fn init_store(
         nb_immutable_file_numbers: u64,
         single_signers_per_epoch: u64,
         single_signatures_per_signers: u64,
         retention_limit: Option<usize>,
     ) -> SingleSignatureStore {
-        let mut values: Vec<(Beacon, HashMap<PartyId, SingleSignatures>)> = Vec::new();
+        let mut values: Vec<(SignedEntityType, HashMap<PartyId, SingleSignatures>)> = Vec::new();
 
         for immutable_file_number in 1..=nb_immutable_file_numbers {
             let mut single_signatures: HashMap<PartyId, SingleSignatures> = HashMap::new();
@@ -123,7 +148,7 @@ mod tests {
                 );
             }
             values.push((
-                Beacon::new("devnet".to_string(), 1, immutable_file_number),
+                immutable_files_full(1, immutable_file_number),
                 single_signatures,
             ));
         }
@@ -133,9 +158,9 @@ mod tests {
         } else {
             None
         };
-        let adapter: MemoryAdapter<Beacon, HashMap<PartyId, SingleSignatures>> =
+        let adapter: MemoryAdapter<SignedEntityType, HashMap<PartyId, SingleSignatures>> =
             MemoryAdapter::new(values).unwrap();
-        SingleSignatureStore::new(Box::new(adapter), retention_limit)
+        SingleSignatureStore::new(Box::new(adapter), retention_limit.into())
     }
 
     #[tokio::test]
@@ -143,7 +168,7 @@ mod tests {
         let store = init_store(0, 0, 0, None);
         let res = store
             .save_single_signatures(
-                &Beacon::new("devnet".to_string(), 1, 0),
+                &immutable_files_full(1, 0),
                 &SingleSignatures {
                     party_id: "0".to_string(),
                     signature: "OK".to_string(),
@@ -159,13 +184,13 @@ mod tests {
     #[tokio::test]
     async fn add_signature_for_new_party_id() {
         let store = init_store(1, 1, 5, None);
-        let beacon = Beacon::new("devnet".to_string(), 1, 1);
+        let signed_entity_type = immutable_files_full(1, 1);
 
         assert_eq!(
             None,
             store
                 .save_single_signatures(
-                    &beacon,
+                    &signed_entity_type,
                     &SingleSignatures {
                         party_id: "10".to_string(),
                         signature: "test".to_string(),
@@ -182,7 +207,7 @@ mod tests {
                 won_indexes: (1..5).collect(),
             }),
             store
-                .get_single_signatures(&beacon)
+                .get_single_signatures(&signed_entity_type)
                 .await
                 .unwrap()
                 .unwrap()
@@ -194,7 +219,7 @@ mod tests {
     async fn get_single_signatures_for_empty_epoch() {
         let store = init_store(2, 1, 1, None);
         let res = store
-            .get_single_signatures(&Beacon::new("devnet".to_string(), 1, 0))
+            .get_single_signatures(&immutable_files_full(1, 0))
             .await
             .unwrap();
 
@@ -205,7 +230,7 @@ mod tests {
     async fn get_single_signatures_for_existing_epoch() {
         let store = init_store(2, 2, 2, None);
         let res = store
-            .get_single_signatures(&Beacon::new("devnet".to_string(), 1, 1))
+            .get_single_signatures(&immutable_files_full(1, 1))
             .await
             .unwrap();
 
@@ -216,16 +241,16 @@ mod tests {
     #[tokio::test]
     async fn using_retention_limit() {
         let store = init_store(2, 2, 2, Some(2));
-        let beacon = Beacon::new("devnet".to_string(), 1, 1);
+        let signed_entity_type = immutable_files_full(1, 1);
 
         assert!(store
-            .get_single_signatures(&beacon)
+            .get_single_signatures(&signed_entity_type)
             .await
             .unwrap()
             .is_some());
         let _ = store
             .save_single_signatures(
-                &Beacon::new("devnet".to_string(), 2, 3),
+                &immutable_files_full(2, 3),
                 &SingleSignatures {
                     party_id: "0".to_string(),
                     signature: "OK".to_string(),
@@ -236,7 +261,35 @@ mod tests {
             .unwrap();
 
         assert!(store
-            .get_single_signatures(&beacon)
+            .get_single_signatures(&signed_entity_type)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn signatures_of_the_signed_entities_of_a_beacon_are_kept_apart() {
+        let store = init_store(0, 0, 0, None);
+        let beacon = Beacon::new("devnet".to_string(), 1, 1);
+        let stake_distribution = SignedEntityType::MithrilStakeDistribution(beacon.epoch);
+        let snapshot = SignedEntityType::CardanoImmutableFilesFull(beacon);
+        let single_signatures = SingleSignatures {
+            party_id: "0".to_string(),
+            signature: "OK".to_string(),
+            won_indexes: vec![1, 5],
+        };
+        store
+            .save_single_signatures(&stake_distribution, &single_signatures)
+            .await
+            .unwrap();
+
+        assert!(store
+            .get_single_signatures(&stake_distribution)
+            .await
+            .unwrap()
+            .is_some());
+        assert!(store
+            .get_single_signatures(&snapshot)
             .await
             .unwrap()
             .is_none());
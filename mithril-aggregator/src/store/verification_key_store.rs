@@ -1,5 +1,5 @@
 use async_trait::async_trait;
-use mithril_common::store::StorePruner;
+use mithril_common::store::{RetentionPolicy, StorePruner};
 use std::collections::HashMap;
 use tokio::sync::RwLock;
 
@@ -27,15 +27,18 @@ pub trait VerificationKeyStorer {
 /// Store for the `VerificationKey`.
 pub struct VerificationKeyStore {
     adapter: RwLock<Adapter>,
-    retention_limit: Option<usize>,
+    retention_policy: RetentionPolicy<Epoch, HashMap<PartyId, Signer>>,
 }
 
 impl VerificationKeyStore {
     /// Create a new instance.
-    pub fn new(adapter: Adapter, retention_limit: Option<usize>) -> Self {
+    pub fn new(
+        adapter: Adapter,
+        retention_policy: RetentionPolicy<Epoch, HashMap<PartyId, Signer>>,
+    ) -> Self {
         Self {
             adapter: RwLock::new(adapter),
-            retention_limit,
+            retention_policy,
         }
     }
 }
@@ -51,8 +54,12 @@ impl StorePruner for VerificationKeyStore {
         &self.adapter
     }
 
-    fn get_max_records(&self) -> Option<usize> {
-        self.retention_limit
+    fn get_retention_policy(&self) -> &RetentionPolicy<Self::Key, Self::Record> {
+        &self.retention_policy
+    }
+
+    fn get_record_epoch(&self, key: &Self::Key, _record: &Self::Record) -> Option<Epoch> {
+        Some(*key)
     }
 }
 
@@ -126,7 +133,7 @@ mod tests {
         };
         let adapter: MemoryAdapter<Epoch, HashMap<PartyId, Signer>> =
             MemoryAdapter::new(values).unwrap();
-        VerificationKeyStore::new(Box::new(adapter), retention_limit)
+        VerificationKeyStore::new(Box::new(adapter), retention_limit.into())
     }
 
     #[tokio::test]
@@ -223,4 +230,37 @@ mod tests {
             .unwrap()
             .is_none());
     }
+
+    #[tokio::test]
+    async fn check_epoch_window_retention() {
+        let mut store = init_store(2, 1, None);
+        store.retention_policy = RetentionPolicy::EpochWindow(2);
+        let _ = store
+            .save_verification_key(
+                Epoch(4),
+                Signer {
+                    party_id: "party_id".to_string(),
+                    verification_key: "whatever".to_string(),
+                    verification_key_signature: None,
+                    operational_certificate: None,
+                    kes_period: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        for (epoch, expect_pruned) in [(1, true), (2, true), (4, false)] {
+            assert_eq!(
+                expect_pruned,
+                store
+                    .get_verification_keys(Epoch(epoch))
+                    .await
+                    .unwrap()
+                    .is_none(),
+                "epoch {} should be pruned: {}",
+                epoch,
+                expect_pruned
+            );
+        }
+    }
 }
@@ -1,9 +1,16 @@
-use std::{error::Error, fs::File, io::prelude::*, io::Write, path::Path, sync::Arc};
+use std::{
+    error::Error,
+    fs::File,
+    io::prelude::*,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use mithril_common::{
-    certificate_chain::{CertificateGenesisProducer, CertificateVerifier},
+    certificate_chain::{CertificateGenesisProducer, CertificateVerifier, GenesisKeyRotation},
     crypto_helper::{
-        key_decode_hex, ProtocolAggregateVerificationKey, ProtocolGenesisSignature,
+        key_decode_hex, ProtocolAggregateVerificationKey, ProtocolGenesisMultiSignature,
         ProtocolGenesisSigner, ProtocolGenesisVerifier,
     },
     entities::{Beacon, ProtocolParameters},
@@ -106,20 +113,104 @@ impl GenesisTools {
         Ok(())
     }
 
-    /// Import signature of the AVK of the genesis stake distribution from a file
+    /// Import signatures of the AVK of the genesis stake distribution from files, one per
+    /// custodian of the genesis policy
     pub async fn import_payload_signature(
         &self,
-        signed_payload_path: &Path,
+        signed_payload_paths: &[PathBuf],
     ) -> GenesisToolsResult<()> {
-        let mut signed_payload_file = File::open(signed_payload_path).unwrap();
-        let mut signed_payload_buffer = Vec::new();
-        signed_payload_file.read_to_end(&mut signed_payload_buffer)?;
-        let genesis_signature = ProtocolGenesisSignature::from_bytes(&signed_payload_buffer)?;
+        let genesis_signature = read_signatures(signed_payload_paths)?;
 
         self.create_and_save_genesis_certificate(genesis_signature)
             .await
     }
 
+    /// Export the rotation to the next genesis key(s) to a payload file, to sign with the
+    /// current genesis secret key(s)
+    pub fn export_rotation_payload_to_sign(
+        &self,
+        next_genesis_verifier: &ProtocolGenesisVerifier,
+        target_path: &Path,
+    ) -> GenesisToolsResult<()> {
+        let mut target_file = File::create(target_path)?;
+        let protocol_message = GenesisKeyRotation::create_rotation_protocol_message(
+            &self.genesis_verifier,
+            next_genesis_verifier,
+        )?;
+        target_file.write_all(protocol_message.compute_hash().as_bytes())?;
+        Ok(())
+    }
+
+    /// Export the genesis key rotation certificate payload to a file, to sign with the next
+    /// genesis secret key(s) once the rotation is signed with the current genesis secret key(s)
+    pub fn export_rotation_certificate_payload_to_sign(
+        &self,
+        next_genesis_verifier: &ProtocolGenesisVerifier,
+        signed_rotation_payload_paths: &[PathBuf],
+        target_path: &Path,
+    ) -> GenesisToolsResult<()> {
+        let genesis_key_rotation =
+            self.read_genesis_key_rotation(next_genesis_verifier, signed_rotation_payload_paths)?;
+        let mut target_file = File::create(target_path)?;
+        let protocol_message =
+            CertificateGenesisProducer::create_genesis_key_rotation_protocol_message(
+                &self.genesis_avk,
+                &genesis_key_rotation,
+            )?;
+        target_file.write_all(protocol_message.compute_hash().as_bytes())?;
+        Ok(())
+    }
+
+    /// Import the signatures of a genesis key rotation, then create a genesis key rotation
+    /// certificate chained to the latest certificate
+    pub async fn import_rotation_payload_signatures(
+        &self,
+        next_genesis_verifier: &ProtocolGenesisVerifier,
+        signed_rotation_payload_paths: &[PathBuf],
+        signed_payload_paths: &[PathBuf],
+    ) -> GenesisToolsResult<()> {
+        let genesis_key_rotation =
+            self.read_genesis_key_rotation(next_genesis_verifier, signed_rotation_payload_paths)?;
+        let genesis_signature = read_signatures(signed_payload_paths)?;
+        let previous_certificate = self.certificate_store.get_list(1).await?.pop().ok_or(
+            "no certificate to rotate the genesis key from, import a genesis certificate instead",
+        )?;
+        let genesis_key_rotation_certificate =
+            CertificateGenesisProducer::create_genesis_key_rotation_certificate(
+                self.protocol_parameters.clone(),
+                self.beacon.clone(),
+                previous_certificate.hash,
+                self.genesis_avk.clone(),
+                &genesis_key_rotation,
+                genesis_signature,
+            )?;
+        self.certificate_verifier
+            .verify_genesis_key_rotation_certificate(
+                &genesis_key_rotation_certificate,
+                &genesis_key_rotation,
+                &self.genesis_verifier,
+            )
+            .await?;
+        self.certificate_store
+            .save(genesis_key_rotation_certificate)
+            .await?;
+        Ok(())
+    }
+
+    fn read_genesis_key_rotation(
+        &self,
+        next_genesis_verifier: &ProtocolGenesisVerifier,
+        signed_rotation_payload_paths: &[PathBuf],
+    ) -> GenesisToolsResult<GenesisKeyRotation> {
+        let genesis_key_rotation = GenesisKeyRotation::new(
+            self.genesis_verifier.as_ref().clone(),
+            next_genesis_verifier.to_owned(),
+            read_signatures(signed_rotation_payload_paths)?,
+        );
+        genesis_key_rotation.verify()?;
+        Ok(genesis_key_rotation)
+    }
+
     /// Automatic bootstrap of the genesis certificate (test only)
     pub async fn bootstrap_test_genesis_certificate(
         &self,
@@ -130,13 +221,13 @@ impl GenesisTools {
             CertificateGenesisProducer::create_genesis_protocol_message(&self.genesis_avk)?;
         let genesis_signature =
             genesis_producer.sign_genesis_protocol_message(genesis_protocol_message)?;
-        self.create_and_save_genesis_certificate(genesis_signature)
+        self.create_and_save_genesis_certificate(genesis_signature.into())
             .await
     }
 
     async fn create_and_save_genesis_certificate(
         &self,
-        genesis_signature: ProtocolGenesisSignature,
+        genesis_signature: ProtocolGenesisMultiSignature,
     ) -> GenesisToolsResult<()> {
         let genesis_certificate = CertificateGenesisProducer::create_genesis_certificate(
             self.protocol_parameters.clone(),
@@ -152,6 +243,22 @@ impl GenesisTools {
     }
 }
 
+/// Read and aggregate the signatures of the custodians, one file per custodian
+fn read_signatures(
+    signed_payload_paths: &[PathBuf],
+) -> GenesisToolsResult<ProtocolGenesisMultiSignature> {
+    let mut multi_signatures = Vec::new();
+    for signed_payload_path in signed_payload_paths {
+        let mut signed_payload_file = File::open(signed_payload_path)?;
+        let mut signed_payload_buffer = Vec::new();
+        signed_payload_file.read_to_end(&mut signed_payload_buffer)?;
+        multi_signatures.push(ProtocolGenesisMultiSignature::from_bytes(
+            &signed_payload_buffer,
+        )?);
+    }
+    Ok(ProtocolGenesisMultiSignature::aggregate(&multi_signatures))
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs;
@@ -159,7 +266,10 @@ mod tests {
 
     use mithril_common::{
         certificate_chain::MithrilCertificateVerifier,
-        crypto_helper::{tests_setup, ProtocolClerk, ProtocolGenesisSigner},
+        crypto_helper::{
+            key_encode_hex, tests_setup, ProtocolClerk, ProtocolGenesisPolicy,
+            ProtocolGenesisSigner,
+        },
         fake_data,
         store::adapter::MemoryAdapter,
     };
@@ -201,7 +311,7 @@ mod tests {
     }
 
     fn build_tools(
-        genesis_signer: &ProtocolGenesisSigner,
+        genesis_verifier: ProtocolGenesisVerifier,
     ) -> (
         GenesisTools,
         Arc<CertificateStore>,
@@ -213,7 +323,7 @@ mod tests {
             MemoryAdapter::new(None).unwrap(),
         )));
         let genesis_avk = create_fake_genesis_avk();
-        let genesis_verifier = Arc::new(genesis_signer.create_genesis_verifier());
+        let genesis_verifier = Arc::new(genesis_verifier);
         let genesis_tools = GenesisTools::new(
             fake_data::protocol_parameters(),
             fake_data::beacon(),
@@ -238,7 +348,7 @@ mod tests {
         let signed_payload_path = test_dir.join("payload-signed.txt");
         let genesis_signer = ProtocolGenesisSigner::create_deterministic_genesis_signer();
         let (genesis_tools, certificate_store, genesis_verifier, certificate_verifier) =
-            build_tools(&genesis_signer);
+            build_tools(genesis_signer.create_genesis_verifier());
 
         genesis_tools
             .export_payload_to_sign(&path)
@@ -246,7 +356,7 @@ mod tests {
         sign_avk_payload(&path, &signed_payload_path, &genesis_signer)
             .expect("sign avk payload should not fail");
         genesis_tools
-            .import_payload_signature(&signed_payload_path)
+            .import_payload_signature(&[signed_payload_path])
             .await
             .expect("import_payload_signature should not fail");
 
@@ -265,7 +375,7 @@ mod tests {
     async fn bootstrap_test_genesis_certificate_works() {
         let genesis_signer = ProtocolGenesisSigner::create_deterministic_genesis_signer();
         let (genesis_tools, certificate_store, genesis_verifier, certificate_verifier) =
-            build_tools(&genesis_signer);
+            build_tools(genesis_signer.create_genesis_verifier());
 
         genesis_tools
             .bootstrap_test_genesis_certificate(genesis_signer)
@@ -282,4 +392,150 @@ mod tests {
                 "verify_genesis_certificate should successully validate the genesis certificate",
             );
     }
+
+    #[tokio::test]
+    async fn export_sign_then_import_genesis_key_rotation() {
+        let test_dir = get_temp_dir("export_genesis_key_rotation");
+        let rotation_payload_path = test_dir.join("rotation-payload.txt");
+        let signed_rotation_payload_path = test_dir.join("rotation-payload-signed.txt");
+        let payload_path = test_dir.join("payload.txt");
+        let signed_payload_path = test_dir.join("payload-signed.txt");
+        let genesis_signer = ProtocolGenesisSigner::create_deterministic_genesis_signer();
+        let next_genesis_signer = ProtocolGenesisSigner::create_non_deterministic_genesis_signer();
+        let next_genesis_verifier = next_genesis_signer.create_genesis_verifier();
+        let (genesis_tools, certificate_store, genesis_verifier, certificate_verifier) =
+            build_tools(genesis_signer.create_genesis_verifier());
+        genesis_tools
+            .bootstrap_test_genesis_certificate(genesis_signer)
+            .await
+            .unwrap();
+
+        genesis_tools
+            .export_rotation_payload_to_sign(&next_genesis_verifier, &rotation_payload_path)
+            .expect("export_rotation_payload_to_sign should not fail");
+        sign_avk_payload(
+            &rotation_payload_path,
+            &signed_rotation_payload_path,
+            &ProtocolGenesisSigner::create_deterministic_genesis_signer(),
+        )
+        .unwrap();
+        genesis_tools
+            .export_rotation_certificate_payload_to_sign(
+                &next_genesis_verifier,
+                &[signed_rotation_payload_path.clone()],
+                &payload_path,
+            )
+            .expect("export_rotation_certificate_payload_to_sign should not fail");
+        sign_avk_payload(&payload_path, &signed_payload_path, &next_genesis_signer).unwrap();
+        genesis_tools
+            .import_rotation_payload_signatures(
+                &next_genesis_verifier,
+                &[signed_rotation_payload_path],
+                &[signed_payload_path],
+            )
+            .await
+            .expect("import_rotation_payload_signatures should not fail");
+
+        let last_certificates = certificate_store.get_list(10).await.unwrap();
+        assert_eq!(2, last_certificates.len());
+        assert_eq!(
+            last_certificates[1].hash,
+            last_certificates[0].previous_hash
+        );
+        for verifier in [next_genesis_verifier, genesis_verifier.as_ref().clone()] {
+            certificate_verifier
+                .verify_certificate_chain(
+                    last_certificates[0].clone(),
+                    certificate_store.clone(),
+                    &verifier,
+                )
+                .await
+                .expect("the certificate chain should be valid through the genesis key rotation");
+        }
+    }
+
+    #[tokio::test]
+    async fn export_genesis_key_rotation_fails_if_not_signed_by_the_current_genesis_key() {
+        let test_dir = get_temp_dir("export_genesis_key_rotation_wrong_key");
+        let rotation_payload_path = test_dir.join("rotation-payload.txt");
+        let signed_rotation_payload_path = test_dir.join("rotation-payload-signed.txt");
+        let genesis_signer = ProtocolGenesisSigner::create_deterministic_genesis_signer();
+        let next_genesis_signer = ProtocolGenesisSigner::create_non_deterministic_genesis_signer();
+        let next_genesis_verifier = next_genesis_signer.create_genesis_verifier();
+        let (genesis_tools, _, _, _) = build_tools(genesis_signer.create_genesis_verifier());
+
+        genesis_tools
+            .export_rotation_payload_to_sign(&next_genesis_verifier, &rotation_payload_path)
+            .unwrap();
+        sign_avk_payload(
+            &rotation_payload_path,
+            &signed_rotation_payload_path,
+            &next_genesis_signer,
+        )
+        .unwrap();
+        genesis_tools
+            .export_rotation_certificate_payload_to_sign(
+                &next_genesis_verifier,
+                &[signed_rotation_payload_path],
+                &test_dir.join("payload.txt"),
+            )
+            .expect_err("a rotation not signed by the current genesis key should be rejected");
+    }
+
+    #[tokio::test]
+    async fn export_sign_then_import_genesis_payload_with_genesis_policy() {
+        let test_dir = get_temp_dir("export_payload_to_sign_with_genesis_policy");
+        let path = test_dir.join("payload.txt");
+        let genesis_signers = (0..3)
+            .map(|_| ProtocolGenesisSigner::create_non_deterministic_genesis_signer())
+            .collect::<Vec<_>>();
+        let genesis_policy = ProtocolGenesisPolicy::new(
+            2,
+            genesis_signers
+                .iter()
+                .map(|signer| {
+                    key_encode_hex(
+                        signer
+                            .create_genesis_verifier()
+                            .to_verification_key()
+                            .unwrap(),
+                    )
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap(),
+        );
+        let (genesis_tools, certificate_store, genesis_verifier, certificate_verifier) =
+            build_tools(ProtocolGenesisVerifier::from_policy(&genesis_policy).unwrap());
+        genesis_tools
+            .export_payload_to_sign(&path)
+            .expect("export_payload_to_sign should not fail");
+        let signed_payload_paths = genesis_signers
+            .iter()
+            .enumerate()
+            .map(|(index, genesis_signer)| {
+                let signed_payload_path = test_dir.join(format!("payload-signed-{}.txt", index));
+                sign_avk_payload(&path, &signed_payload_path, genesis_signer).unwrap();
+                signed_payload_path
+            })
+            .collect::<Vec<_>>();
+
+        genesis_tools
+            .import_payload_signature(&signed_payload_paths[..1])
+            .await
+            .expect_err("import_payload_signature should fail below the policy threshold");
+        genesis_tools
+            .import_payload_signature(&signed_payload_paths[1..])
+            .await
+            .expect("import_payload_signature should not fail");
+
+        let last_certificates = certificate_store.get_list(10).await.unwrap();
+
+        assert_eq!(1, last_certificates.len());
+        certificate_verifier
+            .verify_genesis_certificate(&last_certificates[0], &genesis_verifier)
+            .await
+            .expect(
+                "verify_genesis_certificate should successully validate the genesis certificate",
+            );
+    }
 }
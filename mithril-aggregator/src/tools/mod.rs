@@ -1,10 +1,17 @@
 mod digest_helpers;
 mod genesis;
 mod remote_file_uploader;
+mod s3_file_uploader;
 
 pub use digest_helpers::extract_digest_from_path;
 pub use genesis::{GenesisTools, GenesisToolsDependency};
-pub use remote_file_uploader::{GcpFileUploader, RemoteFileUploader};
+pub use remote_file_uploader::{
+    format_public_url, GcpFileUploader, RemoteFileUploader, GCP_DEFAULT_BUCKET,
+    GCP_DEFAULT_PUBLIC_URL_TEMPLATE,
+};
+pub use s3_file_uploader::{
+    S3Credentials, S3FileUploader, S3_DEFAULT_MULTIPART_PART_SIZE, S3_MIN_MULTIPART_PART_SIZE,
+};
 
 #[cfg(test)]
 pub use remote_file_uploader::MockRemoteFileUploader;
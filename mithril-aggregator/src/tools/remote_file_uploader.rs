@@ -18,21 +18,79 @@ use mockall::automock;
 pub trait RemoteFileUploader: Sync + Send {
     /// Upload a snapshot
     async fn upload_file(&self, filepath: &Path) -> Result<(), String>;
+
+    /// Check if a file is already uploaded
+    async fn file_exists(&self, filename: &str) -> Result<bool, String>;
+
+    /// Public URL of an uploaded file
+    fn get_location(&self, filename: &str) -> String;
+}
+
+/// Format the public URL of an uploaded object from a template, where `{bucket}` is replaced
+/// by the bucket and `{key}` by the key of the object
+pub fn format_public_url(template: &str, bucket: &str, key: &str) -> String {
+    template.replace("{bucket}", bucket).replace("{key}", key)
 }
 
+/// Bucket of the reference testnet
+pub const GCP_DEFAULT_BUCKET: &str = "cardano-testnet";
+
+/// Template of the public URL of the objects stored on Google Cloud Storage
+pub const GCP_DEFAULT_PUBLIC_URL_TEMPLATE: &str = "https://storage.googleapis.com/{bucket}/{key}";
+
 /// GcpFileUploader represents a Google Cloud Platform file uploader interactor
 pub struct GcpFileUploader {
     bucket: String,
+    prefix: Option<String>,
+    public_url_template: String,
+    public_read: bool,
 }
 
-impl Default for GcpFileUploader {
-    fn default() -> Self {
+impl GcpFileUploader {
+    /// GcpFileUploader factory, the uploaded files are given public read access
+    pub fn new(bucket: &str) -> Self {
         Self {
-            bucket: "cardano-testnet".to_string(),
+            bucket: bucket.to_string(),
+            prefix: None,
+            public_url_template: GCP_DEFAULT_PUBLIC_URL_TEMPLATE.to_string(),
+            public_read: true,
+        }
+    }
+
+    /// Set the prefix of the names of the uploaded objects
+    pub fn with_prefix(mut self, prefix: &str) -> Self {
+        let prefix = prefix.trim_matches('/');
+        self.prefix = (!prefix.is_empty()).then(|| prefix.to_string());
+        self
+    }
+
+    /// Set the template of the public URL of the uploaded objects, see [format_public_url]
+    pub fn with_public_url_template(mut self, public_url_template: &str) -> Self {
+        self.public_url_template = public_url_template.to_string();
+        self
+    }
+
+    /// Set whether the uploaded objects are given public read access, else their access is
+    /// the one granted by the bucket
+    pub fn with_public_read(mut self, public_read: bool) -> Self {
+        self.public_read = public_read;
+        self
+    }
+
+    fn object_name(&self, filename: &str) -> String {
+        match &self.prefix {
+            Some(prefix) => format!("{}/{}", prefix, filename),
+            None => filename.to_string(),
         }
     }
 }
 
+impl Default for GcpFileUploader {
+    fn default() -> Self {
+        Self::new(GCP_DEFAULT_BUCKET)
+    }
+}
+
 #[async_trait]
 impl RemoteFileUploader for GcpFileUploader {
     async fn upload_file(&self, filepath: &Path) -> Result<(), String> {
@@ -42,7 +100,7 @@ impl RemoteFileUploader for GcpFileUploader {
             );
         };
 
-        let filename = filepath.file_name().unwrap().to_str().unwrap();
+        let filename = &self.object_name(filepath.file_name().unwrap().to_str().unwrap());
 
         info!("uploading {}", filename);
         let client = Client::default();
@@ -62,6 +120,10 @@ impl RemoteFileUploader for GcpFileUploader {
 
         info!("uploaded {}", filename);
 
+        if !self.public_read {
+            return Ok(());
+        }
+
         // ensure the uploaded file as public read access
         // when a file is uploaded to gcloud storage its permissions are overwritten so
         // we need to put them back
@@ -85,4 +147,65 @@ impl RemoteFileUploader for GcpFileUploader {
 
         Ok(())
     }
+
+    async fn file_exists(&self, filename: &str) -> Result<bool, String> {
+        if env::var("GOOGLE_APPLICATION_CREDENTIALS_JSON").is_err() {
+            return Err(
+                "Missing GOOGLE_APPLICATION_CREDENTIALS_JSON environment variable".to_string(),
+            );
+        };
+
+        // a failed lookup only means that the file will be uploaded again
+        let client = Client::default();
+        Ok(client
+            .object()
+            .read(&self.bucket, &self.object_name(filename))
+            .await
+            .is_ok())
+    }
+
+    fn get_location(&self, filename: &str) -> String {
+        format_public_url(
+            &self.public_url_template,
+            &self.bucket,
+            &self.object_name(filename),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_public_url() {
+        assert_eq!(
+            "https://cdn.example.com/bucket/snapshots/file.tar.gz",
+            format_public_url(
+                "https://cdn.example.com/{bucket}/{key}",
+                "bucket",
+                "snapshots/file.tar.gz"
+            )
+        );
+    }
+
+    #[test]
+    fn test_gcp_file_uploader_location() {
+        assert_eq!(
+            "https://storage.googleapis.com/cardano-testnet/file.tar.gz",
+            GcpFileUploader::default().get_location("file.tar.gz")
+        );
+
+        let file_uploader = GcpFileUploader::new("bucket").with_prefix("/preview/snapshots/");
+        assert_eq!(
+            "https://storage.googleapis.com/bucket/preview/snapshots/file.tar.gz",
+            file_uploader.get_location("file.tar.gz")
+        );
+
+        let file_uploader = file_uploader.with_public_url_template("https://cdn.example.com/{key}");
+        assert_eq!(
+            "https://cdn.example.com/preview/snapshots/file.tar.gz",
+            file_uploader.get_location("file.tar.gz")
+        );
+    }
 }
@@ -0,0 +1,88 @@
+mod test_extensions;
+
+use std::collections::BTreeSet;
+
+use mithril_common::crypto_helper::tests_setup;
+use mithril_common::entities::{
+    ProtocolParameters, SignedEntityKind, SignedEntityType, SignerWithStake,
+};
+use test_extensions::RuntimeTester;
+
+#[tokio::test]
+async fn certify_stake_distribution() {
+    let protocol_parameters = ProtocolParameters {
+        k: 5,
+        m: 100,
+        phi_f: 0.65,
+    };
+    let mut tester = RuntimeTester::build_with_signed_entity_kinds(
+        protocol_parameters.clone(),
+        BTreeSet::from([
+            SignedEntityKind::MithrilStakeDistribution,
+            SignedEntityKind::CardanoImmutableFilesFull,
+        ]),
+    )
+    .await;
+
+    comment!("Create signers & declare stake distribution");
+    let signers = tests_setup::setup_signers(5, &protocol_parameters.clone().into());
+    let signers_with_stake: Vec<SignerWithStake> = signers
+        .iter()
+        .map(|(signer_with_stake, _, _)| signer_with_stake.to_owned())
+        .collect();
+    tester
+        .chain_observer
+        .set_signers(signers_with_stake.clone())
+        .await;
+    tester
+        .deps
+        .simulate_genesis(
+            signers_with_stake.clone(),
+            signers_with_stake,
+            &protocol_parameters,
+        )
+        .await;
+
+    comment!("Boostrap the genesis certificate");
+    tester.register_genesis_certificate(&signers).await.unwrap();
+
+    comment!("Increase immutable number");
+    tester.increase_immutable_number().await.unwrap();
+
+    comment!("The stake distribution of the epoch is signed first");
+    cycle!(tester, "ready");
+    cycle!(tester, "signing");
+    tester.register_signers(&signers).await.unwrap();
+    cycle!(tester, "signing");
+    tester.send_single_signatures(&signers).await.unwrap();
+    cycle!(tester, "ready");
+    let (last_certificates, snapshots) =
+        tester.get_last_certificates_and_snapshots().await.unwrap();
+    assert_eq!((2, 0), (last_certificates.len(), snapshots.len()));
+    assert_eq!(
+        Some(SignedEntityType::MithrilStakeDistribution(
+            last_certificates[0].beacon.epoch
+        )),
+        last_certificates[0].signed_entity_type()
+    );
+
+    let beacon = last_certificates[0].beacon.clone();
+
+    comment!("The immutable files are signed next, at the same beacon");
+    cycle!(tester, "signing");
+    tester.send_single_signatures(&signers).await.unwrap();
+    cycle!(tester, "idle");
+    let (last_certificates, snapshots) =
+        tester.get_last_certificates_and_snapshots().await.unwrap();
+    assert_eq!((3, 1), (last_certificates.len(), snapshots.len()));
+    assert_eq!(
+        Some(SignedEntityType::CardanoImmutableFilesFull(beacon.clone())),
+        last_certificates[0].signed_entity_type()
+    );
+    assert_eq!(beacon, snapshots[0].beacon);
+    assert_eq!(
+        &last_certificates[0].previous_hash, &last_certificates[1].previous_hash,
+        "Both certificates of the epoch should be linked to the genesis certificate"
+    );
+    assert_eq!(&snapshots[0].certificate_hash, &last_certificates[0].hash);
+}
@@ -1,16 +1,17 @@
 use mithril_aggregator::{
-    AggregatorConfig, CertificatePendingStore, CertificateStore, Configuration, DependencyManager,
-    DumbSnapshotUploader, DumbSnapshotter, LocalSnapshotStore, MultiSignerImpl,
-    ProtocolParametersStore, SingleSignatureStore, SnapshotStoreType, SnapshotUploaderType,
-    VerificationKeyStore,
+    AggregatorConfig, AuditLog, CertificateBundleCache, CertificatePendingStore, CertificateStore,
+    Configuration, DependencyManager, DumbSnapshotUploader, DumbSnapshotter, EventBus,
+    FailedRoundStore, LocalSnapshotStore, MetricsService, MultiSignerImpl, ProtocolParametersStore,
+    RuntimeControl, RuntimeStateStore, RuntimeStatus, SignerStatisticsStore, SingleSignatureStore,
+    SnapshotStoreType, SnapshotUploaderType, VerificationKeyStore,
 };
 use mithril_common::certificate_chain::MithrilCertificateVerifier;
 use mithril_common::chain_observer::FakeObserver;
-use mithril_common::crypto_helper::{key_encode_hex, ProtocolGenesisSigner};
+use mithril_common::crypto_helper::ProtocolGenesisSigner;
 use mithril_common::digesters::{DumbImmutableDigester, DumbImmutableFileObserver};
-use mithril_common::entities::ProtocolParameters;
+use mithril_common::entities::{CompressionAlgorithm, ProtocolParameters};
 use mithril_common::store::adapter::MemoryAdapter;
-use mithril_common::store::StakeStore;
+use mithril_common::store::{DumbTransactionManager, RetentionPolicy, StakeStore};
 use mithril_common::{BeaconProviderImpl, CardanoNetwork};
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -26,47 +27,82 @@ pub async fn initialize_dependencies(
     genesis_signer: Arc<ProtocolGenesisSigner>,
 ) -> (Arc<DependencyManager>, AggregatorConfig) {
     let genesis_verifier = Arc::new(genesis_signer.create_genesis_verifier());
-    let genesis_verification_key = genesis_verifier.to_verification_key();
     let config: Configuration = Configuration {
         cardano_cli_path: PathBuf::new(),
         cardano_node_socket_path: PathBuf::new(),
         network_magic: Some(42),
         network: "whatever".to_string(),
         protocol_parameters: default_protocol_parameters,
-        url_snapshot_manifest: "https://storage.googleapis.com/cardano-testnet/snapshots.json"
-            .to_string(),
+        url_snapshot_manifest: None,
         snapshot_store_type: SnapshotStoreType::Local,
         snapshot_uploader_type: SnapshotUploaderType::Local,
+        gcp_bucket: None,
+        gcp_prefix: None,
+        gcp_public_url_template: None,
+        gcp_acl_policy: None,
+        s3_endpoint: None,
+        s3_bucket: None,
+        s3_prefix: None,
+        s3_region: None,
+        s3_public_url_template: None,
+        s3_multipart_part_size: None,
+        snapshot_compression_algorithm: CompressionAlgorithm::Gzip,
+        snapshot_compression_level: None,
+        snapshot_compression_threads: None,
+        snapshot_max_increments: None,
+        snapshot_chunk_size: None,
         server_ip: "0.0.0.0".to_string(),
         server_port: 8000,
         run_interval: 5000,
         db_directory: PathBuf::new(),
         snapshot_directory: PathBuf::new(),
         data_stores_directory: PathBuf::new(),
-        genesis_verification_key: key_encode_hex(&genesis_verification_key).unwrap(),
+        genesis_verification_key: genesis_verifier.to_hex().unwrap(),
+        genesis_policy_path: None,
         store_retention_limit: None,
+        verification_key_store_retention: None,
+        stake_store_retention: None,
+        single_signature_store_retention: None,
+        protocol_parameters_store_retention: None,
+        certificate_store_retention: None,
+        signer_statistics_store_retention: None,
+        failed_round_store_retention: None,
+        signed_entity_types: None,
+        admin_token: None,
+        pending_certificate_timeout: None,
     };
     let certificate_pending_store = Arc::new(CertificatePendingStore::new(Box::new(
         MemoryAdapter::new(None).unwrap(),
     )));
+    let runtime_state_store = Arc::new(RuntimeStateStore::new(Box::new(
+        MemoryAdapter::new(None).unwrap(),
+    )));
+    let failed_round_store = Arc::new(FailedRoundStore::new(
+        Box::new(MemoryAdapter::new(None).unwrap()),
+        config.store_retention_limit.into(),
+    ));
+    let signer_statistics_store = Arc::new(SignerStatisticsStore::new(
+        Box::new(MemoryAdapter::new(None).unwrap()),
+        config.store_retention_limit.into(),
+    ));
     let certificate_store = Arc::new(CertificateStore::new(Box::new(
         MemoryAdapter::new(None).unwrap(),
     )));
     let verification_key_store = Arc::new(VerificationKeyStore::new(
         Box::new(MemoryAdapter::new(None).unwrap()),
-        config.store_retention_limit,
+        config.store_retention_limit.into(),
     ));
     let stake_store = Arc::new(StakeStore::new(
         Box::new(MemoryAdapter::new(None).unwrap()),
-        config.store_retention_limit,
+        config.store_retention_limit.into(),
     ));
     let single_signature_store = Arc::new(SingleSignatureStore::new(
         Box::new(MemoryAdapter::new(None).unwrap()),
-        config.store_retention_limit,
+        config.store_retention_limit.into(),
     ));
     let protocol_parameters_store = Arc::new(ProtocolParametersStore::new(
         Box::new(MemoryAdapter::new(None).unwrap()),
-        None,
+        RetentionPolicy::KeepAll,
     ));
     let multi_signer = MultiSignerImpl::new(
         verification_key_store.clone(),
@@ -74,7 +110,8 @@ pub async fn initialize_dependencies(
         single_signature_store.clone(),
         protocol_parameters_store.clone(),
         chain_observer.clone(),
-    );
+    )
+    .with_signer_statistics_store(signer_statistics_store.clone());
     let multi_signer = Arc::new(RwLock::new(multi_signer));
     let beacon_provider = Arc::new(BeaconProviderImpl::new(
         chain_observer.clone(),
@@ -92,11 +129,15 @@ pub async fn initialize_dependencies(
         snapshot_uploader,
         multi_signer,
         certificate_pending_store,
+        runtime_state_store,
+        failed_round_store,
+        signer_statistics_store,
         certificate_store,
         verification_key_store,
         stake_store,
         single_signature_store,
         protocol_parameters_store,
+        transaction_manager: Arc::new(DumbTransactionManager::new()),
         chain_observer,
         beacon_provider,
         immutable_file_observer,
@@ -104,6 +145,14 @@ pub async fn initialize_dependencies(
         snapshotter,
         certificate_verifier,
         genesis_verifier,
+        metrics_service: Arc::new(MetricsService::new().unwrap()),
+        runtime_status: Arc::new(RwLock::new(RuntimeStatus::default())),
+        event_bus: Arc::new(EventBus::default()),
+        runtime_control: Arc::new(RuntimeControl::new()),
+        audit_log: Arc::new(AuditLog::new(
+            std::env::temp_dir().join("mithril_aggregator_test_admin_audit.log"),
+        )),
+        certificate_bundle_cache: Arc::new(CertificateBundleCache::default()),
     };
 
     let config = AggregatorConfig::new(
@@ -114,3 +163,46 @@ pub async fn initialize_dependencies(
 
     (Arc::new(dependency_manager), config)
 }
+
+/// Build the dependencies of an aggregator restarting with the stores and services of the given
+/// dependencies, only the in-memory context of the multi-signer is lost.
+pub fn restart_dependencies(deps: &DependencyManager) -> Arc<DependencyManager> {
+    let multi_signer = MultiSignerImpl::new(
+        deps.verification_key_store.clone(),
+        deps.stake_store.clone(),
+        deps.single_signature_store.clone(),
+        deps.protocol_parameters_store.clone(),
+        deps.chain_observer.clone(),
+    )
+    .with_signer_statistics_store(deps.signer_statistics_store.clone());
+
+    Arc::new(DependencyManager {
+        config: deps.config.clone(),
+        snapshot_store: deps.snapshot_store.clone(),
+        snapshot_uploader: deps.snapshot_uploader.clone(),
+        multi_signer: Arc::new(RwLock::new(multi_signer)),
+        certificate_pending_store: deps.certificate_pending_store.clone(),
+        runtime_state_store: deps.runtime_state_store.clone(),
+        failed_round_store: deps.failed_round_store.clone(),
+        signer_statistics_store: deps.signer_statistics_store.clone(),
+        certificate_store: deps.certificate_store.clone(),
+        verification_key_store: deps.verification_key_store.clone(),
+        stake_store: deps.stake_store.clone(),
+        single_signature_store: deps.single_signature_store.clone(),
+        protocol_parameters_store: deps.protocol_parameters_store.clone(),
+        transaction_manager: deps.transaction_manager.clone(),
+        chain_observer: deps.chain_observer.clone(),
+        beacon_provider: deps.beacon_provider.clone(),
+        immutable_file_observer: deps.immutable_file_observer.clone(),
+        digester: deps.digester.clone(),
+        snapshotter: deps.snapshotter.clone(),
+        certificate_verifier: deps.certificate_verifier.clone(),
+        genesis_verifier: deps.genesis_verifier.clone(),
+        metrics_service: deps.metrics_service.clone(),
+        runtime_status: deps.runtime_status.clone(),
+        event_bus: deps.event_bus.clone(),
+        runtime_control: deps.runtime_control.clone(),
+        audit_log: deps.audit_log.clone(),
+        certificate_bundle_cache: deps.certificate_bundle_cache.clone(),
+    })
+}
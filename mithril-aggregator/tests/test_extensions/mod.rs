@@ -8,6 +8,6 @@ mod dependency;
 #[macro_use]
 pub mod utilities;
 
-pub use dependency::initialize_dependencies;
+pub use dependency::{initialize_dependencies, restart_dependencies};
 pub use runtime_tester::RuntimeTester;
 pub use utilities::TestSigner;
@@ -1,19 +1,20 @@
-use crate::test_extensions::{initialize_dependencies, TestSigner};
+use crate::test_extensions::{initialize_dependencies, restart_dependencies, TestSigner};
 use mithril_common::certificate_chain::CertificateGenesisProducer;
 use slog::Drain;
+use std::collections::BTreeSet;
 use std::sync::Arc;
 use std::time::Duration;
 
 use mithril_aggregator::{
-    AggregatorRunner, AggregatorRuntime, DependencyManager, DumbSnapshotUploader, DumbSnapshotter,
-    ProtocolParametersStorer,
+    AggregatorConfig, AggregatorRunner, AggregatorRuntime, DependencyManager, DumbSnapshotUploader,
+    DumbSnapshotter, ProtocolParametersStorer,
 };
 use mithril_common::crypto_helper::tests_setup::setup_signers_from_stake_distribution;
 use mithril_common::crypto_helper::{key_encode_hex, ProtocolClerk, ProtocolGenesisSigner};
 use mithril_common::digesters::DumbImmutableFileObserver;
 use mithril_common::entities::{
-    Certificate, Epoch, ImmutableFileNumber, ProtocolParameters, SignerWithStake, SingleSignatures,
-    Snapshot,
+    Certificate, Epoch, ImmutableFileNumber, ProtocolParameters, SignedEntityKind, SignerWithStake,
+    SingleSignatures, Snapshot,
 };
 use mithril_common::{chain_observer::FakeObserver, digesters::DumbImmutableDigester};
 
@@ -33,12 +34,24 @@ pub struct RuntimeTester {
     pub snapshotter: Arc<DumbSnapshotter>,
     pub genesis_signer: Arc<ProtocolGenesisSigner>,
     pub deps: Arc<DependencyManager>,
+    pub config: AggregatorConfig,
     pub runtime: AggregatorRuntime,
     _logs_guard: slog_scope::GlobalLoggerGuard,
 }
 
 impl RuntimeTester {
     pub async fn build(default_protocol_parameters: ProtocolParameters) -> Self {
+        Self::build_with_signed_entity_kinds(
+            default_protocol_parameters,
+            BTreeSet::from([SignedEntityKind::CardanoImmutableFilesFull]),
+        )
+        .await
+    }
+
+    pub async fn build_with_signed_entity_kinds(
+        default_protocol_parameters: ProtocolParameters,
+        signed_entity_kinds: BTreeSet<SignedEntityKind>,
+    ) -> Self {
         let snapshot_uploader = Arc::new(DumbSnapshotUploader::new());
         let chain_observer = Arc::new(FakeObserver::default());
         let immutable_file_observer = Arc::new(DumbImmutableFileObserver::default());
@@ -55,11 +68,8 @@ impl RuntimeTester {
             genesis_signer.clone(),
         )
         .await;
-        let runner = Arc::new(AggregatorRunner::new(config.clone(), deps.clone()));
-        let runtime =
-            AggregatorRuntime::new(Duration::from_millis(config.interval), None, runner.clone())
-                .await
-                .expect("Instantiating the Runtime should not fail.");
+        let config = config.with_signed_entity_kinds(signed_entity_kinds);
+        let runtime = Self::build_runtime(config.clone(), deps.clone()).await;
 
         let decorator = slog_term::PlainDecorator::new(slog_term::TestStdoutWriter);
         let drain = slog_term::CompactFormat::new(decorator).build().fuse();
@@ -74,11 +84,38 @@ impl RuntimeTester {
             snapshotter,
             genesis_signer,
             deps,
+            config,
             runtime,
             _logs_guard: log,
         }
     }
 
+    async fn build_runtime(
+        config: AggregatorConfig,
+        deps: Arc<DependencyManager>,
+    ) -> AggregatorRuntime {
+        let runner = Arc::new(AggregatorRunner::new(config.clone(), deps.clone()));
+
+        AggregatorRuntime::new(
+            Duration::from_millis(config.interval),
+            None,
+            runner,
+            deps.metrics_service.clone(),
+            deps.runtime_status.clone(),
+            deps.event_bus.clone(),
+            deps.runtime_control.clone(),
+        )
+        .await
+        .expect("Instantiating the Runtime should not fail.")
+    }
+
+    /// Simulate a restart of the aggregator: the runtime resumes from its saved state with a
+    /// multi-signer that lost its in-memory context.
+    pub async fn restart(&mut self) {
+        self.deps = restart_dependencies(&self.deps);
+        self.runtime = Self::build_runtime(self.config.clone(), self.deps.clone()).await;
+    }
+
     /// cycle the runtime once
     pub async fn cycle(&mut self) -> Result<(), String> {
         self.runtime
@@ -136,7 +173,7 @@ impl RuntimeTester {
             protocol_parameters,
             beacon,
             genesis_avk,
-            genesis_signature,
+            genesis_signature.into(),
         )
         .map_err(|e| format!("Creating the genesis certificate should not fail: {:?}", e))?;
         self.deps
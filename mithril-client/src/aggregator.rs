@@ -1,19 +1,24 @@
 use async_trait::async_trait;
 use flate2::read::GzDecoder;
 use futures::StreamExt;
-use reqwest::{self, StatusCode};
+use reqwest::{self, RequestBuilder, Response, StatusCode};
 use slog_scope::debug;
 use std::env;
 use std::fs;
-use std::io::{self, Write};
+use std::io::{self, Read, Write};
 use std::path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tar::Archive;
 use thiserror::Error;
 
-use mithril_common::entities::{Certificate, Snapshot};
+use mithril_common::crypto_helper::PROTOCOL_VERSION;
+use mithril_common::entities::{
+    Certificate, CompressionAlgorithm, Snapshot, SnapshotChunk, SnapshotManifest,
+};
+use mithril_common::protocol_version::{check_protocol_version_header, PROTOCOL_VERSION_HEADER};
 
+use mithril_common::certificate_chain::CertificateChainBundle;
 use mithril_common::certificate_chain::CertificateRetriever;
 use mithril_common::certificate_chain::CertificateRetrieverError;
 
@@ -44,6 +49,19 @@ pub enum AggregatorHandlerError {
     /// [AggregatorHandler::download_snapshot] beforehand.
     #[error("archive not found, did you download it beforehand ? Expected path: '{0}'")]
     ArchiveNotFound(PathBuf),
+
+    /// Error raised when the compression format of the downloaded archive is not supported.
+    #[error("unsupported archive format, expected a gzip or zstandard archive: '{0}'")]
+    UnsupportedArchiveFormat(PathBuf),
+
+    /// Error raised when the content of a downloaded snapshot chunk doesn't match its hash.
+    #[error("invalid snapshot chunk, its content doesn't match its hash: '{0}'")]
+    InvalidSnapshotChunk(String),
+
+    /// Error raised when the protocol version of the aggregator is not compatible with the
+    /// client.
+    #[error("incompatible protocol version: '{0}'")]
+    IncompatibleProtocolVersion(String),
 }
 
 /// AggregatorHandler represents a read interactor with an aggregator
@@ -62,9 +80,42 @@ pub trait AggregatorHandler: CertificateRetriever + Sync + Send {
         location: &str,
     ) -> Result<String, AggregatorHandlerError>;
 
+    /// Check if the archive of a snapshot is already downloaded
+    async fn is_snapshot_downloaded(&self, digest: &str) -> Result<bool, AggregatorHandlerError>;
+
+    /// Get the manifest of a snapshot stored as chunks
+    async fn get_snapshot_manifest(
+        &self,
+        location: &str,
+    ) -> Result<SnapshotManifest, AggregatorHandlerError>;
+
+    /// Download the chunks of a snapshot listed in its manifest and assemble its archive, the
+    /// chunks already downloaded are not downloaded again
+    async fn download_snapshot_chunks(
+        &self,
+        manifest_location: &str,
+        manifest: &SnapshotManifest,
+    ) -> Result<String, AggregatorHandlerError>;
+
     /// Unpack snapshot
     async fn unpack_snapshot(&self, digest: &str) -> Result<String, AggregatorHandlerError>;
 
+    /// Unpack the snapshot of the first digest where the snapshot of the second digest is
+    /// unpacked, ie: the base snapshots of an incremental snapshot
+    async fn unpack_snapshot_into(
+        &self,
+        digest: &str,
+        restore_digest: &str,
+    ) -> Result<String, AggregatorHandlerError>;
+
+    /// Get the certificate chain bundle of a certificate, back to the genesis certificate or
+    /// to the trusted checkpoint
+    async fn get_certificate_bundle(
+        &self,
+        certificate_hash: &str,
+        trusted_checkpoint: Option<&str>,
+    ) -> Result<CertificateChainBundle, AggregatorHandlerError>;
+
     /// Upcast to a CertificateRetriever
     fn as_certificate_retriever(&self) -> Arc<dyn CertificateRetriever>;
 }
@@ -86,6 +137,42 @@ impl AggregatorHTTPClient {
         }
     }
 
+    fn get(&self, url: &str) -> RequestBuilder {
+        reqwest::Client::new()
+            .get(url)
+            .header(PROTOCOL_VERSION_HEADER, PROTOCOL_VERSION)
+    }
+
+    /// Download the content of a snapshot chunk
+    async fn download_chunk(location: &str) -> Result<Vec<u8>, AggregatorHandlerError> {
+        let response = reqwest::get(location).await;
+        match response {
+            Ok(response) => match response.status() {
+                StatusCode::OK => Ok(response
+                    .bytes()
+                    .await
+                    .map_err(|e| AggregatorHandlerError::RemoteServerTechnical(e.to_string()))?
+                    .to_vec()),
+                StatusCode::NOT_FOUND => Err(AggregatorHandlerError::RemoteServerLogical(
+                    "snapshot chunk not found".to_string(),
+                )),
+                status_error => Err(AggregatorHandlerError::RemoteServerTechnical(
+                    status_error.to_string(),
+                )),
+            },
+            Err(err) => Err(AggregatorHandlerError::RemoteServerUnreachable(
+                err.to_string(),
+            )),
+        }
+    }
+
+    /// Check the protocol version advertised by the aggregator, an aggregator that doesn't
+    /// advertise it is assumed compatible
+    fn check_protocol_version(response: &Response) -> Result<(), AggregatorHandlerError> {
+        check_protocol_version_header(response.headers())
+            .map_err(|e| AggregatorHandlerError::IncompatibleProtocolVersion(e.to_string()))
+    }
+
     /// Download certificate details
     async fn download_certificate_details(
         &self,
@@ -96,20 +183,23 @@ impl AggregatorHTTPClient {
             "{}/certificate/{}",
             self.aggregator_endpoint, certificate_hash
         );
-        let response = reqwest::get(url.clone()).await;
+        let response = self.get(&url).send().await;
         match response {
-            Ok(response) => match response.status() {
-                StatusCode::OK => match response.json::<Certificate>().await {
-                    Ok(certificate) => Ok(certificate),
-                    Err(err) => Err(AggregatorHandlerError::JsonParseFailed(err.to_string())),
-                },
-                StatusCode::NOT_FOUND => Err(AggregatorHandlerError::RemoteServerLogical(
-                    "certificate not found".to_string(),
-                )),
-                status_error => Err(AggregatorHandlerError::RemoteServerTechnical(
-                    status_error.to_string(),
-                )),
-            },
+            Ok(response) => {
+                Self::check_protocol_version(&response)?;
+                match response.status() {
+                    StatusCode::OK => match response.json::<Certificate>().await {
+                        Ok(certificate) => Ok(certificate),
+                        Err(err) => Err(AggregatorHandlerError::JsonParseFailed(err.to_string())),
+                    },
+                    StatusCode::NOT_FOUND => Err(AggregatorHandlerError::RemoteServerLogical(
+                        "certificate not found".to_string(),
+                    )),
+                    status_error => Err(AggregatorHandlerError::RemoteServerTechnical(
+                        status_error.to_string(),
+                    )),
+                }
+            }
             Err(err) => Err(AggregatorHandlerError::RemoteServerUnreachable(
                 err.to_string(),
             )),
@@ -123,17 +213,20 @@ impl AggregatorHandler for AggregatorHTTPClient {
     async fn list_snapshots(&self) -> Result<Vec<Snapshot>, AggregatorHandlerError> {
         debug!("List snapshots");
         let url = format!("{}/snapshots", self.aggregator_endpoint);
-        let response = reqwest::get(url.clone()).await;
+        let response = self.get(&url).send().await;
         match response {
-            Ok(response) => match response.status() {
-                StatusCode::OK => match response.json::<Vec<Snapshot>>().await {
-                    Ok(snapshots) => Ok(snapshots),
-                    Err(err) => Err(AggregatorHandlerError::JsonParseFailed(err.to_string())),
-                },
-                status_error => Err(AggregatorHandlerError::RemoteServerTechnical(
-                    status_error.to_string(),
-                )),
-            },
+            Ok(response) => {
+                Self::check_protocol_version(&response)?;
+                match response.status() {
+                    StatusCode::OK => match response.json::<Vec<Snapshot>>().await {
+                        Ok(snapshots) => Ok(snapshots),
+                        Err(err) => Err(AggregatorHandlerError::JsonParseFailed(err.to_string())),
+                    },
+                    status_error => Err(AggregatorHandlerError::RemoteServerTechnical(
+                        status_error.to_string(),
+                    )),
+                }
+            }
             Err(err) => Err(AggregatorHandlerError::RemoteServerUnreachable(
                 err.to_string(),
             )),
@@ -144,20 +237,23 @@ impl AggregatorHandler for AggregatorHTTPClient {
     async fn get_snapshot_details(&self, digest: &str) -> Result<Snapshot, AggregatorHandlerError> {
         debug!("Details snapshot {}", digest);
         let url = format!("{}/snapshot/{}", self.aggregator_endpoint, digest);
-        let response = reqwest::get(url.clone()).await;
+        let response = self.get(&url).send().await;
         match response {
-            Ok(response) => match response.status() {
-                StatusCode::OK => match response.json::<Snapshot>().await {
-                    Ok(snapshot) => Ok(snapshot),
-                    Err(err) => Err(AggregatorHandlerError::JsonParseFailed(err.to_string())),
-                },
-                StatusCode::NOT_FOUND => Err(AggregatorHandlerError::RemoteServerLogical(
-                    "snapshot not found".to_string(),
-                )),
-                status_error => Err(AggregatorHandlerError::RemoteServerTechnical(
-                    status_error.to_string(),
-                )),
-            },
+            Ok(response) => {
+                Self::check_protocol_version(&response)?;
+                match response.status() {
+                    StatusCode::OK => match response.json::<Snapshot>().await {
+                        Ok(snapshot) => Ok(snapshot),
+                        Err(err) => Err(AggregatorHandlerError::JsonParseFailed(err.to_string())),
+                    },
+                    StatusCode::NOT_FOUND => Err(AggregatorHandlerError::RemoteServerLogical(
+                        "snapshot not found".to_string(),
+                    )),
+                    status_error => Err(AggregatorHandlerError::RemoteServerTechnical(
+                        status_error.to_string(),
+                    )),
+                }
+            }
             Err(err) => Err(AggregatorHandlerError::RemoteServerUnreachable(
                 err.to_string(),
             )),
@@ -214,9 +310,110 @@ impl AggregatorHandler for AggregatorHTTPClient {
         }
     }
 
+    /// Check if the archive of a snapshot is already downloaded
+    async fn is_snapshot_downloaded(&self, digest: &str) -> Result<bool, AggregatorHandlerError> {
+        Ok(archive_file_path(digest, &self.network)?.exists())
+    }
+
+    /// Get the manifest of a snapshot stored as chunks
+    async fn get_snapshot_manifest(
+        &self,
+        location: &str,
+    ) -> Result<SnapshotManifest, AggregatorHandlerError> {
+        debug!("Snapshot manifest {}", location);
+        let response = reqwest::get(location).await;
+        match response {
+            Ok(response) => match response.status() {
+                StatusCode::OK => match response.json::<SnapshotManifest>().await {
+                    Ok(manifest) => Ok(manifest),
+                    Err(err) => Err(AggregatorHandlerError::JsonParseFailed(err.to_string())),
+                },
+                StatusCode::NOT_FOUND => Err(AggregatorHandlerError::RemoteServerLogical(
+                    "snapshot manifest not found".to_string(),
+                )),
+                status_error => Err(AggregatorHandlerError::RemoteServerTechnical(
+                    status_error.to_string(),
+                )),
+            },
+            Err(err) => Err(AggregatorHandlerError::RemoteServerUnreachable(
+                err.to_string(),
+            )),
+        }
+    }
+
+    /// Download the chunks of a snapshot and assemble its archive
+    async fn download_snapshot_chunks(
+        &self,
+        manifest_location: &str,
+        manifest: &SnapshotManifest,
+    ) -> Result<String, AggregatorHandlerError> {
+        debug!(
+            "Download snapshot {} chunks from {}",
+            manifest.digest, manifest_location
+        );
+        let chunks_path = chunks_directory_path(&self.network)?;
+        fs::create_dir_all(&chunks_path)?;
+        let mut bytes_downloaded = 0;
+        for chunk in &manifest.chunks {
+            let chunk_path = chunks_path.join(&chunk.hash);
+            if !is_chunk_valid(&chunk_path, chunk)? {
+                let location = SnapshotManifest::chunk_location(manifest_location, &chunk.hash);
+                let content = Self::download_chunk(&location).await?;
+                if SnapshotChunk::compute_hash(&content) != chunk.hash {
+                    return Err(AggregatorHandlerError::InvalidSnapshotChunk(location));
+                }
+                // write the chunk in a temporary file first so that an interrupted download
+                // never leaves a partial chunk behind
+                let part_path = chunks_path.join(format!("{}.part", chunk.hash));
+                fs::write(&part_path, &content)?;
+                fs::rename(&part_path, &chunk_path)?;
+            }
+            bytes_downloaded += chunk.size;
+            print!(
+                "Downloaded {}% - {}/{} Bytes\r",
+                100 * bytes_downloaded / manifest.size.max(1),
+                bytes_downloaded,
+                manifest.size
+            );
+            io::stdout().flush().expect("Could not flush stdout");
+        }
+
+        let local_path = archive_file_path(&manifest.digest, &self.network)?;
+        fs::create_dir_all(&local_path.parent().unwrap())?;
+        let mut local_file = fs::File::create(&local_path)?;
+        for chunk in &manifest.chunks {
+            io::copy(
+                &mut fs::File::open(chunks_path.join(&chunk.hash))?,
+                &mut local_file,
+            )?;
+        }
+        // the chunks are only kept to resume an interrupted download
+        for chunk in &manifest.chunks {
+            if let Err(error) = fs::remove_file(chunks_path.join(&chunk.hash)) {
+                if error.kind() != io::ErrorKind::NotFound {
+                    return Err(error.into());
+                }
+            }
+        }
+
+        Ok(local_path.into_os_string().into_string().unwrap())
+    }
+
     /// Unpack snapshot
     async fn unpack_snapshot(&self, digest: &str) -> Result<String, AggregatorHandlerError> {
-        debug!("Unpack snapshot {}", digest);
+        self.unpack_snapshot_into(digest, digest).await
+    }
+
+    /// Unpack snapshot where another snapshot is unpacked
+    async fn unpack_snapshot_into(
+        &self,
+        digest: &str,
+        restore_digest: &str,
+    ) -> Result<String, AggregatorHandlerError> {
+        debug!(
+            "Unpack snapshot {} into snapshot {}",
+            digest, restore_digest
+        );
         println!("Unpacking snapshot...");
         let local_path = archive_file_path(digest, &self.network)?;
         local_path
@@ -224,14 +421,68 @@ impl AggregatorHandler for AggregatorHTTPClient {
             .then_some(())
             .ok_or_else(|| AggregatorHandlerError::ArchiveNotFound(local_path.clone()))?;
 
-        let snapshot_file_tar_gz = fs::File::open(local_path.clone())?;
-        let snapshot_file_tar = GzDecoder::new(snapshot_file_tar_gz);
-        let unpack_dir_path = local_path.parent().unwrap().join(path::Path::new("db"));
+        let mut magic_bytes = Vec::with_capacity(4);
+        fs::File::open(&local_path)?
+            .take(4)
+            .read_to_end(&mut magic_bytes)?;
+        let snapshot_file_compressed = fs::File::open(local_path.clone())?;
+        let snapshot_file_tar: Box<dyn Read> =
+            match CompressionAlgorithm::from_magic_bytes(&magic_bytes) {
+                Some(CompressionAlgorithm::Gzip) => {
+                    Box::new(GzDecoder::new(snapshot_file_compressed))
+                }
+                Some(CompressionAlgorithm::Zstandard) => {
+                    Box::new(zstd::Decoder::new(snapshot_file_compressed)?)
+                }
+                None => return Err(AggregatorHandlerError::UnsupportedArchiveFormat(local_path)),
+            };
+        let unpack_dir_path = archive_file_path(restore_digest, &self.network)?
+            .parent()
+            .unwrap()
+            .join(path::Path::new("db"));
         let mut snapshot_archive = Archive::new(snapshot_file_tar);
         snapshot_archive.unpack(&unpack_dir_path)?;
         Ok(unpack_dir_path.into_os_string().into_string().unwrap())
     }
 
+    /// Get certificate chain bundle
+    async fn get_certificate_bundle(
+        &self,
+        certificate_hash: &str,
+        trusted_checkpoint: Option<&str>,
+    ) -> Result<CertificateChainBundle, AggregatorHandlerError> {
+        debug!("Certificate chain bundle {}", certificate_hash);
+        let url = format!(
+            "{}/certificate/{}/bundle",
+            self.aggregator_endpoint, certificate_hash
+        );
+        let mut request = self.get(&url);
+        if let Some(trusted_checkpoint) = trusted_checkpoint {
+            request = request.query(&[("checkpoint", trusted_checkpoint)]);
+        }
+        let response = request.send().await;
+        match response {
+            Ok(response) => {
+                Self::check_protocol_version(&response)?;
+                match response.status() {
+                    StatusCode::OK => match response.json::<CertificateChainBundle>().await {
+                        Ok(bundle) => Ok(bundle),
+                        Err(err) => Err(AggregatorHandlerError::JsonParseFailed(err.to_string())),
+                    },
+                    StatusCode::NOT_FOUND => Err(AggregatorHandlerError::RemoteServerLogical(
+                        "certificate not found".to_string(),
+                    )),
+                    status_error => Err(AggregatorHandlerError::RemoteServerTechnical(
+                        status_error.to_string(),
+                    )),
+                }
+            }
+            Err(err) => Err(AggregatorHandlerError::RemoteServerUnreachable(
+                err.to_string(),
+            )),
+        }
+    }
+
     /// Upcast to a CertificateRetriever
     fn as_certificate_retriever(&self) -> Arc<dyn CertificateRetriever> {
         Arc::new(self.clone())
@@ -251,6 +502,21 @@ impl CertificateRetriever for AggregatorHTTPClient {
     }
 }
 
+/// Computes local directory of the downloaded snapshot chunks, they are shared by all the
+/// snapshots of a network
+fn chunks_directory_path(network: &str) -> Result<PathBuf, AggregatorHandlerError> {
+    Ok(env::current_dir()?.join(path::Path::new(&format!("data/{}/chunks", network))))
+}
+
+/// Check if a snapshot chunk is already downloaded and not corrupted
+fn is_chunk_valid(chunk_path: &path::Path, chunk: &SnapshotChunk) -> Result<bool, io::Error> {
+    if !chunk_path.exists() {
+        return Ok(false);
+    }
+
+    Ok(SnapshotChunk::compute_hash(&fs::read(chunk_path)?) == chunk.hash)
+}
+
 /// Computes local archive filepath
 fn archive_file_path(digest: &str, network: &str) -> Result<PathBuf, AggregatorHandlerError> {
     Ok(env::current_dir()?.join(path::Path::new(&format!(
@@ -277,6 +543,7 @@ mod tests {
             network: "testnet".to_string(),
             aggregator_endpoint: server.url(""),
             genesis_verification_key: "genesis-vkey".to_string(),
+            genesis_policy_path: None,
         };
         (server, config)
     }
@@ -291,7 +558,12 @@ mod tests {
     }
 
     /// see [`archive_file_path`] to see where the dummy will be created
-    fn build_dummy_snapshot(digest: &str, network: &str, data_expected: &str) {
+    fn build_dummy_snapshot(
+        digest: &str,
+        network: &str,
+        data_expected: &str,
+        compression_algorithm: CompressionAlgorithm,
+    ) {
         let data_file_name = "data.txt";
         let archive_file_path = archive_file_path(digest, network).unwrap();
         let source_directory_name = "src";
@@ -304,12 +576,24 @@ mod tests {
         let mut source_file = fs::File::create(&source_file_path).unwrap();
         write!(source_file, "{}", data_expected).unwrap();
         let archive_file = fs::File::create(&archive_file_path).unwrap();
-        let archive_encoder = GzEncoder::new(&archive_file, Compression::default());
-        let mut archive_builder = tar::Builder::new(archive_encoder);
-        archive_builder
-            .append_dir_all(".", &source_file_path.parent().unwrap())
-            .unwrap();
-        archive_builder.into_inner().unwrap().finish().unwrap();
+        match compression_algorithm {
+            CompressionAlgorithm::Gzip => {
+                let archive_encoder = GzEncoder::new(&archive_file, Compression::default());
+                let mut archive_builder = tar::Builder::new(archive_encoder);
+                archive_builder
+                    .append_dir_all(".", &source_file_path.parent().unwrap())
+                    .unwrap();
+                archive_builder.into_inner().unwrap().finish().unwrap();
+            }
+            CompressionAlgorithm::Zstandard => {
+                let archive_encoder = zstd::Encoder::new(&archive_file, 0).unwrap();
+                let mut archive_builder = tar::Builder::new(archive_encoder);
+                archive_builder
+                    .append_dir_all(".", &source_file_path.parent().unwrap())
+                    .unwrap();
+                archive_builder.into_inner().unwrap().finish().unwrap();
+            }
+        }
     }
 
     #[tokio::test]
@@ -340,6 +624,30 @@ mod tests {
         assert!(snapshots.is_err());
     }
 
+    #[tokio::test]
+    async fn test_list_snapshots_ko_incompatible_protocol_version() {
+        let (server, config) = setup_test();
+        let snapshots_expected = fake_data::snapshots(5);
+        let _snapshots_mock = server.mock(|when, then| {
+            when.path("/snapshots")
+                .header(PROTOCOL_VERSION_HEADER, PROTOCOL_VERSION);
+            then.status(200)
+                .header(PROTOCOL_VERSION_HEADER, "99.0.0")
+                .body(json!(snapshots_expected).to_string());
+        });
+        let aggregator_client =
+            AggregatorHTTPClient::new(config.network, config.aggregator_endpoint);
+        let snapshots = aggregator_client.list_snapshots().await;
+        assert!(
+            matches!(
+                snapshots,
+                Err(AggregatorHandlerError::IncompatibleProtocolVersion(_))
+            ),
+            "unexpected result: {:?}",
+            snapshots
+        );
+    }
+
     #[tokio::test]
     async fn test_list_snapshots_ko_unreachable() {
         let aggregator_client =
@@ -450,6 +758,153 @@ mod tests {
         assert!(local_file_path.is_err());
     }
 
+    /// Serve the chunks of the given content and their manifest, the chunks already downloaded
+    /// are removed
+    fn serve_chunked_snapshot<'a>(
+        server: &'a MockServer,
+        network: &str,
+        digest: &str,
+        content: &[u8],
+    ) -> (SnapshotManifest, Vec<httpmock::Mock<'a>>) {
+        let chunks: Vec<&[u8]> = content.chunks(1024).collect();
+        let manifest = SnapshotManifest::new(
+            digest.to_string(),
+            CompressionAlgorithm::Gzip,
+            chunks
+                .iter()
+                .map(|chunk| {
+                    SnapshotChunk::new(SnapshotChunk::compute_hash(chunk), chunk.len() as u64)
+                })
+                .collect(),
+        );
+        let mocks = manifest
+            .chunks
+            .iter()
+            .zip(chunks)
+            .map(|(chunk, content)| {
+                let chunk_path = chunks_directory_path(network).unwrap().join(&chunk.hash);
+                if chunk_path.exists() {
+                    fs::remove_file(chunk_path).unwrap();
+                }
+                server.mock(|when, then| {
+                    when.path(format!("/chunks/{}", chunk.hash));
+                    then.status(200).body(content);
+                })
+            })
+            .collect();
+
+        (manifest, mocks)
+    }
+
+    #[tokio::test]
+    async fn get_snapshot_manifest_ok() {
+        let (server, config) = setup_test();
+        let (manifest, _) = serve_chunked_snapshot(
+            &server,
+            &config.network,
+            "digest_get_snapshot_manifest_ok",
+            b"content",
+        );
+        let manifest_expected = manifest.clone();
+        let _manifest_mock = server.mock(|when, then| {
+            when.path("/chunks/digest.manifest.json");
+            then.status(200).body(json!(manifest).to_string());
+        });
+        let aggregator_client =
+            AggregatorHTTPClient::new(config.network, config.aggregator_endpoint);
+
+        let manifest = aggregator_client
+            .get_snapshot_manifest(&server.url("/chunks/digest.manifest.json"))
+            .await;
+        assert_eq!(manifest_expected, manifest.unwrap());
+    }
+
+    #[tokio::test]
+    async fn download_snapshot_chunks_ok_only_downloads_missing_chunks() {
+        let digest = "digest_download_snapshot_chunks_ok";
+        let (server, config) = setup_test();
+        let data_expected = "download_snapshot_chunks_ok".repeat(1024);
+        let (manifest, chunk_mocks) =
+            serve_chunked_snapshot(&server, &config.network, digest, data_expected.as_bytes());
+        ensure_snapshot_dir_does_not_exist(digest, &config.network);
+        let aggregator_client =
+            AggregatorHTTPClient::new(config.network, config.aggregator_endpoint);
+        let manifest_location = server.url("/chunks/digest.manifest.json");
+        // the first chunk is left behind by an interrupted download
+        let chunks_path = chunks_directory_path(&config.network).unwrap();
+        fs::create_dir_all(&chunks_path).unwrap();
+        fs::write(
+            chunks_path.join(&manifest.chunks[0].hash),
+            &data_expected.as_bytes()[..1024],
+        )
+        .unwrap();
+
+        let local_file_path = aggregator_client
+            .download_snapshot_chunks(&manifest_location, &manifest)
+            .await
+            .expect("unexpected error");
+        let data_downloaded = fs::read_to_string(local_file_path).unwrap();
+        assert_eq!(data_expected, data_downloaded);
+        for (i, chunk_mock) in chunk_mocks.into_iter().enumerate() {
+            chunk_mock.assert_hits(usize::from(i != 0));
+        }
+    }
+
+    #[tokio::test]
+    async fn download_snapshot_chunks_ok_removes_chunks_once_assembled() {
+        let digest = "digest_download_snapshot_chunks_ok_removes_chunks";
+        let (server, config) = setup_test();
+        let data_expected = "download_snapshot_chunks_ok_removes_chunks".repeat(1024);
+        let (manifest, _chunk_mocks) =
+            serve_chunked_snapshot(&server, &config.network, digest, data_expected.as_bytes());
+        ensure_snapshot_dir_does_not_exist(digest, &config.network);
+        let network = config.network.clone();
+        let aggregator_client =
+            AggregatorHTTPClient::new(config.network, config.aggregator_endpoint);
+
+        aggregator_client
+            .download_snapshot_chunks(&server.url("/chunks/digest.manifest.json"), &manifest)
+            .await
+            .expect("unexpected error");
+
+        let chunks_path = chunks_directory_path(&network).unwrap();
+        for chunk in &manifest.chunks {
+            assert!(
+                !chunks_path.join(&chunk.hash).exists(),
+                "the chunk {} should have been removed",
+                chunk.hash
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn download_snapshot_chunks_ko_invalid_chunk() {
+        let digest = "digest_download_snapshot_chunks_ko_invalid_chunk";
+        let (server, config) = setup_test();
+        let (mut manifest, _) = serve_chunked_snapshot(
+            &server,
+            &config.network,
+            digest,
+            b"download_snapshot_chunks_ko_invalid_chunk",
+        );
+        manifest.chunks[0].hash = SnapshotChunk::compute_hash(b"another content");
+        let _chunk_mock = server.mock(|when, then| {
+            when.path(format!("/chunks/{}", manifest.chunks[0].hash));
+            then.status(200).body("tampered content");
+        });
+        let aggregator_client =
+            AggregatorHTTPClient::new(config.network, config.aggregator_endpoint);
+
+        let result = aggregator_client
+            .download_snapshot_chunks(&server.url("/chunks/digest.manifest.json"), &manifest)
+            .await;
+        assert!(
+            matches!(result, Err(AggregatorHandlerError::InvalidSnapshotChunk(_))),
+            "unexpected result: {:?}",
+            result
+        );
+    }
+
     #[tokio::test]
     async fn unpack_snapshot_ok() {
         let digest = "digest_unpack_snapshot_ok";
@@ -457,7 +912,12 @@ mod tests {
         let data_expected = "1234567890".repeat(1024);
 
         ensure_snapshot_dir_does_not_exist(digest, &config.network);
-        build_dummy_snapshot(digest, &config.network, &data_expected);
+        build_dummy_snapshot(
+            digest,
+            &config.network,
+            &data_expected,
+            CompressionAlgorithm::Gzip,
+        );
 
         let aggregator_client =
             AggregatorHTTPClient::new(config.network, config.aggregator_endpoint);
@@ -465,6 +925,96 @@ mod tests {
         local_dir_path.expect("unexpected error");
     }
 
+    #[tokio::test]
+    async fn unpack_zstandard_snapshot_ok() {
+        let digest = "digest_unpack_zstandard_snapshot_ok";
+        let (_, config) = setup_test();
+        let data_expected = "1234567890".repeat(1024);
+
+        ensure_snapshot_dir_does_not_exist(digest, &config.network);
+        build_dummy_snapshot(
+            digest,
+            &config.network,
+            &data_expected,
+            CompressionAlgorithm::Zstandard,
+        );
+
+        let aggregator_client =
+            AggregatorHTTPClient::new(config.network, config.aggregator_endpoint);
+        let local_dir_path = aggregator_client
+            .unpack_snapshot(digest)
+            .await
+            .expect("unexpected error");
+        let data_unpacked =
+            fs::read_to_string(path::Path::new(&local_dir_path).join("data.txt")).unwrap();
+        assert_eq!(data_expected, data_unpacked);
+    }
+
+    #[tokio::test]
+    async fn unpack_snapshot_ko_unsupported_format() {
+        let digest = "digest_unpack_snapshot_ko_unsupported_format";
+        let (_, config) = setup_test();
+        ensure_snapshot_dir_does_not_exist(digest, &config.network);
+        let archive_file_path = archive_file_path(digest, &config.network).unwrap();
+        fs::create_dir_all(archive_file_path.parent().unwrap()).unwrap();
+        fs::write(&archive_file_path, "not an archive").unwrap();
+
+        let aggregator_client =
+            AggregatorHTTPClient::new(config.network, config.aggregator_endpoint);
+        let result = aggregator_client.unpack_snapshot(digest).await;
+        assert!(
+            matches!(
+                result,
+                Err(AggregatorHandlerError::UnsupportedArchiveFormat(_))
+            ),
+            "unexpected result: {:?}",
+            result
+        );
+    }
+
+    #[tokio::test]
+    async fn unpack_snapshot_into_another_snapshot_ok() {
+        let base_digest = "digest_unpack_snapshot_into_base";
+        let digest = "digest_unpack_snapshot_into";
+        let (_, config) = setup_test();
+        let data_expected = "base".repeat(1024);
+        ensure_snapshot_dir_does_not_exist(base_digest, &config.network);
+        ensure_snapshot_dir_does_not_exist(digest, &config.network);
+        build_dummy_snapshot(
+            base_digest,
+            &config.network,
+            &data_expected,
+            CompressionAlgorithm::Gzip,
+        );
+
+        let aggregator_client =
+            AggregatorHTTPClient::new(config.network.clone(), config.aggregator_endpoint);
+        assert!(aggregator_client
+            .is_snapshot_downloaded(base_digest)
+            .await
+            .unwrap());
+        assert!(!aggregator_client
+            .is_snapshot_downloaded(digest)
+            .await
+            .unwrap());
+        let local_dir_path = aggregator_client
+            .unpack_snapshot_into(base_digest, digest)
+            .await
+            .expect("unexpected error");
+
+        assert_eq!(
+            archive_file_path(digest, &config.network)
+                .unwrap()
+                .parent()
+                .unwrap()
+                .join("db"),
+            PathBuf::from(&local_dir_path)
+        );
+        let data_unpacked =
+            fs::read_to_string(path::Path::new(&local_dir_path).join("data.txt")).unwrap();
+        assert_eq!(data_expected, data_unpacked);
+    }
+
     #[tokio::test]
     async fn unpack_snapshot_ko_noarchive() {
         let digest = "digest_unpack_snapshot_ko_noarchive";
@@ -538,4 +1088,45 @@ mod tests {
             .await;
         assert!(certificate.is_err());
     }
+
+    #[tokio::test]
+    async fn get_certificate_bundle_ok() {
+        let certificate_hash = "certificate-hash-123";
+        let (server, config) = setup_test();
+        let bundle_expected = CertificateChainBundle::new(
+            "genesis-vkey-fingerprint".to_string(),
+            vec![fake_data::certificate(certificate_hash.to_string())],
+        );
+        let _bundle_mock = server.mock(|when, then| {
+            when.path(format!("/certificate/{}/bundle", certificate_hash))
+                .query_param("checkpoint", "checkpoint-hash-456");
+            then.status(200).body(json!(bundle_expected).to_string());
+        });
+        let aggregator_client =
+            AggregatorHTTPClient::new(config.network, config.aggregator_endpoint);
+        let bundle = aggregator_client
+            .get_certificate_bundle(certificate_hash, Some("checkpoint-hash-456"))
+            .await;
+        bundle.as_ref().expect("unexpected error");
+        assert_eq!(bundle.unwrap(), bundle_expected);
+    }
+
+    #[tokio::test]
+    async fn get_certificate_bundle_ko_404() {
+        let certificate_hash = "certificate-hash-123";
+        let (server, config) = setup_test();
+        let _bundle_mock = server.mock(|when, then| {
+            when.path(format!("/certificate/{}/bundle", certificate_hash));
+            then.status(404);
+        });
+        let aggregator_client =
+            AggregatorHTTPClient::new(config.network, config.aggregator_endpoint);
+        let bundle = aggregator_client
+            .get_certificate_bundle(certificate_hash, None)
+            .await;
+        assert!(matches!(
+            bundle,
+            Err(AggregatorHandlerError::RemoteServerLogical(_))
+        ));
+    }
 }
@@ -0,0 +1,64 @@
+use std::{error::Error, fs, path::PathBuf, sync::Arc};
+
+use clap::Parser;
+use config::{builder::DefaultState, ConfigBuilder};
+use slog_scope::debug;
+
+use super::build_certificate_checkpoint_store;
+use crate::{AggregatorHTTPClient, Config, Runtime};
+
+/// Export the certificate chain of a snapshot in a bundle, to verify it offline.
+#[derive(Parser, Debug, Clone)]
+pub struct ExportBundleCommand {
+    /// Stop the exported certificate chain at the last certificate verified on this host
+    /// instead of the genesis certificate. The bundle can then only be verified by a host that
+    /// trusts the same checkpoint.
+    #[clap(long)]
+    up_to_checkpoint: bool,
+
+    /// File where the bundle is written.
+    #[clap(long, default_value = "certificate_chain_bundle.json")]
+    output: PathBuf,
+
+    /// Digest of the snapshot. Use the `list` command to get that information.
+    digest: String,
+}
+
+impl ExportBundleCommand {
+    /// execute export bundle command
+    pub async fn execute(
+        &self,
+        config_builder: ConfigBuilder<DefaultState>,
+    ) -> Result<(), Box<dyn Error>> {
+        debug!("Export certificate chain bundle");
+        let config: Config = config_builder
+            .build()
+            .map_err(|e| format!("configuration build error: {}", e))?
+            .try_deserialize()
+            .map_err(|e| format!("configuration deserialize error: {}", e))?;
+        debug!("{:?}", config);
+        let runtime = Runtime::new(config.network.clone()).with_certificate_checkpoint_store(
+            Arc::new(build_certificate_checkpoint_store(&config.network)?),
+        );
+        let aggregator_handler =
+            AggregatorHTTPClient::new(config.network.clone(), config.aggregator_endpoint);
+        let genesis_verifier = config.build_genesis_verifier()?;
+        let certificate_bundle = runtime
+            .export_certificate_bundle(
+                Arc::new(aggregator_handler),
+                &genesis_verifier,
+                &self.digest,
+                self.up_to_checkpoint,
+            )
+            .await?;
+        fs::write(&self.output, serde_json::to_string(&certificate_bundle)?)?;
+        println!(
+            "Certificate chain bundle of snapshot {} with {} certificate(s) exported to {}",
+            &self.digest,
+            certificate_bundle.certificates.len(),
+            self.output.display()
+        );
+
+        Ok(())
+    }
+}
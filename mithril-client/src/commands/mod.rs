@@ -1,12 +1,36 @@
 //! Command module
 //! This module holds the subcommands that can be used from the CLI.
 //!
+use std::{env, error::Error, fs};
+
+use mithril_common::{
+    certificate_chain::CertificateCheckpointStore, store::adapter::SQLiteAdapter,
+};
+
 mod download;
+mod export_bundle;
 mod list;
 mod restore;
 mod show;
+mod verify;
 
 pub use download::DownloadCommand;
+pub use export_bundle::ExportBundleCommand;
 pub use list::ListCommand;
 pub use restore::RestoreCommand;
 pub use show::ShowCommand;
+pub use verify::VerifyCommand;
+
+/// Open the store of the trusted checkpoint of the certificate chain of a network
+fn build_certificate_checkpoint_store(
+    network: &str,
+) -> Result<CertificateCheckpointStore, Box<dyn Error>> {
+    let checkpoint_store_directory = env::current_dir()?.join("data").join(network);
+    fs::create_dir_all(&checkpoint_store_directory)?;
+    let adapter = SQLiteAdapter::new(
+        "certificate_checkpoint",
+        Some(checkpoint_store_directory.join("checkpoint.sqlite3")),
+    )?;
+
+    Ok(CertificateCheckpointStore::new(Box::new(adapter)))
+}
@@ -1,14 +1,18 @@
-use std::{error::Error, path::Path, sync::Arc};
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use clap::Parser;
 use config::{builder::DefaultState, ConfigBuilder};
 use mithril_common::{
-    certificate_chain::MithrilCertificateVerifier,
-    crypto_helper::{key_decode_hex, ProtocolGenesisVerifier},
-    digesters::CardanoImmutableDigester,
+    certificate_chain::MithrilCertificateVerifier, digesters::CardanoImmutableDigester,
 };
 use slog_scope::debug;
 
+use super::build_certificate_checkpoint_store;
 use crate::{AggregatorHTTPClient, AggregatorHandler, Config, Runtime};
 
 /// Check a downloaded snapshot and restore it if the signature is OK.
@@ -18,6 +22,16 @@ pub struct RestoreCommand {
     #[clap(long)]
     json: bool,
 
+    /// Verify the certificate chain back to the genesis certificate instead of stopping at
+    /// the last certificate verified on this host.
+    #[clap(long)]
+    full_verification: bool,
+
+    /// Verify the certificate chain with a bundle exported by the `export-bundle` command
+    /// instead of querying the aggregator. Only full snapshots can be restored this way.
+    #[clap(long)]
+    bundle: Option<PathBuf>,
+
     /// Digest of the snapshot to download. Use the `list` command to get that information.
     digest: String,
 }
@@ -35,13 +49,19 @@ impl RestoreCommand {
             .try_deserialize()
             .map_err(|e| format!("configuration deserialize error: {}", e))?;
         debug!("{:?}", config);
-        let mut runtime = Runtime::new(config.network.clone());
+        let mut runtime = Runtime::new(config.network.clone())
+            .with_certificate_checkpoint_store(Arc::new(build_certificate_checkpoint_store(
+                &config.network,
+            )?))
+            .with_full_verification(self.full_verification);
+        if let Some(bundle) = &self.bundle {
+            let certificate_bundle = serde_json::from_str(&fs::read_to_string(bundle)?)?;
+            runtime = runtime.with_certificate_bundle(certificate_bundle);
+        }
         let aggregator_handler =
             AggregatorHTTPClient::new(config.network.clone(), config.aggregator_endpoint);
         let certificate_verifier = Box::new(MithrilCertificateVerifier::new(slog_scope::logger()));
-        let genesis_verification_key = key_decode_hex(&config.genesis_verification_key)?;
-        let genesis_verifier =
-            ProtocolGenesisVerifier::from_verification_key(genesis_verification_key);
+        let genesis_verifier = config.build_genesis_verifier()?;
         let unpacked_path = aggregator_handler.unpack_snapshot(&self.digest).await?;
         let digester = Box::new(CardanoImmutableDigester::new(
             Path::new(&unpacked_path).into(),
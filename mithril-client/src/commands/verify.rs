@@ -0,0 +1,81 @@
+use std::{error::Error, fs, path::PathBuf, sync::Arc};
+
+use clap::Parser;
+use config::{builder::DefaultState, ConfigBuilder};
+use mithril_common::certificate_chain::MithrilCertificateVerifier;
+use slog_scope::debug;
+
+use super::build_certificate_checkpoint_store;
+use crate::{AggregatorHTTPClient, Config, Runtime};
+
+/// Verify the certificate chain of a snapshot and report the outcome of every check.
+#[derive(Parser, Debug, Clone)]
+pub struct VerifyCommand {
+    /// Enable JSON output.
+    #[clap(long)]
+    json: bool,
+
+    /// Verify the certificate chain back to the genesis certificate instead of stopping at
+    /// the last certificate verified on this host.
+    #[clap(long)]
+    full_verification: bool,
+
+    /// Verify the certificate chain with a bundle exported by the `export-bundle` command
+    /// instead of querying the aggregator.
+    #[clap(long)]
+    bundle: Option<PathBuf>,
+
+    /// Digest of the snapshot. Use the `list` command to get that information.
+    digest: String,
+}
+
+impl VerifyCommand {
+    /// execute verify command
+    pub async fn execute(
+        &self,
+        config_builder: ConfigBuilder<DefaultState>,
+    ) -> Result<(), Box<dyn Error>> {
+        debug!("Verify certificate chain");
+        let config: Config = config_builder
+            .build()
+            .map_err(|e| format!("configuration build error: {}", e))?
+            .try_deserialize()
+            .map_err(|e| format!("configuration deserialize error: {}", e))?;
+        debug!("{:?}", config);
+        let mut runtime = Runtime::new(config.network.clone())
+            .with_certificate_checkpoint_store(Arc::new(build_certificate_checkpoint_store(
+                &config.network,
+            )?))
+            .with_full_verification(self.full_verification);
+        if let Some(bundle) = &self.bundle {
+            let certificate_bundle = serde_json::from_str(&fs::read_to_string(bundle)?)?;
+            runtime = runtime.with_certificate_bundle(certificate_bundle);
+        }
+        let aggregator_handler =
+            AggregatorHTTPClient::new(config.network.clone(), config.aggregator_endpoint);
+        let certificate_verifier = Box::new(MithrilCertificateVerifier::new(slog_scope::logger()));
+        let genesis_verifier = config.build_genesis_verifier()?;
+        let report = runtime
+            .verify_certificate_chain_report(
+                Arc::new(aggregator_handler),
+                certificate_verifier,
+                &genesis_verifier,
+                &self.digest,
+            )
+            .await?;
+        if self.json {
+            println!("{}", serde_json::to_string(&report)?);
+        } else {
+            print!("{}", report);
+        }
+        if !report.valid {
+            return Err(format!(
+                "the certificate chain of snapshot {} is invalid",
+                &self.digest
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+}
@@ -1,6 +1,11 @@
 use cli_table::{format::Justify, Table};
+use mithril_common::crypto_helper::{
+    key_decode_hex, ProtocolGenesisPolicy, ProtocolGenesisVerifier,
+};
 use mithril_common::entities::{Epoch, HexEncodedGenesisVerificationKey};
 use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::path::PathBuf;
 
 /// Client configuration
 #[derive(Table, Debug, Clone, Serialize, Deserialize)]
@@ -15,6 +20,25 @@ pub struct Config {
 
     /// Genesis verification key
     pub genesis_verification_key: HexEncodedGenesisVerificationKey,
+
+    /// Genesis policy file, a t-of-n policy that overrides the genesis verification key
+    #[table(skip)]
+    pub genesis_policy_path: Option<PathBuf>,
+}
+
+impl Config {
+    /// Create the genesis verifier from the genesis policy file if set, else from the
+    /// genesis verification key
+    pub fn build_genesis_verifier(&self) -> Result<ProtocolGenesisVerifier, Box<dyn Error>> {
+        match &self.genesis_policy_path {
+            Some(genesis_policy_path) => Ok(ProtocolGenesisVerifier::from_policy(
+                &ProtocolGenesisPolicy::from_file(genesis_policy_path)?,
+            )?),
+            None => Ok(ProtocolGenesisVerifier::from_verification_key(
+                key_decode_hex(&self.genesis_verification_key)?,
+            )),
+        }
+    }
 }
 
 /// SnapshotListItem represents a snapshot list item from an aggregator
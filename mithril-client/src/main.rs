@@ -9,7 +9,9 @@ use std::error::Error;
 use std::path::PathBuf;
 use std::sync::Arc;
 
-use mithril_client::commands::{DownloadCommand, ListCommand, RestoreCommand, ShowCommand};
+use mithril_client::commands::{
+    DownloadCommand, ExportBundleCommand, ListCommand, RestoreCommand, ShowCommand, VerifyCommand,
+};
 
 /// CLI args
 #[derive(Parser, Debug, Clone)]
@@ -116,6 +118,14 @@ enum Commands {
     /// Restore a snapshot
     #[clap(arg_required_else_help = true)]
     Restore(RestoreCommand),
+
+    /// Export the certificate chain of a snapshot to verify it offline
+    #[clap(arg_required_else_help = true)]
+    ExportBundle(ExportBundleCommand),
+
+    /// Verify the certificate chain of a snapshot and report the outcome of every check
+    #[clap(arg_required_else_help = true)]
+    Verify(VerifyCommand),
 }
 
 impl Commands {
@@ -128,6 +138,8 @@ impl Commands {
             Self::Download(cmd) => cmd.execute(config_builder).await,
             Self::Show(cmd) => cmd.execute(config_builder).await,
             Self::Restore(cmd) => cmd.execute(config_builder).await,
+            Self::ExportBundle(cmd) => cmd.execute(config_builder).await,
+            Self::Verify(cmd) => cmd.execute(config_builder).await,
         }
     }
 }
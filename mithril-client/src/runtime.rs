@@ -1,4 +1,5 @@
 use slog_scope::debug;
+use std::collections::HashSet;
 use std::str;
 use std::sync::Arc;
 use thiserror::Error;
@@ -7,11 +8,14 @@ use crate::aggregator::{AggregatorHandler, AggregatorHandlerError};
 use crate::entities::*;
 
 use mithril_common::certificate_chain::{
+    CertificateBundleRetriever, CertificateChainBundle, CertificateChainBundleError,
+    CertificateChainVerificationReport, CertificateCheckpointStore, CertificateRetriever,
     CertificateRetrieverError, CertificateVerifier, CertificateVerifierError,
 };
 use mithril_common::crypto_helper::ProtocolGenesisVerifier;
 use mithril_common::digesters::{ImmutableDigester, ImmutableDigesterError};
-use mithril_common::entities::{ProtocolMessagePartKey, Snapshot};
+use mithril_common::entities::{Certificate, ProtocolMessagePartKey, Snapshot};
+use mithril_common::store::StoreError;
 
 /// [Runtime] related errors.
 #[derive(Error, Debug)]
@@ -47,18 +51,71 @@ pub enum RuntimeError {
     /// Error raised when verification fails.
     #[error("verification error: '{0}'")]
     Protocol(#[from] CertificateVerifierError),
+
+    /// Error raised when the trusted checkpoint can't be read or written.
+    #[error("certificate checkpoint store error: '{0}'")]
+    CheckpointStore(#[from] StoreError),
+
+    /// Error raised when a certificate chain bundle is invalid.
+    #[error("certificate chain bundle error: '{0}'")]
+    CertificateChainBundle(#[from] CertificateChainBundleError),
+
+    /// Error raised when the manifest of a chunked snapshot doesn't match the snapshot or its
+    /// certificate.
+    #[error("snapshot manifest error: '{0}'")]
+    InvalidSnapshotManifest(String),
 }
 
 /// Mithril client runtime
 pub struct Runtime {
     /// Cardano network
     pub network: String,
+
+    /// Store of the trusted checkpoint of the certificate chain
+    certificate_checkpoint_store: Option<Arc<CertificateCheckpointStore>>,
+
+    /// Verify the certificate chain back to the genesis certificate even if a
+    /// trusted checkpoint is recorded
+    full_verification: bool,
+
+    /// Certificate chain used instead of querying the aggregator
+    certificate_bundle: Option<CertificateChainBundle>,
 }
 
 impl Runtime {
     /// Runtime factory
     pub fn new(network: String) -> Self {
-        Self { network }
+        Self {
+            network,
+            certificate_checkpoint_store: None,
+            full_verification: false,
+            certificate_bundle: None,
+        }
+    }
+
+    /// Set the store of the trusted checkpoint: the certificate chain is only
+    /// verified up to the checkpoint, which is updated after each successful
+    /// verification.
+    pub fn with_certificate_checkpoint_store(
+        mut self,
+        certificate_checkpoint_store: Arc<CertificateCheckpointStore>,
+    ) -> Self {
+        self.certificate_checkpoint_store = Some(certificate_checkpoint_store);
+        self
+    }
+
+    /// Verify the certificate chain back to the genesis certificate, ignoring
+    /// the trusted checkpoint.
+    pub fn with_full_verification(mut self, full_verification: bool) -> Self {
+        self.full_verification = full_verification;
+        self
+    }
+
+    /// Verify the snapshots with the certificate chain of a bundle instead of
+    /// querying the aggregator, so that it can be done offline.
+    pub fn with_certificate_bundle(mut self, certificate_bundle: CertificateChainBundle) -> Self {
+        self.certificate_bundle = Some(certificate_bundle);
+        self
     }
 
     /// List snapshots
@@ -88,6 +145,9 @@ impl Runtime {
     }
 
     /// Download a snapshot by digest
+    ///
+    /// If it is an incremental snapshot, the snapshots it is based on that are not downloaded
+    /// yet are downloaded too.
     pub async fn download_snapshot<'a>(
         &self,
         aggregator_handler: Arc<dyn AggregatorHandler + 'a>,
@@ -96,18 +156,131 @@ impl Runtime {
     ) -> Result<(String, String), RuntimeError> {
         debug!("Download snapshot {}", digest);
         let snapshot = aggregator_handler.get_snapshot_details(digest).await?;
-        let from = snapshot
-            .locations
-            .get((location_index - 1) as usize)
-            .ok_or_else(|| RuntimeError::InvalidInput("invalid location index".to_string()))?
-            .to_owned();
-        match aggregator_handler.download_snapshot(digest, &from).await {
-            Ok(to) => Ok((from, to)),
-            Err(err) => Err(RuntimeError::AggregatorHandler(err)),
+        let from = get_location(&snapshot, location_index)?;
+        let mut visited_digests = HashSet::from([digest.to_string()]);
+        let mut base_digest = snapshot.base_digest.clone();
+        while let Some(snapshot_digest) = base_digest {
+            check_not_visited(&mut visited_digests, &snapshot_digest)?;
+            let base_snapshot = aggregator_handler
+                .get_snapshot_details(&snapshot_digest)
+                .await?;
+            if !aggregator_handler
+                .is_snapshot_downloaded(&snapshot_digest)
+                .await?
+            {
+                debug!("Download base snapshot {}", snapshot_digest);
+                self.download_snapshot_archive(
+                    aggregator_handler.clone(),
+                    &base_snapshot,
+                    &get_location(&base_snapshot, location_index)?,
+                )
+                .await?;
+            }
+            base_digest = base_snapshot.base_digest;
+        }
+        let to = self
+            .download_snapshot_archive(aggregator_handler, &snapshot, &from)
+            .await?;
+
+        Ok((from, to))
+    }
+
+    /// Download the archive of a snapshot from the given location
+    ///
+    /// If the snapshot is stored as chunks, the location is the one of its manifest: the
+    /// manifest is checked against the snapshot and its hash against the one signed by its
+    /// certificate before its chunks are downloaded.
+    async fn download_snapshot_archive<'a>(
+        &self,
+        aggregator_handler: Arc<dyn AggregatorHandler + 'a>,
+        snapshot: &Snapshot,
+        location: &str,
+    ) -> Result<String, RuntimeError> {
+        let manifest_hash = match &snapshot.manifest_hash {
+            Some(manifest_hash) => manifest_hash,
+            None => {
+                return Ok(aggregator_handler
+                    .download_snapshot(&snapshot.digest, location)
+                    .await?)
+            }
+        };
+        let manifest = aggregator_handler.get_snapshot_manifest(location).await?;
+        if &manifest.compute_hash() != manifest_hash {
+            return Err(RuntimeError::InvalidSnapshotManifest(format!(
+                "the hash of the manifest of the snapshot {} doesn't match",
+                snapshot.digest
+            )));
+        }
+        if manifest.digest != snapshot.digest {
+            return Err(RuntimeError::InvalidSnapshotManifest(format!(
+                "the manifest is the one of the snapshot {}, expected {}",
+                manifest.digest, snapshot.digest
+            )));
+        }
+        let certificate = aggregator_handler
+            .get_certificate_details(&snapshot.certificate_hash)
+            .await?;
+        if certificate.signed_message != certificate.protocol_message.compute_hash() {
+            return Err(RuntimeError::InvalidSnapshotManifest(format!(
+                "the protocol message of the certificate of the snapshot {} doesn't match its signed message",
+                snapshot.digest
+            )));
+        }
+        if certificate
+            .protocol_message
+            .get_message_part(&ProtocolMessagePartKey::SnapshotManifestHash)
+            != Some(manifest_hash)
+        {
+            return Err(RuntimeError::InvalidSnapshotManifest(format!(
+                "the manifest of the snapshot {} is not the one signed by its certificate",
+                snapshot.digest
+            )));
         }
+
+        Ok(aggregator_handler
+            .download_snapshot_chunks(location, &manifest)
+            .await?)
+    }
+
+    /// Export the certificate chain bundle of a snapshot by digest
+    ///
+    /// The bundle holds the certificate chain back to the genesis certificate, unless
+    /// `up_to_checkpoint` is set: it then stops at the trusted checkpoint of this host and can
+    /// only be verified by a host that trusts the same checkpoint.
+    ///
+    /// A bundle only certifies its own snapshot, so it can't be exported for an incremental
+    /// snapshot whose base snapshots would have to be verified too.
+    pub async fn export_certificate_bundle<'a>(
+        &self,
+        aggregator_handler: Arc<dyn AggregatorHandler + 'a>,
+        genesis_verifier: &ProtocolGenesisVerifier,
+        digest: &str,
+        up_to_checkpoint: bool,
+    ) -> Result<CertificateChainBundle, RuntimeError> {
+        debug!("Export certificate chain bundle of snapshot {}", digest);
+        let snapshot = aggregator_handler.get_snapshot_details(digest).await?;
+        if let Some(base_digest) = &snapshot.base_digest {
+            return Err(RuntimeError::InvalidInput(format!(
+                "the snapshot {} is an incremental snapshot based on the snapshot {}, it can't be restored offline with a certificate chain bundle",
+                digest, base_digest
+            )));
+        }
+        let trusted_checkpoint = match up_to_checkpoint {
+            true => self.get_trusted_checkpoint(genesis_verifier).await?,
+            false => None,
+        };
+        let certificate_bundle = aggregator_handler
+            .get_certificate_bundle(&snapshot.certificate_hash, trusted_checkpoint.as_deref())
+            .await?;
+        certificate_bundle.check_genesis_verifier(genesis_verifier)?;
+
+        Ok(certificate_bundle)
     }
 
     /// Restore a snapshot by digest
+    ///
+    /// If it is an incremental snapshot, the snapshots it is based on are restored first,
+    /// from the full snapshot, and the certificate of each of them is verified.
     pub async fn restore_snapshot<'a>(
         &mut self,
         aggregator_handler: Arc<dyn AggregatorHandler + 'a>,
@@ -117,11 +290,57 @@ impl Runtime {
         digest: &str,
     ) -> Result<String, RuntimeError> {
         debug!("Restore snapshot {}", digest);
-        let snapshot = aggregator_handler.get_snapshot_details(digest).await?;
-        let certificate = aggregator_handler
-            .get_certificate_details(&snapshot.certificate_hash)
+        let mut snapshots_to_restore = vec![];
+        let mut visited_digests = HashSet::new();
+        let mut next_digest = Some(digest.to_string());
+        while let Some(snapshot_digest) = next_digest {
+            check_not_visited(&mut visited_digests, &snapshot_digest)?;
+            let (certificate, certificate_retriever, base_digest) = self
+                .get_certificate_to_verify(
+                    aggregator_handler.clone(),
+                    &genesis_verifier,
+                    &snapshot_digest,
+                )
+                .await?;
+            snapshots_to_restore.push((snapshot_digest, certificate, certificate_retriever));
+            next_digest = base_digest;
+        }
+
+        let mut unpacked_path = String::new();
+        for (snapshot_digest, certificate, certificate_retriever) in
+            snapshots_to_restore.into_iter().rev()
+        {
+            unpacked_path = if snapshot_digest == digest {
+                aggregator_handler.unpack_snapshot(digest).await?
+            } else {
+                debug!("Restore base snapshot {}", snapshot_digest);
+                aggregator_handler
+                    .unpack_snapshot_into(&snapshot_digest, digest)
+                    .await?
+            };
+            self.verify_restored_snapshot(
+                digester.as_ref(),
+                certificate_verifier.as_ref(),
+                &genesis_verifier,
+                certificate,
+                certificate_retriever,
+            )
             .await?;
-        let unpacked_path = aggregator_handler.unpack_snapshot(digest).await?;
+        }
+
+        Ok(unpacked_path)
+    }
+
+    /// Verify the digest of the restored immutable files then the certificate chain of the
+    /// given certificate, and update the trusted checkpoint.
+    async fn verify_restored_snapshot(
+        &self,
+        digester: &dyn ImmutableDigester,
+        certificate_verifier: &dyn CertificateVerifier,
+        genesis_verifier: &ProtocolGenesisVerifier,
+        certificate: Certificate,
+        certificate_retriever: Arc<dyn CertificateRetriever>,
+    ) -> Result<(), RuntimeError> {
         let unpacked_snapshot_digest = digester.compute_digest(&certificate.beacon).await?;
         let mut protocol_message = certificate.protocol_message.clone();
         protocol_message.set_message_part(
@@ -131,17 +350,148 @@ impl Runtime {
         if protocol_message.compute_hash() != certificate.signed_message {
             return Err(RuntimeError::DigestDoesntMatch(unpacked_snapshot_digest));
         }
-        certificate_verifier
-            .verify_certificate_chain(
+        let certificate_hash = certificate.hash.clone();
+        let certificate_beacon = certificate.beacon.clone();
+        match self.get_trusted_checkpoint(genesis_verifier).await? {
+            Some(trusted_checkpoint) => {
+                debug!(
+                    "Verify certificate chain up to checkpoint {}",
+                    trusted_checkpoint
+                );
+                certificate_verifier
+                    .verify_certificate_chain_to_checkpoint(
+                        certificate,
+                        certificate_retriever,
+                        genesis_verifier,
+                        &trusted_checkpoint,
+                    )
+                    .await?;
+            }
+            None => {
+                certificate_verifier
+                    .verify_certificate_chain(certificate, certificate_retriever, genesis_verifier)
+                    .await?;
+            }
+        }
+        if let Some(certificate_checkpoint_store) = &self.certificate_checkpoint_store {
+            certificate_checkpoint_store
+                .save_checkpoint(genesis_verifier, &certificate_hash, &certificate_beacon)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Verify the certificate chain of a snapshot by digest and report the
+    /// outcome of every check instead of stopping at the first failure.
+    ///
+    /// The trusted checkpoint is used but never updated.
+    pub async fn verify_certificate_chain_report<'a>(
+        &self,
+        aggregator_handler: Arc<dyn AggregatorHandler + 'a>,
+        certificate_verifier: Box<dyn CertificateVerifier + 'a>,
+        genesis_verifier: &ProtocolGenesisVerifier,
+        digest: &str,
+    ) -> Result<CertificateChainVerificationReport, RuntimeError> {
+        debug!("Verify certificate chain of snapshot {}", digest);
+        let (certificate, certificate_retriever, _) = self
+            .get_certificate_to_verify(aggregator_handler, genesis_verifier, digest)
+            .await?;
+        let trusted_checkpoint = self.get_trusted_checkpoint(genesis_verifier).await?;
+
+        Ok(certificate_verifier
+            .verify_certificate_chain_with_report(
                 certificate,
-                aggregator_handler.as_certificate_retriever(),
-                &genesis_verifier,
+                certificate_retriever,
+                genesis_verifier,
+                trusted_checkpoint.as_deref(),
             )
-            .await?;
-        Ok(unpacked_path.to_owned())
+            .await)
+    }
+
+    /// Return the certificate of a snapshot with the retriever of its certificate chain, and
+    /// the digest of the snapshot it is based on if it is incremental.
+    ///
+    /// A certificate chain bundle only certifies its own snapshot and is only exported for full
+    /// snapshots, so no base snapshot is returned when a bundle is used.
+    async fn get_certificate_to_verify<'a>(
+        &self,
+        aggregator_handler: Arc<dyn AggregatorHandler + 'a>,
+        genesis_verifier: &ProtocolGenesisVerifier,
+        digest: &str,
+    ) -> Result<(Certificate, Arc<dyn CertificateRetriever>, Option<String>), RuntimeError> {
+        match &self.certificate_bundle {
+            Some(certificate_bundle) => {
+                certificate_bundle.check_genesis_verifier(genesis_verifier)?;
+                let certificate = certificate_bundle.get_certificate()?.to_owned();
+                if certificate
+                    .protocol_message
+                    .get_message_part(&ProtocolMessagePartKey::SnapshotDigest)
+                    != Some(&digest.to_string())
+                {
+                    return Err(RuntimeError::InvalidInput(format!(
+                        "the certificate chain bundle does not certify the snapshot {}",
+                        digest
+                    )));
+                }
+                let certificate_retriever: Arc<dyn CertificateRetriever> = Arc::new(
+                    CertificateBundleRetriever::from(certificate_bundle.to_owned()),
+                );
+                Ok((certificate, certificate_retriever, None))
+            }
+            None => {
+                let snapshot = aggregator_handler.get_snapshot_details(digest).await?;
+                let certificate = aggregator_handler
+                    .get_certificate_details(&snapshot.certificate_hash)
+                    .await?;
+                Ok((
+                    certificate,
+                    aggregator_handler.as_certificate_retriever(),
+                    snapshot.base_digest,
+                ))
+            }
+        }
+    }
+
+    async fn get_trusted_checkpoint(
+        &self,
+        genesis_verifier: &ProtocolGenesisVerifier,
+    ) -> Result<Option<String>, RuntimeError> {
+        match &self.certificate_checkpoint_store {
+            Some(certificate_checkpoint_store) if !self.full_verification => {
+                Ok(certificate_checkpoint_store
+                    .get_checkpoint(genesis_verifier)
+                    .await?)
+            }
+            _ => Ok(None),
+        }
     }
 }
 
+/// Return the location of a snapshot archive from its index, starting at 1
+fn get_location(snapshot: &Snapshot, location_index: isize) -> Result<String, RuntimeError> {
+    snapshot
+        .locations
+        .get((location_index - 1) as usize)
+        .ok_or_else(|| RuntimeError::InvalidInput("invalid location index".to_string()))
+        .map(|location| location.to_owned())
+}
+
+/// Record a snapshot visited while walking back the snapshots an incremental snapshot is
+/// based on, fail if it was already visited
+fn check_not_visited(
+    visited_digests: &mut HashSet<String>,
+    digest: &str,
+) -> Result<(), RuntimeError> {
+    if !visited_digests.insert(digest.to_string()) {
+        return Err(RuntimeError::InvalidInput(format!(
+            "the snapshot {} is based on itself",
+            digest
+        )));
+    }
+
+    Ok(())
+}
+
 /// Convert Snapshot to SnapshotListItem routine
 pub(crate) fn convert_to_list_item(snapshot: &Snapshot, network: String) -> SnapshotListItem {
     SnapshotListItem::new(
@@ -167,6 +517,18 @@ pub fn convert_to_field_items(snapshot: &Snapshot, network: String) -> Vec<Snaps
         SnapshotFieldItem::new("Digest".to_string(), snapshot.digest.to_string()),
         SnapshotFieldItem::new("Size".to_string(), format!("{}", snapshot.size)),
     ];
+    if let Some(base_digest) = &snapshot.base_digest {
+        field_items.push(SnapshotFieldItem::new(
+            "Base Digest".to_string(),
+            base_digest.to_string(),
+        ));
+    }
+    if let Some(manifest_hash) = &snapshot.manifest_hash {
+        field_items.push(SnapshotFieldItem::new(
+            "Manifest Hash".to_string(),
+            manifest_hash.to_string(),
+        ));
+    }
     for (idx, location) in snapshot.locations.iter().enumerate() {
         field_items.push(SnapshotFieldItem::new(
             format!("Location {}", idx + 1),
@@ -186,15 +548,19 @@ mod tests {
     use async_trait::async_trait;
 
     use mithril_common::crypto_helper::{ProtocolGenesisSigner, ProtocolGenesisVerifier};
-    use mockall::mock;
+    use mockall::{mock, predicate, Sequence};
 
     use crate::aggregator::AggregatorHandlerError;
     use mithril_common::certificate_chain::{
         CertificateRetriever, CertificateRetrieverError, CertificateVerifierError,
     };
     use mithril_common::digesters::{ImmutableDigester, ImmutableDigesterError};
-    use mithril_common::entities::{Beacon, Certificate, ProtocolParameters};
+    use mithril_common::entities::{
+        Beacon, Certificate, CompressionAlgorithm, ProtocolParameters, SnapshotChunk,
+        SnapshotManifest,
+    };
     use mithril_common::fake_data;
+    use mithril_common::store::adapter::MemoryAdapter;
 
     mock! {
         pub DigesterImpl { }
@@ -246,6 +612,22 @@ mod tests {
                 certificate_retriever: Arc<dyn CertificateRetriever>,
                 genesis_verifier: &ProtocolGenesisVerifier,
             ) -> Result<(), CertificateVerifierError>;
+
+            async fn verify_certificate_chain_to_checkpoint(
+                &self,
+                certificate: Certificate,
+                certificate_retriever: Arc<dyn CertificateRetriever>,
+                genesis_verifier: &ProtocolGenesisVerifier,
+                trusted_checkpoint: &str,
+            ) -> Result<(), CertificateVerifierError>;
+
+            async fn verify_certificate_chain_with_report(
+                &self,
+                certificate: Certificate,
+                certificate_retriever: Arc<dyn CertificateRetriever>,
+                genesis_verifier: &ProtocolGenesisVerifier,
+                trusted_checkpoint: Option<&str>,
+            ) -> CertificateChainVerificationReport;
         }
     }
 
@@ -272,8 +654,30 @@ mod tests {
                 location: &str,
             ) -> Result<String, AggregatorHandlerError>;
 
+            async fn is_snapshot_downloaded(&self, digest: &str) -> Result<bool, AggregatorHandlerError>;
+
+            async fn get_snapshot_manifest(&self, location: &str) -> Result<SnapshotManifest, AggregatorHandlerError>;
+
+            async fn download_snapshot_chunks(
+                &self,
+                manifest_location: &str,
+                manifest: &SnapshotManifest,
+            ) -> Result<String, AggregatorHandlerError>;
+
             async fn unpack_snapshot(&self, digest: &str) -> Result<String, AggregatorHandlerError>;
 
+            async fn unpack_snapshot_into(
+                &self,
+                digest: &str,
+                restore_digest: &str,
+            ) -> Result<String, AggregatorHandlerError>;
+
+            async fn get_certificate_bundle(
+                &self,
+                certificate_hash: &str,
+                trusted_checkpoint: Option<&str>,
+            ) -> Result<CertificateChainBundle, AggregatorHandlerError>;
+
             fn as_certificate_retriever(&self) -> Arc<dyn CertificateRetriever>;
         }
     }
@@ -479,6 +883,388 @@ mod tests {
         );
     }
 
+    async fn get_checkpoint_store(checkpoint: Option<&str>) -> Arc<CertificateCheckpointStore> {
+        let genesis_verifier =
+            ProtocolGenesisSigner::create_deterministic_genesis_signer().create_genesis_verifier();
+        let store = Arc::new(CertificateCheckpointStore::new(Box::new(
+            MemoryAdapter::new(None).unwrap(),
+        )));
+        if let Some(checkpoint) = checkpoint {
+            store
+                .save_checkpoint(&genesis_verifier, checkpoint, &fake_data::beacon())
+                .await
+                .unwrap();
+        }
+
+        store
+    }
+
+    #[tokio::test]
+    async fn test_restore_snapshot_ok_from_trusted_checkpoint() {
+        let fake_certificate = fake_data::certificate("cert-hash-123".to_string());
+        let (mut mock_aggregator_handler, mut mock_verifier, mut mock_digester, genesis_verifier) =
+            get_dependencies();
+        let digest_compute = fake_certificate
+            .protocol_message
+            .get_message_part(&ProtocolMessagePartKey::SnapshotDigest)
+            .unwrap()
+            .to_owned();
+        let digest_restore = digest_compute.clone();
+        let fake_snapshot = fake_data::snapshots(1).first().unwrap().to_owned();
+        mock_aggregator_handler
+            .expect_as_certificate_retriever()
+            .return_once(move || Arc::new(MockAggregatorHandlerImpl::new()));
+        mock_aggregator_handler
+            .expect_get_snapshot_details()
+            .return_once(move |_| Ok(fake_snapshot));
+        mock_aggregator_handler
+            .expect_get_certificate_details()
+            .returning(move |_| Ok(fake_certificate.clone()))
+            .times(1);
+        mock_aggregator_handler
+            .expect_unpack_snapshot()
+            .return_once(move |_| Ok("./target-dir".to_string()));
+        mock_verifier
+            .expect_verify_certificate_chain_to_checkpoint()
+            .withf(|_, _, _, trusted_checkpoint| trusted_checkpoint == "cert-hash-checkpoint")
+            .returning(|_, _, _, _| Ok(()))
+            .times(1);
+        mock_digester
+            .expect_compute_digest()
+            .return_once(move |_| Ok(digest_compute));
+        let checkpoint_store = get_checkpoint_store(Some("cert-hash-checkpoint")).await;
+        let mut client = Runtime::new("testnet".to_string())
+            .with_certificate_checkpoint_store(checkpoint_store.clone());
+        let restore = client
+            .restore_snapshot(
+                Arc::new(mock_aggregator_handler),
+                Box::new(mock_digester),
+                Box::new(mock_verifier),
+                genesis_verifier.clone(),
+                &digest_restore,
+            )
+            .await;
+        restore.expect("unexpected error");
+        assert_eq!(
+            Some("cert-hash-123".to_string()),
+            checkpoint_store
+                .get_checkpoint(&genesis_verifier)
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_restore_snapshot_ok_full_verification_ignores_trusted_checkpoint() {
+        let fake_certificate = fake_data::certificate("cert-hash-123".to_string());
+        let (mut mock_aggregator_handler, mut mock_verifier, mut mock_digester, genesis_verifier) =
+            get_dependencies();
+        let digest_compute = fake_certificate
+            .protocol_message
+            .get_message_part(&ProtocolMessagePartKey::SnapshotDigest)
+            .unwrap()
+            .to_owned();
+        let digest_restore = digest_compute.clone();
+        let fake_snapshot = fake_data::snapshots(1).first().unwrap().to_owned();
+        mock_aggregator_handler
+            .expect_as_certificate_retriever()
+            .return_once(move || Arc::new(MockAggregatorHandlerImpl::new()));
+        mock_aggregator_handler
+            .expect_get_snapshot_details()
+            .return_once(move |_| Ok(fake_snapshot));
+        mock_aggregator_handler
+            .expect_get_certificate_details()
+            .returning(move |_| Ok(fake_certificate.clone()))
+            .times(1);
+        mock_aggregator_handler
+            .expect_unpack_snapshot()
+            .return_once(move |_| Ok("./target-dir".to_string()));
+        mock_verifier
+            .expect_verify_certificate_chain()
+            .returning(|_, _, _| Ok(()))
+            .times(1);
+        mock_verifier
+            .expect_verify_certificate_chain_to_checkpoint()
+            .never();
+        mock_digester
+            .expect_compute_digest()
+            .return_once(move |_| Ok(digest_compute));
+        let checkpoint_store = get_checkpoint_store(Some("cert-hash-checkpoint")).await;
+        let mut client = Runtime::new("testnet".to_string())
+            .with_certificate_checkpoint_store(checkpoint_store.clone())
+            .with_full_verification(true);
+        let restore = client
+            .restore_snapshot(
+                Arc::new(mock_aggregator_handler),
+                Box::new(mock_digester),
+                Box::new(mock_verifier),
+                genesis_verifier.clone(),
+                &digest_restore,
+            )
+            .await;
+        restore.expect("unexpected error");
+        assert_eq!(
+            Some("cert-hash-123".to_string()),
+            checkpoint_store
+                .get_checkpoint(&genesis_verifier)
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_export_certificate_bundle_ok_back_to_genesis() {
+        let fake_snapshot = fake_data::snapshots(1).first().unwrap().to_owned();
+        let digest = fake_snapshot.digest.clone();
+        let (mut mock_aggregator_handler, _mock_verifier, _mock_digester, genesis_verifier) =
+            get_dependencies();
+        let bundle_expected = CertificateChainBundle::new(
+            CertificateChainBundle::compute_genesis_verification_key_fingerprint(&genesis_verifier),
+            vec![fake_data::certificate(
+                fake_snapshot.certificate_hash.clone(),
+            )],
+        );
+        let bundle = bundle_expected.clone();
+        mock_aggregator_handler
+            .expect_get_snapshot_details()
+            .return_once(move |_| Ok(fake_snapshot));
+        mock_aggregator_handler
+            .expect_get_certificate_bundle()
+            .withf(|_, trusted_checkpoint| trusted_checkpoint.is_none())
+            .return_once(move |_, _| Ok(bundle));
+        let client = Runtime::new("testnet".to_string()).with_certificate_checkpoint_store(
+            get_checkpoint_store(Some("cert-hash-checkpoint")).await,
+        );
+        let export = client
+            .export_certificate_bundle(
+                Arc::new(mock_aggregator_handler),
+                &genesis_verifier,
+                &digest,
+                false,
+            )
+            .await;
+        assert_eq!(bundle_expected, export.expect("unexpected error"));
+    }
+
+    #[tokio::test]
+    async fn test_export_certificate_bundle_ok() {
+        let fake_snapshot = fake_data::snapshots(1).first().unwrap().to_owned();
+        let digest = fake_snapshot.digest.clone();
+        let (mut mock_aggregator_handler, _mock_verifier, _mock_digester, genesis_verifier) =
+            get_dependencies();
+        let bundle_expected = CertificateChainBundle::new(
+            CertificateChainBundle::compute_genesis_verification_key_fingerprint(&genesis_verifier),
+            vec![fake_data::certificate(
+                fake_snapshot.certificate_hash.clone(),
+            )],
+        );
+        let bundle = bundle_expected.clone();
+        mock_aggregator_handler
+            .expect_get_snapshot_details()
+            .return_once(move |_| Ok(fake_snapshot));
+        mock_aggregator_handler
+            .expect_get_certificate_bundle()
+            .withf(|_, trusted_checkpoint| trusted_checkpoint == &Some("cert-hash-checkpoint"))
+            .return_once(move |_, _| Ok(bundle));
+        let client = Runtime::new("testnet".to_string()).with_certificate_checkpoint_store(
+            get_checkpoint_store(Some("cert-hash-checkpoint")).await,
+        );
+        let export = client
+            .export_certificate_bundle(
+                Arc::new(mock_aggregator_handler),
+                &genesis_verifier,
+                &digest,
+                true,
+            )
+            .await;
+        assert_eq!(bundle_expected, export.expect("unexpected error"));
+    }
+
+    #[tokio::test]
+    async fn test_export_certificate_bundle_ko_genesis_verification_key_mismatch() {
+        let fake_snapshot = fake_data::snapshots(1).first().unwrap().to_owned();
+        let digest = fake_snapshot.digest.clone();
+        let (mut mock_aggregator_handler, _mock_verifier, _mock_digester, genesis_verifier) =
+            get_dependencies();
+        let bundle = CertificateChainBundle::new(
+            "another-genesis-vkey-fingerprint".to_string(),
+            vec![fake_data::certificate(
+                fake_snapshot.certificate_hash.clone(),
+            )],
+        );
+        mock_aggregator_handler
+            .expect_get_snapshot_details()
+            .return_once(move |_| Ok(fake_snapshot));
+        mock_aggregator_handler
+            .expect_get_certificate_bundle()
+            .return_once(move |_, _| Ok(bundle));
+        let client = Runtime::new("testnet".to_string());
+        let export = client
+            .export_certificate_bundle(
+                Arc::new(mock_aggregator_handler),
+                &genesis_verifier,
+                &digest,
+                false,
+            )
+            .await;
+        assert!(
+            matches!(export, Err(RuntimeError::CertificateChainBundle(_))),
+            "unexpected error type: {:?}",
+            export
+        );
+    }
+
+    #[tokio::test]
+    async fn test_export_certificate_bundle_ko_incremental_snapshot() {
+        let (_full_snapshot, _base_snapshot, snapshot) = fake_incremental_snapshots();
+        let (mut mock_aggregator_handler, _mock_verifier, _mock_digester, genesis_verifier) =
+            get_dependencies();
+        mock_aggregator_handler
+            .expect_get_snapshot_details()
+            .return_once(move |_| Ok(snapshot));
+        mock_aggregator_handler
+            .expect_get_certificate_bundle()
+            .never();
+        let client = Runtime::new("testnet".to_string());
+        let export = client
+            .export_certificate_bundle(
+                Arc::new(mock_aggregator_handler),
+                &genesis_verifier,
+                "increment",
+                false,
+            )
+            .await;
+        assert!(
+            matches!(export, Err(RuntimeError::InvalidInput(_))),
+            "unexpected error type: {:?}",
+            export
+        );
+    }
+
+    #[tokio::test]
+    async fn test_restore_snapshot_ok_offline_from_certificate_bundle() {
+        let fake_certificate = fake_data::certificate("cert-hash-123".to_string());
+        let (mut mock_aggregator_handler, mut mock_verifier, mut mock_digester, genesis_verifier) =
+            get_dependencies();
+        let digest_compute = fake_certificate
+            .protocol_message
+            .get_message_part(&ProtocolMessagePartKey::SnapshotDigest)
+            .unwrap()
+            .to_owned();
+        let digest_restore = digest_compute.clone();
+        let certificate_bundle = CertificateChainBundle::new(
+            CertificateChainBundle::compute_genesis_verification_key_fingerprint(&genesis_verifier),
+            vec![fake_certificate],
+        );
+        mock_aggregator_handler
+            .expect_get_snapshot_details()
+            .never();
+        mock_aggregator_handler
+            .expect_get_certificate_details()
+            .never();
+        mock_aggregator_handler
+            .expect_unpack_snapshot()
+            .return_once(move |_| Ok("./target-dir".to_string()));
+        mock_verifier
+            .expect_verify_certificate_chain()
+            .returning(|_, _, _| Ok(()))
+            .times(1);
+        mock_digester
+            .expect_compute_digest()
+            .return_once(move |_| Ok(digest_compute));
+        let mut client =
+            Runtime::new("testnet".to_string()).with_certificate_bundle(certificate_bundle);
+        let restore = client
+            .restore_snapshot(
+                Arc::new(mock_aggregator_handler),
+                Box::new(mock_digester),
+                Box::new(mock_verifier),
+                genesis_verifier,
+                &digest_restore,
+            )
+            .await;
+        restore.expect("unexpected error");
+    }
+
+    #[tokio::test]
+    async fn test_restore_snapshot_ko_certificate_bundle_of_another_snapshot() {
+        let fake_certificate = fake_data::certificate("cert-hash-123".to_string());
+        let (mock_aggregator_handler, mock_verifier, mock_digester, genesis_verifier) =
+            get_dependencies();
+        let certificate_bundle = CertificateChainBundle::new(
+            CertificateChainBundle::compute_genesis_verification_key_fingerprint(&genesis_verifier),
+            vec![fake_certificate],
+        );
+        let mut client =
+            Runtime::new("testnet".to_string()).with_certificate_bundle(certificate_bundle);
+        let restore = client
+            .restore_snapshot(
+                Arc::new(mock_aggregator_handler),
+                Box::new(mock_digester),
+                Box::new(mock_verifier),
+                genesis_verifier,
+                "another-digest",
+            )
+            .await;
+        assert!(
+            matches!(restore, Err(RuntimeError::InvalidInput(_))),
+            "unexpected error type: {:?}",
+            restore
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verify_certificate_chain_report_keeps_trusted_checkpoint() {
+        let fake_certificate = fake_data::certificate("cert-hash-123".to_string());
+        let (mut mock_aggregator_handler, mut mock_verifier, _, genesis_verifier) =
+            get_dependencies();
+        let fake_snapshot = fake_data::snapshots(1).first().unwrap().to_owned();
+        let fake_report = CertificateChainVerificationReport::new(
+            vec![],
+            Some("cert-hash-checkpoint".to_string()),
+            None,
+            std::time::Duration::from_millis(1),
+        );
+        let expected_report = fake_report.clone();
+        mock_aggregator_handler
+            .expect_as_certificate_retriever()
+            .return_once(move || Arc::new(MockAggregatorHandlerImpl::new()));
+        mock_aggregator_handler
+            .expect_get_snapshot_details()
+            .return_once(move |_| Ok(fake_snapshot));
+        mock_aggregator_handler
+            .expect_get_certificate_details()
+            .return_once(move |_| Ok(fake_certificate));
+        mock_verifier
+            .expect_verify_certificate_chain_with_report()
+            .withf(|_, _, _, trusted_checkpoint| {
+                trusted_checkpoint == &Some("cert-hash-checkpoint")
+            })
+            .return_once(move |_, _, _, _| fake_report)
+            .times(1);
+        let checkpoint_store = get_checkpoint_store(Some("cert-hash-checkpoint")).await;
+        let client = Runtime::new("testnet".to_string())
+            .with_certificate_checkpoint_store(checkpoint_store.clone());
+        let report = client
+            .verify_certificate_chain_report(
+                Arc::new(mock_aggregator_handler),
+                Box::new(mock_verifier),
+                &genesis_verifier,
+                "digest-123",
+            )
+            .await
+            .expect("unexpected error");
+
+        assert_eq!(expected_report, report);
+        assert_eq!(
+            Some("cert-hash-checkpoint".to_string()),
+            checkpoint_store
+                .get_checkpoint(&genesis_verifier)
+                .await
+                .unwrap()
+        );
+    }
+
     #[tokio::test]
     async fn test_restore_snapshot_ko_digester_error() {
         let fake_certificate = fake_data::certificate("cert-hash-123".to_string());
@@ -630,4 +1416,300 @@ mod tests {
             restore
         );
     }
+
+    fn fake_incremental_snapshots() -> (Snapshot, Snapshot, Snapshot) {
+        let mut full_snapshot = fake_data::snapshots(1)[0].clone();
+        full_snapshot.digest = "full".to_string();
+        full_snapshot.certificate_hash = "cert-full".to_string();
+        let mut base_snapshot = full_snapshot.clone();
+        base_snapshot.digest = "base".to_string();
+        base_snapshot.certificate_hash = "cert-base".to_string();
+        base_snapshot.base_digest = Some("full".to_string());
+        let mut snapshot = full_snapshot.clone();
+        snapshot.digest = "increment".to_string();
+        snapshot.certificate_hash = "cert-increment".to_string();
+        snapshot.base_digest = Some("base".to_string());
+
+        (full_snapshot, base_snapshot, snapshot)
+    }
+
+    #[tokio::test]
+    async fn test_download_incremental_snapshot_downloads_missing_base_snapshots() {
+        let (full_snapshot, base_snapshot, snapshot) = fake_incremental_snapshots();
+        let (mut mock_aggregator_handler, _mock_verifier, _mock_digester, _genesis_verifier) =
+            get_dependencies();
+        let snapshots = [full_snapshot, base_snapshot, snapshot];
+        mock_aggregator_handler
+            .expect_get_snapshot_details()
+            .returning(move |digest| {
+                Ok(snapshots
+                    .iter()
+                    .find(|snapshot| snapshot.digest == digest)
+                    .unwrap()
+                    .to_owned())
+            })
+            .times(3);
+        mock_aggregator_handler
+            .expect_is_snapshot_downloaded()
+            .returning(|digest| Ok(digest == "base"))
+            .times(2);
+        mock_aggregator_handler
+            .expect_download_snapshot()
+            .with(predicate::eq("full"), predicate::always())
+            .returning(|_, _| Ok("./full-archive".to_string()))
+            .times(1);
+        mock_aggregator_handler
+            .expect_download_snapshot()
+            .with(predicate::eq("increment"), predicate::always())
+            .returning(|_, _| Ok("./increment-archive".to_string()))
+            .times(1);
+        let client = Runtime::new("testnet".to_string());
+
+        let (_, to) = client
+            .download_snapshot(Arc::new(mock_aggregator_handler), "increment", 1)
+            .await
+            .expect("unexpected error");
+
+        assert_eq!("./increment-archive", to);
+    }
+
+    #[tokio::test]
+    async fn test_restore_incremental_snapshot_from_full_snapshot_ok() {
+        let (full_snapshot, base_snapshot, snapshot) = fake_incremental_snapshots();
+        let (mut mock_aggregator_handler, mut mock_verifier, mut mock_digester, genesis_verifier) =
+            get_dependencies();
+        let digest_compute = fake_data::certificate("cert-hash".to_string())
+            .protocol_message
+            .get_message_part(&ProtocolMessagePartKey::SnapshotDigest)
+            .unwrap()
+            .to_owned();
+        let snapshots = [full_snapshot, base_snapshot, snapshot];
+        let mut sequence = Sequence::new();
+        mock_aggregator_handler
+            .expect_as_certificate_retriever()
+            .returning(|| Arc::new(MockAggregatorHandlerImpl::new()));
+        mock_aggregator_handler
+            .expect_get_snapshot_details()
+            .returning(move |digest| {
+                Ok(snapshots
+                    .iter()
+                    .find(|snapshot| snapshot.digest == digest)
+                    .unwrap()
+                    .to_owned())
+            })
+            .times(3);
+        mock_aggregator_handler
+            .expect_get_certificate_details()
+            .returning(|certificate_hash| Ok(fake_data::certificate(certificate_hash.to_string())))
+            .times(3);
+        mock_aggregator_handler
+            .expect_unpack_snapshot_into()
+            .with(predicate::eq("full"), predicate::eq("increment"))
+            .times(1)
+            .in_sequence(&mut sequence)
+            .return_once(|_, _| Ok("./target-dir".to_string()));
+        mock_aggregator_handler
+            .expect_unpack_snapshot_into()
+            .with(predicate::eq("base"), predicate::eq("increment"))
+            .times(1)
+            .in_sequence(&mut sequence)
+            .return_once(|_, _| Ok("./target-dir".to_string()));
+        mock_aggregator_handler
+            .expect_unpack_snapshot()
+            .with(predicate::eq("increment"))
+            .times(1)
+            .in_sequence(&mut sequence)
+            .return_once(|_| Ok("./target-dir".to_string()));
+        mock_verifier
+            .expect_verify_certificate_chain()
+            .returning(|_, _, _| Ok(()))
+            .times(3);
+        mock_digester
+            .expect_compute_digest()
+            .returning(move |_| Ok(digest_compute.clone()))
+            .times(3);
+        let mut client = Runtime::new("testnet".to_string());
+
+        let restore = client
+            .restore_snapshot(
+                Arc::new(mock_aggregator_handler),
+                Box::new(mock_digester),
+                Box::new(mock_verifier),
+                genesis_verifier,
+                "increment",
+            )
+            .await;
+
+        assert_eq!("./target-dir", restore.expect("unexpected error"));
+    }
+
+    #[tokio::test]
+    async fn test_restore_snapshot_ko_snapshot_based_on_itself() {
+        let mut snapshot = fake_data::snapshots(1)[0].clone();
+        snapshot.digest = "increment".to_string();
+        snapshot.base_digest = Some("increment".to_string());
+        let (mut mock_aggregator_handler, mock_verifier, mock_digester, genesis_verifier) =
+            get_dependencies();
+        mock_aggregator_handler
+            .expect_as_certificate_retriever()
+            .returning(|| Arc::new(MockAggregatorHandlerImpl::new()));
+        mock_aggregator_handler
+            .expect_get_snapshot_details()
+            .return_once(move |_| Ok(snapshot));
+        mock_aggregator_handler
+            .expect_get_certificate_details()
+            .returning(|certificate_hash| Ok(fake_data::certificate(certificate_hash.to_string())));
+        mock_aggregator_handler.expect_unpack_snapshot().never();
+        let mut client = Runtime::new("testnet".to_string());
+
+        let restore = client
+            .restore_snapshot(
+                Arc::new(mock_aggregator_handler),
+                Box::new(mock_digester),
+                Box::new(mock_verifier),
+                genesis_verifier,
+                "increment",
+            )
+            .await;
+
+        assert!(
+            matches!(restore, Err(RuntimeError::InvalidInput(_))),
+            "unexpected error type: {:?}",
+            restore
+        );
+    }
+
+    fn fake_chunked_snapshot() -> (Snapshot, SnapshotManifest) {
+        let mut snapshot = fake_data::snapshots(1)[0].clone();
+        let manifest = SnapshotManifest::new(
+            snapshot.digest.clone(),
+            CompressionAlgorithm::Gzip,
+            vec![SnapshotChunk::new("chunk-hash".to_string(), snapshot.size)],
+        );
+        snapshot.manifest_hash = Some(manifest.compute_hash());
+        snapshot.locations = vec!["http://whatev.er/digest.manifest.json".to_string()];
+
+        (snapshot, manifest)
+    }
+
+    fn fake_certificate_with_manifest_hash(manifest_hash: &str) -> Certificate {
+        let mut certificate = fake_data::certificate("cert-hash".to_string());
+        certificate.protocol_message.set_message_part(
+            ProtocolMessagePartKey::SnapshotManifestHash,
+            manifest_hash.to_string(),
+        );
+        certificate.signed_message = certificate.protocol_message.compute_hash();
+
+        certificate
+    }
+
+    #[tokio::test]
+    async fn test_download_chunked_snapshot_ok() {
+        let (snapshot, manifest) = fake_chunked_snapshot();
+        let certificate = fake_certificate_with_manifest_hash(&manifest.compute_hash());
+        let digest = snapshot.digest.clone();
+        let (mut mock_aggregator_handler, _mock_verifier, _mock_digester, _genesis_verifier) =
+            get_dependencies();
+        mock_aggregator_handler
+            .expect_get_snapshot_details()
+            .return_once(move |_| Ok(snapshot))
+            .times(1);
+        mock_aggregator_handler
+            .expect_get_snapshot_manifest()
+            .with(predicate::eq("http://whatev.er/digest.manifest.json"))
+            .return_once(move |_| Ok(manifest))
+            .times(1);
+        mock_aggregator_handler
+            .expect_get_certificate_details()
+            .return_once(move |_| Ok(certificate))
+            .times(1);
+        mock_aggregator_handler
+            .expect_download_snapshot_chunks()
+            .returning(|_, _| Ok("./archive".to_string()))
+            .times(1);
+        mock_aggregator_handler.expect_download_snapshot().never();
+        let client = Runtime::new("testnet".to_string());
+
+        let (from, to) = client
+            .download_snapshot(Arc::new(mock_aggregator_handler), &digest, 1)
+            .await
+            .expect("unexpected error");
+
+        assert_eq!("http://whatev.er/digest.manifest.json", from);
+        assert_eq!("./archive", to);
+    }
+
+    #[tokio::test]
+    async fn test_download_chunked_snapshot_ko_manifest_not_signed() {
+        let (snapshot, manifest) = fake_chunked_snapshot();
+        let certificate = fake_certificate_with_manifest_hash("another-manifest-hash");
+        let digest = snapshot.digest.clone();
+        let (mut mock_aggregator_handler, _mock_verifier, _mock_digester, _genesis_verifier) =
+            get_dependencies();
+        mock_aggregator_handler
+            .expect_get_snapshot_details()
+            .return_once(move |_| Ok(snapshot))
+            .times(1);
+        mock_aggregator_handler
+            .expect_get_snapshot_manifest()
+            .return_once(move |_| Ok(manifest))
+            .times(1);
+        mock_aggregator_handler
+            .expect_get_certificate_details()
+            .return_once(move |_| Ok(certificate))
+            .times(1);
+        mock_aggregator_handler
+            .expect_download_snapshot_chunks()
+            .never();
+        let client = Runtime::new("testnet".to_string());
+
+        let result = client
+            .download_snapshot(Arc::new(mock_aggregator_handler), &digest, 1)
+            .await;
+
+        assert!(
+            matches!(result, Err(RuntimeError::InvalidSnapshotManifest(_))),
+            "unexpected result: {:?}",
+            result
+        );
+    }
+
+    #[tokio::test]
+    async fn test_download_chunked_snapshot_ko_protocol_message_not_signed() {
+        let (snapshot, manifest) = fake_chunked_snapshot();
+        let mut certificate = fake_data::certificate("cert-hash".to_string());
+        certificate.protocol_message.set_message_part(
+            ProtocolMessagePartKey::SnapshotManifestHash,
+            manifest.compute_hash(),
+        );
+        let digest = snapshot.digest.clone();
+        let (mut mock_aggregator_handler, _mock_verifier, _mock_digester, _genesis_verifier) =
+            get_dependencies();
+        mock_aggregator_handler
+            .expect_get_snapshot_details()
+            .return_once(move |_| Ok(snapshot))
+            .times(1);
+        mock_aggregator_handler
+            .expect_get_snapshot_manifest()
+            .return_once(move |_| Ok(manifest))
+            .times(1);
+        mock_aggregator_handler
+            .expect_get_certificate_details()
+            .return_once(move |_| Ok(certificate))
+            .times(1);
+        mock_aggregator_handler
+            .expect_download_snapshot_chunks()
+            .never();
+        let client = Runtime::new("testnet".to_string());
+
+        let result = client
+            .download_snapshot(Arc::new(mock_aggregator_handler), &digest, 1)
+            .await;
+
+        assert!(
+            matches!(result, Err(RuntimeError::InvalidSnapshotManifest(_))),
+            "unexpected result: {:?}",
+            result
+        );
+    }
 }
@@ -0,0 +1,258 @@
+//! A module used to export the Certificate Chain created by an aggregator in a self-contained
+//! bundle, so that it can be verified offline
+//!
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use thiserror::Error;
+
+use super::{CertificateRetriever, CertificateRetrieverError};
+use crate::crypto_helper::ProtocolGenesisVerifier;
+use crate::entities::Certificate;
+
+/// [CertificateChainBundle] related errors.
+#[derive(Error, Debug)]
+pub enum CertificateChainBundleError {
+    /// Error raised when a certificate of the chain can't be retrieved.
+    #[error("certificate retriever error: '{0}'")]
+    CertificateRetriever(#[from] CertificateRetrieverError),
+
+    /// Error raised when the chain loops.
+    #[error("certificate chain infinite loop at certificate '{0}'")]
+    CertificateChainInfiniteLoop(String),
+
+    /// Error raised when the bundle holds no certificate.
+    #[error("the certificate chain bundle is empty")]
+    Empty,
+
+    /// Error raised when the bundle was not exported for the given genesis verification key.
+    #[error("the certificate chain bundle was exported for the genesis verification key '{bundle_fingerprint}' instead of '{expected_fingerprint}'")]
+    GenesisVerificationKeyMismatch {
+        /// Fingerprint of the genesis verification key recorded in the bundle.
+        bundle_fingerprint: String,
+
+        /// Fingerprint of the genesis verification key used for the verification.
+        expected_fingerprint: String,
+    },
+}
+
+/// A self-contained Certificate Chain: the certificates from an exported certificate back to
+/// the genesis certificate, or to a trusted checkpoint, and the fingerprint of the genesis
+/// verification key that signs the chain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CertificateChainBundle {
+    /// SHA256 fingerprint of the genesis verification key of the chain
+    pub genesis_verification_key_fingerprint: String,
+
+    /// Certificates of the chain, from the exported certificate to the oldest one
+    pub certificates: Vec<Certificate>,
+}
+
+impl CertificateChainBundle {
+    /// CertificateChainBundle factory
+    pub fn new(
+        genesis_verification_key_fingerprint: String,
+        certificates: Vec<Certificate>,
+    ) -> Self {
+        Self {
+            genesis_verification_key_fingerprint,
+            certificates,
+        }
+    }
+
+    /// Build the bundle of a certificate by walking its chain back to the genesis certificate,
+    /// or to the `trusted_checkpoint` certificate if it is reached before.
+    pub async fn build(
+        certificate_hash: &str,
+        certificate_retriever: Arc<dyn CertificateRetriever>,
+        genesis_verifier: &ProtocolGenesisVerifier,
+        trusted_checkpoint: Option<&str>,
+    ) -> Result<Self, CertificateChainBundleError> {
+        let mut certificates = Vec::new();
+        let mut certificate_hashes = HashSet::new();
+        let mut certificate = certificate_retriever
+            .get_certificate_details(certificate_hash)
+            .await?;
+
+        loop {
+            if !certificate_hashes.insert(certificate.hash.clone()) {
+                return Err(CertificateChainBundleError::CertificateChainInfiniteLoop(
+                    certificate.hash,
+                ));
+            }
+            let previous_hash = certificate.previous_hash.clone();
+            let is_last =
+                previous_hash.is_empty() || trusted_checkpoint == Some(certificate.hash.as_str());
+            certificates.push(certificate);
+            if is_last {
+                break;
+            }
+            certificate = certificate_retriever
+                .get_certificate_details(&previous_hash)
+                .await?;
+        }
+
+        Ok(Self::new(
+            Self::compute_genesis_verification_key_fingerprint(genesis_verifier),
+            certificates,
+        ))
+    }
+
+    /// Compute the fingerprint of the genesis verification key, or genesis policy, of a verifier
+    pub fn compute_genesis_verification_key_fingerprint(
+        genesis_verifier: &ProtocolGenesisVerifier,
+    ) -> String {
+        hex::encode(Sha256::digest(genesis_verifier.to_bytes()))
+    }
+
+    /// Get the exported certificate, the most recent of the chain
+    pub fn get_certificate(&self) -> Result<&Certificate, CertificateChainBundleError> {
+        self.certificates
+            .first()
+            .ok_or(CertificateChainBundleError::Empty)
+    }
+
+    /// Check that the bundle was exported for the genesis verification key of a verifier
+    pub fn check_genesis_verifier(
+        &self,
+        genesis_verifier: &ProtocolGenesisVerifier,
+    ) -> Result<(), CertificateChainBundleError> {
+        let expected_fingerprint =
+            Self::compute_genesis_verification_key_fingerprint(genesis_verifier);
+        if self.genesis_verification_key_fingerprint != expected_fingerprint {
+            return Err(
+                CertificateChainBundleError::GenesisVerificationKeyMismatch {
+                    bundle_fingerprint: self.genesis_verification_key_fingerprint.clone(),
+                    expected_fingerprint,
+                },
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// A [CertificateRetriever] that reads the certificates of a [CertificateChainBundle]
+/// instead of querying an aggregator.
+pub struct CertificateBundleRetriever {
+    certificates: HashMap<String, Certificate>,
+}
+
+impl From<CertificateChainBundle> for CertificateBundleRetriever {
+    fn from(bundle: CertificateChainBundle) -> Self {
+        Self {
+            certificates: bundle
+                .certificates
+                .into_iter()
+                .map(|certificate| (certificate.hash.clone(), certificate))
+                .collect(),
+        }
+    }
+}
+
+#[async_trait]
+impl CertificateRetriever for CertificateBundleRetriever {
+    async fn get_certificate_details(
+        &self,
+        certificate_hash: &str,
+    ) -> Result<Certificate, CertificateRetrieverError> {
+        self.certificates
+            .get(certificate_hash)
+            .cloned()
+            .ok_or_else(|| {
+                CertificateRetrieverError::General(format!(
+                    "certificate '{}' is not in the bundle",
+                    certificate_hash
+                ))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::certificate_chain::{CertificateVerifier, MithrilCertificateVerifier};
+    use crate::crypto_helper::tests_setup::setup_certificate_chain;
+    use crate::crypto_helper::ProtocolGenesisSigner;
+
+    fn build_retriever(certificates: &[Certificate]) -> Arc<dyn CertificateRetriever> {
+        Arc::new(CertificateBundleRetriever::from(
+            CertificateChainBundle::new("".to_string(), certificates.to_vec()),
+        ))
+    }
+
+    #[tokio::test]
+    async fn build_bundle_back_to_genesis_and_verify_it_offline() {
+        let (certificates, genesis_verifier) = setup_certificate_chain(10, 2);
+        let bundle = CertificateChainBundle::build(
+            &certificates[0].hash,
+            build_retriever(&certificates),
+            &genesis_verifier,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(certificates, bundle.certificates);
+        bundle.check_genesis_verifier(&genesis_verifier).unwrap();
+
+        let certificate = bundle.get_certificate().unwrap().clone();
+        MithrilCertificateVerifier::new(slog_scope::logger())
+            .verify_certificate_chain(
+                certificate,
+                Arc::new(CertificateBundleRetriever::from(bundle)),
+                &genesis_verifier,
+            )
+            .await
+            .expect("the bundle certificate chain should be valid");
+    }
+
+    #[tokio::test]
+    async fn build_bundle_stops_at_the_trusted_checkpoint() {
+        let (certificates, genesis_verifier) = setup_certificate_chain(10, 2);
+        let bundle = CertificateChainBundle::build(
+            &certificates[0].hash,
+            build_retriever(&certificates),
+            &genesis_verifier,
+            Some(&certificates[3].hash),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(certificates[0..4].to_vec(), bundle.certificates);
+    }
+
+    #[tokio::test]
+    async fn build_bundle_fails_if_a_certificate_is_missing() {
+        let (certificates, genesis_verifier) = setup_certificate_chain(10, 2);
+        let mut available_certificates = certificates.clone();
+        available_certificates.remove(5);
+
+        CertificateChainBundle::build(
+            &certificates[0].hash,
+            build_retriever(&available_certificates),
+            &genesis_verifier,
+            None,
+        )
+        .await
+        .expect_err("a missing certificate should make the build fail");
+    }
+
+    #[test]
+    fn check_genesis_verifier_fails_with_another_genesis_key() {
+        let (certificates, genesis_verifier) = setup_certificate_chain(2, 1);
+        let bundle = CertificateChainBundle::new(
+            CertificateChainBundle::compute_genesis_verification_key_fingerprint(&genesis_verifier),
+            certificates,
+        );
+        let other_genesis_verifier =
+            ProtocolGenesisSigner::create_non_deterministic_genesis_signer()
+                .create_genesis_verifier();
+
+        assert!(matches!(
+            bundle.check_genesis_verifier(&other_genesis_verifier),
+            Err(CertificateChainBundleError::GenesisVerificationKeyMismatch { .. })
+        ));
+    }
+}
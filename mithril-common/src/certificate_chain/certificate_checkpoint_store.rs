@@ -0,0 +1,179 @@
+//! A module used to keep the trusted checkpoints of the Certificate Chain
+//!
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use crate::crypto_helper::ProtocolGenesisVerifier;
+use crate::entities::Beacon;
+use crate::store::{adapter::StoreAdapter, StoreError};
+
+type Adapter = Box<dyn StoreAdapter<Key = String, Record = CertificateCheckpoint>>;
+
+/// A trusted checkpoint of the Certificate Chain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CertificateCheckpoint {
+    /// Hash of the verified certificate
+    pub certificate_hash: String,
+
+    /// Beacon of the verified certificate
+    pub beacon: Beacon,
+}
+
+impl CertificateCheckpoint {
+    /// Tell if this checkpoint is more recent than the given beacon, by epoch then immutable
+    /// file number.
+    fn is_more_recent_than(&self, beacon: &Beacon) -> bool {
+        (self.beacon.epoch, self.beacon.immutable_file_number)
+            > (beacon.epoch, beacon.immutable_file_number)
+    }
+}
+
+/// Store the hash of the most recent certificate whose chain has been verified.
+///
+/// The checkpoint is recorded for a genesis verification key: a certificate
+/// verified with another genesis key is never trusted.
+pub struct CertificateCheckpointStore {
+    adapter: RwLock<Adapter>,
+}
+
+impl CertificateCheckpointStore {
+    /// CertificateCheckpointStore factory
+    pub fn new(adapter: Adapter) -> Self {
+        Self {
+            adapter: RwLock::new(adapter),
+        }
+    }
+
+    /// Get the trusted checkpoint recorded for the given genesis verifier if any.
+    pub async fn get_checkpoint(
+        &self,
+        genesis_verifier: &ProtocolGenesisVerifier,
+    ) -> Result<Option<String>, StoreError> {
+        Ok(self
+            .adapter
+            .read()
+            .await
+            .get_record(&checkpoint_key(genesis_verifier))
+            .await?
+            .map(|checkpoint| checkpoint.certificate_hash))
+    }
+
+    /// Record the hash of a verified certificate as the trusted checkpoint of
+    /// the given genesis verifier.
+    ///
+    /// The checkpoint only moves forward: it is left untouched if the recorded
+    /// certificate is more recent than the given one. Return whether it was replaced.
+    pub async fn save_checkpoint(
+        &self,
+        genesis_verifier: &ProtocolGenesisVerifier,
+        certificate_hash: &str,
+        beacon: &Beacon,
+    ) -> Result<bool, StoreError> {
+        let key = checkpoint_key(genesis_verifier);
+        let mut adapter = self.adapter.write().await;
+        if let Some(checkpoint) = adapter.get_record(&key).await? {
+            if checkpoint.is_more_recent_than(beacon) {
+                return Ok(false);
+            }
+        }
+        adapter
+            .store_record(
+                &key,
+                &CertificateCheckpoint {
+                    certificate_hash: certificate_hash.to_string(),
+                    beacon: beacon.to_owned(),
+                },
+            )
+            .await?;
+
+        Ok(true)
+    }
+}
+
+fn checkpoint_key(genesis_verifier: &ProtocolGenesisVerifier) -> String {
+    hex::encode(genesis_verifier.to_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto_helper::ProtocolGenesisSigner;
+    use crate::entities::Epoch;
+    use crate::store::adapter::MemoryAdapter;
+
+    fn beacon(epoch: u64, immutable_file_number: u64) -> Beacon {
+        Beacon::new("devnet".to_string(), epoch, immutable_file_number)
+    }
+
+    #[tokio::test]
+    async fn checkpoints_are_recorded_by_genesis_verification_key() {
+        let store = CertificateCheckpointStore::new(Box::new(MemoryAdapter::new(None).unwrap()));
+        let genesis_verifier =
+            ProtocolGenesisSigner::create_deterministic_genesis_signer().create_genesis_verifier();
+        let other_genesis_verifier =
+            ProtocolGenesisSigner::create_non_deterministic_genesis_signer()
+                .create_genesis_verifier();
+        assert_eq!(None, store.get_checkpoint(&genesis_verifier).await.unwrap());
+
+        store
+            .save_checkpoint(&genesis_verifier, "cert-hash-1", &beacon(1, 10))
+            .await
+            .unwrap();
+        store
+            .save_checkpoint(&genesis_verifier, "cert-hash-2", &beacon(1, 11))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            Some("cert-hash-2".to_string()),
+            store.get_checkpoint(&genesis_verifier).await.unwrap()
+        );
+        assert_eq!(
+            None,
+            store.get_checkpoint(&other_genesis_verifier).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn checkpoints_only_move_forward() {
+        let store = CertificateCheckpointStore::new(Box::new(MemoryAdapter::new(None).unwrap()));
+        let genesis_verifier =
+            ProtocolGenesisSigner::create_deterministic_genesis_signer().create_genesis_verifier();
+        assert!(store
+            .save_checkpoint(&genesis_verifier, "cert-hash-2", &beacon(2, 20))
+            .await
+            .unwrap());
+
+        for (certificate_hash, older_beacon) in [
+            ("cert-hash-older-epoch", beacon(1, 25)),
+            ("cert-hash-older-immutable", beacon(2, 19)),
+        ] {
+            assert!(!store
+                .save_checkpoint(&genesis_verifier, certificate_hash, &older_beacon)
+                .await
+                .unwrap());
+        }
+        assert_eq!(
+            Some("cert-hash-2".to_string()),
+            store.get_checkpoint(&genesis_verifier).await.unwrap()
+        );
+
+        assert!(store
+            .save_checkpoint(&genesis_verifier, "cert-hash-3", &beacon(3, 20))
+            .await
+            .unwrap());
+        assert_eq!(
+            Some("cert-hash-3".to_string()),
+            store.get_checkpoint(&genesis_verifier).await.unwrap()
+        );
+        let checkpoint = store
+            .adapter
+            .read()
+            .await
+            .get_record(&checkpoint_key(&genesis_verifier))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(Epoch(3), checkpoint.beacon.epoch);
+    }
+}
@@ -3,13 +3,14 @@
 use std::sync::Arc;
 
 use chrono::prelude::*;
-use hex::ToHex;
+use hex::{FromHex, ToHex};
 use thiserror::Error;
 
 use crate::{
     crypto_helper::{
-        key_encode_hex, ProtocolAggregateVerificationKey, ProtocolGenesisSignature,
-        ProtocolGenesisSigner, PROTOCOL_VERSION,
+        key_encode_hex, ProtocolAggregateVerificationKey, ProtocolGenesisError,
+        ProtocolGenesisMultiSignature, ProtocolGenesisSignature, ProtocolGenesisSigner,
+        ProtocolGenesisVerifier, PROTOCOL_VERSION,
     },
     entities::{
         Beacon, Certificate, CertificateMetadata, ProtocolMessage, ProtocolMessagePartKey,
@@ -27,6 +28,14 @@ pub enum CertificateGenesisProducerError {
     /// Error raised when there is no genesis signer available
     #[error("missing genesis signer error")]
     MissingGenesisSigner(),
+
+    /// Error raised when a genesis key rotation certificate misses a part of its protocol message
+    #[error("missing genesis key rotation message part: '{0}'")]
+    MissingGenesisKeyRotationPart(ProtocolMessagePartKey),
+
+    /// Error raised when the genesis key rotation is not signed by the previous genesis key
+    #[error("invalid genesis key rotation signature: '{0}'")]
+    InvalidGenesisKeyRotationSignature(#[from] ProtocolGenesisError),
 }
 
 /// CertificateGenesisProducer is in charge of producing a Genesis Certificate
@@ -67,12 +76,34 @@ impl CertificateGenesisProducer {
             .sign(genesis_protocol_message.compute_hash().as_bytes()))
     }
 
+    /// Create the protocol message of a Genesis Key Rotation Certificate, to sign with the next
+    /// genesis secret key: it holds the Genesis AVK as a Genesis Certificate does, and the
+    /// rotation authorized by the previous genesis secret key
+    pub fn create_genesis_key_rotation_protocol_message(
+        genesis_avk: &ProtocolAggregateVerificationKey,
+        genesis_key_rotation: &GenesisKeyRotation,
+    ) -> Result<ProtocolMessage, CertificateGenesisProducerError> {
+        let mut protocol_message = genesis_key_rotation.create_protocol_message()?;
+        protocol_message.set_message_part(
+            ProtocolMessagePartKey::GenesisKeyRotationSignature,
+            genesis_key_rotation
+                .rotation_signature
+                .to_bytes()
+                .encode_hex::<String>(),
+        );
+        protocol_message.set_message_part(
+            ProtocolMessagePartKey::NextAggregateVerificationKey,
+            key_encode_hex(genesis_avk).map_err(CertificateGenesisProducerError::Codec)?,
+        );
+        Ok(protocol_message)
+    }
+
     /// Create a Genesis Certificate
     pub fn create_genesis_certificate(
         protocol_parameters: ProtocolParameters,
         beacon: Beacon,
         genesis_avk: ProtocolAggregateVerificationKey,
-        genesis_signature: ProtocolGenesisSignature,
+        genesis_signature: ProtocolGenesisMultiSignature,
     ) -> Result<Certificate, CertificateGenesisProducerError> {
         let protocol_version = PROTOCOL_VERSION.to_string();
         let initiated_at = format!("{:?}", Utc::now());
@@ -101,4 +132,154 @@ impl CertificateGenesisProducer {
             genesis_signature,
         ))
     }
+
+    /// Create a Genesis Key Rotation Certificate: a Genesis Certificate signed with the next
+    /// genesis secret key that is chained to the previous certificate, so that the history of
+    /// the certificate chain is kept
+    pub fn create_genesis_key_rotation_certificate(
+        protocol_parameters: ProtocolParameters,
+        beacon: Beacon,
+        previous_hash: String,
+        genesis_avk: ProtocolAggregateVerificationKey,
+        genesis_key_rotation: &GenesisKeyRotation,
+        genesis_signature: ProtocolGenesisMultiSignature,
+    ) -> Result<Certificate, CertificateGenesisProducerError> {
+        let mut certificate = Self::create_genesis_certificate(
+            protocol_parameters,
+            beacon,
+            genesis_avk.clone(),
+            genesis_signature,
+        )?;
+        certificate.previous_hash = previous_hash;
+        certificate.protocol_message =
+            Self::create_genesis_key_rotation_protocol_message(&genesis_avk, genesis_key_rotation)?;
+        certificate.signed_message = certificate.protocol_message.compute_hash();
+        certificate.hash = certificate.compute_hash();
+        Ok(certificate)
+    }
+}
+
+/// A rotation of the genesis key: the previous genesis secret key(s) sign the next genesis
+/// verification key(s) to authorize them
+#[derive(Debug, Clone)]
+pub struct GenesisKeyRotation {
+    /// Genesis verifier of the certificate chain before the rotation
+    pub previous_genesis_verifier: ProtocolGenesisVerifier,
+
+    /// Genesis verifier of the certificate chain after the rotation
+    pub next_genesis_verifier: ProtocolGenesisVerifier,
+
+    /// Signature of the rotation by the previous genesis secret key(s)
+    pub rotation_signature: ProtocolGenesisMultiSignature,
+}
+
+impl GenesisKeyRotation {
+    /// GenesisKeyRotation factory
+    pub fn new(
+        previous_genesis_verifier: ProtocolGenesisVerifier,
+        next_genesis_verifier: ProtocolGenesisVerifier,
+        rotation_signature: ProtocolGenesisMultiSignature,
+    ) -> Self {
+        Self {
+            previous_genesis_verifier,
+            next_genesis_verifier,
+            rotation_signature,
+        }
+    }
+
+    /// Create the rotation protocol message, to sign with the previous genesis secret key(s)
+    pub fn create_rotation_protocol_message(
+        previous_genesis_verifier: &ProtocolGenesisVerifier,
+        next_genesis_verifier: &ProtocolGenesisVerifier,
+    ) -> Result<ProtocolMessage, CertificateGenesisProducerError> {
+        let encode_hex = |genesis_verifier: &ProtocolGenesisVerifier| {
+            genesis_verifier
+                .to_hex()
+                .map_err(|e| CertificateGenesisProducerError::Codec(e.to_string()))
+        };
+        let mut protocol_message = ProtocolMessage::new();
+        protocol_message.set_message_part(
+            ProtocolMessagePartKey::PreviousGenesisVerificationKey,
+            encode_hex(previous_genesis_verifier)?,
+        );
+        protocol_message.set_message_part(
+            ProtocolMessagePartKey::NextGenesisVerificationKey,
+            encode_hex(next_genesis_verifier)?,
+        );
+        Ok(protocol_message)
+    }
+
+    /// Create the protocol message of the rotation, to sign with the previous genesis secret key(s)
+    pub fn create_protocol_message(
+        &self,
+    ) -> Result<ProtocolMessage, CertificateGenesisProducerError> {
+        Self::create_rotation_protocol_message(
+            &self.previous_genesis_verifier,
+            &self.next_genesis_verifier,
+        )
+    }
+
+    /// Read the genesis key rotation of a certificate if it is a Genesis Key Rotation Certificate
+    pub fn from_certificate(
+        certificate: &Certificate,
+    ) -> Result<Option<Self>, CertificateGenesisProducerError> {
+        let protocol_message = &certificate.protocol_message;
+        let next_genesis_verifier = match protocol_message
+            .get_message_part(&ProtocolMessagePartKey::NextGenesisVerificationKey)
+        {
+            Some(next_genesis_verifier) => next_genesis_verifier,
+            None => return Ok(None),
+        };
+        let get_message_part = |key: ProtocolMessagePartKey| {
+            protocol_message
+                .get_message_part(&key)
+                .ok_or(CertificateGenesisProducerError::MissingGenesisKeyRotationPart(key))
+        };
+        let decode_hex = |hex: &str| {
+            ProtocolGenesisVerifier::from_hex(hex)
+                .map_err(|e| CertificateGenesisProducerError::Codec(e.to_string()))
+        };
+        let previous_genesis_verifier =
+            get_message_part(ProtocolMessagePartKey::PreviousGenesisVerificationKey)?;
+        let rotation_signature =
+            get_message_part(ProtocolMessagePartKey::GenesisKeyRotationSignature)?;
+        let rotation_signature = ProtocolGenesisMultiSignature::from_bytes(
+            &Vec::from_hex(rotation_signature)
+                .map_err(|e| CertificateGenesisProducerError::Codec(e.to_string()))?,
+        )
+        .map_err(|e| CertificateGenesisProducerError::Codec(e.to_string()))?;
+
+        Ok(Some(Self::new(
+            decode_hex(previous_genesis_verifier)?,
+            decode_hex(next_genesis_verifier)?,
+            rotation_signature,
+        )))
+    }
+
+    /// Verify that the rotation was signed by the previous genesis secret key(s)
+    pub fn verify(&self) -> Result<(), CertificateGenesisProducerError> {
+        let protocol_message = self.create_protocol_message()?;
+        self.previous_genesis_verifier.verify_multi_signature(
+            protocol_message.compute_hash().as_bytes(),
+            &self.rotation_signature,
+        )?;
+        Ok(())
+    }
+
+    /// Create the verifier of the certificate chain before the rotation
+    pub fn create_previous_genesis_verifier(&self) -> ProtocolGenesisVerifier {
+        self.previous_genesis_verifier.clone()
+    }
+
+    /// Create the verifier of the certificate chain after the rotation
+    pub fn create_next_genesis_verifier(&self) -> ProtocolGenesisVerifier {
+        self.next_genesis_verifier.clone()
+    }
+
+    /// Is the genesis verifier the one of the certificate chain before or after the rotation,
+    /// the rotations of a whole certificate chain are checked by its verification instead
+    pub fn is_trusted_by(&self, genesis_verifier: &ProtocolGenesisVerifier) -> bool {
+        genesis_verifier == &self.previous_genesis_verifier
+            || genesis_verifier == &self.next_genesis_verifier
+    }
 }
@@ -0,0 +1,277 @@
+//! A module used to report the detailed verification of the Certificate Chain created by an
+//! aggregator
+//!
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use crate::entities::{Certificate, Epoch, ImmutableFileNumber};
+
+/// Check run on a certificate of the chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationCheck {
+    /// The protocol version of the certificate is supported
+    ProtocolVersion,
+
+    /// The hash of the certificate matches its content
+    Hash,
+
+    /// The multi signature of a standard certificate is valid
+    MultiSignature,
+
+    /// The aggregate verification key of the certificate is registered in the previous
+    /// certificate
+    AvkLinkage,
+
+    /// The previous certificate has the hash referenced by the certificate
+    PreviousHash,
+
+    /// The genesis signature of a genesis certificate is valid
+    Genesis,
+}
+
+impl Display for VerificationCheck {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ProtocolVersion => write!(f, "protocol version"),
+            Self::Hash => write!(f, "hash"),
+            Self::MultiSignature => write!(f, "multi signature"),
+            Self::AvkLinkage => write!(f, "AVK linkage"),
+            Self::PreviousHash => write!(f, "previous hash"),
+            Self::Genesis => write!(f, "genesis signature"),
+        }
+    }
+}
+
+/// Outcome of a [VerificationCheck]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckReport {
+    /// Check that was run
+    pub check: VerificationCheck,
+
+    /// Error raised by the check if it failed
+    pub error: Option<String>,
+
+    /// Duration of the check in microseconds
+    pub duration_us: u64,
+}
+
+impl CheckReport {
+    /// CheckReport factory
+    pub fn new<E: Display>(
+        check: VerificationCheck,
+        result: Result<(), E>,
+        duration: Duration,
+    ) -> Self {
+        Self {
+            check,
+            error: result.err().map(|e| e.to_string()),
+            duration_us: duration.as_micros() as u64,
+        }
+    }
+
+    /// Did the check pass
+    pub fn is_passed(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Checks run on a certificate of the chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CertificateVerificationReport {
+    /// Hash of the certificate
+    pub certificate_hash: String,
+
+    /// Epoch of the certificate
+    pub epoch: Epoch,
+
+    /// Immutable file number of the certificate
+    pub immutable_file_number: ImmutableFileNumber,
+
+    /// Checks run on the certificate, in the order they were run
+    pub checks: Vec<CheckReport>,
+
+    /// Duration of the verification of the certificate in microseconds
+    pub duration_us: u64,
+}
+
+impl CertificateVerificationReport {
+    /// Create the report of a certificate before running its checks
+    pub fn new(certificate: &Certificate) -> Self {
+        Self {
+            certificate_hash: certificate.hash.clone(),
+            epoch: certificate.beacon.epoch,
+            immutable_file_number: certificate.beacon.immutable_file_number,
+            checks: Vec::new(),
+            duration_us: 0,
+        }
+    }
+
+    /// Record the outcome of a check
+    pub fn add_check<E: Display>(
+        &mut self,
+        check: VerificationCheck,
+        result: Result<(), E>,
+        duration: Duration,
+    ) -> bool {
+        let check_report = CheckReport::new(check, result, duration);
+        let is_passed = check_report.is_passed();
+        self.checks.push(check_report);
+
+        is_passed
+    }
+
+    /// Did all the checks of the certificate pass
+    pub fn is_valid(&self) -> bool {
+        self.checks.iter().all(|check| check.is_passed())
+    }
+}
+
+/// Detailed verification of a Certificate Chain: unlike
+/// [CertificateVerifier::verify_certificate_chain][super::CertificateVerifier::verify_certificate_chain]
+/// the verification does not stop at the first failed check.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CertificateChainVerificationReport {
+    /// Is the whole chain valid
+    pub valid: bool,
+
+    /// Reports of the verified certificates, from the most recent to the oldest
+    pub certificates: Vec<CertificateVerificationReport>,
+
+    /// Hash of the trusted checkpoint where the verification stopped if any
+    pub trusted_checkpoint: Option<String>,
+
+    /// Error that stopped the walk of the chain before its end, if any
+    pub error: Option<String>,
+
+    /// Duration of the verification of the chain in microseconds
+    pub duration_us: u64,
+}
+
+impl CertificateChainVerificationReport {
+    /// Build the report once the walk of the chain is done
+    pub fn new(
+        certificates: Vec<CertificateVerificationReport>,
+        trusted_checkpoint: Option<String>,
+        error: Option<String>,
+        duration: Duration,
+    ) -> Self {
+        let valid = error.is_none()
+            && !certificates.is_empty()
+            && certificates.iter().all(|report| report.is_valid());
+
+        Self {
+            valid,
+            certificates,
+            trusted_checkpoint,
+            error,
+            duration_us: duration.as_micros() as u64,
+        }
+    }
+}
+
+impl Display for CertificateChainVerificationReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Certificate chain is {}: {} certificate(s) verified in {} µs",
+            if self.valid { "valid" } else { "invalid" },
+            self.certificates.len(),
+            self.duration_us
+        )?;
+        for certificate in &self.certificates {
+            writeln!(
+                f,
+                "Certificate #{} @ epoch #{}, immutable file #{} ({} µs)",
+                certificate.certificate_hash,
+                certificate.epoch,
+                certificate.immutable_file_number,
+                certificate.duration_us
+            )?;
+            for check in &certificate.checks {
+                match &check.error {
+                    None => writeln!(f, "  [OK] {} ({} µs)", check.check, check.duration_us)?,
+                    Some(error) => writeln!(
+                        f,
+                        "  [KO] {} ({} µs): {}",
+                        check.check, check.duration_us, error
+                    )?,
+                }
+            }
+        }
+        if let Some(trusted_checkpoint) = &self.trusted_checkpoint {
+            writeln!(f, "Stopped at trusted checkpoint #{}", trusted_checkpoint)?;
+        }
+        if let Some(error) = &self.error {
+            writeln!(f, "Error: {}", error)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_data;
+
+    fn certificate_report(results: Vec<Result<(), &str>>) -> CertificateVerificationReport {
+        let mut report = CertificateVerificationReport::new(&fake_data::certificate(
+            "cert-hash-123".to_string(),
+        ));
+        for result in results {
+            report.add_check(VerificationCheck::Hash, result, Duration::from_micros(10));
+        }
+
+        report
+    }
+
+    #[test]
+    fn chain_is_valid_if_all_checks_passed() {
+        let report = CertificateChainVerificationReport::new(
+            vec![certificate_report(vec![Ok(()), Ok(())])],
+            None,
+            None,
+            Duration::from_millis(1),
+        );
+
+        assert!(report.valid);
+    }
+
+    #[test]
+    fn chain_is_invalid_if_a_check_failed_or_the_walk_stopped() {
+        let report = CertificateChainVerificationReport::new(
+            vec![
+                certificate_report(vec![Ok(())]),
+                certificate_report(vec![Ok(()), Err("invalid multi signature")]),
+            ],
+            None,
+            None,
+            Duration::from_millis(1),
+        );
+        assert!(!report.valid);
+
+        let report = CertificateChainVerificationReport::new(
+            vec![certificate_report(vec![Ok(())])],
+            None,
+            Some("certificate retriever error".to_string()),
+            Duration::from_millis(1),
+        );
+        assert!(!report.valid);
+    }
+
+    #[test]
+    fn render_report_as_text() {
+        let report = CertificateChainVerificationReport::new(
+            vec![certificate_report(vec![Err("hash unmatch")])],
+            None,
+            None,
+            Duration::from_millis(1),
+        );
+
+        let text = report.to_string();
+        assert!(text.starts_with("Certificate chain is invalid"));
+        assert!(text.contains("[KO] hash (10 µs): hash unmatch"));
+    }
+}
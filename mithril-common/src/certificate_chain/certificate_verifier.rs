@@ -3,15 +3,22 @@
 use async_trait::async_trait;
 use hex::{FromHex, ToHex};
 use slog::{debug, Logger};
+use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::Instant;
 use thiserror::Error;
 
-use super::{CertificateRetriever, CertificateRetrieverError};
+use super::{
+    CertificateChainVerificationReport, CertificateGenesisProducerError, CertificateRetriever,
+    CertificateRetrieverError, CertificateVerificationReport, GenesisKeyRotation,
+    VerificationCheck,
+};
 use crate::crypto_helper::{
-    key_decode_hex, ProtocolGenesisError, ProtocolGenesisSignature, ProtocolGenesisVerifier,
+    key_decode_hex, ProtocolGenesisError, ProtocolGenesisMultiSignature, ProtocolGenesisVerifier,
     ProtocolMultiSignature,
 };
 use crate::entities::{Certificate, ProtocolMessagePartKey, ProtocolParameters};
+use crate::protocol_version::{check_protocol_version, ProtocolVersionError};
 
 #[cfg(test)]
 use mockall::automock;
@@ -65,6 +72,24 @@ pub enum CertificateVerifierError {
     /// Error raised when validating the certificate chain if the chain loops.
     #[error("certificate chain infinite loop error")]
     CertificateChainInfiniteLoop,
+
+    /// Error raised when the genesis key rotation of a
+    /// [certificate](https://mithril.network/mithril-common/doc/mithril_common/entities/struct.Certificate.html)
+    /// can't be read or is not signed by the previous genesis key.
+    #[error("genesis key rotation error: '{0}'")]
+    GenesisKeyRotation(#[from] CertificateGenesisProducerError),
+
+    /// Error raised when a genesis key rotation is not trusted by the genesis verification key
+    /// used to verify the certificate chain: the key is in none of the rotations of the chain,
+    /// or a rotation doesn't rotate to the key of the following one.
+    #[error("genesis key rotation is not trusted by the genesis verification key")]
+    GenesisKeyRotationUntrusted,
+
+    /// Error raised when the protocol version of a
+    /// [certificate](https://mithril.network/mithril-common/doc/mithril_common/entities/struct.Certificate.html)
+    /// is not supported, so its format can't be trusted.
+    #[error("certificate protocol version error: '{0}'")]
+    ProtocolVersion(#[from] ProtocolVersionError),
 }
 
 /// CertificateVerifier is the cryptographic engine in charge of verifying multi signatures and certificates
@@ -94,10 +119,36 @@ pub trait CertificateVerifier: Send + Sync {
         certificate_retriever: Arc<dyn CertificateRetriever>,
     ) -> Result<Option<Certificate>, CertificateVerifierError>;
 
+    /// Verify Genesis Key Rotation certificate and returns the genesis verifier of the
+    /// Certificate Chain before the rotation
+    /// The genesis verifier must hold either the previous or the next genesis key of the
+    /// rotation: the certificate is signed with the next genesis key, and the rotation with
+    /// the previous one. The verifications of a certificate chain give the genesis key found
+    /// in the history of its rotations, so that a key rotated several times is still trusted.
+    async fn verify_genesis_key_rotation_certificate(
+        &self,
+        certificate: &Certificate,
+        genesis_key_rotation: &GenesisKeyRotation,
+        genesis_verifier: &ProtocolGenesisVerifier,
+    ) -> Result<ProtocolGenesisVerifier, CertificateVerifierError> {
+        if !genesis_key_rotation.is_trusted_by(genesis_verifier) {
+            return Err(CertificateVerifierError::GenesisKeyRotationUntrusted);
+        }
+        genesis_key_rotation.verify()?;
+        self.verify_genesis_certificate(
+            certificate,
+            &genesis_key_rotation.create_next_genesis_verifier(),
+        )
+        .await?;
+        Ok(genesis_key_rotation.create_previous_genesis_verifier())
+    }
+
     /// Verify if a Certificate is valid and returns the previous Certificate in the chain if exists
     /// Step 1: Check if the hash is valid (i.e. the Certificate has not been tampered by modifying its content)
     /// Step 2: Check that the multi signature is valid if it is a Standard Certificate (i.e verifiction of the Mithril multi signature)
     /// Step 3: Check that the aggregate verification key of the Certificate is registered in the previous Certificate in the chain
+    /// A Genesis Key Rotation Certificate is verified with the genesis keys of the rotation instead of steps 2 & 3: use
+    /// [GenesisKeyRotation::from_certificate] to get the genesis verifier of the previous Certificates in the chain
     async fn verify_certificate(
         &self,
         certificate: &Certificate,
@@ -113,17 +164,353 @@ pub trait CertificateVerifier: Send + Sync {
         genesis_verifier: &ProtocolGenesisVerifier,
     ) -> Result<(), CertificateVerifierError> {
         let mut certificate = certificate;
-        while let Some(previous_certificate) = self
-            .verify_certificate(
-                &certificate,
-                certificate_retriever.clone(),
-                genesis_verifier,
-            )
-            .await?
-        {
+        let mut genesis_key_history = GenesisKeyRotationHistory::new(genesis_verifier);
+        loop {
+            let genesis_verifier = genesis_key_history.get_genesis_verifier(&certificate)?;
+            match self
+                .verify_certificate(
+                    &certificate,
+                    certificate_retriever.clone(),
+                    &genesis_verifier,
+                )
+                .await?
+            {
+                Some(previous_certificate) => certificate = previous_certificate,
+                None => break,
+            }
+        }
+        genesis_key_history.check_trusted()
+    }
+
+    /// Verify that the Certificate Chain associated to a Certificate is valid up to a trusted
+    /// checkpoint, i.e. the hash of a certificate whose chain has already been verified.
+    /// The verification stops once the checkpoint is reached, or at the genesis certificate
+    /// if the checkpoint is not part of the chain.
+    async fn verify_certificate_chain_to_checkpoint(
+        &self,
+        certificate: Certificate,
+        certificate_retriever: Arc<dyn CertificateRetriever>,
+        genesis_verifier: &ProtocolGenesisVerifier,
+        trusted_checkpoint: &str,
+    ) -> Result<(), CertificateVerifierError> {
+        let mut certificate = certificate;
+        let mut genesis_key_history = GenesisKeyRotationHistory::new(genesis_verifier);
+        while !is_trusted_checkpoint(&certificate, trusted_checkpoint) {
+            let genesis_verifier = genesis_key_history.get_genesis_verifier(&certificate)?;
+            match self
+                .verify_certificate(
+                    &certificate,
+                    certificate_retriever.clone(),
+                    &genesis_verifier,
+                )
+                .await?
+            {
+                Some(previous_certificate) => certificate = previous_certificate,
+                None => break,
+            }
+        }
+        genesis_key_history.check_trusted()
+    }
+
+    /// Verify the Certificate Chain associated to a Certificate and report the outcome of
+    /// every check run on each certificate, with their duration.
+    /// The failure of a check doesn't stop the verification: the chain is walked back to the
+    /// genesis certificate, or to the trusted checkpoint, as long as the previous certificates
+    /// can be retrieved.
+    async fn verify_certificate_chain_with_report(
+        &self,
+        certificate: Certificate,
+        certificate_retriever: Arc<dyn CertificateRetriever>,
+        genesis_verifier: &ProtocolGenesisVerifier,
+        trusted_checkpoint: Option<&str>,
+    ) -> CertificateChainVerificationReport {
+        let chain_start = Instant::now();
+        let mut certificate_reports = Vec::new();
+        let mut verified_hashes = HashSet::new();
+        let mut certificate = certificate;
+        let mut genesis_key_history = GenesisKeyRotationHistory::new(genesis_verifier);
+
+        let error = loop {
+            let certificate_start = Instant::now();
+            let mut report = CertificateVerificationReport::new(&certificate);
+
+            let check_start = Instant::now();
+            report.add_check(
+                VerificationCheck::ProtocolVersion,
+                check_protocol_version(&certificate.metadata.protocol_version),
+                check_start.elapsed(),
+            );
+
+            let check_start = Instant::now();
+            report.add_check(
+                VerificationCheck::Hash,
+                check_certificate_hash(&certificate),
+                check_start.elapsed(),
+            );
+            let is_checkpoint = trusted_checkpoint
+                .map(|trusted_checkpoint| is_trusted_checkpoint(&certificate, trusted_checkpoint))
+                .unwrap_or(false);
+            if is_checkpoint {
+                report.duration_us = certificate_start.elapsed().as_micros() as u64;
+                certificate_reports.push(report);
+                break None;
+            }
+            if !verified_hashes.insert(certificate.hash.clone()) {
+                break Some(CertificateVerifierError::CertificateChainInfiniteLoop.to_string());
+            }
+
+            if certificate.previous_hash.is_empty() {
+                let check_start = Instant::now();
+                let result = match genesis_key_history.get_genesis_verifier(&certificate) {
+                    Ok(genesis_verifier) => {
+                        self.verify_genesis_certificate(&certificate, &genesis_verifier)
+                            .await
+                    }
+                    Err(error) => Err(error),
+                };
+                report.add_check(VerificationCheck::Genesis, result, check_start.elapsed());
+                report.duration_us = certificate_start.elapsed().as_micros() as u64;
+                certificate_reports.push(report);
+                break None;
+            }
+
+            let check_start = Instant::now();
+            let is_genesis_key_rotation = match GenesisKeyRotation::from_certificate(&certificate) {
+                Ok(Some(genesis_key_rotation)) => {
+                    let result = match genesis_key_history.get_genesis_verifier(&certificate) {
+                        Ok(genesis_verifier) => self
+                            .verify_genesis_key_rotation_certificate(
+                                &certificate,
+                                &genesis_key_rotation,
+                                &genesis_verifier,
+                            )
+                            .await
+                            .map(|_| ()),
+                        Err(error) => Err(error),
+                    };
+                    report.add_check(VerificationCheck::Genesis, result, check_start.elapsed());
+                    true
+                }
+                Ok(None) => {
+                    let result = self.verify_multi_signature(
+                        certificate.signed_message.as_bytes(),
+                        &certificate.multi_signature,
+                        &certificate.aggregate_verification_key,
+                        &certificate.metadata.protocol_parameters,
+                    );
+                    report.add_check(
+                        VerificationCheck::MultiSignature,
+                        result,
+                        check_start.elapsed(),
+                    );
+                    false
+                }
+                Err(error) => {
+                    report.add_check(
+                        VerificationCheck::Genesis,
+                        Err(CertificateVerifierError::from(error)),
+                        check_start.elapsed(),
+                    );
+                    report.duration_us = certificate_start.elapsed().as_micros() as u64;
+                    certificate_reports.push(report);
+                    break None;
+                }
+            };
+
+            let previous_certificate = match certificate_retriever
+                .get_certificate_details(&certificate.previous_hash)
+                .await
+            {
+                Ok(previous_certificate) => previous_certificate,
+                Err(error) => {
+                    report.duration_us = certificate_start.elapsed().as_micros() as u64;
+                    certificate_reports.push(report);
+                    break Some(CertificateVerifierError::from(error).to_string());
+                }
+            };
+
+            let check_start = Instant::now();
+            report.add_check(
+                VerificationCheck::PreviousHash,
+                check_previous_hash(&certificate, &previous_certificate),
+                check_start.elapsed(),
+            );
+
+            // a genesis key rotation restarts the chain: the AVK is not linked to the previous
+            // certificate
+            if is_genesis_key_rotation {
+                report.duration_us = certificate_start.elapsed().as_micros() as u64;
+                certificate_reports.push(report);
+                certificate = previous_certificate;
+                continue;
+            }
+
+            let check_start = Instant::now();
+            let avk_linkage =
+                check_aggregate_verification_key_linkage(&certificate, &previous_certificate);
+            let is_chain_continued = matches!(avk_linkage, Ok(true));
+            report.add_check(
+                VerificationCheck::AvkLinkage,
+                avk_linkage.map(|_| ()),
+                check_start.elapsed(),
+            );
+
+            report.duration_us = certificate_start.elapsed().as_micros() as u64;
+            certificate_reports.push(report);
+            if !is_chain_continued {
+                break None;
+            }
             certificate = previous_certificate;
+        };
+        let error = error.or_else(|| {
+            genesis_key_history
+                .check_trusted()
+                .err()
+                .map(|error| error.to_string())
+        });
+
+        let reached_checkpoint = match (certificate_reports.last(), trusted_checkpoint) {
+            (Some(report), Some(trusted_checkpoint)) if error.is_none() => {
+                report.certificate_hash == trusted_checkpoint && report.is_valid()
+            }
+            _ => false,
+        };
+        CertificateChainVerificationReport::new(
+            certificate_reports,
+            trusted_checkpoint
+                .filter(|_| reached_checkpoint)
+                .map(|trusted_checkpoint| trusted_checkpoint.to_string()),
+            error,
+            chain_start.elapsed(),
+        )
+    }
+}
+
+/// Check that the hash of a certificate matches its content
+fn check_certificate_hash(certificate: &Certificate) -> Result<(), CertificateVerifierError> {
+    match certificate.hash == certificate.compute_hash() {
+        true => Ok(()),
+        false => Err(CertificateVerifierError::CertificateHashUnmatch),
+    }
+}
+
+/// Check that the certificate retrieved with the `previous_hash` of a certificate has this hash
+fn check_previous_hash(
+    certificate: &Certificate,
+    previous_certificate: &Certificate,
+) -> Result<(), CertificateVerifierError> {
+    match previous_certificate.hash == certificate.previous_hash {
+        true => Ok(()),
+        false => Err(CertificateVerifierError::CertificateChainPreviousHashUnmatch),
+    }
+}
+
+/// Check that the aggregate verification key of a standard certificate is registered in the
+/// previous certificate: as its aggregate verification key if they are on the same epoch, or
+/// as its next aggregate verification key otherwise.
+///
+/// Returns `false` if the previous certificate registers no next aggregate verification key, the
+/// verification of the chain ends there.
+fn check_aggregate_verification_key_linkage(
+    certificate: &Certificate,
+    previous_certificate: &Certificate,
+) -> Result<bool, CertificateVerifierError> {
+    let is_same_epoch = previous_certificate.beacon.epoch == certificate.beacon.epoch;
+    match previous_certificate
+        .protocol_message
+        .get_message_part(&ProtocolMessagePartKey::NextAggregateVerificationKey)
+    {
+        None => Ok(false),
+        Some(next_aggregate_verification_key)
+            if !is_same_epoch
+                && next_aggregate_verification_key == &certificate.aggregate_verification_key =>
+        {
+            Ok(true)
+        }
+        Some(_)
+            if is_same_epoch
+                && previous_certificate.aggregate_verification_key
+                    == certificate.aggregate_verification_key =>
+        {
+            Ok(true)
+        }
+        Some(_) => Err(CertificateVerifierError::CertificateChainAVKUnmatch),
+    }
+}
+
+/// Check that a certificate is the trusted checkpoint, the hash is recomputed as a tampered
+/// certificate could claim the checkpoint hash
+fn is_trusted_checkpoint(certificate: &Certificate, trusted_checkpoint: &str) -> bool {
+    certificate.hash == trusted_checkpoint && certificate.compute_hash() == trusted_checkpoint
+}
+
+/// History of the genesis key rotations met while walking a certificate chain back from its
+/// most recent certificate.
+///
+/// Each rotation is signed with its previous genesis key, so the chain is trusted by a genesis
+/// key found anywhere in this history, however many rotations happened since.
+struct GenesisKeyRotationHistory {
+    /// Genesis verifier trusted to verify the chain
+    trusted_genesis_verifier: ProtocolGenesisVerifier,
+
+    /// Genesis verifier of the certificates before the oldest rotation met, if any
+    current_genesis_verifier: Option<ProtocolGenesisVerifier>,
+
+    /// Is the trusted genesis verifier one of the keys of the rotations met
+    is_trusted_genesis_verifier_met: bool,
+}
+
+impl GenesisKeyRotationHistory {
+    fn new(trusted_genesis_verifier: &ProtocolGenesisVerifier) -> Self {
+        Self {
+            trusted_genesis_verifier: trusted_genesis_verifier.to_owned(),
+            current_genesis_verifier: None,
+            is_trusted_genesis_verifier_met: false,
+        }
+    }
+
+    /// Return the genesis verifier of a certificate, the certificates of the chain must be given
+    /// from the most recent to the oldest
+    fn get_genesis_verifier(
+        &mut self,
+        certificate: &Certificate,
+    ) -> Result<ProtocolGenesisVerifier, CertificateVerifierError> {
+        let genesis_key_rotation = match GenesisKeyRotation::from_certificate(certificate)? {
+            Some(genesis_key_rotation) => genesis_key_rotation,
+            None => {
+                return Ok(self
+                    .current_genesis_verifier
+                    .clone()
+                    .unwrap_or_else(|| self.trusted_genesis_verifier.clone()))
+            }
+        };
+        let next_genesis_verifier = genesis_key_rotation.create_next_genesis_verifier();
+        match &self.current_genesis_verifier {
+            Some(current_genesis_verifier)
+                if current_genesis_verifier != &next_genesis_verifier =>
+            {
+                return Err(CertificateVerifierError::GenesisKeyRotationUntrusted);
+            }
+            Some(_) => {}
+            None => {
+                self.is_trusted_genesis_verifier_met |=
+                    next_genesis_verifier == self.trusted_genesis_verifier;
+            }
+        }
+        self.is_trusted_genesis_verifier_met |=
+            genesis_key_rotation.previous_genesis_verifier == self.trusted_genesis_verifier;
+        self.current_genesis_verifier =
+            Some(genesis_key_rotation.create_previous_genesis_verifier());
+
+        Ok(next_genesis_verifier)
+    }
+
+    /// Check that the rotations met are trusted by the trusted genesis verifier
+    fn check_trusted(&self) -> Result<(), CertificateVerifierError> {
+        match self.current_genesis_verifier.is_none() || self.is_trusted_genesis_verifier_met {
+            true => Ok(()),
+            false => Err(CertificateVerifierError::GenesisKeyRotationUntrusted),
         }
-        Ok(())
     }
 }
 
@@ -175,16 +562,19 @@ impl CertificateVerifier for MithrilCertificateVerifier {
         certificate: &Certificate,
         genesis_verifier: &ProtocolGenesisVerifier,
     ) -> Result<(), CertificateVerifierError> {
-        println!(
+        debug!(
+            self.logger,
             "Verify genesis certificate #{} @ epoch #{}",
-            certificate.hash, certificate.beacon.epoch
+            certificate.hash,
+            certificate.beacon.epoch
         );
-        let genesis_signature = ProtocolGenesisSignature::from_bytes(
+        let genesis_signature = ProtocolGenesisMultiSignature::from_bytes(
             &Vec::from_hex(&certificate.genesis_signature)
                 .map_err(|e| CertificateVerifierError::Codec(e.to_string()))?,
         )
         .map_err(|e| CertificateVerifierError::CodecGenesis(e.to_string()))?;
-        genesis_verifier.verify(certificate.signed_message.as_bytes(), &genesis_signature)?;
+        genesis_verifier
+            .verify_multi_signature(certificate.signed_message.as_bytes(), &genesis_signature)?;
         Ok(())
     }
 
@@ -194,9 +584,11 @@ impl CertificateVerifier for MithrilCertificateVerifier {
         certificate: &Certificate,
         certificate_retriever: Arc<dyn CertificateRetriever>,
     ) -> Result<Option<Certificate>, CertificateVerifierError> {
-        println!(
+        debug!(
+            self.logger,
             "Verify standard certificate #{} @ epoch #{}",
-            certificate.hash, certificate.beacon.epoch
+            certificate.hash,
+            certificate.beacon.epoch
         );
         self.verify_multi_signature(
             certificate.signed_message.as_bytes(),
@@ -207,41 +599,17 @@ impl CertificateVerifier for MithrilCertificateVerifier {
         let previous_certificate = certificate_retriever
             .get_certificate_details(&certificate.previous_hash)
             .await?;
-        let valid_certificate_has_different_epoch_as_previous =
-            |next_aggregate_verification_key: &String| -> bool {
-                next_aggregate_verification_key == &certificate.aggregate_verification_key
-                    && previous_certificate.beacon.epoch != certificate.beacon.epoch
-            };
-        let valid_certificate_has_same_epoch_as_previous = || -> bool {
-            previous_certificate.aggregate_verification_key
-                == certificate.aggregate_verification_key
-                && previous_certificate.beacon.epoch == certificate.beacon.epoch
-        };
-        if previous_certificate.hash != certificate.previous_hash {
-            return Err(CertificateVerifierError::CertificateChainPreviousHashUnmatch);
-        }
+        check_previous_hash(certificate, &previous_certificate)?;
 
-        match &previous_certificate
-            .protocol_message
-            .get_message_part(&ProtocolMessagePartKey::NextAggregateVerificationKey)
-        {
-            Some(next_aggregate_verification_key)
-                if valid_certificate_has_different_epoch_as_previous(
-                    *next_aggregate_verification_key,
-                ) =>
-            {
-                Ok(Some(previous_certificate.to_owned()))
-            }
-            Some(_) if valid_certificate_has_same_epoch_as_previous() => {
-                Ok(Some(previous_certificate.to_owned()))
-            }
-            None => Ok(None),
-            _ => {
+        match check_aggregate_verification_key_linkage(certificate, &previous_certificate) {
+            Ok(true) => Ok(Some(previous_certificate)),
+            Ok(false) => Ok(None),
+            Err(error) => {
                 debug!(
                     self.logger,
                     "Previous certificate {:#?}", previous_certificate
                 );
-                Err(CertificateVerifierError::CertificateChainAVKUnmatch)
+                Err(error)
             }
         }
     }
@@ -261,11 +629,8 @@ impl CertificateVerifier for MithrilCertificateVerifier {
             "certificate_beacon" => ?certificate.beacon
         );
 
-        certificate
-            .hash
-            .eq(&certificate.compute_hash())
-            .then(|| certificate.hash.clone())
-            .ok_or(CertificateVerifierError::CertificateHashUnmatch)?;
+        check_protocol_version(&certificate.metadata.protocol_version)?;
+        check_certificate_hash(certificate)?;
         match certificate.previous_hash.as_str() {
             "" => {
                 self.verify_genesis_certificate(certificate, genesis_verifier)
@@ -275,10 +640,25 @@ impl CertificateVerifier for MithrilCertificateVerifier {
             previous_hash if previous_hash == certificate.hash => {
                 Err(CertificateVerifierError::CertificateChainInfiniteLoop)
             }
-            _ => {
-                self.verify_standard_certificate(certificate, certificate_retriever)
-                    .await
-            }
+            _ => match GenesisKeyRotation::from_certificate(certificate)? {
+                Some(genesis_key_rotation) => {
+                    self.verify_genesis_key_rotation_certificate(
+                        certificate,
+                        &genesis_key_rotation,
+                        genesis_verifier,
+                    )
+                    .await?;
+                    let previous_certificate = certificate_retriever
+                        .get_certificate_details(&certificate.previous_hash)
+                        .await?;
+                    check_previous_hash(certificate, &previous_certificate)?;
+                    Ok(Some(previous_certificate))
+                }
+                None => {
+                    self.verify_standard_certificate(certificate, certificate_retriever)
+                        .await
+                }
+            },
         }
     }
 }
@@ -292,8 +672,12 @@ mod tests {
     use super::CertificateRetriever;
     use super::*;
 
+    use crate::certificate_chain::CertificateGenesisProducer;
     use crate::crypto_helper::tests_setup::*;
-    use crate::crypto_helper::{key_encode_hex, ProtocolClerk};
+    use crate::crypto_helper::{
+        key_encode_hex, ProtocolAggregateVerificationKey, ProtocolClerk, ProtocolGenesisPolicy,
+        ProtocolGenesisSigner,
+    };
 
     mock! {
         pub CertificateRetrieverImpl { }
@@ -433,6 +817,32 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_verify_certificate_ko_unsupported_protocol_version() {
+        let (fake_certificates, genesis_verifier) = setup_certificate_chain(5, 1);
+        let mut fake_certificate1 = fake_certificates[0].clone();
+        fake_certificate1.metadata.protocol_version = "99.0.0".to_string();
+        fake_certificate1.hash = fake_certificate1.compute_hash();
+        let verifier = MithrilCertificateVerifier::new(slog_scope::logger());
+        let verify = verifier
+            .verify_certificate(
+                &fake_certificate1,
+                Arc::new(MockCertificateRetrieverImpl::new()),
+                &genesis_verifier,
+            )
+            .await;
+        assert!(
+            matches!(
+                verify,
+                Err(CertificateVerifierError::ProtocolVersion(
+                    ProtocolVersionError::Incompatible { .. }
+                ))
+            ),
+            "unexpected error type: {:?}",
+            verify
+        );
+    }
+
     #[tokio::test]
     async fn test_verify_certificate_ko_certificate_chain_avk_unmatch() {
         let total_certificates = 5;
@@ -560,4 +970,505 @@ mod tests {
             verify
         );
     }
+
+    #[tokio::test]
+    async fn test_verify_certificate_chain_to_checkpoint_stops_at_checkpoint() {
+        let total_certificates = 15;
+        let certificates_per_epoch = 2;
+        let index_checkpoint = 4;
+        let (fake_certificates, genesis_verifier) =
+            setup_certificate_chain(total_certificates, certificates_per_epoch);
+        let trusted_checkpoint = fake_certificates[index_checkpoint].hash.clone();
+        let mut mock_certificate_retriever = MockCertificateRetrieverImpl::new();
+        let certificate_to_verify = fake_certificates[0].clone();
+        for fake_certificate in fake_certificates.into_iter().skip(1).take(index_checkpoint) {
+            mock_certificate_retriever
+                .expect_get_certificate_details()
+                .returning(move |_| Ok(fake_certificate.clone()))
+                .times(1);
+        }
+        let verifier = MithrilCertificateVerifier::new(slog_scope::logger());
+        let verify = verifier
+            .verify_certificate_chain_to_checkpoint(
+                certificate_to_verify,
+                Arc::new(mock_certificate_retriever),
+                &genesis_verifier,
+                &trusted_checkpoint,
+            )
+            .await;
+        verify.expect("unexpected error");
+    }
+
+    #[tokio::test]
+    async fn test_verify_certificate_chain_to_checkpoint_ok_unknown_checkpoint() {
+        let total_certificates = 15;
+        let certificates_per_epoch = 2;
+        let (fake_certificates, genesis_verifier) =
+            setup_certificate_chain(total_certificates, certificates_per_epoch);
+        let mut mock_certificate_retriever = MockCertificateRetrieverImpl::new();
+        let certificate_to_verify = fake_certificates[0].clone();
+        for fake_certificate in fake_certificates.into_iter().skip(1) {
+            mock_certificate_retriever
+                .expect_get_certificate_details()
+                .returning(move |_| Ok(fake_certificate.clone()))
+                .times(1);
+        }
+        let verifier = MithrilCertificateVerifier::new(slog_scope::logger());
+        let verify = verifier
+            .verify_certificate_chain_to_checkpoint(
+                certificate_to_verify,
+                Arc::new(mock_certificate_retriever),
+                &genesis_verifier,
+                "unknown-checkpoint",
+            )
+            .await;
+        verify.expect("unexpected error");
+    }
+
+    #[tokio::test]
+    async fn test_verify_certificate_chain_to_checkpoint_ko_tampered_checkpoint() {
+        let total_certificates = 15;
+        let certificates_per_epoch = 2;
+        let index_checkpoint = 4;
+        let (mut fake_certificates, genesis_verifier) =
+            setup_certificate_chain(total_certificates, certificates_per_epoch);
+        let trusted_checkpoint = fake_certificates[index_checkpoint].hash.clone();
+        fake_certificates[index_checkpoint].signed_message = "tampered-message".to_string();
+        let mut mock_certificate_retriever = MockCertificateRetrieverImpl::new();
+        let certificate_to_verify = fake_certificates[0].clone();
+        for fake_certificate in fake_certificates.into_iter().skip(1).take(index_checkpoint) {
+            mock_certificate_retriever
+                .expect_get_certificate_details()
+                .returning(move |_| Ok(fake_certificate.clone()))
+                .times(1);
+        }
+        let verifier = MithrilCertificateVerifier::new(slog_scope::logger());
+        let verify = verifier
+            .verify_certificate_chain_to_checkpoint(
+                certificate_to_verify,
+                Arc::new(mock_certificate_retriever),
+                &genesis_verifier,
+                &trusted_checkpoint,
+            )
+            .await;
+        assert!(
+            matches!(
+                verify,
+                Err(CertificateVerifierError::CertificateHashUnmatch)
+            ),
+            "unexpected error type: {:?}",
+            verify
+        );
+    }
+
+    fn build_certificate_retriever(certificates: &[Certificate]) -> MockCertificateRetrieverImpl {
+        let certificates = certificates.to_vec();
+        let mut mock_certificate_retriever = MockCertificateRetrieverImpl::new();
+        mock_certificate_retriever
+            .expect_get_certificate_details()
+            .returning(move |certificate_hash| {
+                certificates
+                    .iter()
+                    .find(|certificate| certificate.hash == certificate_hash)
+                    .cloned()
+                    .ok_or_else(|| CertificateRetrieverError::General("not found".to_string()))
+            });
+
+        mock_certificate_retriever
+    }
+
+    #[tokio::test]
+    async fn test_verify_certificate_chain_with_report_ok() {
+        let (fake_certificates, genesis_verifier) = setup_certificate_chain(15, 2);
+        let certificate_to_verify = fake_certificates[0].clone();
+        let verifier = MithrilCertificateVerifier::new(slog_scope::logger());
+        let report = verifier
+            .verify_certificate_chain_with_report(
+                certificate_to_verify,
+                Arc::new(build_certificate_retriever(&fake_certificates)),
+                &genesis_verifier,
+                None,
+            )
+            .await;
+
+        assert!(report.valid, "unexpected report: {}", report);
+        assert_eq!(fake_certificates.len(), report.certificates.len());
+        assert_eq!(
+            vec![
+                VerificationCheck::ProtocolVersion,
+                VerificationCheck::Hash,
+                VerificationCheck::Genesis
+            ],
+            report
+                .certificates
+                .last()
+                .unwrap()
+                .checks
+                .iter()
+                .map(|check| check.check)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verify_certificate_chain_with_report_does_not_stop_at_first_failure() {
+        let (mut fake_certificates, genesis_verifier) = setup_certificate_chain(15, 2);
+        let index_certificate_fail = 3;
+        fake_certificates[index_certificate_fail].signed_message = "tampered-message".to_string();
+        let certificate_to_verify = fake_certificates[0].clone();
+        let verifier = MithrilCertificateVerifier::new(slog_scope::logger());
+        let report = verifier
+            .verify_certificate_chain_with_report(
+                certificate_to_verify,
+                Arc::new(build_certificate_retriever(&fake_certificates)),
+                &genesis_verifier,
+                None,
+            )
+            .await;
+
+        assert!(!report.valid);
+        assert_eq!(fake_certificates.len(), report.certificates.len());
+        let failed_checks: Vec<VerificationCheck> = report.certificates[index_certificate_fail]
+            .checks
+            .iter()
+            .filter(|check| !check.is_passed())
+            .map(|check| check.check)
+            .collect();
+        assert_eq!(
+            vec![VerificationCheck::Hash, VerificationCheck::MultiSignature],
+            failed_checks
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verify_certificate_chain_with_report_stops_at_checkpoint() {
+        let (fake_certificates, genesis_verifier) = setup_certificate_chain(15, 2);
+        let trusted_checkpoint = fake_certificates[4].hash.clone();
+        let certificate_to_verify = fake_certificates[0].clone();
+        let verifier = MithrilCertificateVerifier::new(slog_scope::logger());
+        let report = verifier
+            .verify_certificate_chain_with_report(
+                certificate_to_verify,
+                Arc::new(build_certificate_retriever(&fake_certificates)),
+                &genesis_verifier,
+                Some(&trusted_checkpoint),
+            )
+            .await;
+
+        assert!(report.valid, "unexpected report: {}", report);
+        assert_eq!(5, report.certificates.len());
+        assert_eq!(Some(trusted_checkpoint), report.trusted_checkpoint);
+    }
+
+    #[tokio::test]
+    async fn test_verify_certificate_chain_with_report_agrees_with_verification_without_next_avk() {
+        let (mut fake_certificates, genesis_verifier) = setup_certificate_chain(5, 1);
+        fake_certificates[2]
+            .protocol_message
+            .message_parts
+            .remove(&ProtocolMessagePartKey::NextAggregateVerificationKey);
+        for index in (0..=2).rev() {
+            fake_certificates[index].hash = fake_certificates[index].compute_hash();
+            if index > 0 {
+                fake_certificates[index - 1].previous_hash = fake_certificates[index].hash.clone();
+            }
+        }
+        let certificate_to_verify = fake_certificates[0].clone();
+        let verifier = MithrilCertificateVerifier::new(slog_scope::logger());
+        verifier
+            .verify_certificate_chain(
+                certificate_to_verify.clone(),
+                Arc::new(build_certificate_retriever(&fake_certificates)),
+                &genesis_verifier,
+            )
+            .await
+            .expect("the verification should end without error at the missing next AVK");
+        let report = verifier
+            .verify_certificate_chain_with_report(
+                certificate_to_verify,
+                Arc::new(build_certificate_retriever(&fake_certificates)),
+                &genesis_verifier,
+                None,
+            )
+            .await;
+
+        assert!(report.valid, "unexpected report: {}", report);
+        assert_eq!(2, report.certificates.len());
+    }
+
+    #[tokio::test]
+    async fn test_verify_certificate_chain_with_report_ko_missing_certificate() {
+        let (mut fake_certificates, genesis_verifier) = setup_certificate_chain(15, 2);
+        fake_certificates.remove(6);
+        let certificate_to_verify = fake_certificates[0].clone();
+        let verifier = MithrilCertificateVerifier::new(slog_scope::logger());
+        let report = verifier
+            .verify_certificate_chain_with_report(
+                certificate_to_verify,
+                Arc::new(build_certificate_retriever(&fake_certificates)),
+                &genesis_verifier,
+                None,
+            )
+            .await;
+
+        assert!(!report.valid);
+        assert_eq!(6, report.certificates.len());
+        assert!(report.error.is_some());
+    }
+
+    fn create_genesis_key_rotation_certificate(
+        previous_certificate: &Certificate,
+        previous_genesis_signer: &ProtocolGenesisSigner,
+        next_genesis_signer: &ProtocolGenesisSigner,
+    ) -> Certificate {
+        let previous_genesis_verifier = previous_genesis_signer.create_genesis_verifier();
+        let next_genesis_verifier = next_genesis_signer.create_genesis_verifier();
+        let rotation_protocol_message = GenesisKeyRotation::create_rotation_protocol_message(
+            &previous_genesis_verifier,
+            &next_genesis_verifier,
+        )
+        .unwrap();
+        let genesis_key_rotation = GenesisKeyRotation::new(
+            previous_genesis_verifier,
+            next_genesis_verifier,
+            previous_genesis_signer
+                .sign(rotation_protocol_message.compute_hash().as_bytes())
+                .into(),
+        );
+        let genesis_avk: ProtocolAggregateVerificationKey =
+            key_decode_hex(&previous_certificate.aggregate_verification_key).unwrap();
+        let protocol_message =
+            CertificateGenesisProducer::create_genesis_key_rotation_protocol_message(
+                &genesis_avk,
+                &genesis_key_rotation,
+            )
+            .unwrap();
+
+        CertificateGenesisProducer::create_genesis_key_rotation_certificate(
+            previous_certificate.metadata.protocol_parameters.clone(),
+            previous_certificate.beacon.clone(),
+            previous_certificate.hash.clone(),
+            genesis_avk,
+            &genesis_key_rotation,
+            next_genesis_signer
+                .sign(protocol_message.compute_hash().as_bytes())
+                .into(),
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_verify_certificate_chain_ok_through_genesis_key_rotation() {
+        let (mut fake_certificates, _) = setup_certificate_chain(5, 2);
+        let previous_genesis_signer = ProtocolGenesisSigner::create_deterministic_genesis_signer();
+        let next_genesis_signer = ProtocolGenesisSigner::create_non_deterministic_genesis_signer();
+        let rotation_certificate = create_genesis_key_rotation_certificate(
+            &fake_certificates[0],
+            &previous_genesis_signer,
+            &next_genesis_signer,
+        );
+        fake_certificates.insert(0, rotation_certificate.clone());
+        let verifier = MithrilCertificateVerifier::new(slog_scope::logger());
+
+        for genesis_verifier in [
+            next_genesis_signer.create_genesis_verifier(),
+            previous_genesis_signer.create_genesis_verifier(),
+        ] {
+            verifier
+                .verify_certificate_chain(
+                    rotation_certificate.clone(),
+                    Arc::new(build_certificate_retriever(&fake_certificates)),
+                    &genesis_verifier,
+                )
+                .await
+                .expect("the certificate chain should be valid through the rotation");
+            let report = verifier
+                .verify_certificate_chain_with_report(
+                    rotation_certificate.clone(),
+                    Arc::new(build_certificate_retriever(&fake_certificates)),
+                    &genesis_verifier,
+                    None,
+                )
+                .await;
+            assert!(report.valid, "unexpected report: {}", report);
+            assert_eq!(fake_certificates.len(), report.certificates.len());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verify_certificate_chain_ok_through_several_genesis_key_rotations() {
+        let (mut fake_certificates, _) = setup_certificate_chain(5, 2);
+        let genesis_signers = [
+            ProtocolGenesisSigner::create_deterministic_genesis_signer(),
+            ProtocolGenesisSigner::create_non_deterministic_genesis_signer(),
+            ProtocolGenesisSigner::create_non_deterministic_genesis_signer(),
+        ];
+        for rotated_genesis_signers in genesis_signers.windows(2) {
+            let rotation_certificate = create_genesis_key_rotation_certificate(
+                &fake_certificates[0],
+                &rotated_genesis_signers[0],
+                &rotated_genesis_signers[1],
+            );
+            fake_certificates.insert(0, rotation_certificate);
+        }
+        let certificate_to_verify = fake_certificates[0].clone();
+        let verifier = MithrilCertificateVerifier::new(slog_scope::logger());
+
+        for genesis_signer in &genesis_signers {
+            let genesis_verifier = genesis_signer.create_genesis_verifier();
+            verifier
+                .verify_certificate_chain(
+                    certificate_to_verify.clone(),
+                    Arc::new(build_certificate_retriever(&fake_certificates)),
+                    &genesis_verifier,
+                )
+                .await
+                .expect("the certificate chain should be valid through the rotations");
+            let report = verifier
+                .verify_certificate_chain_with_report(
+                    certificate_to_verify.clone(),
+                    Arc::new(build_certificate_retriever(&fake_certificates)),
+                    &genesis_verifier,
+                    None,
+                )
+                .await;
+            assert!(report.valid, "unexpected report: {}", report);
+        }
+
+        let other_genesis_verifier =
+            ProtocolGenesisSigner::create_non_deterministic_genesis_signer()
+                .create_genesis_verifier();
+        let error = verifier
+            .verify_certificate_chain(
+                certificate_to_verify,
+                Arc::new(build_certificate_retriever(&fake_certificates)),
+                &other_genesis_verifier,
+            )
+            .await
+            .expect_err("verify_certificate_chain should fail");
+        assert!(
+            matches!(error, CertificateVerifierError::GenesisKeyRotationUntrusted),
+            "unexpected error: {:?}",
+            error
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verify_certificate_chain_ko_genesis_key_rotation_untrusted() {
+        let (mut fake_certificates, _) = setup_certificate_chain(5, 2);
+        let rotation_certificate = create_genesis_key_rotation_certificate(
+            &fake_certificates[0],
+            &ProtocolGenesisSigner::create_deterministic_genesis_signer(),
+            &ProtocolGenesisSigner::create_non_deterministic_genesis_signer(),
+        );
+        fake_certificates.insert(0, rotation_certificate.clone());
+        let other_genesis_verifier =
+            ProtocolGenesisSigner::create_non_deterministic_genesis_signer()
+                .create_genesis_verifier();
+        let verifier = MithrilCertificateVerifier::new(slog_scope::logger());
+
+        let error = verifier
+            .verify_certificate_chain(
+                rotation_certificate,
+                Arc::new(build_certificate_retriever(&fake_certificates)),
+                &other_genesis_verifier,
+            )
+            .await
+            .expect_err("verify_certificate_chain should fail");
+        assert!(
+            matches!(error, CertificateVerifierError::GenesisKeyRotationUntrusted),
+            "unexpected error: {:?}",
+            error
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verify_certificate_chain_ko_genesis_key_rotation_from_another_genesis_key() {
+        let (mut fake_certificates, _) = setup_certificate_chain(5, 2);
+        let forged_genesis_signer =
+            ProtocolGenesisSigner::create_non_deterministic_genesis_signer();
+        let next_genesis_signer = ProtocolGenesisSigner::create_non_deterministic_genesis_signer();
+        let rotation_certificate = create_genesis_key_rotation_certificate(
+            &fake_certificates[0],
+            &forged_genesis_signer,
+            &next_genesis_signer,
+        );
+        fake_certificates.insert(0, rotation_certificate.clone());
+        let verifier = MithrilCertificateVerifier::new(slog_scope::logger());
+
+        let error = verifier
+            .verify_certificate_chain(
+                rotation_certificate,
+                Arc::new(build_certificate_retriever(&fake_certificates)),
+                &next_genesis_signer.create_genesis_verifier(),
+            )
+            .await
+            .expect_err("verify_certificate_chain should fail");
+        assert!(
+            matches!(error, CertificateVerifierError::CertificateGenesis(_)),
+            "unexpected error: {:?}",
+            error
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verify_genesis_certificate_with_genesis_policy() {
+        let (fake_certificates, _) = setup_certificate_chain(5, 2);
+        let mut genesis_certificate = fake_certificates.last().unwrap().clone();
+        let genesis_signers = (0..3)
+            .map(|_| ProtocolGenesisSigner::create_non_deterministic_genesis_signer())
+            .collect::<Vec<_>>();
+        let genesis_policy = ProtocolGenesisPolicy::new(
+            2,
+            genesis_signers
+                .iter()
+                .map(|signer| {
+                    key_encode_hex(
+                        signer
+                            .create_genesis_verifier()
+                            .to_verification_key()
+                            .unwrap(),
+                    )
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap(),
+        );
+        let genesis_verifier = ProtocolGenesisVerifier::from_policy(&genesis_policy).unwrap();
+        let sign = |signers: &[ProtocolGenesisSigner]| {
+            ProtocolGenesisMultiSignature::new(
+                signers
+                    .iter()
+                    .map(|signer| signer.sign(genesis_certificate.signed_message.as_bytes()))
+                    .collect(),
+            )
+            .to_bytes()
+            .encode_hex::<String>()
+        };
+        let verifier = MithrilCertificateVerifier::new(slog_scope::logger());
+
+        genesis_certificate.genesis_signature = sign(&genesis_signers[1..]);
+        verifier
+            .verify_genesis_certificate(&genesis_certificate, &genesis_verifier)
+            .await
+            .expect("a genesis certificate signed by 2 of 3 custodians should be valid");
+
+        genesis_certificate.genesis_signature = sign(&genesis_signers[..1]);
+        let error = verifier
+            .verify_genesis_certificate(&genesis_certificate, &genesis_verifier)
+            .await
+            .expect_err("a genesis certificate signed by 1 of 3 custodians should be invalid");
+        assert!(
+            matches!(
+                error,
+                CertificateVerifierError::CertificateGenesis(
+                    ProtocolGenesisError::ThresholdNotReached {
+                        valid: 1,
+                        threshold: 2
+                    }
+                )
+            ),
+            "unexpected error: {:?}",
+            error
+        );
+    }
 }
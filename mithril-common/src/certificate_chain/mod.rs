@@ -1,11 +1,24 @@
 //! Tools to retrieve, validate the Certificate Chain created by an aggregator
 
+mod certificate_bundle;
+mod certificate_checkpoint_store;
 mod certificate_genesis;
 mod certificate_retriever;
+mod certificate_verification_report;
 mod certificate_verifier;
 
-pub use certificate_genesis::{CertificateGenesisProducer, CertificateGenesisProducerError};
+pub use certificate_bundle::{
+    CertificateBundleRetriever, CertificateChainBundle, CertificateChainBundleError,
+};
+pub use certificate_checkpoint_store::{CertificateCheckpoint, CertificateCheckpointStore};
+pub use certificate_genesis::{
+    CertificateGenesisProducer, CertificateGenesisProducerError, GenesisKeyRotation,
+};
 pub use certificate_retriever::{CertificateRetriever, CertificateRetrieverError};
+pub use certificate_verification_report::{
+    CertificateChainVerificationReport, CertificateVerificationReport, CheckReport,
+    VerificationCheck,
+};
 pub use certificate_verifier::{
     CertificateVerifier, CertificateVerifierError, MithrilCertificateVerifier,
 };
@@ -1,10 +1,17 @@
-use ed25519_dalek::{ExpandedSecretKey, SignatureError};
+use ed25519_dalek::{ExpandedSecretKey, SignatureError, SIGNATURE_LENGTH};
 use rand_chacha_dalek_compat::rand_core::{self, CryptoRng, RngCore, SeedableRng};
 use rand_chacha_dalek_compat::ChaCha20Rng;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
 use thiserror::Error;
 
-use super::{ProtocolGenesisSecretKey, ProtocolGenesisSignature, ProtocolGenesisVerificationKey};
+use super::{
+    key_decode_hex, key_encode_hex, ProtocolGenesisSecretKey, ProtocolGenesisSignature,
+    ProtocolGenesisVerificationKey,
+};
+use crate::entities::HexEncodedGenesisVerificationKey;
 
 #[derive(Error, Debug)]
 /// [ProtocolGenesisSigner] and [ProtocolGenesisVerifier] related errors.
@@ -12,6 +19,28 @@ pub enum ProtocolGenesisError {
     /// Error raised when a Genesis Signature verification fail
     #[error("genesis signature verification error: '{0}'")]
     SignatureVerification(#[from] SignatureError),
+
+    /// Error raised when the valid Genesis Signatures don't reach the threshold of the policy
+    #[error("genesis signature threshold not reached: {valid} valid signature(s) for a threshold of {threshold}")]
+    ThresholdNotReached {
+        /// Number of valid signatures
+        valid: usize,
+        /// Threshold of the genesis policy
+        threshold: usize,
+    },
+
+    /// Error raised when a Genesis Policy is invalid
+    #[error("invalid genesis policy: '{0}'")]
+    InvalidPolicy(String),
+
+    /// Error raised when a single Genesis Verification Key is expected from a Genesis Policy
+    /// holding several keys
+    #[error("a single genesis verification key is expected, the genesis policy holds {0}")]
+    NotSingleVerificationKey(usize),
+
+    /// Error raised when a Codec error occurs
+    #[error("codec error: '{0}'")]
+    Codec(String),
 }
 
 /// A protocol Genesis Signer that is responsible for signing the [Genesis Certificate](https://mithril.network/doc/mithril/mithril-protocol/certificates#the-certificate-chain-design)
@@ -76,21 +105,225 @@ impl ProtocolGenesisSigner {
     }
 }
 
+/// A t-of-n Genesis Policy: the Genesis Certificate must be signed by at least `threshold` of
+/// the custodians of the genesis verification keys
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProtocolGenesisPolicy {
+    /// Minimum number of custodians that must sign
+    pub threshold: usize,
+
+    /// Genesis verification keys of the custodians
+    pub verification_keys: Vec<HexEncodedGenesisVerificationKey>,
+}
+
+impl ProtocolGenesisPolicy {
+    /// ProtocolGenesisPolicy factory
+    pub fn new(threshold: usize, verification_keys: Vec<HexEncodedGenesisVerificationKey>) -> Self {
+        Self {
+            threshold,
+            verification_keys,
+        }
+    }
+
+    /// ProtocolGenesisPolicy from a JSON file
+    pub fn from_file(path: &Path) -> Result<Self, ProtocolGenesisError> {
+        let policy = fs::read_to_string(path).map_err(|e| {
+            ProtocolGenesisError::InvalidPolicy(format!("can't read '{}': {}", path.display(), e))
+        })?;
+        serde_json::from_str(&policy).map_err(|e| {
+            ProtocolGenesisError::InvalidPolicy(format!("can't parse '{}': {}", path.display(), e))
+        })
+    }
+}
+
+/// The Genesis Signatures of the custodians of a [ProtocolGenesisPolicy]
+///
+/// Its bytes are the concatenation of the signatures, so a single signature has the same
+/// encoding as a [ProtocolGenesisSignature].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProtocolGenesisMultiSignature {
+    signatures: Vec<ProtocolGenesisSignature>,
+}
+
+impl ProtocolGenesisMultiSignature {
+    /// ProtocolGenesisMultiSignature factory
+    pub fn new(signatures: Vec<ProtocolGenesisSignature>) -> Self {
+        let mut multi_signature = Self { signatures: vec![] };
+        for signature in signatures {
+            multi_signature.add_signature(signature);
+        }
+        multi_signature
+    }
+
+    /// Aggregate several ProtocolGenesisMultiSignature, duplicate signatures are kept once
+    pub fn aggregate(multi_signatures: &[Self]) -> Self {
+        Self::new(
+            multi_signatures
+                .iter()
+                .flat_map(|multi_signature| multi_signature.signatures.clone())
+                .collect(),
+        )
+    }
+
+    /// Add a signature if it is not already there
+    pub fn add_signature(&mut self, signature: ProtocolGenesisSignature) {
+        if !self.signatures.contains(&signature) {
+            self.signatures.push(signature);
+        }
+    }
+
+    /// Signatures of the custodians
+    pub fn signatures(&self) -> &[ProtocolGenesisSignature] {
+        &self.signatures
+    }
+
+    /// ProtocolGenesisMultiSignature from the concatenation of the signatures bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProtocolGenesisError> {
+        if bytes.is_empty() || bytes.len() % SIGNATURE_LENGTH != 0 {
+            return Err(ProtocolGenesisError::Codec(format!(
+                "invalid genesis signatures length: {}",
+                bytes.len()
+            )));
+        }
+        let signatures = bytes
+            .chunks(SIGNATURE_LENGTH)
+            .map(ProtocolGenesisSignature::from_bytes)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| ProtocolGenesisError::Codec(e.to_string()))?;
+
+        Ok(Self::new(signatures))
+    }
+
+    /// ProtocolGenesisMultiSignature to the concatenation of the signatures bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.signatures
+            .iter()
+            .flat_map(|signature| signature.to_bytes())
+            .collect()
+    }
+}
+
+impl From<ProtocolGenesisSignature> for ProtocolGenesisMultiSignature {
+    fn from(signature: ProtocolGenesisSignature) -> Self {
+        Self::new(vec![signature])
+    }
+}
+
 /// A protocol Genesis Verifier that is responsible for verifying the [Genesis Certificate](https://mithril.network/doc/mithril/mithril-protocol/certificates#the-certificate-chain-design)
-#[derive(Debug, Serialize, Deserialize, Clone)]
+///
+/// It verifies the signatures of a t-of-n [ProtocolGenesisPolicy], a single genesis
+/// verification key being a 1-of-1 policy. It is (de)serialized as its policy, which is
+/// validated when deserialized.
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+#[serde(try_from = "ProtocolGenesisPolicy")]
 pub struct ProtocolGenesisVerifier {
-    pub(crate) verification_key: ProtocolGenesisVerificationKey,
+    pub(crate) verification_keys: Vec<ProtocolGenesisVerificationKey>,
+    pub(crate) threshold: usize,
 }
 
 impl ProtocolGenesisVerifier {
     /// ProtocolGenesisVerifier from ProtocolGenesisVerificationKey
     pub fn from_verification_key(verification_key: ProtocolGenesisVerificationKey) -> Self {
-        Self { verification_key }
+        Self {
+            verification_keys: vec![verification_key],
+            threshold: 1,
+        }
+    }
+
+    /// ProtocolGenesisVerifier from ProtocolGenesisPolicy
+    pub fn from_policy(policy: &ProtocolGenesisPolicy) -> Result<Self, ProtocolGenesisError> {
+        let verification_keys = policy
+            .verification_keys
+            .iter()
+            .map(key_decode_hex)
+            .collect::<Result<Vec<ProtocolGenesisVerificationKey>, _>>()
+            .map_err(ProtocolGenesisError::InvalidPolicy)?;
+        let unique_verification_keys = verification_keys
+            .iter()
+            .map(|verification_key| verification_key.as_bytes())
+            .collect::<HashSet<_>>();
+        if unique_verification_keys.len() != verification_keys.len() {
+            return Err(ProtocolGenesisError::InvalidPolicy(
+                "duplicate genesis verification keys".to_string(),
+            ));
+        }
+        if policy.threshold == 0 || policy.threshold > verification_keys.len() {
+            return Err(ProtocolGenesisError::InvalidPolicy(format!(
+                "threshold {} must be between 1 and the number of genesis verification keys {}",
+                policy.threshold,
+                verification_keys.len()
+            )));
+        }
+
+        Ok(Self {
+            verification_keys,
+            threshold: policy.threshold,
+        })
+    }
+
+    /// ProtocolGenesisVerifier to ProtocolGenesisPolicy
+    pub fn to_policy(&self) -> Result<ProtocolGenesisPolicy, ProtocolGenesisError> {
+        let verification_keys = self
+            .verification_keys
+            .iter()
+            .map(key_encode_hex)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(ProtocolGenesisError::Codec)?;
+
+        Ok(ProtocolGenesisPolicy::new(
+            self.threshold,
+            verification_keys,
+        ))
+    }
+
+    /// ProtocolGenesisVerifier from its hex encoding
+    pub fn from_hex(hex: &str) -> Result<Self, ProtocolGenesisError> {
+        match key_decode_hex::<ProtocolGenesisVerificationKey>(&hex.to_string()) {
+            Ok(verification_key) => Ok(Self::from_verification_key(verification_key)),
+            Err(_) => Self::from_policy(
+                &key_decode_hex(&hex.to_string()).map_err(ProtocolGenesisError::Codec)?,
+            ),
+        }
+    }
+
+    /// ProtocolGenesisVerifier to its hex encoding: the encoding of the genesis verification key
+    /// for a single key verifier, else the encoding of its policy
+    pub fn to_hex(&self) -> Result<String, ProtocolGenesisError> {
+        match self.verification_keys.as_slice() {
+            [verification_key] => key_encode_hex(verification_key),
+            _ => key_encode_hex(self.to_policy()?),
+        }
+        .map_err(ProtocolGenesisError::Codec)
+    }
+
+    /// ProtocolGenesisVerifier to ProtocolGenesisVerificationKey, fails if the verifier holds
+    /// a policy of several keys
+    pub fn to_verification_key(
+        &self,
+    ) -> Result<ProtocolGenesisVerificationKey, ProtocolGenesisError> {
+        match self.verification_keys.as_slice() {
+            [verification_key] => Ok(*verification_key),
+            verification_keys => Err(ProtocolGenesisError::NotSingleVerificationKey(
+                verification_keys.len(),
+            )),
+        }
     }
 
-    /// ProtocolGenesisVerifier to ProtocolGenesisVerificationKey
-    pub fn to_verification_key(&self) -> ProtocolGenesisVerificationKey {
-        self.verification_key
+    /// Bytes identifying the verifier: the genesis verification key bytes for a single key
+    /// verifier, else the threshold followed by the genesis verification keys bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self.verification_keys.as_slice() {
+            [verification_key] => verification_key.as_bytes().to_vec(),
+            verification_keys => (self.threshold as u64)
+                .to_be_bytes()
+                .into_iter()
+                .chain(
+                    verification_keys
+                        .iter()
+                        .flat_map(|verification_key| verification_key.to_bytes()),
+                )
+                .collect(),
+        }
     }
 
     /// Verifies the signature of a message
@@ -99,7 +332,66 @@ impl ProtocolGenesisVerifier {
         message: &[u8],
         signature: &ProtocolGenesisSignature,
     ) -> Result<(), ProtocolGenesisError> {
-        Ok(self.verification_key.verify_strict(message, signature)?)
+        self.verify_multi_signature(message, &(*signature).into())
+    }
+
+    /// Verifies that the signatures of a message reach the threshold of the policy, each
+    /// genesis verification key being counted once
+    pub fn verify_multi_signature(
+        &self,
+        message: &[u8],
+        multi_signature: &ProtocolGenesisMultiSignature,
+    ) -> Result<(), ProtocolGenesisError> {
+        if self.threshold == 0 || self.threshold > self.verification_keys.len() {
+            return Err(ProtocolGenesisError::InvalidPolicy(format!(
+                "threshold {} must be between 1 and the number of genesis verification keys {}",
+                self.threshold,
+                self.verification_keys.len()
+            )));
+        }
+        if let ([verification_key], [signature]) = (
+            self.verification_keys.as_slice(),
+            multi_signature.signatures(),
+        ) {
+            return Ok(verification_key.verify_strict(message, signature)?);
+        }
+        let valid = self
+            .verification_keys
+            .iter()
+            .filter(|verification_key| {
+                multi_signature
+                    .signatures()
+                    .iter()
+                    .any(|signature| verification_key.verify_strict(message, signature).is_ok())
+            })
+            .count();
+        if valid < self.threshold {
+            return Err(ProtocolGenesisError::ThresholdNotReached {
+                valid,
+                threshold: self.threshold,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl TryFrom<ProtocolGenesisPolicy> for ProtocolGenesisVerifier {
+    type Error = ProtocolGenesisError;
+
+    fn try_from(policy: ProtocolGenesisPolicy) -> Result<Self, Self::Error> {
+        Self::from_policy(&policy)
+    }
+}
+
+impl Serialize for ProtocolGenesisVerifier {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.to_policy()
+            .map_err(serde::ser::Error::custom)?
+            .serialize(serializer)
     }
 }
 
@@ -119,13 +411,13 @@ mod tests {
             genesis_signer_2.secret_key.as_bytes()
         );
         assert_eq!(
-            genesis_verifier.verification_key.as_bytes(),
-            genesis_verifier_2.verification_key.as_bytes()
+            genesis_verifier.to_verification_key().unwrap().as_bytes(),
+            genesis_verifier_2.to_verification_key().unwrap().as_bytes()
         );
 
         println!(
             "Deterministic Genesis Verification Key={}",
-            key_encode_hex(genesis_verifier.verification_key.as_bytes()).unwrap()
+            key_encode_hex(genesis_verifier.to_verification_key().unwrap().as_bytes()).unwrap()
         );
         println!(
             "Deterministic Genesis Secret Key=={}",
@@ -140,7 +432,7 @@ mod tests {
 
         println!(
             "Non Deterministic Genesis Verification Key={}",
-            key_encode_hex(genesis_verifier.verification_key.as_bytes()).unwrap()
+            key_encode_hex(genesis_verifier.to_verification_key().unwrap().as_bytes()).unwrap()
         );
         println!(
             "Non Deterministic Genesis Secret Key=={}",
@@ -154,7 +446,7 @@ mod tests {
         let genesis_verifier = genesis_signer.create_genesis_verifier();
         let secret_key_encoded = key_encode_hex(genesis_signer.secret_key.as_bytes()).unwrap();
         let verification_key_encoded =
-            key_encode_hex(genesis_verifier.verification_key.as_bytes()).unwrap();
+            key_encode_hex(genesis_verifier.to_verification_key().unwrap().as_bytes()).unwrap();
         let secret_key_decoded: ProtocolGenesisSecretKey =
             key_decode_hex(&secret_key_encoded).unwrap();
         let verification_key_decoded: ProtocolGenesisVerificationKey =
@@ -171,4 +463,181 @@ mod tests {
             "genesis signature verification should not fail"
         );
     }
+
+    fn setup_genesis_policy(
+        threshold: usize,
+        total: usize,
+    ) -> (Vec<ProtocolGenesisSigner>, ProtocolGenesisPolicy) {
+        let genesis_signers = (0..total)
+            .map(|_| ProtocolGenesisSigner::create_non_deterministic_genesis_signer())
+            .collect::<Vec<_>>();
+        let verification_keys = genesis_signers
+            .iter()
+            .map(|signer| {
+                key_encode_hex(
+                    signer
+                        .create_genesis_verifier()
+                        .to_verification_key()
+                        .unwrap(),
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        (
+            genesis_signers,
+            ProtocolGenesisPolicy::new(threshold, verification_keys),
+        )
+    }
+
+    #[test]
+    fn test_genesis_policy_has_no_single_verification_key() {
+        let (_, genesis_policy) = setup_genesis_policy(1, 2);
+        let genesis_verifier = ProtocolGenesisVerifier::from_policy(&genesis_policy).unwrap();
+
+        assert!(matches!(
+            genesis_verifier.to_verification_key(),
+            Err(ProtocolGenesisError::NotSingleVerificationKey(2))
+        ));
+    }
+
+    #[test]
+    fn test_genesis_policy_threshold_reached() {
+        let (genesis_signers, genesis_policy) = setup_genesis_policy(2, 3);
+        let genesis_verifier = ProtocolGenesisVerifier::from_policy(&genesis_policy).unwrap();
+        let message: &[u8] = b"some message.";
+        let multi_signature = ProtocolGenesisMultiSignature::new(
+            genesis_signers[1..]
+                .iter()
+                .map(|signer| signer.sign(message))
+                .collect(),
+        );
+
+        genesis_verifier
+            .verify_multi_signature(message, &multi_signature)
+            .expect("2 of 3 genesis signatures should reach the threshold");
+    }
+
+    #[test]
+    fn test_genesis_policy_threshold_not_reached() {
+        let (genesis_signers, genesis_policy) = setup_genesis_policy(2, 3);
+        let genesis_verifier = ProtocolGenesisVerifier::from_policy(&genesis_policy).unwrap();
+        let message: &[u8] = b"some message.";
+        let signature = genesis_signers[0].sign(message);
+        let multi_signature = ProtocolGenesisMultiSignature::new(vec![
+            signature,
+            signature,
+            ProtocolGenesisSigner::create_non_deterministic_genesis_signer().sign(message),
+        ]);
+
+        let error = genesis_verifier
+            .verify_multi_signature(message, &multi_signature)
+            .expect_err("duplicate and unknown genesis signatures should not count");
+        assert!(
+            matches!(
+                error,
+                ProtocolGenesisError::ThresholdNotReached {
+                    valid: 1,
+                    threshold: 2
+                }
+            ),
+            "unexpected error: {:?}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_genesis_policy_invalid() {
+        let (_, genesis_policy) = setup_genesis_policy(4, 3);
+        ProtocolGenesisVerifier::from_policy(&genesis_policy)
+            .expect_err("a threshold above the number of keys should be rejected");
+
+        let (_, genesis_policy) = setup_genesis_policy(0, 3);
+        ProtocolGenesisVerifier::from_policy(&genesis_policy)
+            .expect_err("a zero threshold should be rejected");
+
+        let (_, mut genesis_policy) = setup_genesis_policy(2, 2);
+        genesis_policy.verification_keys[1] = genesis_policy.verification_keys[0].clone();
+        ProtocolGenesisVerifier::from_policy(&genesis_policy)
+            .expect_err("duplicate genesis verification keys should be rejected");
+    }
+
+    #[test]
+    fn test_genesis_policy_invalid_is_not_deserialized() {
+        let (_, genesis_policy) = setup_genesis_policy(2, 3);
+        let genesis_verifier = ProtocolGenesisVerifier::from_policy(&genesis_policy).unwrap();
+        let json = serde_json::to_string(&genesis_verifier).unwrap();
+        assert_eq!(
+            genesis_verifier,
+            serde_json::from_str::<ProtocolGenesisVerifier>(&json).unwrap()
+        );
+
+        for (threshold, total) in [(0, 3), (4, 3)] {
+            let (_, genesis_policy) = setup_genesis_policy(threshold, total);
+            let json = serde_json::to_string(&genesis_policy).unwrap();
+            serde_json::from_str::<ProtocolGenesisVerifier>(&json)
+                .expect_err("an invalid genesis policy should not be deserialized");
+        }
+    }
+
+    #[test]
+    fn test_genesis_policy_invalid_threshold_never_verifies() {
+        let (genesis_signers, genesis_policy) = setup_genesis_policy(2, 2);
+        let mut genesis_verifier = ProtocolGenesisVerifier::from_policy(&genesis_policy).unwrap();
+        let message: &[u8] = b"some message.";
+        let multi_signature = ProtocolGenesisMultiSignature::new(
+            genesis_signers
+                .iter()
+                .map(|signer| signer.sign(message))
+                .collect(),
+        );
+
+        for threshold in [0, 3] {
+            genesis_verifier.threshold = threshold;
+            genesis_verifier
+                .verify_multi_signature(message, &multi_signature)
+                .expect_err("an invalid threshold should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_genesis_single_key_is_a_one_of_one_policy() {
+        let genesis_signer = ProtocolGenesisSigner::create_deterministic_genesis_signer();
+        let genesis_verifier = genesis_signer.create_genesis_verifier();
+        let genesis_policy = genesis_verifier.to_policy().unwrap();
+        let message: &[u8] = b"some message.";
+        let signature = genesis_signer.sign(message);
+        let multi_signature = ProtocolGenesisMultiSignature::from(signature);
+
+        assert_eq!(1, genesis_policy.threshold);
+        assert_eq!(
+            genesis_verifier,
+            ProtocolGenesisVerifier::from_policy(&genesis_policy).unwrap()
+        );
+        assert_eq!(
+            genesis_verifier
+                .to_verification_key()
+                .unwrap()
+                .as_bytes()
+                .to_vec(),
+            genesis_verifier.to_bytes()
+        );
+        assert_eq!(signature.to_bytes().to_vec(), multi_signature.to_bytes());
+        assert_eq!(
+            genesis_verifier,
+            ProtocolGenesisVerifier::from_hex(&genesis_verifier.to_hex().unwrap()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_codec_genesis_policy_verifier() {
+        let (_, genesis_policy) = setup_genesis_policy(2, 3);
+        let genesis_verifier = ProtocolGenesisVerifier::from_policy(&genesis_policy).unwrap();
+
+        assert_eq!(
+            genesis_verifier,
+            ProtocolGenesisVerifier::from_hex(&genesis_verifier.to_hex().unwrap()).unwrap()
+        );
+        assert_eq!(genesis_policy, genesis_verifier.to_policy().unwrap());
+    }
 }
@@ -12,7 +12,10 @@ mod types;
 pub use cardano::ColdKeyGenerator;
 pub use cardano::{KESPeriod, OpCert, SerDeShelleyFileFormat};
 pub use codec::*;
-pub use genesis::{ProtocolGenesisError, ProtocolGenesisSigner, ProtocolGenesisVerifier};
+pub use genesis::{
+    ProtocolGenesisError, ProtocolGenesisMultiSignature, ProtocolGenesisPolicy,
+    ProtocolGenesisSigner, ProtocolGenesisVerifier,
+};
 pub use types::*;
 
 /// The current protocol version
@@ -271,7 +271,7 @@ pub fn setup_certificate_chain(
                         fake_certificate.metadata.protocol_parameters,
                         fake_certificate.beacon,
                         next_avk,
-                        genesis_signature,
+                        genesis_signature.into(),
                     )
                     .unwrap()
                 }
@@ -0,0 +1,111 @@
+use crate::entities::{Beacon, Epoch, PartyId, SignedEntityType};
+use serde::{Deserialize, Serialize};
+
+/// AggregatorEvent represents an event of the lifecycle of an aggregator, pushed to the
+/// subscribers of its event stream
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum AggregatorEvent {
+    /// A new pending certificate is available to be signed
+    PendingCertificateCreated {
+        /// Beacon of the pending certificate
+        beacon: Beacon,
+
+        /// Entity certified by the pending certificate
+        signed_entity_type: SignedEntityType,
+    },
+
+    /// A single signature has been accepted for the pending certificate
+    SignatureAccepted {
+        /// Party id of the signer
+        party_id: PartyId,
+    },
+
+    /// Enough single signatures have been collected to create a multi-signature
+    QuorumReached {
+        /// Beacon of the pending certificate
+        beacon: Beacon,
+    },
+
+    /// A certificate has been sealed and stored
+    CertificateSealed {
+        /// Hash of the certificate
+        hash: String,
+
+        /// Beacon of the certificate
+        beacon: Beacon,
+
+        /// Entity certified by the certificate
+        signed_entity_type: SignedEntityType,
+    },
+
+    /// A snapshot has been published
+    SnapshotPublished {
+        /// Digest of the snapshot
+        digest: String,
+
+        /// Beacon of the snapshot
+        beacon: Beacon,
+    },
+
+    /// The aggregator has moved to a new epoch
+    EpochChanged {
+        /// New epoch
+        epoch: Epoch,
+    },
+}
+
+impl AggregatorEvent {
+    /// Name of the event, as sent in the `event` field of the event stream
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::PendingCertificateCreated { .. } => "pending_certificate_created",
+            Self::SignatureAccepted { .. } => "signature_accepted",
+            Self::QuorumReached { .. } => "quorum_reached",
+            Self::CertificateSealed { .. } => "certificate_sealed",
+            Self::SnapshotPublished { .. } => "snapshot_published",
+            Self::EpochChanged { .. } => "epoch_changed",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_data;
+
+    #[test]
+    fn test_name_matches_serialized_tag() {
+        let beacon = fake_data::beacon();
+        let events = vec![
+            AggregatorEvent::PendingCertificateCreated {
+                beacon: beacon.clone(),
+                signed_entity_type: SignedEntityType::MithrilStakeDistribution(beacon.epoch),
+            },
+            AggregatorEvent::SignatureAccepted {
+                party_id: "party".to_string(),
+            },
+            AggregatorEvent::QuorumReached {
+                beacon: beacon.clone(),
+            },
+            AggregatorEvent::CertificateSealed {
+                hash: "hash".to_string(),
+                beacon: beacon.clone(),
+                signed_entity_type: SignedEntityType::CardanoImmutableFilesFull(beacon.clone()),
+            },
+            AggregatorEvent::SnapshotPublished {
+                digest: "digest".to_string(),
+                beacon: beacon.clone(),
+            },
+            AggregatorEvent::EpochChanged {
+                epoch: beacon.epoch,
+            },
+        ];
+
+        for event in events {
+            let json = serde_json::to_value(&event).unwrap();
+            assert_eq!(event.name(), json["event"]);
+            assert_eq!(event, serde_json::from_value(json).unwrap());
+        }
+    }
+}
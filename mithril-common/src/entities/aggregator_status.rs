@@ -0,0 +1,75 @@
+use crate::entities::{Beacon, Epoch, SignedEntityType};
+use serde::{Deserialize, Serialize};
+
+/// AggregatorStatus represents the internal status of an aggregator: the state of its state
+/// machine, the certificate it is collecting signatures for, and the health of its services
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AggregatorStatus {
+    /// State of the state machine of the aggregator ("idle", "ready" or "signing")
+    pub state: String,
+
+    /// Current beacon of the state machine
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub beacon: Option<Beacon>,
+
+    /// Pending certificate the signatures are collected for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pending_certificate: Option<PendingCertificateStatus>,
+
+    /// Last certificate produced
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_certificate: Option<LastCertificateStatus>,
+
+    /// Health of the chain observer
+    pub chain_observer: ChainObserverStatus,
+}
+
+/// PendingCertificateStatus summarizes a pending certificate and the progress of its quorum
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingCertificateStatus {
+    /// Beacon of the pending certificate
+    pub beacon: Beacon,
+
+    /// Entity certified by the pending certificate
+    pub signed_entity_type: SignedEntityType,
+
+    /// Number of signers that can sign the pending certificate
+    pub signers: u64,
+
+    /// Number of single signatures collected
+    pub signatures: u64,
+
+    /// Number of distinct lotteries won by the collected single signatures
+    pub won_lotteries: u64,
+
+    /// Number of lotteries required to create the multi-signature (`k` protocol parameter)
+    pub quorum: u64,
+}
+
+/// LastCertificateStatus summarizes the last certificate produced by an aggregator
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LastCertificateStatus {
+    /// Hash of the certificate
+    pub hash: String,
+
+    /// Beacon of the certificate
+    pub beacon: Beacon,
+
+    /// Date and time at which the certificate was sealed
+    pub sealed_at: String,
+}
+
+/// ChainObserverStatus represents the health of the chain observer of an aggregator
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainObserverStatus {
+    /// Is the chain observer able to read the current epoch
+    pub healthy: bool,
+
+    /// Current epoch read by the chain observer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub epoch: Option<Epoch>,
+
+    /// Error raised by the chain observer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
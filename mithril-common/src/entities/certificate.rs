@@ -1,7 +1,8 @@
 use crate::entities::{
     Beacon, CertificateMetadata, HexEncodedAgregateVerificationKey, HexEncodedGenesisSignature,
-    HexEncodedMultiSignature, ProtocolMessage,
+    HexEncodedMultiSignature, ProtocolMessage, SignedEntityType,
 };
+use crate::protocol_version::{ProtocolVersionError, SemanticProtocolVersion};
 
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
@@ -77,8 +78,26 @@ impl Certificate {
         certificate
     }
 
-    /// Computes the hash of a Certificate
+    /// Computes the hash of a Certificate with the hash format of its protocol version
+    ///
+    /// The certificates with an unknown protocol version are hashed with the `0.x` format, they
+    /// are rejected by the certificate verifier anyway.
     pub fn compute_hash(&self) -> String {
+        match CertificateHashFormat::from_protocol_version(&self.metadata.protocol_version)
+            .unwrap_or(CertificateHashFormat::V0)
+        {
+            CertificateHashFormat::V0 => self.compute_hash_v0(),
+        }
+    }
+
+    /// Entity certified by the Certificate, deduced from the parts of its protocol message
+    ///
+    /// Genesis certificates don't certify any entity.
+    pub fn signed_entity_type(&self) -> Option<SignedEntityType> {
+        self.protocol_message.get_signed_entity_type(&self.beacon)
+    }
+
+    fn compute_hash_v0(&self) -> String {
         let mut hasher = Sha256::new();
         hasher.update(self.previous_hash.as_bytes());
         hasher.update(self.beacon.compute_hash().as_bytes());
@@ -92,10 +111,31 @@ impl Certificate {
     }
 }
 
+/// Format of the hash of a [Certificate], that depends on its protocol version
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CertificateHashFormat {
+    /// Hash format of the `0.x` protocol versions
+    V0,
+}
+
+impl CertificateHashFormat {
+    /// Hash format of a protocol version
+    pub fn from_protocol_version(protocol_version: &str) -> Result<Self, ProtocolVersionError> {
+        match SemanticProtocolVersion::parse(protocol_version)?.major {
+            0 => Ok(Self::V0),
+            _ => Err(ProtocolVersionError::Incompatible {
+                version: protocol_version.to_string(),
+                supported: vec!["0.x".to_string()],
+            }),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::entities::{ProtocolMessagePartKey, ProtocolParameters, SignerWithStake};
+    use crate::fake_data;
 
     #[test]
     fn test_certificate_compute_hash() {
@@ -411,4 +451,51 @@ mod tests {
             .compute_hash()
         );
     }
+
+    #[test]
+    fn test_certificate_hash_format_from_protocol_version() {
+        assert_eq!(
+            Ok(CertificateHashFormat::V0),
+            CertificateHashFormat::from_protocol_version("0.1.0")
+        );
+        assert!(matches!(
+            CertificateHashFormat::from_protocol_version("1.0.0"),
+            Err(ProtocolVersionError::Incompatible { .. })
+        ));
+        assert!(matches!(
+            CertificateHashFormat::from_protocol_version("unknown"),
+            Err(ProtocolVersionError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn test_certificate_signed_entity_type() {
+        let mut certificate = fake_data::certificate("hash".to_string());
+        assert_eq!(
+            Some(SignedEntityType::CardanoImmutableFilesFull(
+                certificate.beacon.clone()
+            )),
+            certificate.signed_entity_type()
+        );
+
+        let mut protocol_message = ProtocolMessage::new();
+        protocol_message.set_message_part(
+            ProtocolMessagePartKey::MithrilStakeDistributionEpoch,
+            certificate.beacon.epoch.to_string(),
+        );
+        protocol_message.set_message_part(
+            ProtocolMessagePartKey::NextAggregateVerificationKey,
+            "next-avk-123".to_string(),
+        );
+        certificate.protocol_message = protocol_message;
+        assert_eq!(
+            Some(SignedEntityType::MithrilStakeDistribution(
+                certificate.beacon.epoch
+            )),
+            certificate.signed_entity_type()
+        );
+
+        certificate.protocol_message = ProtocolMessage::new();
+        assert_eq!(None, certificate.signed_entity_type());
+    }
 }
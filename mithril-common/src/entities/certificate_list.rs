@@ -0,0 +1,18 @@
+use crate::entities::Certificate;
+use serde::{Deserialize, Serialize};
+
+/// CertificateList represents a page of certificates, the most recent first
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CertificateList {
+    /// Certificates of the page
+    pub certificates: Vec<Certificate>,
+
+    /// Total number of certificates meeting the search criteria
+    pub total: u64,
+
+    /// Number of certificates skipped before the page
+    pub offset: u64,
+
+    /// Maximum number of certificates of the page
+    pub limit: u64,
+}
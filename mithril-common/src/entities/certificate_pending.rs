@@ -1,4 +1,4 @@
-use crate::entities::{Beacon, PartyId, ProtocolParameters, Signer};
+use crate::entities::{Beacon, PartyId, ProtocolParameters, SignedEntityType, Signer};
 use serde::{Deserialize, Serialize};
 
 /// CertificatePending represents a pending certificate in the process of production
@@ -20,6 +20,15 @@ pub struct CertificatePending {
 
     /// Signers that will be able to sign on the next epoch
     pub next_signers: Vec<Signer>,
+
+    /// Entity to sign, not sent by the aggregators that only certify the immutable files
+    #[serde(default)]
+    pub signed_entity_type: Option<SignedEntityType>,
+
+    /// Hash of the manifest of the chunks of the snapshot archive, signed along the
+    /// snapshot digest, only set if the snapshot archives are split in chunks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot_manifest_hash: Option<String>,
 }
 
 impl CertificatePending {
@@ -30,6 +39,7 @@ impl CertificatePending {
         next_protocol_parameters: ProtocolParameters,
         signers: Vec<Signer>,
         next_signers: Vec<Signer>,
+        signed_entity_type: SignedEntityType,
     ) -> CertificatePending {
         CertificatePending {
             beacon,
@@ -37,9 +47,24 @@ impl CertificatePending {
             next_protocol_parameters,
             signers,
             next_signers,
+            signed_entity_type: Some(signed_entity_type),
+            snapshot_manifest_hash: None,
         }
     }
 
+    /// Set the hash of the manifest of the chunks of the snapshot archive
+    pub fn with_snapshot_manifest_hash(mut self, snapshot_manifest_hash: String) -> Self {
+        self.snapshot_manifest_hash = Some(snapshot_manifest_hash);
+        self
+    }
+
+    /// Entity to sign, the immutable files at the current beacon if it is not set
+    pub fn get_signed_entity_type(&self) -> SignedEntityType {
+        self.signed_entity_type
+            .clone()
+            .unwrap_or_else(|| SignedEntityType::CardanoImmutableFilesFull(self.beacon.clone()))
+    }
+
     /// get a signer from the certificate pending if it has registered
     pub fn get_signer(&self, party_id: PartyId) -> Option<&Signer> {
         self.signers.iter().find(|s| s.party_id == party_id)
@@ -48,6 +73,7 @@ impl CertificatePending {
 
 #[cfg(test)]
 mod tests {
+    use crate::entities::SignedEntityType;
     use crate::fake_data;
 
     #[test]
@@ -56,4 +82,15 @@ mod tests {
         assert!(certificate_pending.get_signer("1".to_string()).is_some());
         assert!(certificate_pending.get_signer("5".to_string()).is_none());
     }
+
+    #[test]
+    fn certificate_pending_without_signed_entity_type_signs_immutable_files() {
+        let mut certificate_pending = fake_data::certificate_pending();
+        certificate_pending.signed_entity_type = None;
+
+        assert_eq!(
+            SignedEntityType::CardanoImmutableFilesFull(certificate_pending.beacon.clone()),
+            certificate_pending.get_signed_entity_type()
+        );
+    }
 }
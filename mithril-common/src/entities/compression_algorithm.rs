@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// Compression algorithm of the snapshot archives
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompressionAlgorithm {
+    /// Gzip compression format
+    Gzip,
+
+    /// Zstandard compression format
+    #[serde(alias = "zstd")]
+    Zstandard,
+}
+
+impl CompressionAlgorithm {
+    /// Extension of the tar archives compressed with this algorithm, ie: `tar.gz`
+    pub fn tar_file_extension(&self) -> String {
+        match self {
+            Self::Gzip => "tar.gz".to_string(),
+            Self::Zstandard => "tar.zst".to_string(),
+        }
+    }
+
+    /// Detect the compression algorithm of an archive from its first bytes, if supported
+    pub fn from_magic_bytes(bytes: &[u8]) -> Option<Self> {
+        match bytes {
+            [0x1f, 0x8b, ..] => Some(Self::Gzip),
+            [0x28, 0xb5, 0x2f, 0xfd, ..] => Some(Self::Zstandard),
+            _ => None,
+        }
+    }
+}
+
+impl Default for CompressionAlgorithm {
+    /// Snapshot archives were all compressed with gzip before the algorithm was recorded
+    fn default() -> Self {
+        Self::Gzip
+    }
+}
+
+impl Display for CompressionAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Gzip => write!(f, "gzip"),
+            Self::Zstandard => write!(f, "zstandard"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compression_algorithm_from_magic_bytes() {
+        assert_eq!(
+            Some(CompressionAlgorithm::Gzip),
+            CompressionAlgorithm::from_magic_bytes(&[0x1f, 0x8b, 0x08, 0x00])
+        );
+        assert_eq!(
+            Some(CompressionAlgorithm::Zstandard),
+            CompressionAlgorithm::from_magic_bytes(&[0x28, 0xb5, 0x2f, 0xfd])
+        );
+        assert_eq!(None, CompressionAlgorithm::from_magic_bytes(b"ustar"));
+        assert_eq!(None, CompressionAlgorithm::from_magic_bytes(&[]));
+    }
+
+    #[test]
+    fn compression_algorithm_is_deserialized_from_its_name_or_alias() {
+        assert_eq!(
+            CompressionAlgorithm::Zstandard,
+            serde_json::from_str::<CompressionAlgorithm>("\"zstd\"").unwrap()
+        );
+        assert_eq!(
+            CompressionAlgorithm::Zstandard,
+            serde_json::from_str::<CompressionAlgorithm>("\"zstandard\"").unwrap()
+        );
+        assert_eq!(
+            CompressionAlgorithm::Gzip,
+            serde_json::from_str::<CompressionAlgorithm>("\"gzip\"").unwrap()
+        );
+    }
+}
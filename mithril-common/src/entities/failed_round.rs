@@ -0,0 +1,122 @@
+use crate::entities::{
+    Beacon, CertificatePending, LotteryIndex, PartyId, SignedEntityType, SingleSignatures,
+};
+use serde::{Deserialize, Serialize};
+
+/// FailedRoundSigner represents a signer that sent its single signature during a failed round
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FailedRoundSigner {
+    /// The unique identifier of the signer
+    pub party_id: PartyId,
+
+    /// The indexes of the lotteries won by the signer
+    pub won_indexes: Vec<LotteryIndex>,
+}
+
+/// FailedRound represents a pending certificate that expired before its quorum was reached
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FailedRound {
+    /// Beacon of the pending certificate
+    pub beacon: Beacon,
+
+    /// Entity that was signed
+    pub signed_entity_type: SignedEntityType,
+
+    /// Date and time at which the pending certificate was created
+    pub started_at: String,
+
+    /// Date and time at which the pending certificate expired
+    pub expired_at: String,
+
+    /// Signers that sent their single signature
+    pub signers: Vec<FailedRoundSigner>,
+
+    /// Signers of the pending certificate that did not send their single signature
+    pub missing_signers: Vec<PartyId>,
+
+    /// Number of distinct lotteries won by the signers
+    pub won_lotteries: u64,
+
+    /// Number of distinct lotteries required to reach the quorum (the `k` protocol parameter)
+    pub quorum: u64,
+}
+
+impl FailedRound {
+    /// FailedRound factory, from the expired pending certificate and the single signatures
+    /// received for it
+    pub fn new(
+        certificate_pending: &CertificatePending,
+        single_signatures: &[SingleSignatures],
+        started_at: String,
+        expired_at: String,
+    ) -> FailedRound {
+        let signers = single_signatures
+            .iter()
+            .map(|single_signatures| FailedRoundSigner {
+                party_id: single_signatures.party_id.clone(),
+                won_indexes: single_signatures.won_indexes.clone(),
+            })
+            .collect::<Vec<_>>();
+        let won_lotteries = SingleSignatures::count_won_lotteries(single_signatures);
+        let missing_signers = certificate_pending
+            .signers
+            .iter()
+            .filter(|signer| !signers.iter().any(|s| s.party_id == signer.party_id))
+            .map(|signer| signer.party_id.clone())
+            .collect();
+
+        FailedRound {
+            beacon: certificate_pending.beacon.clone(),
+            signed_entity_type: certificate_pending.get_signed_entity_type(),
+            started_at,
+            expired_at,
+            signers,
+            missing_signers,
+            won_lotteries,
+            quorum: certificate_pending.protocol_parameters.k,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_data;
+
+    #[test]
+    fn failed_round_counts_distinct_won_lotteries_and_missing_signers() {
+        let mut certificate_pending = fake_data::certificate_pending();
+        certificate_pending.signers = fake_data::signers(3);
+        let party_ids = certificate_pending
+            .signers
+            .iter()
+            .map(|signer| signer.party_id.clone())
+            .collect::<Vec<_>>();
+        let single_signatures = vec![
+            SingleSignatures::new(party_ids[0].clone(), "signature".to_string(), vec![1, 3]),
+            SingleSignatures::new(party_ids[1].clone(), "signature".to_string(), vec![3, 7]),
+        ];
+
+        let failed_round = FailedRound::new(
+            &certificate_pending,
+            &single_signatures,
+            "2022-10-18T10:00:00Z".to_string(),
+            "2022-10-18T11:00:00Z".to_string(),
+        );
+
+        assert_eq!(3, failed_round.won_lotteries);
+        assert_eq!(
+            certificate_pending.protocol_parameters.k,
+            failed_round.quorum
+        );
+        assert_eq!(
+            party_ids[0..2].to_vec(),
+            failed_round
+                .signers
+                .iter()
+                .map(|signer| signer.party_id.clone())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(vec![party_ids[2].clone()], failed_round.missing_signers);
+    }
+}
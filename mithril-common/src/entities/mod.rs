@@ -1,33 +1,55 @@
 //! The entities used by, and exchanged between, the aggregator, signers and client.
 
+mod aggregator_event;
+mod aggregator_status;
 mod beacon;
 mod cardano_network;
 mod certificate;
+mod certificate_list;
 mod certificate_metadata;
 mod certificate_pending;
+mod compression_algorithm;
 mod epoch;
 mod epoch_settings;
+mod failed_round;
 mod http_server_error;
 mod protocol_message;
 mod protocol_parameters;
+mod registered_signers;
+mod signed_entity_type;
 mod signer;
+mod signer_statistics;
+mod signing_stake_distribution;
 mod single_signatures;
 mod snapshot;
+mod snapshot_manifest;
 mod type_alias;
 
+pub use aggregator_event::AggregatorEvent;
+pub use aggregator_status::{
+    AggregatorStatus, ChainObserverStatus, LastCertificateStatus, PendingCertificateStatus,
+};
 pub use beacon::{Beacon, BeaconComparison, BeaconComparisonError};
 pub use cardano_network::CardanoNetwork;
-pub use certificate::Certificate;
+pub use certificate::{Certificate, CertificateHashFormat};
+pub use certificate_list::CertificateList;
 pub use certificate_metadata::CertificateMetadata;
 pub use certificate_pending::CertificatePending;
+pub use compression_algorithm::CompressionAlgorithm;
 pub use epoch::{Epoch, EpochError};
 pub use epoch_settings::EpochSettings;
+pub use failed_round::{FailedRound, FailedRoundSigner};
 pub use http_server_error::{ClientError, InternalServerError};
 pub use protocol_message::{ProtocolMessage, ProtocolMessagePartKey, ProtocolMessagePartValue};
 pub use protocol_parameters::ProtocolParameters;
+pub use registered_signers::RegisteredSigners;
+pub use signed_entity_type::{SignedEntityKind, SignedEntityKindError, SignedEntityType};
 pub use signer::{Signer, SignerWithStake};
+pub use signer_statistics::SignerStatistics;
+pub use signing_stake_distribution::SigningStakeDistribution;
 pub use single_signatures::SingleSignatures;
 pub use snapshot::Snapshot;
+pub use snapshot_manifest::{SnapshotChunk, SnapshotManifest};
 pub use type_alias::{
     HexEncodedAgregateVerificationKey, HexEncodedGenesisSecretKey, HexEncodedGenesisSignature,
     HexEncodedGenesisVerificationKey, HexEncodedKey, HexEncodedMultiSignature, HexEncodedOpCert,
@@ -1,3 +1,5 @@
+use crate::entities::{Beacon, SignedEntityType};
+
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::{collections::BTreeMap, fmt::Display};
@@ -14,6 +16,32 @@ pub enum ProtocolMessagePartKey {
     /// aka AVK(n-1)
     #[serde(rename = "next_aggregate_verification_key")]
     NextAggregateVerificationKey,
+
+    /// The ProtocolMessage part key associated to the genesis verification key that signs a
+    /// genesis key rotation certificate
+    #[serde(rename = "next_genesis_verification_key")]
+    NextGenesisVerificationKey,
+
+    /// The ProtocolMessage part key associated to the genesis verification key that signed the
+    /// certificate chain before a genesis key rotation
+    #[serde(rename = "previous_genesis_verification_key")]
+    PreviousGenesisVerificationKey,
+
+    /// The ProtocolMessage part key associated to the signature of a genesis key rotation by
+    /// the previous genesis key
+    #[serde(rename = "genesis_key_rotation_signature")]
+    GenesisKeyRotationSignature,
+
+    /// The ProtocolMessage part key associated to the epoch of a certified Mithril stake
+    /// distribution, the stake distribution itself is committed by the next aggregate
+    /// verification key
+    #[serde(rename = "mithril_stake_distribution_epoch")]
+    MithrilStakeDistributionEpoch,
+
+    /// The ProtocolMessage part key associated to the hash of the manifest of the chunks of
+    /// a snapshot archive, only set if the snapshot archives are split in chunks
+    #[serde(rename = "snapshot_manifest_hash")]
+    SnapshotManifestHash,
 }
 
 impl Display for ProtocolMessagePartKey {
@@ -21,6 +49,11 @@ impl Display for ProtocolMessagePartKey {
         match *self {
             Self::SnapshotDigest => write!(f, "snapshot_digest"),
             Self::NextAggregateVerificationKey => write!(f, "next_aggregate_verification_key"),
+            Self::NextGenesisVerificationKey => write!(f, "next_genesis_verification_key"),
+            Self::PreviousGenesisVerificationKey => write!(f, "previous_genesis_verification_key"),
+            Self::GenesisKeyRotationSignature => write!(f, "genesis_key_rotation_signature"),
+            Self::MithrilStakeDistributionEpoch => write!(f, "mithril_stake_distribution_epoch"),
+            Self::SnapshotManifestHash => write!(f, "snapshot_manifest_hash"),
         }
     }
 }
@@ -71,6 +104,23 @@ impl ProtocolMessage {
         });
         hex::encode(hasher.finalize())
     }
+
+    /// Entity signed with this message at the given beacon, deduced from the message parts
+    pub fn get_signed_entity_type(&self, beacon: &Beacon) -> Option<SignedEntityType> {
+        if self
+            .get_message_part(&ProtocolMessagePartKey::SnapshotDigest)
+            .is_some()
+        {
+            Some(SignedEntityType::CardanoImmutableFilesFull(beacon.clone()))
+        } else if self
+            .get_message_part(&ProtocolMessagePartKey::MithrilStakeDistributionEpoch)
+            .is_some()
+        {
+            Some(SignedEntityType::MithrilStakeDistribution(beacon.epoch))
+        } else {
+            None
+        }
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,18 @@
+use crate::entities::{Epoch, SignerWithStake};
+use serde::{Deserialize, Serialize};
+
+/// RegisteredSigners represents the signers registered for the current and the next epoch
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegisteredSigners {
+    /// Current epoch
+    pub epoch: Epoch,
+
+    /// Signers registered to sign during the current epoch, with their stake
+    pub signers: Vec<SignerWithStake>,
+
+    /// Next epoch
+    pub next_epoch: Epoch,
+
+    /// Signers registered to sign during the next epoch, with their stake
+    pub next_signers: Vec<SignerWithStake>,
+}
@@ -0,0 +1,137 @@
+use crate::entities::{Beacon, Epoch};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use thiserror::Error;
+
+/// The entities that can be certified by the Mithril network, with the beacon at which they
+/// are certified
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SignedEntityType {
+    /// Mithril stake distribution of the signers, certified once per epoch
+    MithrilStakeDistribution(Epoch),
+
+    /// Full Cardano immutable files, certified at each new immutable file
+    CardanoImmutableFilesFull(Beacon),
+}
+
+impl SignedEntityType {
+    /// Kind of the signed entity
+    pub fn kind(&self) -> SignedEntityKind {
+        match self {
+            Self::MithrilStakeDistribution(_) => SignedEntityKind::MithrilStakeDistribution,
+            Self::CardanoImmutableFilesFull(_) => SignedEntityKind::CardanoImmutableFilesFull,
+        }
+    }
+
+    /// Epoch at which the entity is certified
+    pub fn get_epoch(&self) -> Epoch {
+        match self {
+            Self::MithrilStakeDistribution(epoch) => *epoch,
+            Self::CardanoImmutableFilesFull(beacon) => beacon.epoch,
+        }
+    }
+}
+
+impl Display for SignedEntityType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MithrilStakeDistribution(epoch) => write!(f, "{}({})", self.kind(), epoch),
+            Self::CardanoImmutableFilesFull(beacon) => write!(f, "{}({})", self.kind(), beacon),
+        }
+    }
+}
+
+/// [SignedEntityKind] parsing related errors.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum SignedEntityKindError {
+    /// Error raised when the name of a signed entity kind is unknown
+    #[error("unknown signed entity kind: '{0}'")]
+    Unknown(String),
+}
+
+/// The kinds of entities that can be certified, in the order in which they are certified
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum SignedEntityKind {
+    /// Mithril stake distribution
+    MithrilStakeDistribution,
+
+    /// Full Cardano immutable files
+    CardanoImmutableFilesFull,
+}
+
+impl SignedEntityKind {
+    /// Build the entity of this kind to certify at the given beacon
+    pub fn to_signed_entity_type(&self, beacon: &Beacon) -> SignedEntityType {
+        match self {
+            Self::MithrilStakeDistribution => {
+                SignedEntityType::MithrilStakeDistribution(beacon.epoch)
+            }
+            Self::CardanoImmutableFilesFull => {
+                SignedEntityType::CardanoImmutableFilesFull(beacon.clone())
+            }
+        }
+    }
+}
+
+impl Display for SignedEntityKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MithrilStakeDistribution => write!(f, "MithrilStakeDistribution"),
+            Self::CardanoImmutableFilesFull => write!(f, "CardanoImmutableFilesFull"),
+        }
+    }
+}
+
+impl FromStr for SignedEntityKind {
+    type Err = SignedEntityKindError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "MithrilStakeDistribution" => Ok(Self::MithrilStakeDistribution),
+            "CardanoImmutableFilesFull" => Ok(Self::CardanoImmutableFilesFull),
+            kind => Err(SignedEntityKindError::Unknown(kind.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signed_entity_kind_to_signed_entity_type() {
+        let beacon = Beacon::new("devnet".to_string(), 3, 25);
+
+        assert_eq!(
+            SignedEntityType::MithrilStakeDistribution(Epoch(3)),
+            SignedEntityKind::MithrilStakeDistribution.to_signed_entity_type(&beacon)
+        );
+        assert_eq!(
+            SignedEntityType::CardanoImmutableFilesFull(beacon.clone()),
+            SignedEntityKind::CardanoImmutableFilesFull.to_signed_entity_type(&beacon)
+        );
+    }
+
+    #[test]
+    fn test_signed_entity_kind_from_str() {
+        for kind in [
+            SignedEntityKind::MithrilStakeDistribution,
+            SignedEntityKind::CardanoImmutableFilesFull,
+        ] {
+            assert_eq!(Ok(kind), kind.to_string().parse());
+        }
+        assert_eq!(
+            Err(SignedEntityKindError::Unknown("Unknown".to_string())),
+            "Unknown".parse::<SignedEntityKind>()
+        );
+    }
+
+    #[test]
+    fn test_stake_distribution_is_certified_first() {
+        assert!(
+            SignedEntityKind::MithrilStakeDistribution
+                < SignedEntityKind::CardanoImmutableFilesFull
+        );
+    }
+}
@@ -0,0 +1,83 @@
+use crate::entities::{Epoch, PartyId};
+use serde::{Deserialize, Serialize};
+
+/// SignerStatistics represents the participation of a signer during an epoch
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignerStatistics {
+    /// The unique identifier of the signer
+    pub party_id: PartyId,
+
+    /// Epoch of the statistics
+    pub epoch: Epoch,
+
+    /// Whether the signer registered its verification key to sign during the epoch
+    pub registered: bool,
+
+    /// Number of single signatures sent by the signer
+    pub signatures_sent: u64,
+
+    /// Number of lottery indexes won by the signer, summed over its single signatures
+    pub won_lotteries: u64,
+
+    /// Number of certificates whose multi-signature includes a single signature of the signer
+    pub certificates_included: u64,
+
+    /// Sum of the delays between the creation of a pending certificate and the reception of
+    /// the single signature of the signer for it, in milliseconds
+    pub signature_latency_total_ms: u64,
+
+    /// Longest delay between the creation of a pending certificate and the reception of the
+    /// single signature of the signer for it, in milliseconds
+    pub signature_latency_max_ms: u64,
+}
+
+impl SignerStatistics {
+    /// SignerStatistics factory, with nothing recorded yet
+    pub fn new(party_id: PartyId, epoch: Epoch) -> SignerStatistics {
+        SignerStatistics {
+            party_id,
+            epoch,
+            registered: false,
+            signatures_sent: 0,
+            won_lotteries: 0,
+            certificates_included: 0,
+            signature_latency_total_ms: 0,
+            signature_latency_max_ms: 0,
+        }
+    }
+
+    /// Record a single signature sent by the signer
+    pub fn record_single_signature(&mut self, won_lotteries: u64, latency_ms: u64) {
+        self.signatures_sent += 1;
+        self.won_lotteries += won_lotteries;
+        self.signature_latency_total_ms += latency_ms;
+        self.signature_latency_max_ms = self.signature_latency_max_ms.max(latency_ms);
+    }
+
+    /// Average delay between the creation of a pending certificate and the reception of the
+    /// single signature of the signer for it, in milliseconds
+    pub fn signature_latency_average_ms(&self) -> Option<u64> {
+        self.signature_latency_total_ms
+            .checked_div(self.signatures_sent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_single_signature_accumulates_lotteries_and_latencies() {
+        let mut statistics = SignerStatistics::new("party".to_string(), Epoch(3));
+        assert_eq!(None, statistics.signature_latency_average_ms());
+
+        statistics.record_single_signature(2, 300);
+        statistics.record_single_signature(1, 100);
+
+        assert_eq!(2, statistics.signatures_sent);
+        assert_eq!(3, statistics.won_lotteries);
+        assert_eq!(400, statistics.signature_latency_total_ms);
+        assert_eq!(300, statistics.signature_latency_max_ms);
+        assert_eq!(Some(200), statistics.signature_latency_average_ms());
+    }
+}
@@ -0,0 +1,27 @@
+use crate::entities::{Epoch, HexEncodedAgregateVerificationKey, StakeDistribution};
+use serde::{Deserialize, Serialize};
+
+/// SigningStakeDistribution represents the stake distribution of the registered signers of the
+/// current and the next epoch, and the aggregate verification keys computed from them
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SigningStakeDistribution {
+    /// Current epoch
+    pub epoch: Epoch,
+
+    /// Stakes of the signers registered to sign during the current epoch
+    pub stake_distribution: StakeDistribution,
+
+    /// Aggregate verification key of the current epoch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aggregate_verification_key: Option<HexEncodedAgregateVerificationKey>,
+
+    /// Next epoch
+    pub next_epoch: Epoch,
+
+    /// Stakes of the signers registered to sign during the next epoch
+    pub next_stake_distribution: StakeDistribution,
+
+    /// Aggregate verification key of the next epoch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_aggregate_verification_key: Option<HexEncodedAgregateVerificationKey>,
+}
@@ -1,6 +1,7 @@
 use crate::crypto_helper::{key_decode_hex, ProtocolSingleSignature};
 use crate::entities::{HexEncodedSingleSignature, LotteryIndex, PartyId};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 
 /// SingleSignatures represent single signatures originating from a participant in the network
 /// for a digest at won lottery indexes
@@ -41,6 +42,18 @@ impl SingleSignatures {
             )),
         }
     }
+
+    /// Number of distinct lotteries won by the given single signatures, the quorum is reached
+    /// when it reaches the `k` protocol parameter
+    pub fn count_won_lotteries<'a>(
+        single_signatures: impl IntoIterator<Item = &'a SingleSignatures>,
+    ) -> u64 {
+        single_signatures
+            .into_iter()
+            .flat_map(|single_signatures| single_signatures.won_indexes.iter())
+            .collect::<BTreeSet<_>>()
+            .len() as u64
+    }
 }
 
 #[cfg(test)]
@@ -68,4 +81,15 @@ mod tests {
 
         assert_eq!(protocol_sigs, signature.to_protocol_signature().unwrap());
     }
+
+    #[test]
+    fn won_lotteries_are_counted_once() {
+        let single_signatures = [
+            SingleSignatures::new("1".to_string(), "sig-1".to_string(), vec![1, 4]),
+            SingleSignatures::new("2".to_string(), "sig-2".to_string(), vec![4, 7]),
+        ];
+
+        assert_eq!(3, SingleSignatures::count_won_lotteries(&single_signatures));
+        assert_eq!(0, SingleSignatures::count_won_lotteries(&[]));
+    }
 }
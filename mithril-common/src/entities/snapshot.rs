@@ -1,4 +1,4 @@
-use crate::entities::Beacon;
+use crate::entities::{Beacon, CompressionAlgorithm};
 use serde::{Deserialize, Serialize};
 
 /// Snapshot represents a snapshot file and its metadata
@@ -21,6 +21,20 @@ pub struct Snapshot {
 
     /// Locations where the binary content of the snapshot can be retrieved
     pub locations: Vec<String>,
+
+    /// Compression algorithm of the snapshot archive
+    #[serde(default)]
+    pub compression_algorithm: CompressionAlgorithm,
+
+    /// Digest of the snapshot this incremental snapshot is based on, its archive only contains
+    /// the immutable files added since the base snapshot. Not set for a full snapshot.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_digest: Option<String>,
+
+    /// Hash of the manifest listing the chunks of the snapshot archive, the locations then
+    /// point to this manifest. Not set for a snapshot stored as a single archive.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manifest_hash: Option<String>,
 }
 
 impl Snapshot {
@@ -32,6 +46,7 @@ impl Snapshot {
         size: u64,
         created_at: String,
         locations: Vec<String>,
+        compression_algorithm: CompressionAlgorithm,
     ) -> Snapshot {
         Snapshot {
             digest,
@@ -40,6 +55,20 @@ impl Snapshot {
             size,
             created_at,
             locations,
+            compression_algorithm,
+            base_digest: None,
+            manifest_hash: None,
         }
     }
+
+    /// Whether the archive of the snapshot only contains the immutable files added since its
+    /// base snapshot
+    pub fn is_incremental(&self) -> bool {
+        self.base_digest.is_some()
+    }
+
+    /// Whether the archive of the snapshot is stored as chunks listed in a manifest
+    pub fn is_chunked(&self) -> bool {
+        self.manifest_hash.is_some()
+    }
 }
@@ -0,0 +1,202 @@
+use crate::entities::CompressionAlgorithm;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// SnapshotChunk represents a content addressed chunk of a snapshot archive
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotChunk {
+    /// Hash of the content of the chunk, used as its name in the chunk storage
+    pub hash: String,
+
+    /// Size of the chunk in Bytes
+    pub size: u64,
+}
+
+impl SnapshotChunk {
+    /// SnapshotChunk factory
+    pub fn new(hash: String, size: u64) -> Self {
+        Self { hash, size }
+    }
+
+    /// Computes the hash of the content of a chunk
+    pub fn compute_hash(content: &[u8]) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(content);
+        hex::encode(hasher.finalize())
+    }
+}
+
+/// SnapshotManifest lists, in order, the chunks that must be concatenated to rebuild the
+/// archive of a snapshot.
+///
+/// The hash of the manifest is part of the protocol message signed by the certificate of the
+/// snapshot, so that the manifest can be checked against this certificate before downloading
+/// any chunk.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    /// Digest of the snapshot
+    pub digest: String,
+
+    /// Compression algorithm of the snapshot archive
+    pub compression_algorithm: CompressionAlgorithm,
+
+    /// Size of the snapshot archive in Bytes
+    pub size: u64,
+
+    /// Chunks of the snapshot archive
+    pub chunks: Vec<SnapshotChunk>,
+}
+
+impl SnapshotManifest {
+    /// SnapshotManifest factory
+    pub fn new(
+        digest: String,
+        compression_algorithm: CompressionAlgorithm,
+        chunks: Vec<SnapshotChunk>,
+    ) -> Self {
+        let size = chunks.iter().map(|chunk| chunk.size).sum();
+
+        Self {
+            digest,
+            compression_algorithm,
+            size,
+            chunks,
+        }
+    }
+
+    /// Create the manifest of a snapshot archive split in chunks of the given size, the last
+    /// chunk may be smaller.
+    ///
+    /// The chunks are fixed size slices of the compressed archive: they allow resuming
+    /// transfers but are not deduplicated between snapshots.
+    pub fn from_archive(
+        archive_path: &Path,
+        chunk_size: u64,
+        digest: String,
+        compression_algorithm: CompressionAlgorithm,
+    ) -> io::Result<Self> {
+        if chunk_size == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "chunk size must be greater than zero",
+            ));
+        }
+        let mut archive = File::open(archive_path)?;
+        let mut chunks = Vec::new();
+
+        loop {
+            let mut content = Vec::new();
+            (&mut archive).take(chunk_size).read_to_end(&mut content)?;
+            if content.is_empty() {
+                break;
+            }
+            chunks.push(SnapshotChunk::new(
+                SnapshotChunk::compute_hash(&content),
+                content.len() as u64,
+            ));
+        }
+
+        Ok(Self::new(digest, compression_algorithm, chunks))
+    }
+
+    /// Computes the hash of the manifest
+    pub fn compute_hash(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.digest.as_bytes());
+        hasher.update(self.compression_algorithm.to_string().as_bytes());
+        hasher.update(self.size.to_be_bytes());
+        self.chunks.iter().for_each(|chunk| {
+            hasher.update(chunk.hash.as_bytes());
+            hasher.update(chunk.size.to_be_bytes());
+        });
+        hex::encode(hasher.finalize())
+    }
+
+    /// Location of a chunk given the location of the manifest, the chunks being stored next
+    /// to the manifest
+    pub fn chunk_location(manifest_location: &str, chunk_hash: &str) -> String {
+        match manifest_location.rsplit_once('/') {
+            Some((directory, _)) => format!("{}/{}", directory, chunk_hash),
+            None => chunk_hash.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn dummy_manifest() -> SnapshotManifest {
+        SnapshotManifest::new(
+            "digest-123".to_string(),
+            CompressionAlgorithm::Gzip,
+            vec![
+                SnapshotChunk::new("chunk-hash-1".to_string(), 10),
+                SnapshotChunk::new("chunk-hash-2".to_string(), 5),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_snapshot_manifest_compute_hash() {
+        let manifest = dummy_manifest();
+        let hash_expected = manifest.compute_hash();
+        assert_eq!(15, manifest.size);
+
+        let mut manifest_modified = manifest.clone();
+        manifest_modified.digest = "digest-456".to_string();
+        assert_ne!(hash_expected, manifest_modified.compute_hash());
+
+        let mut manifest_modified = manifest.clone();
+        manifest_modified.chunks.swap(0, 1);
+        assert_ne!(hash_expected, manifest_modified.compute_hash());
+
+        let mut manifest_modified = manifest;
+        manifest_modified.chunks[1].hash = "chunk-hash-3".to_string();
+        assert_ne!(hash_expected, manifest_modified.compute_hash());
+    }
+
+    #[test]
+    fn test_snapshot_manifest_from_archive() {
+        let archive_path =
+            std::env::temp_dir().join("mithril_test_snapshot_manifest_from_archive.tar.gz");
+        let content = b"0123456789abcdefghijklmnopqrstuvwxyz";
+        File::create(&archive_path)
+            .unwrap()
+            .write_all(content)
+            .unwrap();
+
+        let manifest = SnapshotManifest::from_archive(
+            &archive_path,
+            16,
+            "digest-123".to_string(),
+            CompressionAlgorithm::Gzip,
+        )
+        .expect("creating the manifest of an archive should not fail");
+
+        assert_eq!(content.len() as u64, manifest.size);
+        assert_eq!(
+            vec![
+                SnapshotChunk::new(SnapshotChunk::compute_hash(&content[0..16]), 16),
+                SnapshotChunk::new(SnapshotChunk::compute_hash(&content[16..32]), 16),
+                SnapshotChunk::new(SnapshotChunk::compute_hash(&content[32..]), 4),
+            ],
+            manifest.chunks
+        );
+    }
+
+    #[test]
+    fn test_snapshot_manifest_chunk_location() {
+        assert_eq!(
+            "http://whatev.er/chunks/chunk-hash-1",
+            SnapshotManifest::chunk_location(
+                "http://whatev.er/chunks/digest-123.manifest.json",
+                "chunk-hash-1"
+            )
+        );
+    }
+}
@@ -60,6 +60,9 @@ pub fn certificate_pending() -> entities::CertificatePending {
     let current_signers = signers[1..3].to_vec();
     let next_signers = signers[2..5].to_vec();
 
+    // Signed entity type
+    let signed_entity_type = entities::SignedEntityType::CardanoImmutableFilesFull(beacon.clone());
+
     // Certificate pending
     entities::CertificatePending::new(
         beacon,
@@ -67,6 +70,7 @@ pub fn certificate_pending() -> entities::CertificatePending {
         next_protocol_parameters,
         current_signers,
         next_signers,
+        signed_entity_type,
     )
 }
 
@@ -164,6 +168,7 @@ pub fn snapshots(total: u64) -> Vec<entities::Snapshot> {
                 size,
                 created_at,
                 locations,
+                entities::CompressionAlgorithm::Gzip,
             )
         })
         .collect::<Vec<entities::Snapshot>>()
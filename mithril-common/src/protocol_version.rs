@@ -0,0 +1,254 @@
+//! Protocol version semantics shared by the Mithril nodes
+//!
+//! The protocol versions are semantic versions: two versions are compatible if they have the
+//! same major version, or the same minor version for the `0.x` versions. The aggregator
+//! advertises the protocol versions it supports, and the signers and clients check that they
+//! are compatible with them.
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use thiserror::Error;
+use warp::http::HeaderMap;
+
+use crate::crypto_helper::PROTOCOL_VERSION;
+use crate::entities::ProtocolVersion;
+
+/// HTTP header in which a node sends its current protocol version
+pub const PROTOCOL_VERSION_HEADER: &str = "mithril-protocol-version";
+
+/// Protocol versions supported by the node, the current one first
+pub const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &[PROTOCOL_VERSION];
+
+/// [SemanticProtocolVersion] and protocol versions negotiation related errors.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ProtocolVersionError {
+    /// Error raised when a protocol version is not a semantic version
+    #[error("malformed protocol version: '{0}'")]
+    Malformed(String),
+
+    /// Error raised when a protocol version is not compatible with the supported ones
+    #[error("protocol version '{version}' is not compatible with the supported protocol versions: {supported:?}")]
+    Incompatible {
+        /// Incompatible protocol version
+        version: ProtocolVersion,
+
+        /// Supported protocol versions
+        supported: Vec<ProtocolVersion>,
+    },
+}
+
+/// A protocol version parsed as a `major.minor.patch` semantic version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SemanticProtocolVersion {
+    /// Major version
+    pub major: u64,
+
+    /// Minor version
+    pub minor: u64,
+
+    /// Patch version
+    pub patch: u64,
+}
+
+impl SemanticProtocolVersion {
+    /// SemanticProtocolVersion factory
+    pub fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Parse a `major.minor.patch` protocol version
+    pub fn parse(version: &str) -> Result<Self, ProtocolVersionError> {
+        let numbers = version
+            .split('.')
+            .map(|number| number.parse::<u64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| ProtocolVersionError::Malformed(version.to_string()))?;
+
+        match numbers.as_slice() {
+            [major, minor, patch] => Ok(Self::new(*major, *minor, *patch)),
+            _ => Err(ProtocolVersionError::Malformed(version.to_string())),
+        }
+    }
+
+    /// Are the messages and certificates formats of the two versions compatible
+    pub fn is_compatible_with(&self, other: &Self) -> bool {
+        match (self.major, other.major) {
+            (0, 0) => self.minor == other.minor,
+            (major, other_major) => major == other_major,
+        }
+    }
+}
+
+impl Display for SemanticProtocolVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Protocol versions advertised by a node
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProtocolVersions {
+    /// Current protocol version of the node
+    pub protocol_version: ProtocolVersion,
+
+    /// Protocol versions supported by the node
+    pub supported_protocol_versions: Vec<ProtocolVersion>,
+}
+
+impl ProtocolVersions {
+    /// ProtocolVersions factory
+    pub fn new(
+        protocol_version: ProtocolVersion,
+        supported_protocol_versions: Vec<ProtocolVersion>,
+    ) -> Self {
+        Self {
+            protocol_version,
+            supported_protocol_versions,
+        }
+    }
+}
+
+impl Default for ProtocolVersions {
+    fn default() -> Self {
+        Self::new(
+            PROTOCOL_VERSION.to_string(),
+            SUPPORTED_PROTOCOL_VERSIONS
+                .iter()
+                .map(|version| version.to_string())
+                .collect(),
+        )
+    }
+}
+
+/// Check that a protocol version is compatible with one of the supported protocol versions
+pub fn check_protocol_version(version: &str) -> Result<(), ProtocolVersionError> {
+    negotiate_protocol_version(&[version.to_string()]).map(|_| ())
+}
+
+/// Check the protocol version sent by a peer in the [PROTOCOL_VERSION_HEADER] header of a
+/// request or a response, a peer that doesn't send it is assumed compatible
+pub fn check_protocol_version_header(headers: &HeaderMap) -> Result<(), ProtocolVersionError> {
+    match headers.get(PROTOCOL_VERSION_HEADER) {
+        Some(version) => check_protocol_version(
+            version
+                .to_str()
+                .map_err(|_| ProtocolVersionError::Malformed(format!("{:?}", version)))?,
+        ),
+        None => Ok(()),
+    }
+}
+
+/// Select the most recent supported protocol version that is compatible with one of the
+/// protocol versions of a peer
+pub fn negotiate_protocol_version(
+    peer_versions: &[ProtocolVersion],
+) -> Result<ProtocolVersion, ProtocolVersionError> {
+    let peer_versions = peer_versions
+        .iter()
+        .map(|version| SemanticProtocolVersion::parse(version))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut supported_versions = SUPPORTED_PROTOCOL_VERSIONS
+        .iter()
+        .map(|version| SemanticProtocolVersion::parse(version))
+        .collect::<Result<Vec<_>, _>>()?;
+    supported_versions.sort();
+
+    supported_versions
+        .into_iter()
+        .rev()
+        .find(|supported_version| {
+            peer_versions
+                .iter()
+                .any(|peer_version| supported_version.is_compatible_with(peer_version))
+        })
+        .map(|version| version.to_string())
+        .ok_or_else(|| ProtocolVersionError::Incompatible {
+            version: peer_versions
+                .iter()
+                .map(|version| version.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+            supported: ProtocolVersions::default().supported_protocol_versions,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_protocol_version() {
+        assert_eq!(
+            SemanticProtocolVersion::new(1, 22, 333),
+            SemanticProtocolVersion::parse("1.22.333").unwrap()
+        );
+        assert_eq!(
+            "1.22.333",
+            SemanticProtocolVersion::parse("1.22.333")
+                .unwrap()
+                .to_string()
+        );
+        for malformed_version in ["", "1.2", "1.2.3.4", "1.x.3", "v1.2.3"] {
+            assert_eq!(
+                Err(ProtocolVersionError::Malformed(
+                    malformed_version.to_string()
+                )),
+                SemanticProtocolVersion::parse(malformed_version),
+            );
+        }
+    }
+
+    #[test]
+    fn test_protocol_version_compatibility() {
+        let parse = |version| SemanticProtocolVersion::parse(version).unwrap();
+
+        assert!(parse("0.1.0").is_compatible_with(&parse("0.1.7")));
+        assert!(!parse("0.1.0").is_compatible_with(&parse("0.2.0")));
+        assert!(parse("1.0.0").is_compatible_with(&parse("1.4.2")));
+        assert!(!parse("1.0.0").is_compatible_with(&parse("2.0.0")));
+        assert!(!parse("0.1.0").is_compatible_with(&parse("1.1.0")));
+    }
+
+    #[test]
+    fn test_negotiate_protocol_version() {
+        assert_eq!(
+            Ok(PROTOCOL_VERSION.to_string()),
+            negotiate_protocol_version(&[PROTOCOL_VERSION.to_string()])
+        );
+        assert_eq!(
+            Ok(PROTOCOL_VERSION.to_string()),
+            negotiate_protocol_version(&["99.0.0".to_string(), PROTOCOL_VERSION.to_string()])
+        );
+        assert!(matches!(
+            negotiate_protocol_version(&["99.0.0".to_string()]),
+            Err(ProtocolVersionError::Incompatible { .. })
+        ));
+        check_protocol_version(PROTOCOL_VERSION).expect("the current version should be supported");
+        check_protocol_version("99.0.0").expect_err("a future major version should be rejected");
+    }
+
+    #[test]
+    fn test_check_protocol_version_header() {
+        let mut headers = HeaderMap::new();
+        check_protocol_version_header(&headers)
+            .expect("a peer without protocol version should be assumed compatible");
+
+        headers.insert(PROTOCOL_VERSION_HEADER, PROTOCOL_VERSION.parse().unwrap());
+        check_protocol_version_header(&headers).expect("the current version should be supported");
+
+        headers.insert(PROTOCOL_VERSION_HEADER, "99.0.0".parse().unwrap());
+        assert!(matches!(
+            check_protocol_version_header(&headers),
+            Err(ProtocolVersionError::Incompatible { .. })
+        ));
+
+        headers.insert(PROTOCOL_VERSION_HEADER, "not-a-version".parse().unwrap());
+        assert!(matches!(
+            check_protocol_version_header(&headers),
+            Err(ProtocolVersionError::Malformed(_))
+        ));
+    }
+}
@@ -75,6 +75,10 @@ where
         }
     }
 
+    async fn get_all_records(&self) -> Result<Vec<(Self::Key, Self::Record)>, AdapterError> {
+        self.get_last_n_records(1).await
+    }
+
     async fn remove(&mut self, key: &Self::Key) -> Result<Option<Self::Record>, AdapterError> {
         if let Some(record) = self.get_record(key).await? {
             self.last_key = None;
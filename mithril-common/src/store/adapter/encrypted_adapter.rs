@@ -0,0 +1,488 @@
+use async_trait::async_trait;
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest, Sha256};
+use std::{fmt::Debug, fs, marker::PhantomData, path::Path};
+use thiserror::Error;
+
+use super::{AdapterError, StoreAdapter};
+
+const KEY_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 12;
+
+/// [EncryptedStoreAdapter] related errors.
+#[derive(Debug, Error)]
+pub enum StoreEncryptionError {
+    /// The encryption key is not a valid hex encoded 32 bytes key.
+    #[error("invalid store encryption key: {0}")]
+    InvalidKey(String),
+
+    /// The file holding the encryption key can't be read.
+    #[error("could not read the store encryption key file '{path}': {error}")]
+    KeyFile {
+        /// Path of the key file.
+        path: String,
+
+        /// Underlying IO error.
+        error: std::io::Error,
+    },
+
+    /// The record was encrypted with a key that is not known by the adapter.
+    #[error("record was encrypted with the key '{record_key}' but the configured key is '{current_key}', is the store encryption key correct?")]
+    UnknownKey {
+        /// Fingerprint of the key used to encrypt the record.
+        record_key: String,
+
+        /// Fingerprint of the current key of the adapter.
+        current_key: String,
+    },
+
+    /// The record can't be authenticated with the key it claims to be encrypted with.
+    #[error("record decryption failed with the key '{0}': the key is wrong or the record has been tampered with")]
+    Decryption(String),
+
+    /// The record can't be encrypted.
+    #[error("record encryption failed with the key '{0}'")]
+    Encryption(String),
+
+    /// The record or its key can't be (de)serialized.
+    #[error("record serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+impl From<StoreEncryptionError> for AdapterError {
+    fn from(error: StoreEncryptionError) -> Self {
+        AdapterError::EncryptionError(error.into())
+    }
+}
+
+/// Symmetric key used to encrypt the records of an [EncryptedStoreAdapter].
+///
+/// It is (de)serialized as a hex string, its [Debug] output only shows its
+/// fingerprint so it never ends up in logs.
+#[derive(Clone, PartialEq, Eq)]
+pub struct StoreEncryptionKey {
+    key: [u8; KEY_LENGTH],
+}
+
+impl StoreEncryptionKey {
+    /// Generate a new random key.
+    pub fn generate() -> Self {
+        let generated_key = ChaCha20Poly1305::generate_key(&mut OsRng);
+        let mut key = [0u8; KEY_LENGTH];
+        key.copy_from_slice(&generated_key);
+
+        Self { key }
+    }
+
+    /// Decode a hex encoded key.
+    pub fn from_hex(hex_key: &str) -> Result<Self, StoreEncryptionError> {
+        let bytes = hex::decode(hex_key.trim())
+            .map_err(|e| StoreEncryptionError::InvalidKey(e.to_string()))?;
+        let key: [u8; KEY_LENGTH] = bytes.try_into().map_err(|_| {
+            StoreEncryptionError::InvalidKey(format!("a key must be {} bytes long", KEY_LENGTH))
+        })?;
+
+        Ok(Self { key })
+    }
+
+    /// Read a hex encoded key from the given file.
+    pub fn from_file(path: &Path) -> Result<Self, StoreEncryptionError> {
+        let hex_key = fs::read_to_string(path).map_err(|error| StoreEncryptionError::KeyFile {
+            path: path.display().to_string(),
+            error,
+        })?;
+
+        Self::from_hex(&hex_key)
+    }
+
+    /// Hex encode the key.
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.key)
+    }
+
+    /// Compute a fingerprint identifying the key without disclosing it.
+    pub fn fingerprint(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.key);
+
+        hex::encode(&hasher.finalize()[..8])
+    }
+
+    fn cipher(&self) -> ChaCha20Poly1305 {
+        ChaCha20Poly1305::new(Key::from_slice(&self.key))
+    }
+}
+
+impl Debug for StoreEncryptionKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "StoreEncryptionKey({})", self.fingerprint())
+    }
+}
+
+impl Serialize for StoreEncryptionKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_hex())
+    }
+}
+
+impl<'de> Deserialize<'de> for StoreEncryptionKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let hex_key = String::deserialize(deserializer)?;
+
+        Self::from_hex(&hex_key).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Record as it is persisted by an [EncryptedStoreAdapter].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncryptedRecord {
+    /// Fingerprint of the key used to encrypt the record.
+    pub key_fingerprint: String,
+
+    /// Hex encoded nonce.
+    pub nonce: String,
+
+    /// Hex encoded ciphertext, including the authentication tag.
+    pub ciphertext: String,
+}
+
+/// A [StoreAdapter] that encrypts records with ChaCha20-Poly1305 before
+/// handing them to another adapter.
+///
+/// Keys are stored in clear and are authenticated along with their record, so
+/// a record can't be moved to another key without being detected.
+///
+/// To rotate the encryption key, give the new key as the current key and the
+/// old one as a previous key, then call [reencrypt_records][EncryptedStoreAdapter::reencrypt_records].
+pub struct EncryptedStoreAdapter<K, R> {
+    adapter: Box<dyn StoreAdapter<Key = K, Record = EncryptedRecord>>,
+    current_key: StoreEncryptionKey,
+    previous_keys: Vec<StoreEncryptionKey>,
+    record: PhantomData<R>,
+}
+
+impl<K, R> EncryptedStoreAdapter<K, R>
+where
+    K: Send + Sync + Serialize,
+    R: Send + Sync + Serialize + DeserializeOwned,
+{
+    /// Create a new instance encrypting records with the given key.
+    pub fn new(
+        adapter: Box<dyn StoreAdapter<Key = K, Record = EncryptedRecord>>,
+        current_key: StoreEncryptionKey,
+    ) -> Self {
+        Self {
+            adapter,
+            current_key,
+            previous_keys: Vec::new(),
+            record: PhantomData,
+        }
+    }
+
+    /// Keys that are only used to decrypt records that have not been
+    /// re-encrypted with the current key yet.
+    pub fn with_previous_keys(mut self, previous_keys: Vec<StoreEncryptionKey>) -> Self {
+        self.previous_keys = previous_keys;
+        self
+    }
+
+    /// Check that every record can be decrypted and re-encrypt with the
+    /// current key the ones that were encrypted with a previous key.
+    ///
+    /// Return the number of re-encrypted records.
+    pub async fn reencrypt_records(&mut self) -> Result<usize, AdapterError> {
+        let mut nb_reencrypted = 0;
+
+        for (key, encrypted_record) in self.adapter.get_all_records().await? {
+            let record = self.decrypt(&key, &encrypted_record)?;
+
+            if encrypted_record.key_fingerprint != self.current_key.fingerprint() {
+                let encrypted_record = self.encrypt(&key, &record)?;
+                self.adapter.store_record(&key, &encrypted_record).await?;
+                nb_reencrypted += 1;
+            }
+        }
+
+        Ok(nb_reencrypted)
+    }
+
+    fn find_key(&self, fingerprint: &str) -> Result<&StoreEncryptionKey, StoreEncryptionError> {
+        std::iter::once(&self.current_key)
+            .chain(self.previous_keys.iter())
+            .find(|key| key.fingerprint() == fingerprint)
+            .ok_or_else(|| StoreEncryptionError::UnknownKey {
+                record_key: fingerprint.to_string(),
+                current_key: self.current_key.fingerprint(),
+            })
+    }
+
+    fn encrypt(&self, key: &K, record: &R) -> Result<EncryptedRecord, StoreEncryptionError> {
+        let aad = serde_json::to_vec(key)?;
+        let plaintext = serde_json::to_vec(record)?;
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .current_key
+            .cipher()
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: &plaintext,
+                    aad: &aad,
+                },
+            )
+            .map_err(|_| StoreEncryptionError::Encryption(self.current_key.fingerprint()))?;
+
+        Ok(EncryptedRecord {
+            key_fingerprint: self.current_key.fingerprint(),
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(ciphertext),
+        })
+    }
+
+    fn decrypt(&self, key: &K, record: &EncryptedRecord) -> Result<R, StoreEncryptionError> {
+        let encryption_key = self.find_key(&record.key_fingerprint)?;
+        let decryption_error = || StoreEncryptionError::Decryption(encryption_key.fingerprint());
+        let aad = serde_json::to_vec(key)?;
+        let nonce = hex::decode(&record.nonce).map_err(|_| decryption_error())?;
+        if nonce.len() != NONCE_LENGTH {
+            return Err(decryption_error());
+        }
+        let ciphertext = hex::decode(&record.ciphertext).map_err(|_| decryption_error())?;
+        let plaintext = encryption_key
+            .cipher()
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: &aad,
+                },
+            )
+            .map_err(|_| decryption_error())?;
+
+        Ok(serde_json::from_slice(&plaintext)?)
+    }
+}
+
+#[async_trait]
+impl<K, R> StoreAdapter for EncryptedStoreAdapter<K, R>
+where
+    K: Send + Sync + Serialize,
+    R: Send + Sync + Serialize + DeserializeOwned,
+{
+    type Key = K;
+    type Record = R;
+
+    async fn store_record(
+        &mut self,
+        key: &Self::Key,
+        record: &Self::Record,
+    ) -> Result<(), AdapterError> {
+        let encrypted_record = self.encrypt(key, record)?;
+
+        self.adapter.store_record(key, &encrypted_record).await
+    }
+
+    async fn get_record(&self, key: &Self::Key) -> Result<Option<Self::Record>, AdapterError> {
+        match self.adapter.get_record(key).await? {
+            Some(encrypted_record) => Ok(Some(self.decrypt(key, &encrypted_record)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn record_exists(&self, key: &Self::Key) -> Result<bool, AdapterError> {
+        self.adapter.record_exists(key).await
+    }
+
+    async fn get_last_n_records(
+        &self,
+        how_many: usize,
+    ) -> Result<Vec<(Self::Key, Self::Record)>, AdapterError> {
+        let mut records = Vec::new();
+
+        for (key, encrypted_record) in self.adapter.get_last_n_records(how_many).await? {
+            let record = self.decrypt(&key, &encrypted_record)?;
+            records.push((key, record));
+        }
+
+        Ok(records)
+    }
+
+    async fn get_all_records(&self) -> Result<Vec<(Self::Key, Self::Record)>, AdapterError> {
+        let mut records = Vec::new();
+
+        for (key, encrypted_record) in self.adapter.get_all_records().await? {
+            let record = self.decrypt(&key, &encrypted_record)?;
+            records.push((key, record));
+        }
+
+        Ok(records)
+    }
+
+    async fn remove(&mut self, key: &Self::Key) -> Result<Option<Self::Record>, AdapterError> {
+        match self.adapter.remove(key).await? {
+            Some(encrypted_record) => Ok(Some(self.decrypt(key, &encrypted_record)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn get_iter(&self) -> Result<Box<dyn Iterator<Item = Self::Record> + '_>, AdapterError> {
+        // Keys are needed to authenticate the records, so all of them are
+        // fetched and decrypted upfront.
+        let records = self
+            .get_all_records()
+            .await?
+            .into_iter()
+            .map(|(_key, record)| record);
+
+        Ok(Box::new(records))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::adapter::MemoryAdapter;
+
+    fn init_adapter(key: StoreEncryptionKey) -> EncryptedStoreAdapter<u64, String> {
+        EncryptedStoreAdapter::new(Box::new(MemoryAdapter::new(None).unwrap()), key)
+    }
+
+    #[tokio::test]
+    async fn store_and_get_record() {
+        let mut adapter = init_adapter(StoreEncryptionKey::generate());
+        adapter.store_record(&1, &"one".to_string()).await.unwrap();
+        adapter.store_record(&2, &"two".to_string()).await.unwrap();
+
+        assert_eq!(
+            Some("one".to_string()),
+            adapter.get_record(&1).await.unwrap()
+        );
+        assert!(adapter.record_exists(&2).await.unwrap());
+        assert_eq!(None, adapter.get_record(&3).await.unwrap());
+        assert_eq!(
+            vec!["two".to_string(), "one".to_string()],
+            adapter.get_iter().await.unwrap().collect::<Vec<String>>()
+        );
+        assert_eq!(Some("two".to_string()), adapter.remove(&2).await.unwrap());
+        assert_eq!(
+            vec![(1, "one".to_string())],
+            adapter.get_last_n_records(5).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn records_are_not_stored_in_clear() {
+        let key = StoreEncryptionKey::generate();
+        let mut adapter = init_adapter(key.clone());
+        adapter
+            .store_record(&1, &"secret".to_string())
+            .await
+            .unwrap();
+
+        let (_, encrypted_record) = adapter.adapter.get_last_n_records(1).await.unwrap()[0].clone();
+        assert_eq!(key.fingerprint(), encrypted_record.key_fingerprint);
+        assert!(!encrypted_record.ciphertext.contains(&hex::encode("secret")));
+    }
+
+    #[tokio::test]
+    async fn wrong_key_fails_with_a_clear_error() {
+        let mut adapter = init_adapter(StoreEncryptionKey::generate());
+        adapter.store_record(&1, &"one".to_string()).await.unwrap();
+        adapter.current_key = StoreEncryptionKey::generate();
+
+        let error = adapter
+            .get_record(&1)
+            .await
+            .expect_err("decrypting with another key should fail");
+        assert!(
+            error
+                .to_string()
+                .contains("is the store encryption key correct"),
+            "unexpected error: {}",
+            error
+        );
+    }
+
+    #[tokio::test]
+    async fn tampered_record_fails_authentication() {
+        let mut adapter = init_adapter(StoreEncryptionKey::generate());
+        adapter.store_record(&1, &"one".to_string()).await.unwrap();
+        let (_, encrypted_record) = adapter.adapter.get_last_n_records(1).await.unwrap()[0].clone();
+        // Move the record to another key
+        adapter
+            .adapter
+            .store_record(&2, &encrypted_record)
+            .await
+            .unwrap();
+
+        let error = adapter
+            .get_record(&2)
+            .await
+            .expect_err("a record moved to another key should not be decrypted");
+        assert!(
+            error.to_string().contains("tampered"),
+            "unexpected error: {}",
+            error
+        );
+    }
+
+    #[tokio::test]
+    async fn rotate_key_and_reencrypt_records() {
+        let previous_key = StoreEncryptionKey::generate();
+        let new_key = StoreEncryptionKey::generate();
+        let mut adapter = init_adapter(previous_key.clone());
+        adapter.store_record(&1, &"one".to_string()).await.unwrap();
+        adapter.store_record(&2, &"two".to_string()).await.unwrap();
+
+        let mut adapter = EncryptedStoreAdapter {
+            current_key: new_key.clone(),
+            ..adapter
+        }
+        .with_previous_keys(vec![previous_key]);
+        adapter
+            .store_record(&3, &"three".to_string())
+            .await
+            .unwrap();
+
+        assert_eq!(2, adapter.reencrypt_records().await.unwrap());
+        assert_eq!(0, adapter.reencrypt_records().await.unwrap());
+
+        let adapter = EncryptedStoreAdapter {
+            previous_keys: Vec::new(),
+            ..adapter
+        };
+        assert_eq!(
+            vec![
+                (3, "three".to_string()),
+                (2, "two".to_string()),
+                (1, "one".to_string())
+            ],
+            adapter.get_last_n_records(5).await.unwrap()
+        );
+        assert!(adapter
+            .adapter
+            .get_last_n_records(5)
+            .await
+            .unwrap()
+            .iter()
+            .all(|(_, record)| record.key_fingerprint == new_key.fingerprint()));
+    }
+
+    #[test]
+    fn key_hex_round_trip() {
+        let key = StoreEncryptionKey::generate();
+
+        assert_eq!(key, StoreEncryptionKey::from_hex(&key.to_hex()).unwrap());
+        assert_eq!(
+            key,
+            serde_json::from_str(&serde_json::to_string(&key).unwrap()).unwrap()
+        );
+        assert!(!format!("{:?}", key).contains(&key.to_hex()));
+        StoreEncryptionKey::from_hex("00ff").expect_err("a short key should be rejected");
+        StoreEncryptionKey::from_hex("not hex").expect_err("a non hex key should be rejected");
+    }
+}
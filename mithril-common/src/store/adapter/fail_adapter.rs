@@ -64,6 +64,12 @@ where
         ))
     }
 
+    async fn get_all_records(&self) -> Result<Vec<(Self::Key, Self::Record)>, AdapterError> {
+        Err(AdapterError::GeneralError(
+            "Fail adapter always fails".to_string(),
+        ))
+    }
+
     async fn remove(&mut self, _key: &Self::Key) -> Result<Option<Self::Record>, AdapterError> {
         Err(AdapterError::GeneralError(
             "Fail adapter always fails".to_string(),
@@ -1,11 +1,12 @@
 use std::path::Path;
 use std::{
     collections::hash_map::DefaultHasher,
-    fs::{self, Metadata},
+    fs,
     hash::{Hash, Hasher},
     io::Write,
     marker::PhantomData,
     path::PathBuf,
+    time::SystemTime,
 };
 
 use async_trait::async_trait;
@@ -62,26 +63,28 @@ where
     }
 
     fn get_hash_iter(&self) -> Result<Box<dyn Iterator<Item = String>>, AdapterError> {
-        let mut hashes: Vec<(String, Metadata)> = Vec::new();
-        let glob_expr = format!("{}/*.key", &self.dirpath.to_str().unwrap());
+        let mut hashes: Vec<(String, SystemTime)> = Vec::new();
+        let glob_expr = format!("{}/*.key", self.dirpath.display());
 
         for entry in glob(&glob_expr).map_err(|e| AdapterError::OpeningStreamError(e.into()))? {
             let path = entry.map_err(|e| AdapterError::OpeningStreamError(e.into()))?;
-            let metadata =
-                fs::metadata(&path).map_err(|e| AdapterError::OpeningStreamError(e.into()))?;
-            hashes.push((
-                path.as_path()
-                    .file_stem()
-                    .unwrap()
-                    .to_str()
-                    .unwrap()
-                    .to_string(),
-                metadata,
-            ));
+            let modified = fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .map_err(|e| AdapterError::OpeningStreamError(e.into()))?;
+            let hash = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .ok_or_else(|| {
+                    AdapterError::ParsingDataError(
+                        format!("invalid key file name '{}'", path.display()).into(),
+                    )
+                })?
+                .to_string();
+            hashes.push((hash, modified));
         }
-        hashes.sort_by_key(|(_, meta)| meta.modified().unwrap());
+        hashes.sort_by_key(|(_, modified)| *modified);
 
-        Ok(Box::new(hashes.into_iter().rev().map(|(hash, _meta)| hash)))
+        Ok(Box::new(hashes.into_iter().rev().map(|(hash, _)| hash)))
     }
 
     fn write_file(&self, filename: &str, msg: &str) -> Result<(), AdapterError> {
@@ -128,6 +131,29 @@ where
 
         Ok(Some(record))
     }
+
+    /// Read the keys and the records stored under the given hashes
+    fn read_key_records(&self, hashes: Vec<String>) -> Result<Vec<(K, V)>, AdapterError> {
+        let mut records: Vec<(K, V)> = vec![];
+
+        for hash in hashes {
+            let filename = format!("{}.key", hash);
+            let content = fs::read_to_string(self.dirpath.join(filename))
+                .map_err(|e| AdapterError::OpeningStreamError(e.into()))?;
+            let key: K = serde_json::from_str(&content)
+                .map_err(|e| AdapterError::ParsingDataError(e.into()))?;
+            let record = self
+                .read_record(&self.get_filename_from_key(&key))?
+                .ok_or_else(|| {
+                    AdapterError::ParsingDataError(
+                        format!("no record is associated to the key file '{}.key'", hash).into(),
+                    )
+                })?;
+            records.push((key, record));
+        }
+
+        Ok(records)
+    }
 }
 
 #[async_trait]
@@ -176,27 +202,26 @@ where
         how_many: usize,
     ) -> Result<Vec<(Self::Key, Self::Record)>, AdapterError> {
         let hashes: Vec<String> = self.get_hash_iter()?.take(how_many).collect();
-        let mut records: Vec<(K, V)> = vec![];
 
-        for hash in hashes {
-            let filename = format!("{}.key", hash);
-            let content = fs::read_to_string(self.dirpath.join(filename))
-                .map_err(|e| AdapterError::OpeningStreamError(e.into()))?;
-            let key: K = serde_json::from_str(&content)
-                .map_err(|e| AdapterError::ParsingDataError(e.into()))?;
-            let record = self.get_record(&key).await?.unwrap();
-            // panic if no value file is associated to the key
-            // remove offending key if this case occures
-            records.push((key, record));
-        }
+        self.read_key_records(hashes)
+    }
 
-        Ok(records)
+    async fn get_all_records(&self) -> Result<Vec<(Self::Key, Self::Record)>, AdapterError> {
+        let hashes: Vec<String> = self.get_hash_iter()?.collect();
+
+        self.read_key_records(hashes)
     }
 
     async fn remove(&mut self, key: &Self::Key) -> Result<Option<Self::Record>, AdapterError> {
         if let Some(value) = self.get_record(key).await? {
             let path = self.get_filename_from_key(key);
             fs::remove_file(path).map_err(|e| AdapterError::MutationError(e.into()))?;
+            let key_path = self
+                .dirpath
+                .join(format!("{}.key", Self::get_hash_from_key(key)));
+            if key_path.exists() {
+                fs::remove_file(key_path).map_err(|e| AdapterError::MutationError(e.into()))?;
+            }
 
             Ok(Some(value))
         } else {
@@ -329,6 +354,16 @@ mod tests {
 
         assert_eq!("one".to_string(), value);
         assert!(!adapter.record_exists(&1).await.unwrap());
+        assert_eq!(5, adapter.get_all_records().await.unwrap().len());
+    }
+
+    #[tokio::test]
+    async fn get_all_records_fails_when_a_record_file_is_missing() {
+        let (dir, adapter) = get_adapter("get_all_records_fails_when_a_record_file_is_missing");
+        init_dir(&dir);
+        fs::remove_file(dir.join("1E9F734161D62DD9.json")).unwrap();
+
+        assert!(adapter.get_all_records().await.is_err());
     }
 
     #[tokio::test]
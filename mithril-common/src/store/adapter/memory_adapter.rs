@@ -84,6 +84,15 @@ where
             .collect())
     }
 
+    async fn get_all_records(&self) -> Result<Vec<(Self::Key, Self::Record)>, AdapterError> {
+        Ok(self
+            .index
+            .iter()
+            .rev()
+            .map(|k| (k.clone(), self.values.get(k).unwrap().clone()))
+            .collect())
+    }
+
     async fn remove(&mut self, key: &Self::Key) -> Result<Option<Self::Record>, AdapterError> {
         self.index.retain(|k| *k != *key);
 
@@ -167,6 +176,21 @@ mod tests {
         assert_eq!((5, "value 5".to_string()), vals[0]);
     }
 
+    #[tokio::test]
+    async fn get_all_values() {
+        let adapter = init_adapter(3);
+        let vals = adapter.get_all_records().await.unwrap();
+
+        assert_eq!(
+            vec![
+                (3, "value 3".to_string()),
+                (2, "value 2".to_string()),
+                (1, "value 1".to_string())
+            ],
+            vals
+        );
+    }
+
     #[tokio::test]
     async fn save_new_values() {
         let mut adapter = init_adapter(2);
@@ -1,16 +1,23 @@
 //! Define a generic way to store data with the [Store Adapter][store_adapter::StoreAdapter], with
-//! two main implementations ([in memory][MemoryAdapter] or [filesystem as json][JsonFileStoreAdapter])
+//! two main implementations ([in memory][MemoryAdapter] or [filesystem as json][JsonFileStoreAdapter]),
+//! a wrapper that [encrypts records][EncryptedStoreAdapter] before storing them in another adapter
 //! and two more for testing ([a stub with one record][DumbStoreAdapter] and one which
 //! [always fails][FailStoreAdapter]).
 
+mod encrypted_adapter;
 mod jsonfile_store_adapter;
 mod memory_adapter;
 mod sqlite_adapter;
 mod store_adapter;
 
+pub use encrypted_adapter::{
+    EncryptedRecord, EncryptedStoreAdapter, StoreEncryptionError, StoreEncryptionKey,
+};
 pub use jsonfile_store_adapter::JsonFileStoreAdapter;
 pub use memory_adapter::MemoryAdapter;
-pub use sqlite_adapter::{SQLiteAdapter, SQLiteResultIterator};
+pub use sqlite_adapter::{
+    open_sqlite_connection, SQLiteAdapter, SQLiteConnection, SQLiteResultIterator,
+};
 pub use store_adapter::*;
 
 mod dumb_adapter;
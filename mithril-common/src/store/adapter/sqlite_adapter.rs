@@ -2,20 +2,103 @@ use async_trait::async_trait;
 use serde::{de::DeserializeOwned, Serialize};
 use sha2::{Digest, Sha256};
 use sqlite::{Connection, State, Statement};
-use tokio::sync::{Mutex, MutexGuard};
+use tokio::sync::{Mutex, MutexGuard, OwnedMutexGuard, TryLockError};
 
-use std::{marker::PhantomData, path::PathBuf, sync::Arc, thread::sleep, time::Duration};
+use std::{
+    future::Future, marker::PhantomData, path::PathBuf, sync::Arc, thread::sleep, time::Duration,
+};
 
-use super::{AdapterError, StoreAdapter};
+use super::{AdapterError, RecordCondition, RecordConditionParameter, StoreAdapter};
 
 type Result<T> = std::result::Result<T, AdapterError>;
 
 const DELAY_MS_ON_LOCK: u32 = 50;
 const NB_RETRIES_ON_LOCK: u32 = 3;
 
+tokio::task_local! {
+    /// Identifier of the connection whose transaction is run by the current task.
+    static TRANSACTION_CONNECTION_ID: usize;
+}
+
+/// Connection to a SQLite3 database that can be shared between several [SQLiteAdapter].
+///
+/// While a transaction is open on the connection, only the units of work run in this
+/// transaction can use it, the other users wait until it is committed or rolled back.
+#[derive(Clone)]
+pub struct SQLiteConnection {
+    connection: Arc<Mutex<Connection>>,
+    transaction_lock: Arc<Mutex<()>>,
+}
+
+impl SQLiteConnection {
+    fn new(connection: Connection) -> Self {
+        Self {
+            connection: Arc::new(Mutex::new(connection)),
+            transaction_lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Lock the connection, waiting for the end of the ongoing transaction unless called from
+    /// one of its units of work.
+    pub async fn lock(&self) -> MutexGuard<'_, Connection> {
+        if self.is_in_transaction_scope() {
+            return self.connection.lock().await;
+        }
+        // The transaction lock is held until the connection is locked so that no transaction
+        // can begin in between.
+        let _transaction_lock = self.transaction_lock.lock().await;
+
+        self.connection.lock().await
+    }
+
+    /// Try to lock the connection without waiting.
+    pub fn try_lock(&self) -> std::result::Result<MutexGuard<'_, Connection>, TryLockError> {
+        self.connection.try_lock()
+    }
+
+    /// Lock the connection, blocking the current thread. Must not be called from an
+    /// asynchronous context.
+    pub(crate) fn blocking_lock(&self) -> MutexGuard<'_, Connection> {
+        self.connection.blocking_lock()
+    }
+
+    /// Reserve the connection to a transaction until the returned guard is dropped.
+    pub(crate) async fn lock_for_transaction(&self) -> OwnedMutexGuard<()> {
+        self.transaction_lock.clone().lock_owned().await
+    }
+
+    /// Run a unit of work of the transaction the connection is reserved to.
+    pub(crate) async fn run_in_transaction_scope<F: Future>(&self, unit_of_work: F) -> F::Output {
+        TRANSACTION_CONNECTION_ID
+            .scope(self.id(), unit_of_work)
+            .await
+    }
+
+    fn id(&self) -> usize {
+        Arc::as_ptr(&self.connection) as usize
+    }
+
+    fn is_in_transaction_scope(&self) -> bool {
+        TRANSACTION_CONNECTION_ID
+            .try_with(|id| *id == self.id())
+            .unwrap_or(false)
+    }
+}
+
+/// Open a SQLite3 connection on the given file, or in memory if no file is given.
+pub fn open_sqlite_connection(file: Option<PathBuf>) -> Result<SQLiteConnection> {
+    let connection = match file {
+        Some(filepath) => Connection::open(filepath),
+        None => Connection::open(":memory:"),
+    }
+    .map_err(|e| AdapterError::InitializationError(e.into()))?;
+
+    Ok(SQLiteConnection::new(connection))
+}
+
 /// Store adapter for SQLite3
 pub struct SQLiteAdapter<K, V> {
-    connection: Arc<Mutex<Connection>>,
+    connection: SQLiteConnection,
     table: String,
     key: PhantomData<K>,
     value: PhantomData<V>,
@@ -28,13 +111,19 @@ where
 {
     /// Create a new SQLiteAdapter instance.
     pub fn new(table_name: &str, file: Option<PathBuf>) -> Result<Self> {
-        let connection = match file {
-            Some(filepath) => Connection::open(filepath),
-            None => Connection::open(":memory:"),
-        }
-        .map_err(|e| AdapterError::InitializationError(e.into()))?;
-        Self::check_table_exists(&connection, table_name)?;
-        let connection = Arc::new(Mutex::new(connection));
+        Self::with_connection(table_name, open_sqlite_connection(file)?)
+    }
+
+    /// Create a new SQLiteAdapter instance using an already opened connection.
+    ///
+    /// Adapters sharing the same connection can be enrolled in the same transaction.
+    pub fn with_connection(table_name: &str, connection: SQLiteConnection) -> Result<Self> {
+        Self::check_table_exists(
+            &connection
+                .try_lock()
+                .map_err(|e| AdapterError::InitializationError(e.into()))?,
+            table_name,
+        )?;
 
         Ok(Self {
             connection,
@@ -44,6 +133,11 @@ where
         })
     }
 
+    /// Return the connection used by this adapter.
+    pub fn get_connection(&self) -> SQLiteConnection {
+        self.connection.clone()
+    }
+
     fn check_table_exists(connection: &Connection, table_name: &str) -> Result<()> {
         let sql = format!(
             "select exists(select 1 from sqlite_master where type='table' and name='{}')",
@@ -140,6 +234,95 @@ where
 
         Ok(maybe_value)
     }
+
+    async fn fetch_last_records(&self, limit: Option<usize>) -> Result<Vec<(K, V)>>
+    where
+        K: DeserializeOwned,
+    {
+        let connection = self.connection.lock().await;
+        let sql = format!(
+            "select cast(key as text) as key, cast(value as text) as value from {} order by ROWID desc{}",
+            self.table,
+            if limit.is_some() { " limit ?1" } else { "" }
+        );
+        let mut statement = connection
+            .prepare(sql)
+            .map_err(|e| AdapterError::InitializationError(e.into()))?;
+        if let Some(limit) = limit {
+            statement = statement
+                .bind::<i64>(1, i64::try_from(limit).unwrap_or(i64::MAX))
+                .map_err(|e| AdapterError::InitializationError(e.into()))?;
+        }
+
+        Self::read_records(statement)
+    }
+
+    /// Read the key and the value of each row returned by the given statement.
+    fn read_records(statement: Statement) -> Result<Vec<(K, V)>>
+    where
+        K: DeserializeOwned,
+    {
+        statement
+            .into_cursor()
+            .map(|row| {
+                let row = row.map_err(|e| AdapterError::QueryError(e.into()))?;
+                let key: K = serde_json::from_str(&row.get::<String, _>(0))
+                    .map_err(|e| AdapterError::ParsingDataError(e.into()))?;
+                let value: V = serde_json::from_str(&row.get::<String, _>(1))
+                    .map_err(|e| AdapterError::ParsingDataError(e.into()))?;
+
+                Ok((key, value))
+            })
+            .collect()
+    }
+
+    /// Bind the parameters of the given condition, from the first parameter of the statement,
+    /// and return the index of the next parameter.
+    fn bind_condition<'a>(
+        statement: Statement<'a>,
+        condition: &RecordCondition<V>,
+    ) -> Result<(Statement<'a>, usize)> {
+        condition
+            .parameters()
+            .iter()
+            .try_fold((statement, 1), |(statement, index), parameter| {
+                let statement = match parameter {
+                    RecordConditionParameter::Integer(value) => {
+                        statement.bind::<i64>(index, *value)
+                    }
+                    RecordConditionParameter::Text(value) => {
+                        statement.bind::<&str>(index, value.as_str())
+                    }
+                }
+                .map_err(|e| AdapterError::InitializationError(e.into()))?;
+
+                Ok((statement, index + 1))
+            })
+    }
+
+    fn count_records(
+        &self,
+        connection: &Connection,
+        condition: &RecordCondition<V>,
+    ) -> Result<usize> {
+        let sql = format!(
+            "select count(*) from {} as record where {}",
+            self.table,
+            condition.expression()
+        );
+        let statement = connection
+            .prepare(sql)
+            .map_err(|e| AdapterError::InitializationError(e.into()))?;
+        let (mut statement, _) = Self::bind_condition(statement, condition)?;
+        statement
+            .next()
+            .map_err(|e| AdapterError::QueryError(e.into()))?;
+        let count = statement
+            .read::<i64>(0)
+            .map_err(|e| AdapterError::ParsingDataError(e.into()))?;
+
+        Ok(count as usize)
+    }
 }
 
 #[async_trait]
@@ -210,29 +393,38 @@ where
     }
 
     async fn get_last_n_records(&self, how_many: usize) -> Result<Vec<(Self::Key, Self::Record)>> {
+        self.fetch_last_records(Some(how_many)).await
+    }
+
+    async fn get_all_records(&self) -> Result<Vec<(Self::Key, Self::Record)>> {
+        self.fetch_last_records(None).await
+    }
+
+    /// The condition is evaluated by the SQL query, only the records of the page are read.
+    async fn get_records_page(
+        &self,
+        condition: &RecordCondition<Self::Record>,
+        offset: usize,
+        limit: usize,
+    ) -> Result<(Vec<(Self::Key, Self::Record)>, usize)> {
         let connection = self.connection.lock().await;
+        let total = self.count_records(&connection, condition)?;
         let sql = format!(
-            "select cast(key as text) as key, cast(value as text) as value from {} order by ROWID desc limit ?1",
-            self.table
+            "select cast(key as text) as key, cast(value as text) as value from {} as record where {} order by ROWID desc limit ? offset ?",
+            self.table,
+            condition.expression()
         );
-        let cursor = connection
+        let statement = connection
             .prepare(sql)
+            .map_err(|e| AdapterError::InitializationError(e.into()))?;
+        let (statement, index) = Self::bind_condition(statement, condition)?;
+        let statement = statement
+            .bind::<i64>(index, i64::try_from(limit).unwrap_or(i64::MAX))
             .map_err(|e| AdapterError::InitializationError(e.into()))?
-            .bind::<i64>(1, how_many as i64)
-            .map_err(|e| AdapterError::InitializationError(e.into()))?
-            .into_cursor();
-
-        let results = cursor
-            .map(|row| {
-                let row = row.unwrap();
-                let key: K = serde_json::from_str(&row.get::<String, _>(0)).unwrap();
-                let value: V = serde_json::from_str(&row.get::<String, _>(1)).unwrap();
-
-                (key, value)
-            })
-            .collect();
+            .bind::<i64>(index + 1, i64::try_from(offset).unwrap_or(i64::MAX))
+            .map_err(|e| AdapterError::InitializationError(e.into()))?;
 
-        Ok(results)
+        Ok((Self::read_records(statement)?, total))
     }
 
     async fn remove(&mut self, key: &Self::Key) -> Result<Option<Self::Record>> {
@@ -533,6 +725,35 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_get_all_records() {
+        let test_name = "test_get_all_records";
+        let mut adapter = init_db(test_name, None);
+        assert!(adapter.get_all_records().await.unwrap().is_empty());
+        adapter
+            .store_record(&1, "one".to_string().borrow())
+            .await
+            .unwrap();
+        adapter
+            .store_record(&2, "two".to_string().borrow())
+            .await
+            .unwrap();
+        assert_eq!(
+            vec![(2_u64, "two".to_string()), (1_u64, "one".to_string())],
+            adapter
+                .get_all_records()
+                .await
+                .expect("get all records should not fail")
+        );
+        assert_eq!(
+            adapter.get_all_records().await.unwrap(),
+            adapter
+                .get_last_n_records(usize::MAX)
+                .await
+                .expect("get last N records should not fail")
+        );
+    }
+
     #[tokio::test]
     async fn check_get_last_n_modified_records() {
         let test_name = "check_get_last_n_modified_records";
@@ -559,4 +780,28 @@ mod tests {
             values
         );
     }
+
+    #[tokio::test]
+    async fn test_get_records_page() {
+        let test_name = "test_get_records_page";
+        let mut adapter = init_db(test_name, None);
+        for key in 1..=5 {
+            adapter
+                .store_record(&key, format!("record {}", key).borrow())
+                .await
+                .unwrap();
+        }
+        let condition = RecordCondition::all().and(
+            "json_extract(record.value, '$') != ?",
+            vec![RecordConditionParameter::Text("record 4".to_string())],
+            |record: &String| record != "record 4",
+        );
+
+        let (records, total) = adapter.get_records_page(&condition, 1, 2).await.unwrap();
+        assert_eq!(4, total);
+        assert_eq!(
+            vec![(3, "record 3".to_string()), (2, "record 2".to_string())],
+            records
+        );
+    }
 }
@@ -33,6 +33,76 @@ pub enum AdapterError {
     /// Type conversion cannot be performed by this adapter.
     #[error("type conversion error, this adapter does not know how to handle this: {0}")]
     TypeError(SubError),
+
+    /// Error raised when a record can't be encrypted or decrypted.
+    #[error("encryption error: {0}")]
+    EncryptionError(SubError),
+}
+
+/// Value of a parameter of the SQL expression of a [RecordCondition].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordConditionParameter {
+    /// Integer parameter
+    Integer(i64),
+
+    /// Text parameter
+    Text(String),
+}
+
+/// Condition that the records of a page returned by [StoreAdapter::get_records_page] must meet.
+///
+/// The condition is given both as a SQL expression on the json `value` column of the `record`
+/// table, for the adapters that evaluate it in their queries, and as a predicate on the record
+/// for the other ones. Both must select the same records.
+pub struct RecordCondition<R> {
+    expression: String,
+    parameters: Vec<RecordConditionParameter>,
+    predicate: Box<dyn Fn(&R) -> bool + Send + Sync>,
+}
+
+impl<R: 'static> RecordCondition<R> {
+    /// Condition met by every record.
+    pub fn all() -> Self {
+        Self {
+            expression: "1 = 1".to_string(),
+            parameters: vec![],
+            predicate: Box::new(|_| true),
+        }
+    }
+
+    /// Also require the given condition, the parameters of the SQL expression are written `?`
+    /// and bound in the given order.
+    pub fn and(
+        self,
+        expression: &str,
+        parameters: Vec<RecordConditionParameter>,
+        predicate: impl Fn(&R) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        let previous_predicate = self.predicate;
+
+        Self {
+            expression: format!("{} and ({})", self.expression, expression),
+            parameters: [self.parameters, parameters].concat(),
+            predicate: Box::new(move |record| previous_predicate(record) && predicate(record)),
+        }
+    }
+}
+
+impl<R> RecordCondition<R> {
+    /// SQL expression of the condition
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// Parameters of the SQL expression of the condition, in order
+    pub fn parameters(&self) -> &[RecordConditionParameter] {
+        &self.parameters
+    }
+
+    /// Does the given record meet the condition
+    pub fn matches(&self, record: &R) -> bool {
+        (self.predicate)(record)
+    }
 }
 
 /// Represent a way to store Key/Value pair data.
@@ -63,6 +133,34 @@ pub trait StoreAdapter: Sync + Send {
         how_many: usize,
     ) -> Result<Vec<(Self::Key, Self::Record)>, AdapterError>;
 
+    /// Get all the records in the store, from the latest to the oldest.
+    async fn get_all_records(&self) -> Result<Vec<(Self::Key, Self::Record)>, AdapterError>;
+
+    /// Get at most `limit` records meeting the given condition, from the latest to the oldest,
+    /// skipping the `offset` first ones. The total number of records meeting the condition is
+    /// returned along the page.
+    ///
+    /// By default all the records are read and filtered with the predicate of the condition.
+    async fn get_records_page(
+        &self,
+        condition: &RecordCondition<Self::Record>,
+        offset: usize,
+        limit: usize,
+    ) -> Result<(Vec<(Self::Key, Self::Record)>, usize), AdapterError> {
+        let records = self
+            .get_all_records()
+            .await?
+            .into_iter()
+            .filter(|(_, record)| condition.matches(record))
+            .collect::<Vec<_>>();
+        let total = records.len();
+
+        Ok((
+            records.into_iter().skip(offset).take(limit).collect(),
+            total,
+        ))
+    }
+
     /// remove values from store
     ///
     /// if the value exists it is returned by the adapter otherwise None is returned
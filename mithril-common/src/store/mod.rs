@@ -1,11 +1,24 @@
-//! Define a generic way to store data with the [Store Adapters][adapter], and the [StakeStorer]
-//! to store stakes.
+//! Define a generic way to store data with the [Store Adapters][adapter], the [StakeStorer]
+//! to store stakes, the [TransactionManager] to group mutations made on several stores,
+//! the [RetentionPolicy] used by a [StorePruner] to prune old records, and the [StoreMigrator]
+//! to copy a store from an adapter to another.
 
 pub mod adapter;
 mod error;
+mod retention_policy;
 mod stake_store;
+mod store_migrator;
 mod store_pruner;
+mod transaction_manager;
 
 pub use error::StoreError;
+pub use retention_policy::{
+    PruneReport, RetentionPolicy, RetentionPolicyConfiguration, RetentionPolicyConfigurationError,
+    RetentionPredicate,
+};
 pub use stake_store::{StakeStore, StakeStorer};
+pub use store_migrator::{StoreMigrationError, StoreMigrationReport, StoreMigrator};
 pub use store_pruner::StorePruner;
+pub use transaction_manager::{
+    DumbTransactionManager, SQLiteTransactionManager, Transaction, TransactionManager,
+};
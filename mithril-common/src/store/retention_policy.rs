@@ -0,0 +1,321 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Display};
+use std::str::FromStr;
+use std::sync::Arc;
+use thiserror::Error;
+
+use crate::entities::Epoch;
+
+/// Predicate telling if a record of a store can be pruned.
+pub type RetentionPredicate<K, R> = Arc<dyn Fn(&K, &R) -> bool + Sync + Send>;
+
+/// Select the records of a store that must be pruned.
+pub enum RetentionPolicy<K, R> {
+    /// Never prune any record.
+    KeepAll,
+
+    /// Keep only the given number of most recent records.
+    Count(usize),
+
+    /// Keep only the records of the given number of most recent epochs, the
+    /// records that do not belong to an epoch are kept. A window of 0 epoch
+    /// prunes every record that belongs to an epoch.
+    EpochWindow(u64),
+
+    /// Prune the records for which the predicate returns `true`.
+    Predicate(RetentionPredicate<K, R>),
+
+    /// Prune only the records selected by all the given policies.
+    All(Vec<RetentionPolicy<K, R>>),
+}
+
+impl<K, R> RetentionPolicy<K, R> {
+    /// Return the indexes of the records to prune.
+    ///
+    /// The records must be sorted from the most recent to the oldest, as
+    /// returned by [StoreAdapter::get_last_n_records][super::adapter::StoreAdapter::get_last_n_records],
+    /// `get_epoch` gives the epoch a record belongs to if any.
+    pub fn select_records_to_prune(
+        &self,
+        records: &[(K, R)],
+        get_epoch: &dyn Fn(&K, &R) -> Option<Epoch>,
+    ) -> Vec<usize> {
+        match self {
+            Self::KeepAll => Vec::new(),
+            Self::Count(max_records) => (*max_records..records.len()).collect(),
+            Self::EpochWindow(nb_epochs) => {
+                let epochs: Vec<Option<Epoch>> = records
+                    .iter()
+                    .map(|(key, record)| get_epoch(key, record))
+                    .collect();
+                let latest_epoch = match epochs.iter().flatten().max() {
+                    Some(epoch) => *epoch,
+                    None => return Vec::new(),
+                };
+
+                epochs
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, epoch)| {
+                        matches!(epoch, Some(epoch) if epoch.0.saturating_add(*nb_epochs) <= latest_epoch.0)
+                    })
+                    .map(|(index, _)| index)
+                    .collect()
+            }
+            Self::Predicate(predicate) => records
+                .iter()
+                .enumerate()
+                .filter(|(_, (key, record))| predicate(key, record))
+                .map(|(index, _)| index)
+                .collect(),
+            Self::All(policies) => {
+                let mut policies = policies.iter();
+                let mut selection = match policies.next() {
+                    Some(policy) => policy.select_records_to_prune(records, get_epoch),
+                    None => return Vec::new(),
+                };
+
+                for policy in policies {
+                    let other_selection = policy.select_records_to_prune(records, get_epoch);
+                    selection.retain(|index| other_selection.contains(index));
+                }
+
+                selection
+            }
+        }
+    }
+}
+
+impl<K, R> From<Option<usize>> for RetentionPolicy<K, R> {
+    /// Count policy from a retention limit, no limit meaning that every record is kept.
+    fn from(retention_limit: Option<usize>) -> Self {
+        match retention_limit {
+            Some(max_records) => Self::Count(max_records),
+            None => Self::KeepAll,
+        }
+    }
+}
+
+impl<K, R> Debug for RetentionPolicy<K, R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::KeepAll => write!(f, "KeepAll"),
+            Self::Count(max_records) => f.debug_tuple("Count").field(max_records).finish(),
+            Self::EpochWindow(nb_epochs) => f.debug_tuple("EpochWindow").field(nb_epochs).finish(),
+            Self::Predicate(_) => write!(f, "Predicate"),
+            Self::All(policies) => f.debug_tuple("All").field(policies).finish(),
+        }
+    }
+}
+
+/// Error raised when a retention policy configuration can't be parsed.
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("invalid retention policy '{0}', expected 'keep_all', 'count:<number of records>' or 'epochs:<number of epochs, at least 1>'")]
+pub struct RetentionPolicyConfigurationError(String);
+
+/// Retention policy of a store as set in the configuration.
+///
+/// It is written as `keep_all`, `count:<number of records>` or
+/// `epochs:<number of epochs>` so it can be set from an environment variable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum RetentionPolicyConfiguration {
+    /// See [RetentionPolicy::KeepAll].
+    KeepAll,
+
+    /// See [RetentionPolicy::Count].
+    Count(usize),
+
+    /// See [RetentionPolicy::EpochWindow].
+    EpochWindow(u64),
+}
+
+impl RetentionPolicyConfiguration {
+    /// Build the retention policy of a store from its configuration if any,
+    /// else from the global retention limit.
+    pub fn build_policy<K, R>(
+        configuration: &Option<Self>,
+        retention_limit: Option<usize>,
+    ) -> RetentionPolicy<K, R> {
+        match configuration {
+            Some(Self::KeepAll) => RetentionPolicy::KeepAll,
+            Some(Self::Count(max_records)) => RetentionPolicy::Count(*max_records),
+            Some(Self::EpochWindow(nb_epochs)) => RetentionPolicy::EpochWindow(*nb_epochs),
+            None => retention_limit.into(),
+        }
+    }
+}
+
+impl FromStr for RetentionPolicyConfiguration {
+    type Err = RetentionPolicyConfigurationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || RetentionPolicyConfigurationError(s.to_string());
+
+        match s.trim().split_once(':') {
+            None if s.trim() == "keep_all" => Ok(Self::KeepAll),
+            Some(("count", value)) => Ok(Self::Count(value.parse().map_err(|_| error())?)),
+            // A window of no epoch would prune every record.
+            Some(("epochs", value)) => match value.parse().map_err(|_| error())? {
+                0 => Err(error()),
+                nb_epochs => Ok(Self::EpochWindow(nb_epochs)),
+            },
+            _ => Err(error()),
+        }
+    }
+}
+
+impl TryFrom<String> for RetentionPolicyConfiguration {
+    type Error = RetentionPolicyConfigurationError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl Display for RetentionPolicyConfiguration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::KeepAll => write!(f, "keep_all"),
+            Self::Count(max_records) => write!(f, "count:{}", max_records),
+            Self::EpochWindow(nb_epochs) => write!(f, "epochs:{}", nb_epochs),
+        }
+    }
+}
+
+impl From<RetentionPolicyConfiguration> for String {
+    fn from(configuration: RetentionPolicyConfiguration) -> Self {
+        configuration.to_string()
+    }
+}
+
+/// Records pruned, or that would be pruned, by a [StorePruner][super::StorePruner].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PruneReport<K> {
+    /// Keys of the records selected by the retention policy and not referenced.
+    pub pruned_keys: Vec<K>,
+
+    /// Keys of the records selected by the retention policy but kept because
+    /// other records reference them.
+    pub protected_keys: Vec<K>,
+}
+
+impl<K> Default for PruneReport<K> {
+    fn default() -> Self {
+        Self {
+            pruned_keys: Vec::new(),
+            protected_keys: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records(epochs: &[u64]) -> Vec<(u64, Option<Epoch>)> {
+        epochs
+            .iter()
+            .enumerate()
+            .map(|(index, epoch)| (index as u64, Some(Epoch(*epoch))))
+            .collect()
+    }
+
+    fn get_epoch(_key: &u64, epoch: &Option<Epoch>) -> Option<Epoch> {
+        *epoch
+    }
+
+    #[test]
+    fn keep_all_selects_nothing() {
+        let policy: RetentionPolicy<u64, Option<Epoch>> = RetentionPolicy::KeepAll;
+
+        assert!(policy
+            .select_records_to_prune(&records(&[3, 2, 1]), &get_epoch)
+            .is_empty());
+    }
+
+    #[test]
+    fn count_selects_the_oldest_records_above_the_limit() {
+        let policy: RetentionPolicy<u64, Option<Epoch>> = RetentionPolicy::Count(2);
+
+        assert_eq!(
+            vec![2, 3],
+            policy.select_records_to_prune(&records(&[4, 3, 2, 1]), &get_epoch)
+        );
+    }
+
+    #[test]
+    fn epoch_window_selects_records_of_older_epochs() {
+        let policy: RetentionPolicy<u64, Option<Epoch>> = RetentionPolicy::EpochWindow(2);
+        // several records per epoch and a gap: only epochs 7 and 6 are kept
+        let mut records = records(&[7, 7, 6, 4, 4, 3]);
+        records.push((6, None));
+
+        assert_eq!(
+            vec![3, 4, 5],
+            policy.select_records_to_prune(&records, &get_epoch)
+        );
+    }
+
+    #[test]
+    fn predicate_selects_matching_records() {
+        let policy: RetentionPolicy<u64, Option<Epoch>> =
+            RetentionPolicy::Predicate(Arc::new(|key, _| key % 2 == 0));
+
+        assert_eq!(
+            vec![0, 2],
+            policy.select_records_to_prune(&records(&[3, 2, 1]), &get_epoch)
+        );
+    }
+
+    #[test]
+    fn all_selects_records_selected_by_every_policy() {
+        let policy: RetentionPolicy<u64, Option<Epoch>> = RetentionPolicy::All(vec![
+            RetentionPolicy::Count(1),
+            RetentionPolicy::Predicate(Arc::new(|key, _| key % 2 == 0)),
+        ]);
+
+        assert_eq!(
+            vec![2],
+            policy.select_records_to_prune(&records(&[3, 2, 1]), &get_epoch)
+        );
+    }
+
+    #[test]
+    fn parse_retention_policy_configuration() {
+        assert_eq!(
+            Ok(RetentionPolicyConfiguration::KeepAll),
+            "keep_all".parse()
+        );
+        assert_eq!(
+            Ok(RetentionPolicyConfiguration::Count(10)),
+            "count:10".parse()
+        );
+        assert_eq!(
+            Ok(RetentionPolicyConfiguration::EpochWindow(3)),
+            "epochs:3".parse()
+        );
+        "epochs:three"
+            .parse::<RetentionPolicyConfiguration>()
+            .expect_err("a non numeric number of epochs should fail");
+        "epochs:0"
+            .parse::<RetentionPolicyConfiguration>()
+            .expect_err("a window of no epoch should fail");
+        "forever"
+            .parse::<RetentionPolicyConfiguration>()
+            .expect_err("an unknown policy should fail");
+    }
+
+    #[test]
+    fn build_policy_falls_back_to_the_retention_limit() {
+        let policy: RetentionPolicy<u64, u64> =
+            RetentionPolicyConfiguration::build_policy(&None, Some(5));
+        assert!(matches!(policy, RetentionPolicy::Count(5)));
+
+        let policy: RetentionPolicy<u64, u64> = RetentionPolicyConfiguration::build_policy(
+            &Some(RetentionPolicyConfiguration::EpochWindow(2)),
+            Some(5),
+        );
+        assert!(matches!(policy, RetentionPolicy::EpochWindow(2)));
+    }
+}
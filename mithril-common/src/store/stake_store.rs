@@ -3,7 +3,7 @@ use tokio::sync::RwLock;
 
 use crate::entities::{Epoch, StakeDistribution};
 
-use super::{adapter::StoreAdapter, StoreError, StorePruner};
+use super::{adapter::StoreAdapter, RetentionPolicy, StoreError, StorePruner};
 
 type Adapter = Box<dyn StoreAdapter<Key = Epoch, Record = StakeDistribution>>;
 
@@ -31,15 +31,18 @@ pub trait StakeStorer {
 /// A [StakeStorer] that use a [StoreAdapter] to store data.
 pub struct StakeStore {
     adapter: RwLock<Adapter>,
-    retention_limit: Option<usize>,
+    retention_policy: RetentionPolicy<Epoch, StakeDistribution>,
 }
 
 impl StakeStore {
     /// StakeStore factory
-    pub fn new(adapter: Adapter, retention_limit: Option<usize>) -> Self {
+    pub fn new(
+        adapter: Adapter,
+        retention_policy: RetentionPolicy<Epoch, StakeDistribution>,
+    ) -> Self {
         Self {
             adapter: RwLock::new(adapter),
-            retention_limit,
+            retention_policy,
         }
     }
 }
@@ -55,8 +58,12 @@ impl StorePruner for StakeStore {
         &self.adapter
     }
 
-    fn get_max_records(&self) -> Option<usize> {
-        self.retention_limit
+    fn get_retention_policy(&self) -> &RetentionPolicy<Self::Key, Self::Record> {
+        &self.retention_policy
+    }
+
+    fn get_record_epoch(&self, key: &Self::Key, _record: &Self::Record) -> Option<Epoch> {
+        Some(*key)
     }
 }
 
@@ -125,7 +132,7 @@ mod tests {
             None
         };
         let adapter: MemoryAdapter<Epoch, StakeDistribution> = MemoryAdapter::new(values).unwrap();
-        StakeStore::new(Box::new(adapter), retention_limit)
+        StakeStore::new(Box::new(adapter), retention_limit.into())
     }
 
     #[tokio::test]
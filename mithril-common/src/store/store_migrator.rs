@@ -0,0 +1,397 @@
+use serde::{de::DeserializeOwned, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt::Display;
+use std::hash::Hash;
+use std::path::PathBuf;
+use thiserror::Error;
+
+use super::adapter::{
+    AdapterError, JsonFileStoreAdapter, SQLiteAdapter, SQLiteConnection, StoreAdapter,
+};
+
+/// Error raised when a store migration fails.
+#[derive(Error, Debug)]
+pub enum StoreMigrationError {
+    /// Error raised when the source or the target adapter fails.
+    #[error("store adapter error: {0}")]
+    Adapter(#[from] AdapterError),
+
+    /// Error raised when the source store does not exist.
+    #[error("source store '{0}' does not exist")]
+    MissingSource(PathBuf),
+
+    /// Error raised when a record can't be serialized to compute its hash.
+    #[error("could not serialize a record to compute its hash: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    /// Error raised when the target already holds a different record for a
+    /// key of the source, it is never overwritten.
+    #[error("the target store already holds a different record for the key {0}")]
+    Conflict(String),
+
+    /// Error raised when the migrated records do not match the source records.
+    #[error("verification failed: {missing} record(s) missing and {mismatched} record(s) different in the target store out of {source_count}")]
+    Verification {
+        /// Number of records of the source store.
+        source_count: usize,
+
+        /// Number of source records not found in the target store.
+        missing: usize,
+
+        /// Number of source records that differ in the target store.
+        mismatched: usize,
+    },
+
+    /// Error raised when the target store does not hold as many records as the source store.
+    #[error("count mismatch: {source_count} record(s) in the source store but {target_count} in the target store")]
+    CountMismatch {
+        /// Number of records of the source store.
+        source_count: usize,
+
+        /// Number of records of the target store.
+        target_count: usize,
+    },
+}
+
+/// Outcome of a store migration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoreMigrationReport {
+    /// Number of records of the source store.
+    pub source_count: usize,
+
+    /// Number of records of the target store after the migration.
+    pub target_count: usize,
+
+    /// Number of records copied by this run.
+    pub migrated_count: usize,
+
+    /// Number of records already in the target store, skipped by this run.
+    pub skipped_count: usize,
+
+    /// Hash of all the source records, checked against the target records.
+    pub records_hash: String,
+}
+
+impl Display for StoreMigrationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} source record(s), {} migrated, {} already migrated, {} record(s) in the target store, records hash: {}",
+            self.source_count,
+            self.migrated_count,
+            self.skipped_count,
+            self.target_count,
+            self.records_hash
+        )
+    }
+}
+
+/// Copy all the records of a source store into a target store.
+///
+/// The migration can be run several times: records already in the target are
+/// skipped, so an interrupted migration resumes where it stopped and a
+/// completed one is left untouched. The records are copied from the oldest to
+/// the most recent so the target keeps the source ordering.
+pub struct StoreMigrator<K, R> {
+    source: Box<dyn StoreAdapter<Key = K, Record = R>>,
+    target: Box<dyn StoreAdapter<Key = K, Record = R>>,
+}
+
+impl<K, R> StoreMigrator<K, R>
+where
+    K: Serialize + Sync + Send,
+    R: Serialize + Sync + Send,
+{
+    /// Create a new instance.
+    pub fn new(
+        source: Box<dyn StoreAdapter<Key = K, Record = R>>,
+        target: Box<dyn StoreAdapter<Key = K, Record = R>>,
+    ) -> Self {
+        Self { source, target }
+    }
+
+    /// Copy the source records missing in the target then verify the target.
+    pub async fn migrate(&mut self) -> Result<StoreMigrationReport, StoreMigrationError> {
+        let mut records = self.source.get_all_records().await?;
+        records.reverse();
+        let mut migrated_count = 0;
+        let mut skipped_count = 0;
+
+        for (key, record) in records.iter() {
+            match self.target.get_record(key).await? {
+                Some(target_record) if hash_record(&target_record)? == hash_record(record)? => {
+                    skipped_count += 1;
+                }
+                Some(_) => {
+                    return Err(StoreMigrationError::Conflict(serde_json::to_string(key)?));
+                }
+                None => {
+                    self.target.store_record(key, record).await?;
+                    migrated_count += 1;
+                }
+            }
+        }
+
+        let mut report = self.verify_records(&records).await?;
+        report.migrated_count = migrated_count;
+        report.skipped_count = skipped_count;
+
+        Ok(report)
+    }
+
+    /// Check that all the source records are in the target without copying
+    /// anything.
+    pub async fn verify(&self) -> Result<StoreMigrationReport, StoreMigrationError> {
+        let records = self.source.get_all_records().await?;
+
+        self.verify_records(&records).await
+    }
+
+    async fn verify_records(
+        &self,
+        records: &[(K, R)],
+    ) -> Result<StoreMigrationReport, StoreMigrationError> {
+        let mut missing = 0;
+        let mut mismatched = 0;
+        let mut hashes = Vec::new();
+
+        for (key, record) in records {
+            let record_hash = hash_record(record)?;
+
+            match self.target.get_record(key).await? {
+                None => missing += 1,
+                Some(target_record) if hash_record(&target_record)? != record_hash => {
+                    mismatched += 1
+                }
+                Some(_) => {}
+            }
+            hashes.push((serde_json::to_string(key)?, record_hash));
+        }
+
+        if missing > 0 || mismatched > 0 {
+            return Err(StoreMigrationError::Verification {
+                source_count: records.len(),
+                missing,
+                mismatched,
+            });
+        }
+
+        let target_count = self.target.get_all_records().await?.len();
+        if target_count != records.len() {
+            return Err(StoreMigrationError::CountMismatch {
+                source_count: records.len(),
+                target_count,
+            });
+        }
+
+        hashes.sort();
+        let mut hasher = Sha256::new();
+        for (key, record_hash) in hashes {
+            hasher.update(key.as_bytes());
+            hasher.update(record_hash.as_bytes());
+        }
+
+        Ok(StoreMigrationReport {
+            source_count: records.len(),
+            target_count,
+            migrated_count: 0,
+            skipped_count: 0,
+            records_hash: hex::encode(hasher.finalize()),
+        })
+    }
+}
+
+impl<K, R> StoreMigrator<K, R>
+where
+    K: Hash + PartialEq + Serialize + DeserializeOwned + Sync + Send + 'static,
+    R: Serialize + DeserializeOwned + Sync + Send + 'static,
+{
+    /// Create a migrator from a [JsonFileStoreAdapter] directory to a table of
+    /// a SQLite database.
+    pub fn from_json_to_sqlite(
+        json_store_directory: PathBuf,
+        table_name: &str,
+        connection: SQLiteConnection,
+    ) -> Result<Self, StoreMigrationError> {
+        if !json_store_directory.is_dir() {
+            return Err(StoreMigrationError::MissingSource(json_store_directory));
+        }
+        let source = JsonFileStoreAdapter::new(json_store_directory)?;
+        let target = SQLiteAdapter::with_connection(table_name, connection)?;
+
+        Ok(Self::new(Box::new(source), Box::new(target)))
+    }
+
+    /// Migrate a [JsonFileStoreAdapter] directory to a table of a SQLite
+    /// database, or only verify it if `verify_only` is set, as done by the
+    /// `migrate-store` command of the nodes.
+    pub async fn run_json_to_sqlite(
+        json_store_directory: PathBuf,
+        table_name: &str,
+        connection: SQLiteConnection,
+        verify_only: bool,
+    ) -> Result<StoreMigrationReport, StoreMigrationError> {
+        let mut migrator = Self::from_json_to_sqlite(json_store_directory, table_name, connection)?;
+
+        match verify_only {
+            true => migrator.verify().await,
+            false => migrator.migrate().await,
+        }
+    }
+}
+
+/// Hash of the canonical json serialization of a record: the keys of the
+/// maps are sorted so equal records always have the same hash.
+fn hash_record<R: Serialize>(record: &R) -> Result<String, serde_json::Error> {
+    let canonical_json = serde_json::to_string(&serde_json::to_value(record)?)?;
+
+    Ok(hex::encode(Sha256::digest(canonical_json.as_bytes())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::adapter::{open_sqlite_connection, MemoryAdapter};
+
+    fn source_records() -> Vec<(u64, String)> {
+        vec![
+            (1, "one".to_string()),
+            (2, "two".to_string()),
+            (3, "three".to_string()),
+        ]
+    }
+
+    async fn init_migrator(
+        target_records: Vec<(u64, String)>,
+    ) -> (StoreMigrator<u64, String>, SQLiteConnection) {
+        let connection = open_sqlite_connection(None).unwrap();
+        let source = MemoryAdapter::new(Some(source_records())).unwrap();
+        let target = SQLiteAdapter::with_connection("target", connection.clone()).unwrap();
+        let mut migrator = StoreMigrator::new(Box::new(source), Box::new(target));
+        for (key, record) in target_records {
+            migrator.target.store_record(&key, &record).await.unwrap();
+        }
+
+        (migrator, connection)
+    }
+
+    #[tokio::test]
+    async fn migrate_copies_all_records_keeping_their_order() {
+        let (mut migrator, connection) = init_migrator(vec![]).await;
+
+        let report = migrator.migrate().await.unwrap();
+        assert_eq!(3, report.source_count);
+        assert_eq!(3, report.target_count);
+        assert_eq!(3, report.migrated_count);
+        assert_eq!(0, report.skipped_count);
+
+        let target: SQLiteAdapter<u64, String> =
+            SQLiteAdapter::with_connection("target", connection).unwrap();
+        assert_eq!(
+            vec![
+                (3, "three".to_string()),
+                (2, "two".to_string()),
+                (1, "one".to_string())
+            ],
+            target.get_all_records().await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn migrate_is_idempotent() {
+        let (mut migrator, _connection) = init_migrator(vec![]).await;
+        let first_report = migrator.migrate().await.unwrap();
+
+        let report = migrator.migrate().await.unwrap();
+        assert_eq!(0, report.migrated_count);
+        assert_eq!(3, report.skipped_count);
+        assert_eq!(first_report.records_hash, report.records_hash);
+    }
+
+    #[tokio::test]
+    async fn migrate_resumes_an_interrupted_migration() {
+        let (mut migrator, _connection) = init_migrator(vec![(1, "one".to_string())]).await;
+
+        let report = migrator.migrate().await.unwrap();
+        assert_eq!(2, report.migrated_count);
+        assert_eq!(1, report.skipped_count);
+        assert_eq!(3, report.target_count);
+    }
+
+    #[tokio::test]
+    async fn migrate_never_overwrites_a_different_record() {
+        let (mut migrator, _connection) = init_migrator(vec![(2, "deux".to_string())]).await;
+
+        let error = migrator.migrate().await.unwrap_err();
+        assert!(
+            matches!(error, StoreMigrationError::Conflict(ref key) if key == "2"),
+            "unexpected error: {:?}",
+            error
+        );
+    }
+
+    #[tokio::test]
+    async fn verify_fails_when_records_are_missing() {
+        let (migrator, _connection) = init_migrator(vec![(1, "one".to_string())]).await;
+
+        let error = migrator.verify().await.unwrap_err();
+        assert!(
+            matches!(
+                error,
+                StoreMigrationError::Verification {
+                    source_count: 3,
+                    missing: 2,
+                    mismatched: 0
+                }
+            ),
+            "unexpected error: {:?}",
+            error
+        );
+    }
+
+    #[tokio::test]
+    async fn verify_fails_when_the_target_holds_more_records() {
+        let (mut migrator, _connection) = init_migrator(vec![(4, "four".to_string())]).await;
+
+        let error = migrator.migrate().await.unwrap_err();
+        assert!(
+            matches!(
+                error,
+                StoreMigrationError::CountMismatch {
+                    source_count: 3,
+                    target_count: 4
+                }
+            ),
+            "unexpected error: {:?}",
+            error
+        );
+    }
+
+    #[test]
+    fn hash_record_does_not_depend_on_the_map_ordering() {
+        let mut first = std::collections::HashMap::new();
+        let mut second = std::collections::HashMap::new();
+        for index in 0..20 {
+            first.insert(index.to_string(), index);
+        }
+        for index in (0..20).rev() {
+            second.insert(index.to_string(), index);
+        }
+
+        assert_eq!(hash_record(&first).unwrap(), hash_record(&second).unwrap());
+    }
+
+    #[test]
+    fn from_json_to_sqlite_fails_if_the_json_store_does_not_exist() {
+        let connection = open_sqlite_connection(None).unwrap();
+
+        let result = StoreMigrator::<u64, String>::from_json_to_sqlite(
+            std::env::temp_dir()
+                .join("mithril_test")
+                .join("not_a_json_store"),
+            "target",
+            connection,
+        );
+        assert!(matches!(result, Err(StoreMigrationError::MissingSource(_))));
+    }
+}
@@ -1,14 +1,17 @@
 use async_trait::async_trait;
+use std::{collections::HashSet, hash::Hash};
 use tokio::sync::RwLock;
 
-use super::{adapter::StoreAdapter, StoreError};
+use crate::entities::Epoch;
+
+use super::{adapter::StoreAdapter, PruneReport, RetentionPolicy, StoreError};
 
 /// Implementing this trait will make store able to limit the number of the
-/// stored records by pruning them if a limit is set.
+/// stored records by pruning them according to a [RetentionPolicy].
 #[async_trait]
 pub trait StorePruner {
     /// The key type
-    type Key: Sync + Send;
+    type Key: Sync + Send + Clone + Eq + Hash;
 
     /// The record type
     type Record: Sync + Send;
@@ -17,25 +20,79 @@ pub trait StorePruner {
     fn get_adapter(&self)
         -> &RwLock<Box<dyn StoreAdapter<Key = Self::Key, Record = Self::Record>>>;
 
-    /// Return the maximum number of elements that can exist in this store. If None, there is no limit.
-    fn get_max_records(&self) -> Option<usize>;
+    /// Return the retention policy applied when pruning this store.
+    fn get_retention_policy(&self) -> &RetentionPolicy<Self::Key, Self::Record>;
+
+    /// Return the epoch the given record belongs to if any, used by the epoch
+    /// based retention policies.
+    fn get_record_epoch(&self, key: &Self::Key, record: &Self::Record) -> Option<Epoch>;
+
+    /// Return the keys of the records other records point to. It is computed
+    /// once per prune pass from all the records of this store.
+    fn get_referenced_keys(&self, _records: &[(Self::Key, Self::Record)]) -> HashSet<Self::Key> {
+        HashSet::new()
+    }
+
+    /// Tell if the given record is referenced, in which case it is never
+    /// pruned. `referenced_keys` is given by [StorePruner::get_referenced_keys].
+    async fn is_referenced(
+        &self,
+        key: &Self::Key,
+        _record: &Self::Record,
+        referenced_keys: &HashSet<Self::Key>,
+    ) -> Result<bool, StoreError> {
+        Ok(referenced_keys.contains(key))
+    }
+
+    /// Return the records that would be deleted by [StorePruner::prune] without
+    /// deleting them.
+    async fn get_prune_report(&self) -> Result<PruneReport<Self::Key>, StoreError> {
+        let records = self.get_adapter().read().await.get_all_records().await?;
+
+        self.build_prune_report(&records).await
+    }
 
-    /// Prune elements exceeding the specified limit.
-    async fn prune(&self) -> Result<(), StoreError> {
-        let retention_len = self.get_max_records().unwrap_or(usize::MAX);
+    /// Build the prune report of the given records, sorted from the most
+    /// recent to the oldest.
+    async fn build_prune_report(
+        &self,
+        records: &[(Self::Key, Self::Record)],
+    ) -> Result<PruneReport<Self::Key>, StoreError> {
+        let mut report = PruneReport::default();
+        let selection = self
+            .get_retention_policy()
+            .select_records_to_prune(records, &|key, record| self.get_record_epoch(key, record));
+        if selection.is_empty() {
+            return Ok(report);
+        }
+        let referenced_keys = self.get_referenced_keys(records);
+
+        for index in selection {
+            let (key, record) = &records[index];
+
+            if self.is_referenced(key, record, &referenced_keys).await? {
+                report.protected_keys.push(key.clone());
+            } else {
+                report.pruned_keys.push(key.clone());
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Prune the records selected by the retention policy that are not
+    /// referenced by other records.
+    async fn prune(&self) -> Result<PruneReport<Self::Key>, StoreError> {
         let lock = self.get_adapter();
         let mut adapter = lock.write().await;
+        let records = adapter.get_all_records().await?;
+        let report = self.build_prune_report(&records).await?;
 
-        for (epoch, _record) in adapter
-            .get_last_n_records(usize::MAX)
-            .await?
-            .into_iter()
-            .skip(retention_len)
-        {
-            adapter.remove(&epoch).await?;
+        for key in report.pruned_keys.iter() {
+            adapter.remove(key).await?;
         }
 
-        Ok(())
+        Ok(report)
     }
 }
 
@@ -49,9 +106,11 @@ mod tests {
 
     struct TestStore {
         adapter: RwLock<Box<dyn StoreAdapter<Key = u64, Record = String>>>,
-        record_limit: Option<usize>,
+        retention_policy: RetentionPolicy<u64, String>,
+        referenced_keys: Vec<u64>,
     }
 
+    #[async_trait]
     impl StorePruner for TestStore {
         type Key = u64;
         type Record = String;
@@ -62,8 +121,19 @@ mod tests {
             &self.adapter
         }
 
-        fn get_max_records(&self) -> Option<usize> {
-            self.record_limit
+        fn get_retention_policy(&self) -> &RetentionPolicy<Self::Key, Self::Record> {
+            &self.retention_policy
+        }
+
+        fn get_record_epoch(&self, key: &Self::Key, _record: &Self::Record) -> Option<Epoch> {
+            Some(Epoch(*key))
+        }
+
+        fn get_referenced_keys(
+            &self,
+            _records: &[(Self::Key, Self::Record)],
+        ) -> HashSet<Self::Key> {
+            self.referenced_keys.iter().cloned().collect()
         }
     }
 
@@ -94,7 +164,8 @@ mod tests {
         for data_len in 1_u64..=6 {
             let store = TestStore {
                 adapter: RwLock::new(Box::new(get_adapter(data_len).await)),
-                record_limit: None,
+                retention_policy: RetentionPolicy::KeepAll,
+                referenced_keys: Vec::new(),
             };
 
             store.prune().await.unwrap();
@@ -111,7 +182,8 @@ mod tests {
         for data_len in 1_u64..=6 {
             let store = TestStore {
                 adapter: RwLock::new(Box::new(get_adapter(6).await)),
-                record_limit: Some(data_len as usize),
+                retention_policy: RetentionPolicy::Count(data_len as usize),
+                referenced_keys: Vec::new(),
             };
 
             store.prune().await.unwrap();
@@ -123,4 +195,62 @@ mod tests {
             );
         }
     }
+
+    #[tokio::test]
+    async fn test_pruning_with_epoch_window() {
+        let store = TestStore {
+            adapter: RwLock::new(Box::new(get_adapter(6).await)),
+            retention_policy: RetentionPolicy::EpochWindow(2),
+            referenced_keys: Vec::new(),
+        };
+
+        let report = store.prune().await.unwrap();
+        assert_eq!(vec![3, 2, 1, 0], report.pruned_keys);
+        assert_eq!(
+            vec![5, 4],
+            store
+                .adapter
+                .read()
+                .await
+                .get_all_records()
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|(key, _)| key)
+                .collect::<Vec<u64>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_referenced_records_are_not_pruned() {
+        let store = TestStore {
+            adapter: RwLock::new(Box::new(get_adapter(6).await)),
+            retention_policy: RetentionPolicy::Count(2),
+            referenced_keys: vec![1],
+        };
+
+        let report = store.prune().await.unwrap();
+        assert_eq!(vec![3, 2, 0], report.pruned_keys);
+        assert_eq!(vec![1], report.protected_keys);
+        assert_eq!(
+            3,
+            store.adapter.read().await.get_iter().await.unwrap().count()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_prune_report_does_not_delete_records() {
+        let store = TestStore {
+            adapter: RwLock::new(Box::new(get_adapter(6).await)),
+            retention_policy: RetentionPolicy::Count(2),
+            referenced_keys: Vec::new(),
+        };
+
+        let report = store.get_prune_report().await.unwrap();
+        assert_eq!(vec![3, 2, 1, 0], report.pruned_keys);
+        assert_eq!(
+            6,
+            store.adapter.read().await.get_iter().await.unwrap().count()
+        );
+    }
 }
@@ -0,0 +1,292 @@
+use async_trait::async_trait;
+use std::future::Future;
+use tokio::sync::OwnedMutexGuard;
+
+use super::{
+    adapter::{AdapterError, SQLiteConnection},
+    StoreError,
+};
+
+/// Group mutations made on several stores in a single unit of work, so that
+/// they are all applied or none are.
+#[async_trait]
+pub trait TransactionManager: Sync + Send {
+    /// Start a new transaction.
+    async fn begin(&self) -> Result<Transaction, StoreError>;
+}
+
+/// A transaction started by a [TransactionManager].
+///
+/// Until it is committed or rolled back, the connection of the transaction is only usable by
+/// the units of work run with [Transaction::run]. It is rolled back if it is dropped before
+/// being committed: the connection stays reserved to the transaction until this rollback is
+/// done, so no other mutation can be applied in between.
+pub struct Transaction {
+    connection: Option<(SQLiteConnection, OwnedMutexGuard<()>)>,
+}
+
+impl Transaction {
+    /// Run a unit of work whose mutations belong to the transaction.
+    pub async fn run<F: Future>(&self, unit_of_work: F) -> F::Output {
+        match &self.connection {
+            Some((connection, _)) => connection.run_in_transaction_scope(unit_of_work).await,
+            None => unit_of_work.await,
+        }
+    }
+
+    /// Apply all the mutations made since the transaction started.
+    pub async fn commit(mut self) -> Result<(), StoreError> {
+        if let Some((connection, _)) = &self.connection {
+            Self::execute(connection, "commit transaction").await?;
+        }
+        self.connection = None;
+
+        Ok(())
+    }
+
+    /// Discard all the mutations made since the transaction started.
+    pub async fn rollback(mut self) -> Result<(), StoreError> {
+        if let Some((connection, _)) = &self.connection {
+            Self::execute(connection, "rollback transaction").await?;
+        }
+        self.connection = None;
+
+        Ok(())
+    }
+
+    async fn execute(connection: &SQLiteConnection, sql: &str) -> Result<(), StoreError> {
+        connection
+            .run_in_transaction_scope(async { connection.lock().await.execute(sql) })
+            .await
+            .map_err(|e| AdapterError::MutationError(e.into()))?;
+
+        Ok(())
+    }
+}
+
+impl Drop for Transaction {
+    fn drop(&mut self) {
+        if let Some((connection, transaction_lock)) = self.connection.take() {
+            match tokio::runtime::Handle::try_current() {
+                // The connection can't be locked blocking from an asynchronous context, the
+                // rollback is done by a task that keeps the connection reserved until it is over.
+                Ok(runtime) => {
+                    runtime.spawn(async move {
+                        let _ = Self::execute(&connection, "rollback transaction").await;
+                        drop(transaction_lock);
+                    });
+                }
+                Err(_) => {
+                    let _ = connection.blocking_lock().execute("rollback transaction");
+                }
+            }
+        }
+    }
+}
+
+/// [TransactionManager] for the stores using a [SQLiteAdapter][super::adapter::SQLiteAdapter]
+/// on a shared connection.
+///
+/// Only the adapters built with the same connection as this manager are part
+/// of its transactions.
+pub struct SQLiteTransactionManager {
+    connection: SQLiteConnection,
+}
+
+impl SQLiteTransactionManager {
+    /// Create a new instance.
+    pub fn new(connection: SQLiteConnection) -> Self {
+        Self { connection }
+    }
+}
+
+#[async_trait]
+impl TransactionManager for SQLiteTransactionManager {
+    async fn begin(&self) -> Result<Transaction, StoreError> {
+        let transaction_lock = self.connection.lock_for_transaction().await;
+        Transaction::execute(&self.connection, "begin transaction").await?;
+
+        Ok(Transaction {
+            connection: Some((self.connection.clone(), transaction_lock)),
+        })
+    }
+}
+
+/// A [TransactionManager] that does nothing, for stores that can't take part
+/// in a transaction (in memory, testing).
+#[derive(Default)]
+pub struct DumbTransactionManager {}
+
+impl DumbTransactionManager {
+    /// DumbTransactionManager factory
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+#[async_trait]
+impl TransactionManager for DumbTransactionManager {
+    async fn begin(&self) -> Result<Transaction, StoreError> {
+        Ok(Transaction { connection: None })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::adapter::{open_sqlite_connection, SQLiteAdapter, StoreAdapter};
+
+    fn init_adapters() -> (
+        SQLiteTransactionManager,
+        SQLiteAdapter<u64, String>,
+        SQLiteAdapter<u64, String>,
+    ) {
+        let connection = open_sqlite_connection(None).unwrap();
+        let first_adapter = SQLiteAdapter::with_connection("first", connection.clone()).unwrap();
+        let second_adapter = SQLiteAdapter::with_connection("second", connection.clone()).unwrap();
+
+        (
+            SQLiteTransactionManager::new(connection),
+            first_adapter,
+            second_adapter,
+        )
+    }
+
+    #[tokio::test]
+    async fn commit_apply_mutations_on_all_enrolled_adapters() {
+        let (transaction_manager, mut first_adapter, mut second_adapter) = init_adapters();
+
+        let transaction = transaction_manager.begin().await.unwrap();
+        transaction
+            .run(async {
+                first_adapter
+                    .store_record(&1, &"one".to_string())
+                    .await
+                    .unwrap();
+                second_adapter
+                    .store_record(&2, &"two".to_string())
+                    .await
+                    .unwrap();
+            })
+            .await;
+        transaction.commit().await.unwrap();
+
+        assert_eq!(
+            Some("one".to_string()),
+            first_adapter.get_record(&1).await.unwrap()
+        );
+        assert_eq!(
+            Some("two".to_string()),
+            second_adapter.get_record(&2).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn rollback_discard_mutations_on_all_enrolled_adapters() {
+        let (transaction_manager, mut first_adapter, mut second_adapter) = init_adapters();
+        first_adapter
+            .store_record(&1, &"one".to_string())
+            .await
+            .unwrap();
+
+        let transaction = transaction_manager.begin().await.unwrap();
+        transaction
+            .run(async {
+                first_adapter.remove(&1).await.unwrap();
+                second_adapter
+                    .store_record(&2, &"two".to_string())
+                    .await
+                    .unwrap();
+            })
+            .await;
+        transaction.rollback().await.unwrap();
+
+        assert_eq!(
+            Some("one".to_string()),
+            first_adapter.get_record(&1).await.unwrap()
+        );
+        assert!(!second_adapter.record_exists(&2).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn drop_rollback_mutations_on_all_enrolled_adapters() {
+        let (transaction_manager, mut first_adapter, _) = init_adapters();
+
+        let transaction = transaction_manager.begin().await.unwrap();
+        transaction
+            .run(first_adapter.store_record(&1, &"one".to_string()))
+            .await
+            .unwrap();
+        drop(transaction);
+
+        assert!(!first_adapter.record_exists(&1).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn drop_rollback_before_any_other_mutation() {
+        let (transaction_manager, mut first_adapter, mut second_adapter) = init_adapters();
+
+        let transaction = transaction_manager.begin().await.unwrap();
+        transaction
+            .run(first_adapter.store_record(&1, &"one".to_string()))
+            .await
+            .unwrap();
+        drop(transaction);
+        second_adapter
+            .store_record(&2, &"two".to_string())
+            .await
+            .unwrap();
+
+        assert!(!first_adapter.record_exists(&1).await.unwrap());
+        assert_eq!(
+            Some("two".to_string()),
+            second_adapter.get_record(&2).await.unwrap()
+        );
+    }
+
+    #[test]
+    fn drop_rollback_mutations_outside_of_a_runtime() {
+        let (transaction_manager, mut first_adapter, _) = init_adapters();
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+
+        let transaction = runtime.block_on(async {
+            let transaction = transaction_manager.begin().await.unwrap();
+            transaction
+                .run(first_adapter.store_record(&1, &"one".to_string()))
+                .await
+                .unwrap();
+            transaction
+        });
+        drop(transaction);
+
+        assert!(!runtime.block_on(first_adapter.record_exists(&1)).unwrap());
+    }
+
+    #[tokio::test]
+    async fn mutations_outside_the_transaction_wait_for_its_end() {
+        let (transaction_manager, mut first_adapter, mut second_adapter) = init_adapters();
+
+        let transaction = transaction_manager.begin().await.unwrap();
+        let concurrent_write = tokio::spawn(async move {
+            second_adapter
+                .store_record(&2, &"two".to_string())
+                .await
+                .unwrap();
+            second_adapter
+        });
+        transaction
+            .run(first_adapter.store_record(&1, &"one".to_string()))
+            .await
+            .unwrap();
+        tokio::task::yield_now().await;
+        assert!(!concurrent_write.is_finished());
+        transaction.rollback().await.unwrap();
+        let second_adapter = concurrent_write.await.unwrap();
+
+        assert!(!first_adapter.record_exists(&1).await.unwrap());
+        assert_eq!(
+            Some("two".to_string()),
+            second_adapter.get_record(&2).await.unwrap()
+        );
+    }
+}
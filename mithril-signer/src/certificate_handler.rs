@@ -1,13 +1,16 @@
 use async_trait::async_trait;
-use reqwest::{self, StatusCode};
-use slog_scope::debug;
+use reqwest::{self, RequestBuilder, Response, StatusCode};
+use slog_scope::{debug, warn};
 use std::io;
+use std::time::Duration;
 use thiserror::Error;
-use tokio::sync::RwLock;
+use tokio::sync::{mpsc::Sender, RwLock};
 
 use mithril_common::{
-    entities::{CertificatePending, EpochSettings, Signer, SingleSignatures},
+    crypto_helper::PROTOCOL_VERSION,
+    entities::{AggregatorEvent, CertificatePending, EpochSettings, Signer, SingleSignatures},
     fake_data,
+    protocol_version::{check_protocol_version_header, PROTOCOL_VERSION_HEADER},
 };
 
 #[cfg(test)]
@@ -35,6 +38,10 @@ pub enum CertificateHandlerError {
     /// Mostly network errors.
     #[error("io error: {0}")]
     IOError(#[from] io::Error),
+
+    /// The protocol version of the aggregator is not compatible with the signer.
+    #[error("incompatible protocol version: '{0}'")]
+    IncompatibleProtocolVersion(String),
 }
 
 /// Trait for mocking and testing a `CertificateHandler`
@@ -74,6 +81,75 @@ impl CertificateHandlerHTTPClient {
             aggregator_endpoint,
         }
     }
+
+    fn get(&self, url: &str) -> RequestBuilder {
+        reqwest::Client::new()
+            .get(url)
+            .header(PROTOCOL_VERSION_HEADER, PROTOCOL_VERSION)
+    }
+
+    fn post(&self, url: &str) -> RequestBuilder {
+        reqwest::Client::new()
+            .post(url)
+            .header(PROTOCOL_VERSION_HEADER, PROTOCOL_VERSION)
+    }
+
+    /// Subscribe to the Server-Sent Events stream of the lifecycle events of the aggregator
+    pub async fn subscribe_events(
+        &self,
+    ) -> Result<AggregatorEventSubscription, CertificateHandlerError> {
+        debug!("Subscribe to aggregator events");
+        let url = format!("{}/events", self.aggregator_endpoint);
+        let response = self.get(&url).send().await;
+        match response {
+            Ok(response) => {
+                Self::check_protocol_version(&response)?;
+                match response.status() {
+                    StatusCode::OK => Ok(AggregatorEventSubscription::new(response)),
+                    _ => Err(CertificateHandlerError::RemoteServerTechnical(
+                        response.text().await.unwrap_or_default(),
+                    )),
+                }
+            }
+            Err(err) => Err(CertificateHandlerError::RemoteServerUnreachable(
+                err.to_string(),
+            )),
+        }
+    }
+
+    /// Forward the events of the aggregator to the given sender until it is closed, the
+    /// subscription is renewed after `retry_interval` when it fails or ends
+    pub async fn forward_events(&self, sender: Sender<AggregatorEvent>, retry_interval: Duration) {
+        while !sender.is_closed() {
+            match self.subscribe_events().await {
+                Ok(mut subscription) => loop {
+                    match subscription.next_event().await {
+                        Ok(Some(event)) => {
+                            if sender.send(event).await.is_err() {
+                                return;
+                            }
+                        }
+                        Ok(None) => break,
+                        Err(err) => {
+                            warn!("Aggregator events stream interrupted"; "error" => ?err);
+                            break;
+                        }
+                    }
+                },
+                Err(err) => {
+                    debug!("Could not subscribe to aggregator events"; "error" => ?err);
+                }
+            }
+            tokio::time::sleep(retry_interval).await;
+        }
+    }
+
+    /// Check the protocol version advertised by the aggregator, an aggregator that doesn't
+    /// advertise it is assumed compatible
+    fn check_protocol_version(response: &Response) -> Result<(), CertificateHandlerError> {
+        check_protocol_version_header(response.headers())
+            .map_err(|e| CertificateHandlerError::IncompatibleProtocolVersion(e.to_string()))
+    }
 }
 
 #[async_trait]
@@ -83,17 +159,20 @@ impl CertificateHandler for CertificateHandlerHTTPClient {
     ) -> Result<Option<EpochSettings>, CertificateHandlerError> {
         debug!("Retrieve epoch settings");
         let url = format!("{}/epoch-settings", self.aggregator_endpoint);
-        let response = reqwest::get(url.clone()).await;
+        let response = self.get(&url).send().await;
         match response {
-            Ok(response) => match response.status() {
-                StatusCode::OK => match response.json::<EpochSettings>().await {
-                    Ok(epoch_settings) => Ok(Some(epoch_settings)),
-                    Err(err) => Err(CertificateHandlerError::JsonParseFailed(err.to_string())),
-                },
-                _ => Err(CertificateHandlerError::RemoteServerTechnical(
-                    response.text().await.unwrap_or_default(),
-                )),
-            },
+            Ok(response) => {
+                Self::check_protocol_version(&response)?;
+                match response.status() {
+                    StatusCode::OK => match response.json::<EpochSettings>().await {
+                        Ok(epoch_settings) => Ok(Some(epoch_settings)),
+                        Err(err) => Err(CertificateHandlerError::JsonParseFailed(err.to_string())),
+                    },
+                    _ => Err(CertificateHandlerError::RemoteServerTechnical(
+                        response.text().await.unwrap_or_default(),
+                    )),
+                }
+            }
             Err(err) => Err(CertificateHandlerError::RemoteServerUnreachable(
                 err.to_string(),
             )),
@@ -105,18 +184,21 @@ impl CertificateHandler for CertificateHandlerHTTPClient {
     ) -> Result<Option<CertificatePending>, CertificateHandlerError> {
         debug!("Retrieve pending certificate");
         let url = format!("{}/certificate-pending", self.aggregator_endpoint);
-        let response = reqwest::get(url.clone()).await;
+        let response = self.get(&url).send().await;
         match response {
-            Ok(response) => match response.status() {
-                StatusCode::OK => match response.json::<CertificatePending>().await {
-                    Ok(pending_certificate) => Ok(Some(pending_certificate)),
-                    Err(err) => Err(CertificateHandlerError::JsonParseFailed(err.to_string())),
-                },
-                StatusCode::NO_CONTENT => Ok(None),
-                _ => Err(CertificateHandlerError::RemoteServerTechnical(
-                    response.text().await.unwrap_or_default(),
-                )),
-            },
+            Ok(response) => {
+                Self::check_protocol_version(&response)?;
+                match response.status() {
+                    StatusCode::OK => match response.json::<CertificatePending>().await {
+                        Ok(pending_certificate) => Ok(Some(pending_certificate)),
+                        Err(err) => Err(CertificateHandlerError::JsonParseFailed(err.to_string())),
+                    },
+                    StatusCode::NO_CONTENT => Ok(None),
+                    _ => Err(CertificateHandlerError::RemoteServerTechnical(
+                        response.text().await.unwrap_or_default(),
+                    )),
+                }
+            }
             Err(err) => Err(CertificateHandlerError::RemoteServerUnreachable(
                 err.to_string(),
             )),
@@ -126,18 +208,20 @@ impl CertificateHandler for CertificateHandlerHTTPClient {
     async fn register_signer(&self, signer: &Signer) -> Result<(), CertificateHandlerError> {
         debug!("Register signer");
         let url = format!("{}/register-signer", self.aggregator_endpoint);
-        let client = reqwest::Client::new();
-        let response = client.post(url.clone()).json(signer).send().await;
+        let response = self.post(&url).json(signer).send().await;
         match response {
-            Ok(response) => match response.status() {
-                StatusCode::CREATED => Ok(()),
-                StatusCode::BAD_REQUEST => Err(CertificateHandlerError::RemoteServerLogical(
-                    format!("bad request: {}", response.text().await.unwrap_or_default()),
-                )),
-                _ => Err(CertificateHandlerError::RemoteServerTechnical(
-                    response.text().await.unwrap_or_default(),
-                )),
-            },
+            Ok(response) => {
+                Self::check_protocol_version(&response)?;
+                match response.status() {
+                    StatusCode::CREATED => Ok(()),
+                    StatusCode::BAD_REQUEST => Err(CertificateHandlerError::RemoteServerLogical(
+                        format!("bad request: {}", response.text().await.unwrap_or_default()),
+                    )),
+                    _ => Err(CertificateHandlerError::RemoteServerTechnical(
+                        response.text().await.unwrap_or_default(),
+                    )),
+                }
+            }
             Err(err) => Err(CertificateHandlerError::RemoteServerUnreachable(
                 err.to_string(),
             )),
@@ -150,21 +234,23 @@ impl CertificateHandler for CertificateHandlerHTTPClient {
     ) -> Result<(), CertificateHandlerError> {
         debug!("Register signatures");
         let url = format!("{}/register-signatures", self.aggregator_endpoint);
-        let client = reqwest::Client::new();
-        let response = client.post(url.clone()).json(signatures).send().await;
+        let response = self.post(&url).json(signatures).send().await;
         match response {
-            Ok(response) => match response.status() {
-                StatusCode::CREATED => Ok(()),
-                StatusCode::BAD_REQUEST => Err(CertificateHandlerError::RemoteServerLogical(
-                    format!("bad request: {}", response.text().await.unwrap_or_default()),
-                )),
-                StatusCode::CONFLICT => Err(CertificateHandlerError::RemoteServerLogical(
-                    "already registered single signatures".to_string(),
-                )),
-                _ => Err(CertificateHandlerError::RemoteServerTechnical(
-                    response.text().await.unwrap_or_default(),
-                )),
-            },
+            Ok(response) => {
+                Self::check_protocol_version(&response)?;
+                match response.status() {
+                    StatusCode::CREATED => Ok(()),
+                    StatusCode::BAD_REQUEST => Err(CertificateHandlerError::RemoteServerLogical(
+                        format!("bad request: {}", response.text().await.unwrap_or_default()),
+                    )),
+                    StatusCode::CONFLICT => Err(CertificateHandlerError::RemoteServerLogical(
+                        "already registered single signatures".to_string(),
+                    )),
+                    _ => Err(CertificateHandlerError::RemoteServerTechnical(
+                        response.text().await.unwrap_or_default(),
+                    )),
+                }
+            }
             Err(err) => Err(CertificateHandlerError::RemoteServerUnreachable(
                 err.to_string(),
             )),
@@ -172,6 +258,62 @@ impl CertificateHandler for CertificateHandlerHTTPClient {
     }
 }
 
+/// AggregatorEventSubscription reads the events of an aggregator from its Server-Sent Events
+/// stream
+pub struct AggregatorEventSubscription {
+    response: Response,
+    buffer: Vec<u8>,
+}
+
+impl AggregatorEventSubscription {
+    fn new(response: Response) -> Self {
+        Self {
+            response,
+            buffer: vec![],
+        }
+    }
+
+    /// Wait for the next event of the stream, returns `None` once the stream is closed
+    pub async fn next_event(&mut self) -> Result<Option<AggregatorEvent>, CertificateHandlerError> {
+        loop {
+            if let Some(position) = self.buffer.windows(2).position(|bytes| bytes == b"\n\n") {
+                let block: Vec<u8> = self.buffer.drain(..position + 2).collect();
+                match Self::parse_event(&String::from_utf8_lossy(&block))? {
+                    Some(event) => return Ok(Some(event)),
+                    None => continue,
+                }
+            }
+
+            match self.response.chunk().await {
+                Ok(Some(chunk)) => self.buffer.extend_from_slice(&chunk),
+                Ok(None) => return Ok(None),
+                Err(err) => {
+                    return Err(CertificateHandlerError::RemoteServerUnreachable(
+                        err.to_string(),
+                    ))
+                }
+            }
+        }
+    }
+
+    /// Parse the data of an event block, blocks without data (ie: keep-alive comments) are
+    /// skipped
+    fn parse_event(block: &str) -> Result<Option<AggregatorEvent>, CertificateHandlerError> {
+        let data = block
+            .lines()
+            .filter_map(|line| line.strip_prefix("data:"))
+            .map(|data| data.strip_prefix(' ').unwrap_or(data))
+            .collect::<Vec<_>>();
+        if data.is_empty() {
+            return Ok(None);
+        }
+
+        serde_json::from_str(&data.join("\n"))
+            .map(Some)
+            .map_err(|e| CertificateHandlerError::JsonParseFailed(e.to_string()))
+    }
+}
+
 /// This certificate handler is intended to be used by test services.
 /// It actually does not communicate with an aggregator host but mimics this behavior.
 /// It is driven by a Tester that controls the CertificatePending it can return and it can return its internal state for testing.
@@ -283,8 +425,14 @@ mod tests {
             db_directory: Path::new("./db").to_path_buf(),
             data_stores_directory: Path::new("./stores").to_path_buf(),
             store_retention_limit: None,
+            protocol_initializer_store_retention: None,
+            stake_store_retention: None,
             kes_secret_key_path: None,
             operational_certificate_path: None,
+            store_encryption_key: None,
+            store_encryption_key_path: None,
+            store_previous_encryption_key: None,
+            store_previous_encryption_key_path: None,
         };
         (server, config)
     }
@@ -366,6 +514,59 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_subscribe_events_ok_200() {
+        let (server, config) = setup_test();
+        let events_expected = vec![
+            AggregatorEvent::EpochChanged {
+                epoch: fake_data::beacon().epoch,
+            },
+            AggregatorEvent::PendingCertificateCreated {
+                beacon: fake_data::beacon(),
+                signed_entity_type: fake_data::certificate_pending().get_signed_entity_type(),
+            },
+        ];
+        let body = format!(
+            "event:{}\ndata:{}\n\n:\n\nevent:{}\ndata: {}\n\n",
+            events_expected[0].name(),
+            json!(events_expected[0]),
+            events_expected[1].name(),
+            json!(events_expected[1]),
+        );
+        let _events_mock = server.mock(|when, then| {
+            when.path("/events");
+            then.status(200)
+                .header("content-type", "text/event-stream")
+                .body(body);
+        });
+        let certificate_handler = CertificateHandlerHTTPClient::new(config.aggregator_endpoint);
+        let mut subscription = certificate_handler
+            .subscribe_events()
+            .await
+            .expect("unexpected error");
+        let mut events = vec![];
+        while let Some(event) = subscription.next_event().await.expect("unexpected error") {
+            events.push(event);
+        }
+        assert_eq!(events_expected, events);
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_events_ko_500() {
+        let (server, config) = setup_test();
+        let _events_mock = server.mock(|when, then| {
+            when.path("/events");
+            then.status(500).body("an error occurred");
+        });
+        let certificate_handler = CertificateHandlerHTTPClient::new(config.aggregator_endpoint);
+        let subscription = certificate_handler.subscribe_events().await;
+        assert_eq!(
+            CertificateHandlerError::RemoteServerTechnical("an error occurred".to_string())
+                .to_string(),
+            subscription.err().unwrap().to_string()
+        );
+    }
+
     #[tokio::test]
     async fn test_register_signer_ok_201() {
         let single_signers = fake_data::signers(1);
@@ -380,6 +581,44 @@ mod tests {
         register_signer.expect("unexpected error");
     }
 
+    #[tokio::test]
+    async fn test_register_signer_sends_protocol_version() {
+        let single_signers = fake_data::signers(1);
+        let single_signer = single_signers.first().unwrap();
+        let (server, config) = setup_test();
+        let _snapshots_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/register-signer")
+                .header(PROTOCOL_VERSION_HEADER, PROTOCOL_VERSION);
+            then.status(201)
+                .header(PROTOCOL_VERSION_HEADER, PROTOCOL_VERSION);
+        });
+        let certificate_handler = CertificateHandlerHTTPClient::new(config.aggregator_endpoint);
+        let register_signer = certificate_handler.register_signer(single_signer).await;
+        register_signer.expect("unexpected error");
+    }
+
+    #[tokio::test]
+    async fn test_register_signer_ko_incompatible_protocol_version() {
+        let single_signers = fake_data::signers(1);
+        let single_signer = single_signers.first().unwrap();
+        let (server, config) = setup_test();
+        let _snapshots_mock = server.mock(|when, then| {
+            when.method(POST).path("/register-signer");
+            then.status(201).header(PROTOCOL_VERSION_HEADER, "99.0.0");
+        });
+        let certificate_handler = CertificateHandlerHTTPClient::new(config.aggregator_endpoint);
+        let register_signer = certificate_handler.register_signer(single_signer).await;
+        assert!(
+            matches!(
+                register_signer,
+                Err(CertificateHandlerError::IncompatibleProtocolVersion(_))
+            ),
+            "unexpected result: {:?}",
+            register_signer
+        );
+    }
+
     #[tokio::test]
     async fn test_register_signer_ok_400() {
         let single_signers = fake_data::signers(1);
@@ -2,7 +2,16 @@ use config::ConfigError;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
-use mithril_common::{entities::PartyId, CardanoNetwork};
+use mithril_common::{
+    entities::PartyId,
+    store::{
+        adapter::{StoreEncryptionError, StoreEncryptionKey},
+        RetentionPolicy, RetentionPolicyConfiguration,
+    },
+    CardanoNetwork,
+};
+
+const SQLITE_FILE: &str = "signer.sqlite3";
 
 /// Client configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -40,11 +49,33 @@ pub struct Config {
     /// Store retention limit. If set to None, no limit will be set.
     pub store_retention_limit: Option<usize>,
 
+    /// Retention policy of the protocol initializer store, overrides `store_retention_limit`.
+    pub protocol_initializer_store_retention: Option<RetentionPolicyConfiguration>,
+
+    /// Retention policy of the stake store, overrides `store_retention_limit`.
+    pub stake_store_retention: Option<RetentionPolicyConfiguration>,
+
     /// File path to the KES secret key of the pool
     pub kes_secret_key_path: Option<PathBuf>,
 
     /// File path to the operational certificate of the pool
     pub operational_certificate_path: Option<PathBuf>,
+
+    /// Hex encoded key used to encrypt the protocol initializers at rest.
+    /// Usually set with the `STORE_ENCRYPTION_KEY` environment variable.
+    pub store_encryption_key: Option<StoreEncryptionKey>,
+
+    /// File path to the hex encoded key used to encrypt the protocol
+    /// initializers at rest, takes precedence over `store_encryption_key`.
+    pub store_encryption_key_path: Option<PathBuf>,
+
+    /// Previous store encryption key, only used to re-encrypt the protocol
+    /// initializers with the current key after a key rotation.
+    pub store_previous_encryption_key: Option<StoreEncryptionKey>,
+
+    /// File path to the previous store encryption key, takes precedence over
+    /// `store_previous_encryption_key`.
+    pub store_previous_encryption_key_path: Option<PathBuf>,
 }
 
 impl Config {
@@ -53,4 +84,46 @@ impl Config {
         CardanoNetwork::from_code(self.network.clone(), self.network_magic)
             .map_err(|e| ConfigError::Message(e.to_string()))
     }
+
+    /// Return the file of the SQLite stores.
+    pub fn get_sqlite_file(&self) -> PathBuf {
+        self.data_stores_directory.join(SQLITE_FILE)
+    }
+
+    /// Return the retention policy of a store from its own configuration if
+    /// set, else from the `store_retention_limit`.
+    pub fn get_store_retention_policy<K, R>(
+        &self,
+        store_retention: &Option<RetentionPolicyConfiguration>,
+    ) -> RetentionPolicy<K, R> {
+        RetentionPolicyConfiguration::build_policy(store_retention, self.store_retention_limit)
+    }
+
+    /// Return the key used to encrypt the signer secrets if any.
+    pub fn get_store_encryption_key(
+        &self,
+    ) -> Result<Option<StoreEncryptionKey>, StoreEncryptionError> {
+        Self::read_store_encryption_key(&self.store_encryption_key, &self.store_encryption_key_path)
+    }
+
+    /// Return the key the signer secrets were encrypted with before the last
+    /// key rotation if any.
+    pub fn get_store_previous_encryption_key(
+        &self,
+    ) -> Result<Option<StoreEncryptionKey>, StoreEncryptionError> {
+        Self::read_store_encryption_key(
+            &self.store_previous_encryption_key,
+            &self.store_previous_encryption_key_path,
+        )
+    }
+
+    fn read_store_encryption_key(
+        key: &Option<StoreEncryptionKey>,
+        key_path: &Option<PathBuf>,
+    ) -> Result<Option<StoreEncryptionKey>, StoreEncryptionError> {
+        match key_path {
+            Some(path) => StoreEncryptionKey::from_file(path).map(Some),
+            None => Ok(key.clone()),
+        }
+    }
 }
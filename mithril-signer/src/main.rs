@@ -1,12 +1,17 @@
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
 use slog::{o, Drain, Level, Logger};
 use slog_scope::debug;
 use std::sync::Arc;
 use std::time::Duration;
 use std::{error::Error, path::PathBuf};
 
+use mithril_common::crypto_helper::ProtocolInitializer;
+use mithril_common::entities::{Epoch, StakeDistribution};
+use mithril_common::store::adapter::open_sqlite_connection;
+use mithril_common::store::StoreMigrator;
 use mithril_signer::{
-    Config, ProductionServiceBuilder, ServiceBuilder, SignerRunner, SignerState, StateMachine,
+    CertificateHandlerHTTPClient, Config, ProductionServiceBuilder, ServiceBuilder, SignerRunner,
+    SignerState, StateMachine,
 };
 
 /// CLI args
@@ -36,6 +41,75 @@ pub struct Args {
         help = "Directory where the configuration file is located"
     )]
     configuration_dir: PathBuf,
+
+    /// Command to run instead of the signer
+    #[clap(subcommand)]
+    command: Option<SignerCommand>,
+}
+
+/// Signer commands
+#[derive(Subcommand, Debug, Clone)]
+enum SignerCommand {
+    /// Json file store to SQLite migration command, records already migrated are skipped
+    MigrateStore {
+        /// Store to migrate
+        #[clap(long, value_enum)]
+        store: MigratedStore,
+
+        /// Directory of the json file store to migrate
+        #[clap(long)]
+        json_store_directory: PathBuf,
+
+        /// Only verify that the records of the json file store are in the SQLite database
+        #[clap(long)]
+        verify_only: bool,
+    },
+}
+
+/// Stores that can be migrated from a json file store to the SQLite database
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum MigratedStore {
+    /// Protocol initializers, encrypted at the next start if a store encryption key is set
+    ProtocolInitializer,
+    Stake,
+}
+
+impl SignerCommand {
+    async fn execute(&self, config: &Config) -> Result<(), Box<dyn Error>> {
+        match self {
+            Self::MigrateStore {
+                store,
+                json_store_directory,
+                verify_only,
+            } => {
+                std::fs::create_dir_all(&config.data_stores_directory)?;
+                let connection = open_sqlite_connection(Some(config.get_sqlite_file()))?;
+                let report = match store {
+                    MigratedStore::ProtocolInitializer => {
+                        StoreMigrator::<Epoch, ProtocolInitializer>::run_json_to_sqlite(
+                            json_store_directory.clone(),
+                            "protocol_initializer",
+                            connection,
+                            *verify_only,
+                        )
+                        .await?
+                    }
+                    MigratedStore::Stake => {
+                        StoreMigrator::<Epoch, StakeDistribution>::run_json_to_sqlite(
+                            json_store_directory.clone(),
+                            "stake",
+                            connection,
+                            *verify_only,
+                        )
+                        .await?
+                    }
+                };
+                println!("{:?} store: {}", store, report);
+
+                Ok(())
+            }
+        }
+    }
 }
 
 impl Args {
@@ -83,13 +157,29 @@ async fn main() -> Result<(), Box<dyn Error>> {
         .map_err(|e| format!("configuration deserialize error: {}", e))?;
     debug!("Started"; "run_mode" => &args.run_mode, "config" => format!("{:?}", config));
 
+    if let Some(command) = &args.command {
+        return command.execute(&config).await;
+    }
+
+    // Subscribe to the events of the aggregator, the state machine falls back to polling it
+    // every run interval when they are not available
+    let (event_sender, event_receiver) = tokio::sync::mpsc::channel(16);
+    let events_client = CertificateHandlerHTTPClient::new(config.aggregator_endpoint.clone());
+    let run_interval = Duration::from_millis(config.run_interval);
+    tokio::spawn(async move {
+        events_client
+            .forward_events(event_sender, run_interval)
+            .await
+    });
+
     let mut state_machine = StateMachine::new(
         SignerState::Unregistered(None),
         Box::new(SignerRunner::new(
             config.clone(),
-            ProductionServiceBuilder::new(&config).build()?,
+            ProductionServiceBuilder::new(&config).build().await?,
         )),
-        Duration::from_millis(config.run_interval),
-    );
+        run_interval,
+    )
+    .with_events(event_receiver);
     state_machine.run().await
 }
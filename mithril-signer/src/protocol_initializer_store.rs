@@ -1,5 +1,5 @@
 use async_trait::async_trait;
-use mithril_common::store::{StoreError, StorePruner};
+use mithril_common::store::{RetentionPolicy, StoreError, StorePruner};
 use tokio::sync::RwLock;
 
 use mithril_common::crypto_helper::ProtocolInitializer;
@@ -34,15 +34,18 @@ pub trait ProtocolInitializerStorer: Sync + Send {
 /// Implementation of the ProtocolInitializerStorer
 pub struct ProtocolInitializerStore {
     adapter: RwLock<Adapter>,
-    retention_limit: Option<usize>,
+    retention_policy: RetentionPolicy<Epoch, ProtocolInitializer>,
 }
 
 impl ProtocolInitializerStore {
     /// Create a new ProtocolInitializerStore.
-    pub fn new(adapter: Adapter, retention_limit: Option<usize>) -> Self {
+    pub fn new(
+        adapter: Adapter,
+        retention_policy: RetentionPolicy<Epoch, ProtocolInitializer>,
+    ) -> Self {
         Self {
             adapter: RwLock::new(adapter),
-            retention_limit,
+            retention_policy,
         }
     }
 }
@@ -58,8 +61,12 @@ impl StorePruner for ProtocolInitializerStore {
         &self.adapter
     }
 
-    fn get_max_records(&self) -> Option<usize> {
-        self.retention_limit
+    fn get_retention_policy(&self) -> &RetentionPolicy<Self::Key, Self::Record> {
+        &self.retention_policy
+    }
+
+    fn get_record_epoch(&self, key: &Self::Key, _record: &Self::Record) -> Option<Epoch> {
+        Some(*key)
     }
 }
 
@@ -143,7 +150,7 @@ mod tests {
         };
         let adapter: MemoryAdapter<Epoch, ProtocolInitializer> =
             MemoryAdapter::new(values).unwrap();
-        ProtocolInitializerStore::new(Box::new(adapter), retention_limit)
+        ProtocolInitializerStore::new(Box::new(adapter), retention_limit.into())
     }
 
     #[tokio::test]
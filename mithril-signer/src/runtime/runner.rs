@@ -14,7 +14,7 @@ use mithril_common::{
     crypto_helper::key_encode_hex,
     entities::{
         Beacon, CertificatePending, Epoch, EpochSettings, ProtocolMessage, ProtocolMessagePartKey,
-        Signer, SignerWithStake, SingleSignatures,
+        SignedEntityType, Signer, SignerWithStake, SingleSignatures,
     },
     store::StakeStorer,
 };
@@ -66,10 +66,11 @@ pub trait Runner {
         signers: &[Signer],
     ) -> Result<Vec<SignerWithStake>, Box<dyn StdError + Sync + Send>>;
 
-    /// Create the message to be signed with the single signature.
+    /// Create the message that certifies the given entity, to be signed with the single
+    /// signature.
     async fn compute_message(
         &self,
-        beacon: &Beacon,
+        signed_entity_type: &SignedEntityType,
         next_signers: &[SignerWithStake],
     ) -> Result<ProtocolMessage, Box<dyn StdError + Sync + Send>>;
 
@@ -334,19 +335,32 @@ impl Runner for SignerRunner {
 
     async fn compute_message(
         &self,
-        beacon: &Beacon,
+        signed_entity_type: &SignedEntityType,
         next_signers: &[SignerWithStake],
     ) -> Result<ProtocolMessage, Box<dyn StdError + Sync + Send>> {
-        debug!("RUNNER: compute_message");
+        debug!("RUNNER: compute_message"; "signed_entity_type" => ?signed_entity_type);
 
         let mut message = ProtocolMessage::new();
-        // 1 set the digest in the message
-        let digest = self.services.digester.compute_digest(beacon).await?;
-        info!(" > set message digest: {}", digest);
-        message.set_message_part(ProtocolMessagePartKey::SnapshotDigest, digest);
+        // 1 set the signed entity in the message
+        match signed_entity_type {
+            SignedEntityType::MithrilStakeDistribution(epoch) => {
+                info!(" > set message stake distribution epoch: {}", epoch);
+                message.set_message_part(
+                    ProtocolMessagePartKey::MithrilStakeDistributionEpoch,
+                    epoch.to_string(),
+                );
+            }
+            SignedEntityType::CardanoImmutableFilesFull(beacon) => {
+                let digest = self.services.digester.compute_digest(beacon).await?;
+                info!(" > set message digest: {}", digest);
+                message.set_message_part(ProtocolMessagePartKey::SnapshotDigest, digest);
+            }
+        }
 
         // 2 set the next signers keys and stakes in the message
-        let next_signer_retrieval_epoch = beacon.epoch.offset_to_next_signer_retrieval_epoch()?;
+        let next_signer_retrieval_epoch = signed_entity_type
+            .get_epoch()
+            .offset_to_next_signer_retrieval_epoch()?;
         let next_protocol_initializer = self
             .services
             .protocol_initializer_store
@@ -437,7 +451,7 @@ mod tests {
     use mithril_common::digesters::{DumbImmutableDigester, DumbImmutableFileObserver};
     use mithril_common::entities::{Epoch, StakeDistribution};
     use mithril_common::store::adapter::{DumbStoreAdapter, MemoryAdapter};
-    use mithril_common::store::{StakeStore, StakeStorer};
+    use mithril_common::store::{RetentionPolicy, StakeStore, StakeStorer};
     use mithril_common::{
         chain_observer::FakeObserver, BeaconProvider, BeaconProviderError, BeaconProviderImpl,
     };
@@ -465,7 +479,10 @@ mod tests {
         let adapter: MemoryAdapter<Epoch, ProtocolInitializer> = MemoryAdapter::new(None).unwrap();
         let chain_observer = Arc::new(FakeObserver::default());
         SignerServices {
-            stake_store: Arc::new(StakeStore::new(Box::new(DumbStoreAdapter::new()), None)),
+            stake_store: Arc::new(StakeStore::new(
+                Box::new(DumbStoreAdapter::new()),
+                RetentionPolicy::KeepAll,
+            )),
             certificate_handler: Arc::new(DumbCertificateHandler::default()),
             chain_observer: chain_observer.clone(),
             digester: Arc::new(DumbImmutableDigester::new(DIGESTER_RESULT, true)),
@@ -477,7 +494,7 @@ mod tests {
             )),
             protocol_initializer_store: Arc::new(ProtocolInitializerStore::new(
                 Box::new(adapter),
-                None,
+                RetentionPolicy::KeepAll,
             )),
         }
     }
@@ -499,7 +516,13 @@ mod tests {
             data_stores_directory: PathBuf::new(),
             kes_secret_key_path: None,
             operational_certificate_path: None,
+            store_encryption_key: None,
+            store_encryption_key_path: None,
+            store_previous_encryption_key: None,
+            store_previous_encryption_key_path: None,
             store_retention_limit: None,
+            protocol_initializer_store_retention: None,
+            stake_store_retention: None,
         };
 
         SignerRunner::new(
@@ -710,7 +733,27 @@ mod tests {
 
         let runner = init_runner(Some(services), None);
         let message = runner
-            .compute_message(&current_beacon, &next_signers)
+            .compute_message(
+                &SignedEntityType::CardanoImmutableFilesFull(current_beacon.clone()),
+                &next_signers,
+            )
+            .await
+            .expect("compute_message should not fail");
+
+        assert_eq!(expected, message);
+
+        expected
+            .message_parts
+            .remove(&ProtocolMessagePartKey::SnapshotDigest);
+        expected.set_message_part(
+            ProtocolMessagePartKey::MithrilStakeDistributionEpoch,
+            current_beacon.epoch.to_string(),
+        );
+        let message = runner
+            .compute_message(
+                &SignedEntityType::MithrilStakeDistribution(current_beacon.epoch),
+                &next_signers,
+            )
             .await
             .expect("compute_message should not fail");
 
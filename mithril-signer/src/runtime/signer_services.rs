@@ -1,12 +1,19 @@
+use async_trait::async_trait;
+use slog_scope::info;
 use std::error::Error as StdError;
 use std::fs;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use mithril_common::{
     chain_observer::{CardanoCliChainObserver, CardanoCliRunner, ChainObserver},
-    crypto_helper::{OpCert, ProtocolPartyId, SerDeShelleyFileFormat},
+    crypto_helper::{OpCert, ProtocolInitializer, ProtocolPartyId, SerDeShelleyFileFormat},
     digesters::{CardanoImmutableDigester, ImmutableDigester, ImmutableFileSystemObserver},
-    store::{adapter::SQLiteAdapter, StakeStore},
+    entities::Epoch,
+    store::{
+        adapter::{EncryptedRecord, EncryptedStoreAdapter, SQLiteAdapter, StoreAdapter},
+        StakeStore,
+    },
     BeaconProvider, BeaconProviderImpl,
 };
 
@@ -26,9 +33,10 @@ type ProtocolInitializerStoreService = Arc<dyn ProtocolInitializerStorer>;
 
 /// The ServiceBuilder is intended to manage Services instance creation.
 /// The goal of this is to put all this code out of the way of business code.
+#[async_trait]
 pub trait ServiceBuilder {
     /// Create a SignerService instance.
-    fn build(&self) -> Result<SignerServices, Box<dyn StdError>>;
+    async fn build(&self) -> Result<SignerServices, Box<dyn StdError>>;
 }
 
 /// Create a SignerService instance for Production environment.
@@ -62,23 +70,77 @@ impl<'a> ProductionServiceBuilder<'a> {
                 .ok_or("A party_id should at least be provided")?),
         }
     }
+
+    /// Build the protocol initializer store adapter, encrypting records if a
+    /// store encryption key is configured.
+    ///
+    /// When encryption is enabled, the records encrypted with the previous key
+    /// and the records stored in clear are re-encrypted with the current key.
+    /// When it is not, the build fails if some records are encrypted since they could not be
+    /// read anymore.
+    async fn build_protocol_initializer_adapter(
+        &self,
+        sqlite_db_path: Option<PathBuf>,
+    ) -> Result<Box<dyn StoreAdapter<Key = Epoch, Record = ProtocolInitializer>>, Box<dyn StdError>>
+    {
+        let mut plain_adapter = SQLiteAdapter::new("protocol_initializer", sqlite_db_path.clone())?;
+        let encryption_key = match self.config.get_store_encryption_key()? {
+            Some(key) => key,
+            None => {
+                let encrypted_adapter: SQLiteAdapter<Epoch, EncryptedRecord> =
+                    SQLiteAdapter::new("encrypted_protocol_initializer", sqlite_db_path)?;
+                if !encrypted_adapter.get_last_n_records(1).await?.is_empty() {
+                    return Err("The protocol initializer store holds encrypted records but no store encryption key is configured".into());
+                }
+
+                return Ok(Box::new(plain_adapter));
+            }
+        };
+        let previous_keys = self
+            .config
+            .get_store_previous_encryption_key()?
+            .into_iter()
+            .collect();
+        let mut adapter = EncryptedStoreAdapter::new(
+            Box::new(SQLiteAdapter::new(
+                "encrypted_protocol_initializer",
+                sqlite_db_path,
+            )?),
+            encryption_key,
+        )
+        .with_previous_keys(previous_keys);
+
+        let nb_reencrypted = adapter.reencrypt_records().await?;
+        let plain_records = plain_adapter.get_all_records().await?;
+        for (epoch, protocol_initializer) in plain_records.iter().rev() {
+            adapter.store_record(epoch, protocol_initializer).await?;
+            plain_adapter.remove(epoch).await?;
+        }
+        info!(
+            "Protocol initializer store encryption enabled";
+            "reencrypted_records" => nb_reencrypted,
+            "encrypted_plain_records" => plain_records.len()
+        );
+
+        Ok(Box::new(adapter))
+    }
 }
 
+#[async_trait]
 impl<'a> ServiceBuilder for ProductionServiceBuilder<'a> {
     /// Build a Services for the Production environment.
-    fn build(&self) -> Result<SignerServices, Box<dyn StdError>> {
+    async fn build(&self) -> Result<SignerServices, Box<dyn StdError>> {
         if !self.config.data_stores_directory.exists() {
             fs::create_dir_all(self.config.data_stores_directory.clone())
                 .map_err(|e| format!("Could not create data stores directory: {:?}", e))?;
         }
 
-        let sqlite_db_path = Some(self.config.data_stores_directory.join("signer.sqlite3"));
+        let sqlite_db_path = Some(self.config.get_sqlite_file());
         let protocol_initializer_store = Arc::new(ProtocolInitializerStore::new(
-            Box::new(SQLiteAdapter::new(
-                "protocol_initializer",
-                sqlite_db_path.clone(),
-            )?),
-            self.config.store_retention_limit,
+            self.build_protocol_initializer_adapter(sqlite_db_path.clone())
+                .await?,
+            self.config
+                .get_store_retention_policy(&self.config.protocol_initializer_store_retention),
         ));
         let single_signer = Arc::new(MithrilSingleSigner::new(self.compute_protocol_party_id()?));
         let certificate_handler = Arc::new(CertificateHandlerHTTPClient::new(
@@ -90,7 +152,8 @@ impl<'a> ServiceBuilder for ProductionServiceBuilder<'a> {
         ));
         let stake_store = Arc::new(StakeStore::new(
             Box::new(SQLiteAdapter::new("stake", sqlite_db_path)?),
-            self.config.store_retention_limit,
+            self.config
+                .get_store_retention_policy(&self.config.stake_store_retention),
         ));
         let chain_observer = Arc::new(CardanoCliChainObserver::new(Box::new(
             CardanoCliRunner::new(
@@ -147,10 +210,17 @@ pub struct SignerServices {
 mod tests {
     use super::*;
 
+    use mithril_common::{fake_data, store::adapter::StoreEncryptionKey};
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
     use std::path::PathBuf;
 
     fn get_test_dir() -> PathBuf {
-        let test_dir = std::env::temp_dir().join("mithril_test");
+        get_test_dir_named("mithril_test")
+    }
+
+    fn get_test_dir_named(name: &str) -> PathBuf {
+        let test_dir = std::env::temp_dir().join(name);
 
         if test_dir.exists() {
             fs::remove_dir_all(&test_dir).expect(&*format!("Could not remove dir {:?}", test_dir));
@@ -160,8 +230,8 @@ mod tests {
         test_dir
     }
 
-    #[test]
-    fn test_auto_create_stores_directory() {
+    #[tokio::test]
+    async fn test_auto_create_stores_directory() {
         let stores_dir = get_test_dir().join("stores");
         let config = Config {
             cardano_cli_path: PathBuf::new(),
@@ -174,15 +244,75 @@ mod tests {
             db_directory: PathBuf::new(),
             data_stores_directory: stores_dir.clone(),
             store_retention_limit: None,
+            protocol_initializer_store_retention: None,
+            stake_store_retention: None,
             kes_secret_key_path: None,
             operational_certificate_path: None,
+            store_encryption_key: None,
+            store_encryption_key_path: None,
+            store_previous_encryption_key: None,
+            store_previous_encryption_key_path: None,
         };
 
         assert!(!stores_dir.exists());
         let service_builder = ProductionServiceBuilder::new(&config);
         service_builder
             .build()
+            .await
             .expect("service builder build should not fail");
         assert!(stores_dir.exists());
     }
+
+    #[tokio::test]
+    async fn test_build_fails_without_encryption_key_when_encrypted_records_exist() {
+        let stores_dir = get_test_dir_named("mithril_test_missing_encryption_key").join("stores");
+        fs::create_dir_all(&stores_dir).unwrap();
+        let mut config = Config {
+            cardano_cli_path: PathBuf::new(),
+            cardano_node_socket_path: PathBuf::new(),
+            network_magic: None,
+            network: "preview".to_string(),
+            aggregator_endpoint: "".to_string(),
+            party_id: Some("party-123456".to_string()),
+            run_interval: 1000,
+            db_directory: PathBuf::new(),
+            data_stores_directory: stores_dir,
+            store_retention_limit: None,
+            protocol_initializer_store_retention: None,
+            stake_store_retention: None,
+            kes_secret_key_path: None,
+            operational_certificate_path: None,
+            store_encryption_key: Some(StoreEncryptionKey::generate()),
+            store_encryption_key_path: None,
+            store_previous_encryption_key: None,
+            store_previous_encryption_key_path: None,
+        };
+        let sqlite_db_path = Some(config.get_sqlite_file());
+        let mut adapter = ProductionServiceBuilder::new(&config)
+            .build_protocol_initializer_adapter(sqlite_db_path.clone())
+            .await
+            .expect("building the encrypted adapter should not fail");
+        let protocol_initializer = ProtocolInitializer::setup(
+            fake_data::protocol_parameters().into(),
+            None::<PathBuf>,
+            Some(0),
+            100,
+            &mut ChaCha20Rng::from_seed([0u8; 32]),
+        )
+        .expect("protocol initializer should not fail");
+        adapter
+            .store_record(&Epoch(1), &protocol_initializer)
+            .await
+            .unwrap();
+
+        config.store_encryption_key = None;
+        let result = ProductionServiceBuilder::new(&config)
+            .build_protocol_initializer_adapter(sqlite_db_path)
+            .await;
+
+        assert!(
+            result.is_err(),
+            "building the adapter without encryption key should fail when encrypted records exist"
+        );
+    }
 }
@@ -1,7 +1,12 @@
 use slog_scope::{debug, error, info};
-use std::{error::Error, fmt::Display, thread::sleep, time::Duration};
+use std::{error::Error, fmt::Display, time::Duration};
+use tokio::sync::mpsc::Receiver;
+use tokio::time::{sleep_until, Instant};
 
-use mithril_common::entities::{Beacon, CertificatePending, Epoch, EpochSettings, SignerWithStake};
+use mithril_common::entities::{
+    AggregatorEvent, Beacon, CertificatePending, Epoch, EpochSettings, ProtocolMessagePartKey,
+    SignedEntityType, SignerWithStake,
+};
 
 use super::Runner;
 
@@ -15,6 +20,10 @@ pub struct RegisteredState {
 #[derive(Debug, PartialEq, Eq)]
 pub struct SignedState {
     beacon: Beacon,
+
+    /// Type of the entity signed for this beacon, the aggregator may certify several
+    /// entities for a same beacon
+    signed_entity_type: SignedEntityType,
 }
 
 /// Different possible states of the state machine.
@@ -70,6 +79,7 @@ pub struct StateMachine {
     state: SignerState,
     runner: Box<dyn Runner>,
     state_sleep: Duration,
+    events: Option<Receiver<AggregatorEvent>>,
 }
 
 impl StateMachine {
@@ -83,9 +93,18 @@ impl StateMachine {
             state: starting_state,
             runner,
             state_sleep,
+            events: None,
         }
     }
 
+    /// Wake up the state machine on the events of the aggregator instead of waiting for the
+    /// end of each sleep.
+    pub fn with_events(mut self, events: Receiver<AggregatorEvent>) -> Self {
+        self.events = Some(events);
+
+        self
+    }
+
     /// Return the current state of the state machine.
     pub fn get_state(&self) -> &SignerState {
         &self.state
@@ -104,8 +123,35 @@ impl StateMachine {
                 "… Cycle finished, Sleeping for {} ms",
                 self.state_sleep.as_millis()
             );
-            sleep(self.state_sleep);
+            self.wait_next_cycle().await;
+        }
+    }
+
+    /// Sleep until the next cycle, a received aggregator event that the signer must act upon
+    /// ends the sleep early.
+    async fn wait_next_cycle(&mut self) {
+        let deadline = Instant::now() + self.state_sleep;
+
+        while let Some(events) = self.events.as_mut() {
+            let event = tokio::select! {
+                _ = sleep_until(deadline) => return,
+                event = events.recv() => event,
+            };
+
+            match event {
+                Some(
+                    event @ (AggregatorEvent::PendingCertificateCreated { .. }
+                    | AggregatorEvent::EpochChanged { .. }),
+                ) => {
+                    info!("→ Aggregator event received, starting a new cycle"; "event" => event.name());
+                    return;
+                }
+                Some(event) => debug!(" ⋅ Aggregator event ignored"; "event" => event.name()),
+                None => self.events = None,
+            }
         }
+
+        sleep_until(deadline).await;
     }
 
     /// Perform a cycle of the state machine.
@@ -170,6 +216,21 @@ impl StateMachine {
                         self.state =
                             SignerState::Registered(RegisteredState { beacon: new_beacon });
                     }
+                } else if let Some(pending_certificate) = self
+                    .runner
+                    .get_pending_certificate()
+                    .await?
+                    .filter(|pending_certificate| {
+                        pending_certificate.get_signed_entity_type() != state.signed_entity_type
+                    })
+                {
+                    info!(
+                        " → pending certificate for another signed entity, transiting to REGISTERED";
+                        "signed_entity_type" => ?pending_certificate.get_signed_entity_type()
+                    );
+                    self.state = SignerState::Registered(RegisteredState {
+                        beacon: state.beacon.clone(),
+                    });
                 } else {
                     info!(" ⋅ NO new beacon detected, waiting");
                 }
@@ -250,10 +311,25 @@ impl StateMachine {
             .associate_signers_with_stake(next_retrieval_epoch, &pending_certificate.next_signers)
             .await?;
 
-        let message = self
+        let signed_entity_type = pending_certificate.get_signed_entity_type();
+        debug!(" > signing entity"; "signed_entity_type" => ?signed_entity_type);
+        let mut message = self
             .runner
-            .compute_message(current_beacon, &next_signers)
+            .compute_message(&signed_entity_type, &next_signers)
             .await?;
+        // The manifest of the chunks of the snapshot archive is built by the aggregator and
+        // its hash is given along the pending certificate: the signers do not compute it, so
+        // the quorum only vouches that this is the value supplied by the aggregator. It is
+        // only signed along the immutable files it is the manifest of.
+        if let (SignedEntityType::CardanoImmutableFilesFull(_), Some(snapshot_manifest_hash)) = (
+            &signed_entity_type,
+            &pending_certificate.snapshot_manifest_hash,
+        ) {
+            message.set_message_part(
+                ProtocolMessagePartKey::SnapshotManifestHash,
+                snapshot_manifest_hash.clone(),
+            );
+        }
         let single_signatures = self
             .runner
             .compute_single_signature(current_beacon.epoch, &message, &signers)
@@ -262,6 +338,7 @@ impl StateMachine {
 
         Ok(SignedState {
             beacon: current_beacon.clone(),
+            signed_entity_type,
         })
     }
 }
@@ -279,9 +356,38 @@ mod tests {
             state: init_state,
             runner: Box::new(runner),
             state_sleep: Duration::from_millis(100),
+            events: None,
         }
     }
 
+    #[tokio::test]
+    async fn wait_next_cycle_ends_on_relevant_aggregator_event() {
+        let (sender, receiver) = tokio::sync::mpsc::channel(10);
+        let mut state_machine = StateMachine::new(
+            SignerState::Unregistered(None),
+            Box::new(MockSignerRunner::new()),
+            Duration::from_secs(3600),
+        )
+        .with_events(receiver);
+        sender
+            .send(AggregatorEvent::SignatureAccepted {
+                party_id: "party".to_string(),
+            })
+            .await
+            .unwrap();
+        sender
+            .send(AggregatorEvent::PendingCertificateCreated {
+                beacon: fake_data::beacon(),
+                signed_entity_type: fake_data::certificate_pending().get_signed_entity_type(),
+            })
+            .await
+            .unwrap();
+
+        tokio::time::timeout(Duration::from_secs(5), state_machine.wait_next_cycle())
+            .await
+            .expect("The state machine should have been woken up by the aggregator event");
+    }
+
     #[tokio::test]
     async fn unregistered_epoch_settings_not_found() {
         let mut runner = MockSignerRunner::new();
@@ -444,6 +550,8 @@ mod tests {
 
         let mut certificate_pending = fake_data::certificate_pending();
         certificate_pending.beacon = beacon.clone();
+        let expected_signed_entity_type = certificate_pending.get_signed_entity_type();
+        let signed_entity_type = expected_signed_entity_type.clone();
         let mut runner = MockSignerRunner::new();
         runner
             .expect_get_current_beacon()
@@ -464,6 +572,7 @@ mod tests {
             .returning(|_, _, _| Ok(Some(fake_data::single_signatures(vec![1, 5, 23]))));
         runner
             .expect_compute_message()
+            .withf(move |signed_entity_type, _| signed_entity_type == &expected_signed_entity_type)
             .once()
             .returning(|_, _| Ok(ProtocolMessage::new()));
         runner
@@ -483,7 +592,8 @@ mod tests {
                     epoch: Epoch(9),
                     immutable_file_number: 99,
                     ..Default::default()
-                }
+                },
+                signed_entity_type,
             }),
             *state_machine.get_state(),
             "state machine did not return a RegisteredState but {:?}",
@@ -491,6 +601,96 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn registered_to_signed_with_snapshot_manifest_hash() {
+        let beacon = Beacon {
+            immutable_file_number: 99,
+            epoch: Epoch(9),
+            ..Default::default()
+        };
+        let mut expected_message = ProtocolMessage::new();
+        expected_message.set_message_part(
+            ProtocolMessagePartKey::SnapshotManifestHash,
+            "manifest-hash".to_string(),
+        );
+
+        sign_pending_certificate_with_snapshot_manifest_hash(
+            SignedEntityType::CardanoImmutableFilesFull(beacon.clone()),
+            beacon,
+            expected_message,
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn snapshot_manifest_hash_is_not_signed_along_the_stake_distribution() {
+        let beacon = Beacon {
+            immutable_file_number: 99,
+            epoch: Epoch(9),
+            ..Default::default()
+        };
+
+        sign_pending_certificate_with_snapshot_manifest_hash(
+            SignedEntityType::MithrilStakeDistribution(beacon.epoch),
+            beacon,
+            ProtocolMessage::new(),
+        )
+        .await;
+    }
+
+    async fn sign_pending_certificate_with_snapshot_manifest_hash(
+        signed_entity_type: SignedEntityType,
+        beacon: Beacon,
+        expected_message: ProtocolMessage,
+    ) {
+        let state = RegisteredState {
+            beacon: beacon.clone(),
+        };
+        let mut certificate_pending = fake_data::certificate_pending()
+            .with_snapshot_manifest_hash("manifest-hash".to_string());
+        certificate_pending.beacon = beacon.clone();
+        certificate_pending.signed_entity_type = Some(signed_entity_type);
+        let mut runner = MockSignerRunner::new();
+        runner
+            .expect_get_current_beacon()
+            .once()
+            .returning(move || Ok(beacon.clone()));
+        runner
+            .expect_get_pending_certificate()
+            .once()
+            .returning(move || Ok(Some(certificate_pending.clone())));
+        runner.expect_can_i_sign().once().returning(|_| Ok(true));
+        runner
+            .expect_associate_signers_with_stake()
+            .times(2)
+            .returning(|_, _| Ok(fake_data::signers_with_stakes(4)));
+        runner
+            .expect_compute_message()
+            .once()
+            .returning(|_, _| Ok(ProtocolMessage::new()));
+        runner
+            .expect_compute_single_signature()
+            .withf(move |_, message, _| message == &expected_message)
+            .once()
+            .returning(|_, _, _| Ok(Some(fake_data::single_signatures(vec![1, 5, 23]))));
+        runner
+            .expect_send_single_signature()
+            .once()
+            .returning(|_| Ok(()));
+
+        let mut state_machine = init_state_machine(SignerState::Registered(state), runner);
+        state_machine
+            .cycle()
+            .await
+            .expect("Cycling the state machine should not fail");
+
+        assert!(
+            state_machine.get_state().is_signed(),
+            "state machine did not return a SignedState but {:?}",
+            state_machine.get_state()
+        );
+    }
+
     #[tokio::test]
     async fn signed_to_registered() {
         let beacon = Beacon {
@@ -504,6 +704,7 @@ mod tests {
         };
         let state = SignedState {
             beacon: beacon.clone(),
+            signed_entity_type: SignedEntityType::CardanoImmutableFilesFull(beacon.clone()),
         };
 
         let mut runner = MockSignerRunner::new();
@@ -542,6 +743,7 @@ mod tests {
         };
         let state = SignedState {
             beacon: beacon.clone(),
+            signed_entity_type: SignedEntityType::CardanoImmutableFilesFull(beacon.clone()),
         };
 
         let mut runner = MockSignerRunner::new();
@@ -561,4 +763,73 @@ mod tests {
             *state_machine.get_state()
         );
     }
+
+    #[tokio::test]
+    async fn signed_to_signed_when_the_same_entity_is_pending() {
+        let beacon = fake_data::beacon();
+        let state = SignedState {
+            beacon: beacon.clone(),
+            signed_entity_type: SignedEntityType::CardanoImmutableFilesFull(beacon.clone()),
+        };
+        let mut certificate_pending = fake_data::certificate_pending();
+        certificate_pending.beacon = beacon.clone();
+        certificate_pending.signed_entity_type = None;
+
+        let mut runner = MockSignerRunner::new();
+        runner
+            .expect_get_current_beacon()
+            .once()
+            .returning(move || Ok(beacon.to_owned()));
+        runner
+            .expect_get_pending_certificate()
+            .once()
+            .returning(move || Ok(Some(certificate_pending.to_owned())));
+
+        let mut state_machine = init_state_machine(SignerState::Signed(state), runner);
+        state_machine
+            .cycle()
+            .await
+            .expect("Cycling the state machine should not fail");
+
+        assert!(
+            state_machine.get_state().is_signed(),
+            "state machine did not return a SignedState but {:?}",
+            state_machine.get_state()
+        );
+    }
+
+    #[tokio::test]
+    async fn signed_to_registered_when_another_entity_is_pending_for_the_same_beacon() {
+        let beacon = fake_data::beacon();
+        let state = SignedState {
+            beacon: beacon.clone(),
+            signed_entity_type: SignedEntityType::MithrilStakeDistribution(beacon.epoch),
+        };
+        let mut certificate_pending = fake_data::certificate_pending();
+        certificate_pending.beacon = beacon.clone();
+        certificate_pending.signed_entity_type =
+            Some(SignedEntityType::CardanoImmutableFilesFull(beacon.clone()));
+
+        let mut runner = MockSignerRunner::new();
+        let current_beacon = beacon.clone();
+        runner
+            .expect_get_current_beacon()
+            .once()
+            .returning(move || Ok(current_beacon.to_owned()));
+        runner
+            .expect_get_pending_certificate()
+            .once()
+            .returning(move || Ok(Some(certificate_pending.to_owned())));
+
+        let mut state_machine = init_state_machine(SignerState::Signed(state), runner);
+        state_machine
+            .cycle()
+            .await
+            .expect("Cycling the state machine should not fail");
+
+        assert_eq!(
+            SignerState::Registered(RegisteredState { beacon }),
+            *state_machine.get_state()
+        );
+    }
 }
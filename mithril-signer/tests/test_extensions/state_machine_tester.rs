@@ -57,8 +57,14 @@ impl StateMachineTester {
             run_interval: 5000,
             data_stores_directory: PathBuf::new(),
             store_retention_limit: None,
+            protocol_initializer_store_retention: None,
+            stake_store_retention: None,
             kes_secret_key_path: None,
             operational_certificate_path: None,
+            store_encryption_key: None,
+            store_encryption_key_path: None,
+            store_previous_encryption_key: None,
+            store_previous_encryption_key_path: None,
         };
 
         let decorator = slog_term::PlainDecorator::new(slog_term::TestStdoutWriter);
@@ -83,14 +89,14 @@ impl StateMachineTester {
         let digester = Arc::new(DumbImmutableDigester::new("DIGEST", true));
         let protocol_initializer_store = Arc::new(ProtocolInitializerStore::new(
             Box::new(MemoryAdapter::new(None).unwrap()),
-            config.store_retention_limit,
+            config.store_retention_limit.into(),
         ));
         let single_signer = Arc::new(MithrilSingleSigner::new(
             config.party_id.to_owned().unwrap_or_default(),
         ));
         let stake_store = Arc::new(StakeStore::new(
             Box::new(MemoryAdapter::new(None).unwrap()),
-            config.store_retention_limit,
+            config.store_retention_limit.into(),
         ));
 
         let services = SignerServices {